@@ -433,6 +433,9 @@ fn construct_params(config: ParamsConfig) -> RollupParams {
     // TODO add in bitcoin network
     RollupParams {
         rollup_name: config.name,
+        inscription_tag: None,
+        deposit_magic_bytes: None,
+        batched_inscriptions: false,
         block_time: config.block_time_sec * 1000,
         cred_rule: cr,
         // TODO do we want to remove this?
@@ -453,6 +456,7 @@ fn construct_params(config: ParamsConfig) -> RollupParams {
         target_l2_batch_size: config.epoch_slots as u64,
         address_length: 20,
         deposit_amount: config.deposit_sats,
+        federation_script: None,
         rollup_vk: config.rollup_vk,
         // TODO make configurable
         dispatch_assignment_dur: 64,
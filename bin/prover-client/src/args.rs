@@ -98,6 +98,19 @@ pub struct Args {
     )]
     pub sp1_workers: usize,
 
+    /// The RPC endpoint of the SP1 prover network to send proving requests to.
+    ///
+    /// Proofs are generated locally if unset.  Requires `sp1_network_key_file`.
+    #[cfg(feature = "sp1")]
+    #[argh(option, description = "sp1 prover network RPC endpoint")]
+    pub sp1_network_rpc: Option<String>,
+
+    /// Path to the file with the private key proving requests to the SP1 prover network are
+    /// signed with.
+    #[cfg(feature = "sp1")]
+    #[argh(option, description = "sp1 prover network private key file")]
+    pub sp1_network_key_file: Option<PathBuf>,
+
     /// The number of native prover workers to spawn.
     ///
     /// Defaults to `20`.
@@ -179,6 +192,27 @@ impl Args {
         workers
     }
 
+    /// Returns the prover the SP1 hosts generate proofs with.
+    ///
+    /// This is the SP1 prover network if both its endpoint and key file are set, and the default
+    /// for the build otherwise.
+    #[cfg(feature = "sp1")]
+    pub fn get_sp1_prover_mode(&self) -> anyhow::Result<strata_sp1_adapter::ProverMode> {
+        use strata_sp1_adapter::ProverMode;
+
+        match (&self.sp1_network_rpc, &self.sp1_network_key_file) {
+            (Some(endpoint), Some(key_file)) => {
+                let key = fs::read_to_string(key_file)?.trim().to_string();
+                Ok(ProverMode::Network {
+                    endpoint: endpoint.clone(),
+                    key,
+                })
+            }
+            (None, None) => Ok(ProverMode::default()),
+            _ => anyhow::bail!("sp1 prover network needs both an RPC endpoint and a key file"),
+        }
+    }
+
     /// Resolves the rollup params file to use, from a path, and validates
     /// it to ensure it passes sanity checks.
     pub fn resolve_and_validate_rollup_params(&self) -> anyhow::Result<RollupParams> {
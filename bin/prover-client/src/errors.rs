@@ -38,6 +38,10 @@ pub enum ProvingTaskError {
     #[error("Proof with ID {0:?} does not exist in DB.")]
     ProofNotFound(ProofKey),
 
+    /// Occurs when a stored proof fails verification against its host.
+    #[error("Proof with ID {0:?} failed verification: {1}")]
+    ProofVerificationFailed(ProofKey, ZkVmError),
+
     /// Occurs when a state transition is invalid based on the task's current status.
     #[error("Invalid status transition: {0:?} -> {1:?}")]
     InvalidStatusTransition(ProvingTaskStatus, ProvingTaskStatus),
@@ -46,6 +50,14 @@ pub enum ProvingTaskError {
     #[error("Invalid input: Expected {0:?}")]
     InvalidInput(String),
 
+    /// Occurs when a task references an input that is missing from the input store.
+    #[error("Input of task with ID {0:?} does not exist in DB.")]
+    InputNotFound(ProofKey),
+
+    /// Occurs when an input kept in the input store can't be decoded.
+    #[error("Failed to decode the stored input: {0}")]
+    InputDecode(String),
+
     /// Occurs when the required witness data for a proving task is missing.
     #[error("Witness not found")]
     WitnessNotFound,
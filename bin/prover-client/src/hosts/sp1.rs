@@ -1,12 +1,30 @@
-use std::sync::LazyLock;
+use std::sync::{LazyLock, OnceLock};
 
 use strata_primitives::proof::ProofContext;
-use strata_sp1_adapter::SP1Host;
+use strata_sp1_adapter::{ProverMode, SP1Host};
 use strata_sp1_guest_builder::*;
+use tracing::*;
+
+/// Prover the hosts generate proofs with, set once on startup.
+static PROVER_MODE: OnceLock<ProverMode> = OnceLock::new();
+
+/// Sets the prover the hosts generate proofs with.  Has to be called before
+/// any of the hosts is used, otherwise they use the default prover.
+pub fn set_prover_mode(prover_mode: ProverMode) {
+    info!(?prover_mode, "using sp1 prover");
+    if PROVER_MODE.set(prover_mode).is_err() {
+        warn!("sp1 prover already set, ignoring");
+    }
+}
+
+fn make_host(elf: &[u8], proving_key: &[u8], verifying_key: &[u8]) -> SP1Host {
+    let prover_mode = PROVER_MODE.get().cloned().unwrap_or_default();
+    SP1Host::new_from_bytes(elf, proving_key, verifying_key).with_prover_mode(prover_mode)
+}
 
 pub static BTC_BLOCKSPACE_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
     {
-        SP1Host::new_from_bytes(
+        make_host(
             &GUEST_BTC_BLOCKSPACE_ELF,
             &GUEST_BTC_BLOCKSPACE_PK,
             &GUEST_BTC_BLOCKSPACE_VK,
@@ -16,13 +34,13 @@ pub static BTC_BLOCKSPACE_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(||
 
 pub static L1_BATCH_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
     {
-        SP1Host::new_from_bytes(&GUEST_L1_BATCH_ELF, &GUEST_L1_BATCH_PK, &GUEST_L1_BATCH_VK)
+        make_host(&GUEST_L1_BATCH_ELF, &GUEST_L1_BATCH_PK, &GUEST_L1_BATCH_VK)
     }
 });
 
 pub static EVM_EE_STF_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
     {
-        SP1Host::new_from_bytes(
+        make_host(
             &GUEST_EVM_EE_STF_ELF,
             &GUEST_EVM_EE_STF_PK,
             &GUEST_EVM_EE_STF_VK,
@@ -30,21 +48,15 @@ pub static EVM_EE_STF_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
     }
 });
 
-pub static CL_STF_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
-    {
-        SP1Host::new_from_bytes(&GUEST_CL_STF_ELF, &GUEST_CL_STF_PK, &GUEST_CL_STF_VK)
-    }
-});
+pub static CL_STF_HOST: LazyLock<SP1Host> =
+    std::sync::LazyLock::new(|| make_host(&GUEST_CL_STF_ELF, &GUEST_CL_STF_PK, &GUEST_CL_STF_VK));
 
-pub static CL_AGG_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
-    {
-        SP1Host::new_from_bytes(&GUEST_CL_AGG_ELF, &GUEST_CL_AGG_PK, &GUEST_CL_AGG_VK)
-    }
-});
+pub static CL_AGG_HOST: LazyLock<SP1Host> =
+    std::sync::LazyLock::new(|| make_host(&GUEST_CL_AGG_ELF, &GUEST_CL_AGG_PK, &GUEST_CL_AGG_VK));
 
 pub static CHECKPOINT_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
     {
-        SP1Host::new_from_bytes(
+        make_host(
             &GUEST_CHECKPOINT_ELF,
             &GUEST_CHECKPOINT_PK,
             &GUEST_CHECKPOINT_VK,
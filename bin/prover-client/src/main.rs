@@ -49,6 +49,12 @@ async fn main_inner(args: Args) -> anyhow::Result<()> {
         .resolve_and_validate_rollup_params()
         .context("Failed to resolve and validate rollup parameters")?;
 
+    #[cfg(feature = "sp1")]
+    hosts::sp1::set_prover_mode(
+        args.get_sp1_prover_mode()
+            .context("Failed to resolve the SP1 prover")?,
+    );
+
     let el_client = HttpClientBuilder::default()
         .build(args.get_reth_rpc_url())
         .context("Failed to connect to the Ethereum client")?;
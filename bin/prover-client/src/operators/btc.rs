@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use bitcoin::consensus::{deserialize, serialize};
 use strata_btcio::rpc::{traits::Reader, BitcoinClient};
 use strata_primitives::{
     params::RollupParams,
@@ -77,4 +78,17 @@ impl ProvingOp for BtcBlockspaceOperator {
             block,
         })
     }
+
+    fn encode_input(&self, input: &BlockspaceProofInput) -> Result<Vec<u8>, ProvingTaskError> {
+        Ok(serialize(&input.block))
+    }
+
+    fn decode_input(&self, buf: &[u8]) -> Result<BlockspaceProofInput, ProvingTaskError> {
+        let block = deserialize(buf).map_err(|e| ProvingTaskError::InputDecode(e.to_string()))?;
+
+        Ok(BlockspaceProofInput {
+            rollup_params: self.rollup_params.as_ref().clone(),
+            block,
+        })
+    }
 }
@@ -140,4 +140,24 @@ impl ProvingOp for CheckpointOperator {
             l2_batch,
         })
     }
+
+    fn encode_input(&self, input: &CheckpointProverInput) -> Result<Vec<u8>, ProvingTaskError> {
+        Ok(borsh::to_vec(&(
+            input.l1_batch.receipt(),
+            input.l1_batch.vk(),
+            input.l2_batch.receipt(),
+            input.l2_batch.vk(),
+        ))?)
+    }
+
+    fn decode_input(&self, buf: &[u8]) -> Result<CheckpointProverInput, ProvingTaskError> {
+        let (l1_batch_proof, l1_batch_vk, cl_agg_proof, cl_agg_vk) = borsh::from_slice(buf)?;
+
+        let rollup_params = self.rollup_params.as_ref().clone();
+        Ok(CheckpointProverInput {
+            rollup_params,
+            l1_batch: AggregationInput::new(l1_batch_proof, l1_batch_vk),
+            l2_batch: AggregationInput::new(cl_agg_proof, cl_agg_vk),
+        })
+    }
 }
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use strata_db::traits::ProofDatabase;
 use strata_primitives::proof::{ProofContext, ProofKey};
-use strata_proofimpl_cl_agg::{ClAggInput, ClAggProver};
+use strata_proofimpl_cl_agg::{ClAggInput, ClAggProver, CL_STF_PROGRAM};
 use strata_rocksdb::prover::db::ProofDb;
+use strata_zkvm::{VerificationKey, VkRegistry};
 use tokio::sync::Mutex;
 
 use super::{cl_stf::ClStfOperator, ProvingOp};
@@ -91,6 +92,37 @@ impl ProvingOp for ClAggOperator {
             ProofContext::ClStf(*start_blkid),
             *task_id.host(),
         ));
-        Ok(ClAggInput { batch, cl_stf_vk })
+        Ok(ClAggInput {
+            batch,
+            vk_registry: cl_stf_vk_registry(cl_stf_vk)?,
+        })
     }
+
+    fn encode_input(&self, input: &ClAggInput) -> Result<Vec<u8>, ProvingTaskError> {
+        let cl_stf_vk = input
+            .vk_registry
+            .get(CL_STF_PROGRAM)
+            .map_err(|e| ProvingTaskError::ZkVmError(e.into()))?;
+
+        Ok(borsh::to_vec(&(&input.batch, cl_stf_vk))?)
+    }
+
+    fn decode_input(&self, buf: &[u8]) -> Result<ClAggInput, ProvingTaskError> {
+        let (batch, cl_stf_vk) = borsh::from_slice(buf)?;
+
+        Ok(ClAggInput {
+            batch,
+            vk_registry: cl_stf_vk_registry(cl_stf_vk)?,
+        })
+    }
+}
+
+/// Returns a registry with only the CL STF program's verification key, which is all the proofs
+/// in a CL aggregation batch are checked against.
+fn cl_stf_vk_registry(cl_stf_vk: VerificationKey) -> Result<Arc<VkRegistry>, ProvingTaskError> {
+    let mut vk_registry = VkRegistry::new();
+    vk_registry
+        .register(CL_STF_PROGRAM, cl_stf_vk)
+        .map_err(|e| ProvingTaskError::ZkVmError(e.into()))?;
+    Ok(Arc::new(vk_registry))
 }
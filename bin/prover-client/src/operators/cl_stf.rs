@@ -148,4 +148,26 @@ impl ProvingOp for ClStfOperator {
             evm_ee_vk,
         })
     }
+
+    fn encode_input(&self, input: &ClStfInput) -> Result<Vec<u8>, ProvingTaskError> {
+        Ok(borsh::to_vec(&(
+            &input.pre_state,
+            &input.l2_block,
+            &input.evm_ee_proof,
+            &input.evm_ee_vk,
+        ))?)
+    }
+
+    fn decode_input(&self, buf: &[u8]) -> Result<ClStfInput, ProvingTaskError> {
+        let (pre_state, l2_block, evm_ee_proof, evm_ee_vk) = borsh::from_slice(buf)?;
+
+        let rollup_params = self.rollup_params.as_ref().clone();
+        Ok(ClStfInput {
+            rollup_params,
+            pre_state,
+            l2_block,
+            evm_ee_proof,
+            evm_ee_vk,
+        })
+    }
 }
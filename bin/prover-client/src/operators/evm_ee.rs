@@ -117,4 +117,12 @@ impl ProvingOp for EvmEeOperator {
 
         Ok(mini_batch)
     }
+
+    fn encode_input(&self, input: &EvmEeProofInput) -> Result<Vec<u8>, ProvingTaskError> {
+        Ok(bincode::serialize(input)?)
+    }
+
+    fn decode_input(&self, buf: &[u8]) -> Result<EvmEeProofInput, ProvingTaskError> {
+        Ok(bincode::deserialize(buf)?)
+    }
 }
@@ -143,4 +143,12 @@ impl ProvingOp for L1BatchOperator {
             blockspace_vk,
         })
     }
+
+    fn encode_input(&self, input: &L1BatchProofInput) -> Result<Vec<u8>, ProvingTaskError> {
+        Ok(borsh::to_vec(input)?)
+    }
+
+    fn decode_input(&self, buf: &[u8]) -> Result<L1BatchProofInput, ProvingTaskError> {
+        Ok(borsh::from_slice(buf)?)
+    }
 }
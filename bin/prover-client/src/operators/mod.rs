@@ -5,6 +5,10 @@
 //! creating tasks, fetching inputs, and performing the proof computation using various supported
 //! ZKVMs.
 //!
+//! Fetched inputs are kept in the [`ProofDb`]'s content-addressed input store and tasks reference
+//! them by hash, so a task that is proven again doesn't fetch its input again and identical inputs
+//! are only stored once.
+//!
 //! The operations are designed to interact with a [`ProofDb`] for storing and retrieving proofs,
 //! a [`TaskTracker`] for managing task dependencies, and [`ZkVmHost`] host for ZKVM-specific
 //! computations.
@@ -83,6 +87,56 @@ pub trait ProvingOp {
         db: &ProofDb,
     ) -> Result<<Self::Prover as ZkVmProver>::Input, ProvingTaskError>;
 
+    /// Serializes the input so it can be kept in the prover input store.
+    ///
+    /// Parts of the input the operator always has at hand, like the rollup params, can be left
+    /// out and filled back in by [`Self::decode_input`].
+    fn encode_input(
+        &self,
+        input: &<Self::Prover as ZkVmProver>::Input,
+    ) -> Result<Vec<u8>, ProvingTaskError>;
+
+    /// Reconstructs an input serialized with [`Self::encode_input`].
+    fn decode_input(
+        &self,
+        buf: &[u8],
+    ) -> Result<<Self::Prover as ZkVmProver>::Input, ProvingTaskError>;
+
+    /// Loads the input for the task from the prover input store, fetching and storing it first if
+    /// the task doesn't reference one yet.
+    ///
+    /// # Arguments
+    /// - `task_id`: The key representing the proof task.
+    /// - `db`: A reference to the proof database.
+    ///
+    /// # Returns
+    /// The input required by the prover for the specified task.
+    async fn load_input(
+        &self,
+        task_id: &ProofKey,
+        db: &ProofDb,
+    ) -> Result<<Self::Prover as ZkVmProver>::Input, ProvingTaskError> {
+        let input_hash = db
+            .get_task_input(*task_id)
+            .map_err(ProvingTaskError::DatabaseError)?;
+        if let Some(input_hash) = input_hash {
+            let buf = db
+                .get_input(input_hash)
+                .map_err(ProvingTaskError::DatabaseError)?
+                .ok_or(ProvingTaskError::InputNotFound(*task_id))?;
+            return self.decode_input(&buf);
+        }
+
+        let input = self.fetch_input(task_id, db).await?;
+        let input_hash = db
+            .put_input(self.encode_input(&input)?)
+            .map_err(ProvingTaskError::DatabaseError)?;
+        db.put_task_input(*task_id, input_hash)
+            .map_err(ProvingTaskError::DatabaseError)?;
+
+        Ok(input)
+    }
+
     /// Executes the proof computation for the specified task.
     ///
     /// # Arguments
@@ -101,9 +155,9 @@ pub trait ProvingOp {
         info!("Starting proof generation");
 
         let input = self
-            .fetch_input(task_id, db)
+            .load_input(task_id, db)
             .await
-            .inspect_err(|e| error!(?e, "Failed to fetch input"))?;
+            .inspect_err(|e| error!(?e, "Failed to load input"))?;
 
         let proof_res = <Self::Prover as ZkVmProver>::prove(&input, host);
 
@@ -2,9 +2,11 @@ use std::sync::Arc;
 
 use jsonrpsee::http_client::HttpClient;
 use strata_btcio::rpc::BitcoinClient;
+use strata_db::traits::ProofDatabase;
 use strata_primitives::{params::RollupParams, proof::ProofContext};
 use strata_rocksdb::prover::db::ProofDb;
 use strata_rpc_types::ProofKey;
+use strata_zkvm::{ProofReceipt, VerificationKey, ZkVmHost};
 
 use super::{
     btc::BtcBlockspaceOperator, checkpoint::CheckpointOperator, cl_agg::ClAggOperator,
@@ -12,7 +14,7 @@ use super::{
 };
 use crate::{
     errors::ProvingTaskError,
-    hosts::{resolve_host, ZkVmHostInstance},
+    hosts::{get_verification_key, resolve_host, ZkVmHostInstance},
 };
 
 /// A struct that manages various proof operators, each corresponding to a distinct proof type.
@@ -138,6 +140,42 @@ impl ProofOperator {
         }
     }
 
+    /// Returns the stored proof for `proof_key` along with the verification key it can be checked
+    /// against, or `None` if the task hasn't completed yet.
+    pub fn get_proof(
+        proof_key: &ProofKey,
+        db: &ProofDb,
+    ) -> Result<Option<(ProofReceipt, VerificationKey)>, ProvingTaskError> {
+        let proof = db
+            .get_proof(*proof_key)
+            .map_err(ProvingTaskError::DatabaseError)?;
+        Ok(proof.map(|proof| (proof, get_verification_key(proof_key))))
+    }
+
+    /// Same as [`Self::get_proof`], but also verifies the proof with the host it was generated by
+    /// so a corrupted proof is never handed out.
+    pub fn get_verified_proof(
+        proof_key: &ProofKey,
+        db: &ProofDb,
+    ) -> Result<Option<(ProofReceipt, VerificationKey)>, ProvingTaskError> {
+        let Some((proof, vk)) = Self::get_proof(proof_key, db)? else {
+            return Ok(None);
+        };
+
+        let res = match resolve_host(proof_key) {
+            ZkVmHostInstance::Native(host) => host.verify(&proof),
+
+            #[cfg(feature = "sp1")]
+            ZkVmHostInstance::SP1(host) => host.verify(&proof),
+
+            #[cfg(feature = "risc0")]
+            ZkVmHostInstance::Risc0(host) => host.verify(&proof),
+        };
+        res.map_err(|e| ProvingTaskError::ProofVerificationFailed(*proof_key, e))?;
+
+        Ok(Some((proof, vk)))
+    }
+
     /// Returns a reference to the [`BtcBlockspaceOperator`].
     pub fn btc_operator(&self) -> &BtcBlockspaceOperator {
         &self.btc_blockspace_operator
@@ -168,3 +206,68 @@ impl ProofOperator {
         &self.checkpoint_operator
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_primitives::proof::{ProofContext, ProofZkVm};
+    use strata_rocksdb::test_utils::get_rocksdb_tmp_instance_for_prover;
+    use strata_state::l1::L1BlockId;
+    use strata_test_utils::ArbitraryGenerator;
+    use strata_zkvm::{Proof, PublicValues};
+
+    use super::*;
+
+    fn setup_db() -> ProofDb {
+        let (db, db_ops) = get_rocksdb_tmp_instance_for_prover().unwrap();
+        ProofDb::new(db, db_ops)
+    }
+
+    fn gen_proof_key(host: ProofZkVm) -> ProofKey {
+        let blkid: L1BlockId = ArbitraryGenerator::new().generate();
+        ProofKey::new(ProofContext::BtcBlockspace(blkid), host)
+    }
+
+    #[test]
+    fn test_get_proof_pending() {
+        let db = setup_db();
+        let key = gen_proof_key(ProofZkVm::Native);
+
+        assert!(ProofOperator::get_proof(&key, &db).unwrap().is_none());
+        assert!(ProofOperator::get_verified_proof(&key, &db)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_get_proof_completed() {
+        let db = setup_db();
+        let key = gen_proof_key(ProofZkVm::Native);
+        let receipt = ProofReceipt::new(Proof::new(vec![1, 2, 3]), PublicValues::new(vec![4, 5]));
+        db.put_proof(key, receipt.clone()).unwrap();
+
+        let (proof, vk) = ProofOperator::get_verified_proof(&key, &db)
+            .unwrap()
+            .expect("proof should be available");
+        assert_eq!(proof, receipt);
+        assert_eq!(vk, get_verification_key(&key));
+    }
+
+    #[cfg(feature = "sp1")]
+    #[test]
+    fn test_get_verified_proof_corrupted() {
+        let db = setup_db();
+        let key = gen_proof_key(ProofZkVm::SP1);
+        let receipt = ProofReceipt::new(Proof::new(vec![0xff; 16]), PublicValues::new(vec![]));
+        db.put_proof(key, receipt.clone()).unwrap();
+
+        // the unverified accessor hands the proof out as is
+        let (proof, _) = ProofOperator::get_proof(&key, &db).unwrap().unwrap();
+        assert_eq!(proof, receipt);
+
+        let res = ProofOperator::get_verified_proof(&key, &db);
+        assert!(matches!(
+            res,
+            Err(ProvingTaskError::ProofVerificationFailed(k, _)) if k == key
+        ));
+    }
+}
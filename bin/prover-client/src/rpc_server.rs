@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, RpcModule};
 use strata_prover_client_rpc_api::StrataProverClientApiServer;
 use strata_rocksdb::prover::db::ProofDb;
-use strata_rpc_types::ProofKey;
+use strata_rpc_types::{ProofKey, RpcProvingTaskStatus, RpcTaskSummary};
 use tokio::sync::{oneshot, Mutex};
 use tracing::{info, warn};
 
@@ -148,11 +148,18 @@ impl StrataProverClientApiServer for ProverClientRpc {
         unimplemented!()
     }
 
-    async fn get_task_status(&self, key: ProofKey) -> RpcResult<Option<String>> {
-        let status = self.task_tracker.lock().await.get_task(key).cloned();
-        match status {
-            Ok(status) => RpcResult::Ok(Some(format!("{:?}", status))),
-            Err(_) => RpcResult::Ok(Some(format!("{:?}", status))),
-        }
+    async fn get_task_status(&self, key: ProofKey) -> RpcResult<Option<RpcProvingTaskStatus>> {
+        let task_tracker = self.task_tracker.lock().await;
+        Ok(task_tracker
+            .get_task(key)
+            .ok()
+            .map(RpcProvingTaskStatus::from))
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: Option<RpcProvingTaskStatus>,
+    ) -> RpcResult<Vec<RpcTaskSummary>> {
+        Ok(self.task_tracker.lock().await.list_tasks(filter))
     }
 }
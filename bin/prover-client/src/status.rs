@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use strata_primitives::proof::ProofKey;
+use strata_rpc_types::RpcProvingTaskStatus;
 
 use crate::errors::ProvingTaskError;
 
@@ -63,6 +64,18 @@ impl ProvingTaskStatus {
     }
 }
 
+impl From<&ProvingTaskStatus> for RpcProvingTaskStatus {
+    fn from(status: &ProvingTaskStatus) -> Self {
+        match status {
+            ProvingTaskStatus::WaitingForDependencies(_) => Self::WaitingForDependencies,
+            ProvingTaskStatus::Pending => Self::Pending,
+            ProvingTaskStatus::ProvingInProgress => Self::ProvingInProgress,
+            ProvingTaskStatus::Completed => Self::Completed,
+            ProvingTaskStatus::Failed => Self::Failed,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use strata_primitives::proof::{ProofContext, ProofZkVm};
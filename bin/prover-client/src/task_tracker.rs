@@ -1,14 +1,51 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use strata_primitives::proof::{ProofContext, ProofKey, ProofZkVm};
+use strata_rpc_types::{RpcProvingTaskStatus, RpcTaskSummary};
 
 use crate::{errors::ProvingTaskError, status::ProvingTaskStatus};
 
+/// Bookkeeping about a task besides its status.
+#[derive(Debug, Clone)]
+struct TaskMeta {
+    /// When the task was created, in UNIX epoch millis.
+    created_at: u64,
+    /// When the status of the task last changed, in UNIX epoch millis.
+    updated_at: u64,
+    /// Number of times proving the task was started.
+    attempts: u32,
+}
+
+impl TaskMeta {
+    fn new() -> Self {
+        let now = now_millis();
+        Self {
+            created_at: now,
+            updated_at: now,
+            attempts: 0,
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_millis() as u64
+}
+
 /// Manages tasks and their states for proving operations.
 #[derive(Debug, Clone)]
 pub struct TaskTracker {
     /// A map of task IDs to their statuses.
     tasks: HashMap<ProofKey, ProvingTaskStatus>,
+    /// A map of task IDs to their bookkeeping.
+    meta: HashMap<ProofKey, TaskMeta>,
+    /// A map of task IDs to the IDs of the tasks they depend on.
+    depends_on: HashMap<ProofKey, Vec<ProofKey>>,
     /// Count of the tasks that are in progress
     in_progress_tasks: HashMap<ProofZkVm, usize>,
     /// List of ZkVm for which the task is created
@@ -37,6 +74,8 @@ impl TaskTracker {
 
         TaskTracker {
             tasks: HashMap::new(),
+            meta: HashMap::new(),
+            depends_on: HashMap::new(),
             in_progress_tasks: HashMap::new(),
             vms,
         }
@@ -66,8 +105,10 @@ impl TaskTracker {
 
     /// Inserts a new task with the given dependencies.
     ///
-    /// - If no dependencies are provided, the task is marked as `Pending`.
-    /// - If dependencies are provided, the task is marked as `WaitingForDependencies`.
+    /// - If no dependencies are provided, or all of them are completed, the task is marked as
+    ///   `Pending`.
+    /// - If any dependency has failed, the task is marked as `Failed` right away.
+    /// - Otherwise the task is marked as `WaitingForDependencies` on the uncompleted ones.
     ///
     /// Returns an error if the task already exists.
     pub fn insert_task(
@@ -79,19 +120,30 @@ impl TaskTracker {
             return Err(ProvingTaskError::TaskAlreadyFound(id));
         }
 
+        let mut waiting = HashSet::new();
+        let mut dep_failed = false;
         for dep in &deps {
-            if !self.tasks.contains_key(dep) {
-                return Err(ProvingTaskError::DependencyNotFound(*dep));
+            match self.tasks.get(dep) {
+                None => return Err(ProvingTaskError::DependencyNotFound(*dep)),
+                Some(ProvingTaskStatus::Completed) => {}
+                Some(ProvingTaskStatus::Failed) => dep_failed = true,
+                Some(_) => {
+                    waiting.insert(*dep);
+                }
             }
         }
 
-        let status = if deps.is_empty() {
+        let status = if dep_failed {
+            ProvingTaskStatus::Failed
+        } else if waiting.is_empty() {
             ProvingTaskStatus::Pending
         } else {
-            ProvingTaskStatus::WaitingForDependencies(HashSet::from_iter(deps))
+            ProvingTaskStatus::WaitingForDependencies(waiting)
         };
 
         self.tasks.insert(id, status);
+        self.meta.insert(id, TaskMeta::new());
+        self.depends_on.insert(id, deps);
 
         Ok(())
     }
@@ -105,10 +157,19 @@ impl TaskTracker {
             .ok_or(ProvingTaskError::TaskNotFound(id))
     }
 
+    /// Returns the IDs of the tasks the given task depends on.
+    pub fn get_task_deps(&self, id: ProofKey) -> Result<&[ProofKey], ProvingTaskError> {
+        self.depends_on
+            .get(&id)
+            .map(Vec::as_slice)
+            .ok_or(ProvingTaskError::TaskNotFound(id))
+    }
+
     /// Updates the status of a task.
     ///
     /// - Allows valid transitions as per the state machine.
     /// - Automatically resolves dependencies if a task is completed.
+    /// - Marks every task that (transitively) depends on a task as failed if it fails.
     ///
     /// Returns an error for invalid transitions or if the task does not exist.
     pub fn update_status(
@@ -117,12 +178,19 @@ impl TaskTracker {
         new_status: ProvingTaskStatus,
     ) -> Result<(), ProvingTaskError> {
         if let Some(status) = self.tasks.get_mut(&id) {
+            let was_in_progress = *status == ProvingTaskStatus::ProvingInProgress;
+
             // Check for valid status transitions
             status.transition(new_status.clone())?;
 
+            let now = now_millis();
+            let meta = self.meta.entry(id).or_insert_with(TaskMeta::new);
+            meta.updated_at = now;
+
             if new_status == ProvingTaskStatus::ProvingInProgress {
                 // Increment value if key exists, or insert with a default value of 1
                 *self.in_progress_tasks.entry(*id.host()).or_insert(0) += 1;
+                meta.attempts += 1;
             }
 
             if new_status == ProvingTaskStatus::Completed {
@@ -130,21 +198,60 @@ impl TaskTracker {
                 *self.in_progress_tasks.entry(*id.host()).or_insert(0) -= 1;
 
                 // Resolve dependencies if a task is completed
-                for task_status in self.tasks.values_mut() {
+                for (task_id, task_status) in self.tasks.iter_mut() {
                     if let ProvingTaskStatus::WaitingForDependencies(deps) = task_status {
                         deps.remove(&id);
                         if deps.is_empty() {
                             task_status.transition(ProvingTaskStatus::Pending)?;
+                            if let Some(meta) = self.meta.get_mut(task_id) {
+                                meta.updated_at = now;
+                            }
                         }
                     }
                 }
             }
+
+            if new_status == ProvingTaskStatus::Failed {
+                if was_in_progress {
+                    *self.in_progress_tasks.entry(*id.host()).or_insert(0) -= 1;
+                }
+                self.fail_dependents(id, now);
+            }
             Ok(())
         } else {
             Err(ProvingTaskError::TaskNotFound(id))
         }
     }
 
+    /// Marks all the tasks that are waiting on `id`, directly or through other tasks, as failed
+    /// since they can never be proven.
+    fn fail_dependents(&mut self, id: ProofKey, now: u64) {
+        let mut queue = vec![id];
+        while let Some(failed) = queue.pop() {
+            for (task_id, deps) in &self.depends_on {
+                if !deps.contains(&failed) {
+                    continue;
+                }
+
+                let Some(status) = self.tasks.get_mut(task_id) else {
+                    continue;
+                };
+                if !matches!(
+                    status,
+                    ProvingTaskStatus::WaitingForDependencies(_) | ProvingTaskStatus::Pending
+                ) {
+                    continue;
+                }
+
+                *status = ProvingTaskStatus::Failed;
+                if let Some(meta) = self.meta.get_mut(task_id) {
+                    meta.updated_at = now;
+                }
+                queue.push(*task_id);
+            }
+        }
+    }
+
     /// Filters and retrieves a list of `ProofKey` references for tasks whose status
     /// matches the given filter function.
     ///
@@ -170,6 +277,29 @@ impl TaskTracker {
             })
             .collect()
     }
+
+    /// Lists summaries of the tasks, optionally only the ones with the given status, ordered by
+    /// creation time.
+    pub fn list_tasks(&self, filter: Option<RpcProvingTaskStatus>) -> Vec<RpcTaskSummary> {
+        let mut tasks: Vec<_> = self
+            .tasks
+            .iter()
+            .map(|(key, status)| (key, RpcProvingTaskStatus::from(status)))
+            .filter(|(_, status)| filter.map_or(true, |f| f == *status))
+            .map(|(key, status)| {
+                let meta = self.meta.get(key).cloned().unwrap_or_else(TaskMeta::new);
+                RpcTaskSummary {
+                    key: *key,
+                    status,
+                    created_at: meta.created_at,
+                    updated_at: meta.updated_at,
+                    attempts: meta.attempts,
+                }
+            })
+            .collect();
+        tasks.sort_by_key(|t| t.created_at);
+        tasks
+    }
 }
 
 #[cfg(test)]
@@ -260,4 +390,162 @@ mod tests {
             "Task should become Pending after all dependencies are resolved"
         );
     }
+
+    #[test]
+    fn test_list_tasks_filtered() {
+        let mut tracker = TaskTracker::new();
+        let (id, deps) = gen_task_with_deps(3);
+        for dep in &deps {
+            tracker.insert_task(*dep, vec![]).unwrap();
+        }
+        tracker.insert_task(id, deps.clone()).unwrap();
+
+        // one dep completed, one failed after an attempt, one still pending
+        tracker
+            .update_status(deps[0], ProvingTaskStatus::ProvingInProgress)
+            .unwrap();
+        tracker
+            .update_status(deps[0], ProvingTaskStatus::Completed)
+            .unwrap();
+        tracker
+            .update_status(deps[1], ProvingTaskStatus::ProvingInProgress)
+            .unwrap();
+        tracker
+            .update_status(deps[1], ProvingTaskStatus::Failed)
+            .unwrap();
+
+        assert_eq!(tracker.list_tasks(None).len(), 4);
+
+        let keys = |status| -> HashSet<ProofKey> {
+            tracker
+                .list_tasks(Some(status))
+                .into_iter()
+                .map(|t| t.key)
+                .collect()
+        };
+        assert_eq!(
+            keys(RpcProvingTaskStatus::Completed),
+            HashSet::from([deps[0]])
+        );
+        // the failure cascades to the task depending on it
+        assert_eq!(
+            keys(RpcProvingTaskStatus::Failed),
+            HashSet::from([deps[1], id])
+        );
+        assert_eq!(
+            keys(RpcProvingTaskStatus::Pending),
+            HashSet::from([deps[2]])
+        );
+        assert!(keys(RpcProvingTaskStatus::WaitingForDependencies).is_empty());
+        assert!(keys(RpcProvingTaskStatus::ProvingInProgress).is_empty());
+
+        let failed = tracker
+            .list_tasks(Some(RpcProvingTaskStatus::Failed))
+            .into_iter()
+            .find(|t| t.key == deps[1])
+            .unwrap();
+        assert_eq!(failed.attempts, 1);
+        assert!(failed.updated_at >= failed.created_at);
+
+        let pending = &tracker.list_tasks(Some(RpcProvingTaskStatus::Pending))[0];
+        assert_eq!(pending.attempts, 0);
+    }
+
+    /// Builds the EL -> CL -> CL-agg proof DAG of two blocks, returning the tasks in insertion
+    /// order as `(el, cl, agg)`.
+    fn gen_proof_dag(tracker: &mut TaskTracker) -> (Vec<ProofKey>, Vec<ProofKey>, ProofKey) {
+        let host = ProofZkVm::Native;
+        let mut gen = ArbitraryGenerator::new();
+
+        let mut el = Vec::new();
+        let mut cl = Vec::new();
+        for _ in 0..2 {
+            let el_key =
+                ProofKey::new(ProofContext::EvmEeStf(gen.generate(), gen.generate()), host);
+            tracker.insert_task(el_key, vec![]).unwrap();
+            let cl_key = ProofKey::new(ProofContext::ClStf(gen.generate()), host);
+            tracker.insert_task(cl_key, vec![el_key]).unwrap();
+            el.push(el_key);
+            cl.push(cl_key);
+        }
+
+        let agg = ProofKey::new(ProofContext::ClAgg(gen.generate(), gen.generate()), host);
+        tracker.insert_task(agg, cl.clone()).unwrap();
+
+        (el, cl, agg)
+    }
+
+    #[test]
+    fn test_dag_execution_order() {
+        let mut tracker = TaskTracker::new();
+        let (el, cl, agg) = gen_proof_dag(&mut tracker);
+
+        // run the pending tasks in rounds, like the prover manager does
+        let mut order = Vec::new();
+        loop {
+            let pending =
+                tracker.get_tasks_by_status(|status| matches!(status, ProvingTaskStatus::Pending));
+            if pending.is_empty() {
+                break;
+            }
+            for task in pending {
+                for dep in tracker.get_task_deps(task).unwrap() {
+                    assert_eq!(
+                        tracker.get_task(*dep).unwrap(),
+                        &ProvingTaskStatus::Completed
+                    );
+                }
+                tracker
+                    .update_status(task, ProvingTaskStatus::ProvingInProgress)
+                    .unwrap();
+                tracker
+                    .update_status(task, ProvingTaskStatus::Completed)
+                    .unwrap();
+                order.push(task);
+            }
+        }
+
+        assert_eq!(order.len(), 5);
+        let pos = |key: &ProofKey| order.iter().position(|k| k == key).unwrap();
+        for i in 0..2 {
+            assert!(pos(&el[i]) < pos(&cl[i]));
+            assert!(pos(&cl[i]) < pos(&agg));
+        }
+        assert_eq!(order.last(), Some(&agg));
+    }
+
+    #[test]
+    fn test_dag_failure_propagation() {
+        let mut tracker = TaskTracker::new();
+        let (el, cl, agg) = gen_proof_dag(&mut tracker);
+
+        tracker
+            .update_status(el[0], ProvingTaskStatus::ProvingInProgress)
+            .unwrap();
+        tracker
+            .update_status(el[0], ProvingTaskStatus::Failed)
+            .unwrap();
+
+        assert_eq!(tracker.get_task(cl[0]).unwrap(), &ProvingTaskStatus::Failed);
+        assert_eq!(tracker.get_task(agg).unwrap(), &ProvingTaskStatus::Failed);
+        assert_eq!(
+            tracker.get_in_progress_tasks().get(&ProofZkVm::Native),
+            Some(&0)
+        );
+
+        // the other branch is unaffected
+        assert_eq!(
+            tracker.get_task(el[1]).unwrap(),
+            &ProvingTaskStatus::Pending
+        );
+        assert!(matches!(
+            tracker.get_task(cl[1]),
+            Ok(ProvingTaskStatus::WaitingForDependencies(_))
+        ));
+
+        // tasks created on top of a failed task fail right away
+        let (id, _) = gen_task_with_deps(0);
+        tracker.insert_task(id, vec![cl[0]]).unwrap();
+        assert_eq!(tracker.get_task(id).unwrap(), &ProvingTaskStatus::Failed);
+    }
 }
@@ -56,4 +56,16 @@ pub struct Args {
 
     #[argh(option, description = "database retry count")]
     pub db_retry_count: Option<u16>,
+
+    #[argh(
+        option,
+        description = "number of threads database ops run on, defaults to the number of CPUs"
+    )]
+    pub db_threads: Option<usize>,
+
+    #[argh(
+        option,
+        description = "L1 height to start scanning at, for snapshot imports or re-indexing, only for this run"
+    )]
+    pub l1_start_height: Option<u64>,
 }
@@ -1,9 +1,21 @@
 use std::{path::PathBuf, sync::Arc};
 
-use bitcoin::Network;
+use bitcoin::{Address, Network};
 use serde::Deserialize;
-use strata_btcio::reader::config::ReaderConfig;
-use strata_primitives::{params::Params, relay::types::RelayerConfig};
+use strata_btcio::{
+    poll::DEFAULT_POLL_JITTER_PCT,
+    reader::config::{ReaderConfig, DEFAULT_MAX_PENDING_SYNC_EVENTS},
+    rpc::DEFAULT_RPC_POOL_SIZE,
+    writer::config::{BlobBatchingConfig, InscriptionFeePolicy, SmartFeeBounds, WriterConfig},
+};
+use strata_consensus_logic::csm::metrics::DEFAULT_SLOW_EVENT_THRESHOLD_MS;
+use strata_primitives::{
+    params::{
+        Params, RollupParams, DEFAULT_CLIENT_STATE_RETENTION_INTERVAL, DEFAULT_EL_CHECK_TIMEOUT,
+        DEFAULT_MAX_BLOCK_SIZE, DEFAULT_MAX_BLOCK_TIME_DRIFT, DEFAULT_MAX_REORG_DEPTH,
+    },
+    relay::types::RelayerConfig,
+};
 
 use crate::args::Args;
 
@@ -37,6 +49,12 @@ pub struct ClientConfig {
     pub l2_blocks_fetch_limit: u64,
     pub datadir: PathBuf,
     pub db_retry_count: u16,
+    /// Size of the threadpool shared by database ops.
+    #[serde(default = "default_db_threads")]
+    pub db_threads: usize,
+    /// Enables RPCs meant for debugging, like dumping the full consensus state.
+    #[serde(default)]
+    pub enable_debug_rpc: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,7 +62,80 @@ pub struct SyncConfig {
     pub l1_follow_distance: u64,
     pub max_reorg_depth: u32,
     pub client_poll_dur_ms: u32,
+    /// How much the L1 reader's and writer's poll intervals are randomly
+    /// varied by, in percent.
+    #[serde(default = "default_client_poll_jitter_pct")]
+    pub client_poll_jitter_pct: u8,
     pub client_checkpoint_interval: u32,
+    #[serde(default)]
+    pub allow_force_finalize: bool,
+    #[serde(default = "default_max_block_size")]
+    pub max_block_size: usize,
+    /// Max number of L2 blocks fork choice will revert to switch to a new tip.
+    #[serde(default = "default_max_l2_reorg_depth")]
+    pub max_l2_reorg_depth: u64,
+    /// Interval of the client states kept once they're behind finality.
+    #[serde(default = "default_client_state_retention_interval")]
+    pub client_state_retention_interval: u64,
+    /// How far ahead of our clock a block's timestamp can be, in milliseconds.
+    #[serde(default = "default_max_block_time_drift_ms")]
+    pub max_block_time_drift_ms: u64,
+    /// How long the EL gets to check a block before it's left unverified, in
+    /// milliseconds.
+    #[serde(default = "default_el_check_timeout_ms")]
+    pub el_check_timeout_ms: u64,
+    /// Duration after which processing a sync event gets logged as slow, in
+    /// milliseconds.
+    #[serde(default = "default_slow_sync_event_ms")]
+    pub slow_sync_event_ms: u64,
+    /// Number of sync events the CSM can fall behind by before the L1 reader
+    /// pauses.
+    #[serde(default = "default_max_pending_sync_events")]
+    pub max_pending_sync_events: u64,
+    /// Check the stored L1 blocks link to each other before starting the
+    /// reader.
+    #[serde(default)]
+    pub verify_l1_chain_on_startup: bool,
+}
+
+fn default_db_threads() -> usize {
+    strata_storage::pool::default_db_threads()
+}
+
+fn default_client_poll_jitter_pct() -> u8 {
+    DEFAULT_POLL_JITTER_PCT
+}
+
+fn default_max_block_size() -> usize {
+    DEFAULT_MAX_BLOCK_SIZE
+}
+
+fn default_max_l2_reorg_depth() -> u64 {
+    DEFAULT_MAX_REORG_DEPTH
+}
+
+fn default_client_state_retention_interval() -> u64 {
+    DEFAULT_CLIENT_STATE_RETENTION_INTERVAL
+}
+
+fn default_max_block_time_drift_ms() -> u64 {
+    DEFAULT_MAX_BLOCK_TIME_DRIFT
+}
+
+fn default_el_check_timeout_ms() -> u64 {
+    DEFAULT_EL_CHECK_TIMEOUT
+}
+
+fn default_slow_sync_event_ms() -> u64 {
+    DEFAULT_SLOW_EVENT_THRESHOLD_MS
+}
+
+fn default_max_pending_sync_events() -> u64 {
+    DEFAULT_MAX_PENDING_SYNC_EVENTS
+}
+
+fn default_rpc_pool_size() -> usize {
+    DEFAULT_RPC_POOL_SIZE
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,6 +144,10 @@ pub struct BitcoindConfig {
     pub rpc_user: String,
     pub rpc_password: String,
     pub network: Network,
+
+    /// Most connections to bitcoind kept open and reused across RPC calls.
+    #[serde(default = "default_rpc_pool_size")]
+    pub rpc_pool_size: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -66,6 +161,60 @@ pub struct ExecConfig {
     pub reth: RethELConfig,
 }
 
+/// Fee rate to pay for inscription txns, either a fixed one or one estimated by bitcoind.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FeeRateConfig {
+    /// Fixed fee rate, in sat/vB.
+    Fixed(u64),
+
+    /// Fee rate estimated by bitcoind, kept within bounds, all in sat/vB.
+    Smart { min: u64, max: u64, fallback: u64 },
+}
+
+impl FeeRateConfig {
+    fn to_fee_policy(&self) -> anyhow::Result<InscriptionFeePolicy> {
+        Ok(match *self {
+            FeeRateConfig::Fixed(fee_rate) => InscriptionFeePolicy::Fixed(fee_rate),
+            FeeRateConfig::Smart { min, max, fallback } => {
+                InscriptionFeePolicy::Smart(SmartFeeBounds::new(min, max, fallback)?)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BtcioWriterConfig {
+    /// Fee rate of the inscription commit txns, and of the reveal txns unless `reveal_fee_rate`
+    /// is set.  Estimated by bitcoind within default bounds if unset.
+    pub commit_fee_rate: Option<FeeRateConfig>,
+
+    /// Fee rate of the inscription reveal txns, if different from the commit txns'.
+    pub reveal_fee_rate: Option<FeeRateConfig>,
+
+    /// Inscribes pending blobs together in the same reveal txn, if set and the rollup params
+    /// allow it.
+    pub batching: Option<BatchingConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchingConfig {
+    /// Most blobs to inscribe in a single reveal txn.
+    pub max_batch_blobs: usize,
+
+    /// Most bytes of blob data to inscribe in a single reveal txn, has to fit in a standard one.
+    pub max_batch_bytes: usize,
+
+    /// Longest a blob is held back waiting for others to batch with, in milliseconds.
+    pub max_wait_ms: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BtcioConfig {
+    #[serde(default)]
+    pub writer: BtcioWriterConfig,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub client: ClientConfig,
@@ -73,6 +222,8 @@ pub struct Config {
     pub sync: SyncConfig,
     pub exec: ExecConfig,
     pub relayer: RelayerConfig,
+    #[serde(default)]
+    pub btcio: BtcioConfig,
 }
 
 impl Config {
@@ -92,6 +243,7 @@ impl Config {
                 network: args
                     .network
                     .ok_or_else(|| "args: no bitcoin --network provided".to_string())?,
+                rpc_pool_size: DEFAULT_RPC_POOL_SIZE,
             },
             client: ClientConfig {
                 rpc_host: args
@@ -120,12 +272,24 @@ impl Config {
                 },
                 l2_blocks_fetch_limit: 1_000,
                 db_retry_count: 5,
+                db_threads: args.db_threads.unwrap_or_else(default_db_threads),
+                enable_debug_rpc: false,
             },
             sync: SyncConfig {
                 l1_follow_distance: 6,
                 max_reorg_depth: 4,
                 client_poll_dur_ms: 200,
+                client_poll_jitter_pct: DEFAULT_POLL_JITTER_PCT,
                 client_checkpoint_interval: 10,
+                allow_force_finalize: false,
+                max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+                max_l2_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+                client_state_retention_interval: DEFAULT_CLIENT_STATE_RETENTION_INTERVAL,
+                max_block_time_drift_ms: DEFAULT_MAX_BLOCK_TIME_DRIFT,
+                el_check_timeout_ms: DEFAULT_EL_CHECK_TIMEOUT,
+                slow_sync_event_ms: DEFAULT_SLOW_EVENT_THRESHOLD_MS,
+                max_pending_sync_events: DEFAULT_MAX_PENDING_SYNC_EVENTS,
+                verify_l1_chain_on_startup: false,
             },
             exec: ExecConfig {
                 reth: RethELConfig {
@@ -140,6 +304,7 @@ impl Config {
                 stale_duration: 120,
                 relay_misc: true,
             },
+            btcio: BtcioConfig::default(),
         })
     }
 
@@ -183,6 +348,9 @@ impl Config {
         if let Some(db_retry_count) = args.db_retry_count {
             self.client.db_retry_count = db_retry_count;
         }
+        if let Some(db_threads) = args.db_threads {
+            self.client.db_threads = db_threads;
+        }
     }
 
     pub fn get_reader_config(&self, params: Arc<Params>) -> ReaderConfig {
@@ -191,12 +359,40 @@ impl Config {
             self.sync.client_poll_dur_ms,
             params,
         )
+        .with_poll_jitter_pct(self.sync.client_poll_jitter_pct)
+        .with_max_pending_events(self.sync.max_pending_sync_events)
+    }
+
+    pub fn get_writer_config(
+        &self,
+        sequencer_address: Address,
+        rollup_params: &RollupParams,
+    ) -> anyhow::Result<WriterConfig> {
+        let writer = &self.btcio.writer;
+        let mut writer_config = WriterConfig::new(sequencer_address, rollup_params)?
+            .with_poll_jitter_pct(self.sync.client_poll_jitter_pct);
+        if let Some(fee_rate) = &writer.commit_fee_rate {
+            writer_config = writer_config.with_inscription_fee_policy(fee_rate.to_fee_policy()?);
+        }
+        if let Some(fee_rate) = &writer.reveal_fee_rate {
+            writer_config = writer_config.with_reveal_fee_policy(fee_rate.to_fee_policy()?);
+        }
+        if let Some(batching) = &writer.batching {
+            writer_config = writer_config.with_batching(BlobBatchingConfig::new(
+                batching.max_batch_blobs,
+                batching.max_batch_bytes,
+                batching.max_wait_ms,
+            )?);
+        }
+        Ok(writer_config)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::config::Config;
+    use strata_btcio::poll::DEFAULT_POLL_JITTER_PCT;
+
+    use crate::config::{Config, FeeRateConfig};
 
     #[test]
     fn test_config_load() {
@@ -239,6 +435,10 @@ mod test {
             "should be able to load sequencer TOML config but got: {:?}",
             config.err()
         );
+        assert_eq!(
+            config.unwrap().sync.client_poll_jitter_pct,
+            DEFAULT_POLL_JITTER_PCT
+        );
 
         let config_string_fullnode = r#"
             [bitcoind_rpc]
@@ -280,4 +480,74 @@ mod test {
             config.err()
         );
     }
+
+    #[test]
+    fn test_writer_config_load() {
+        let config_string = r#"
+            [bitcoind_rpc]
+            rpc_url = "http://localhost:18332"
+            rpc_user = "alpen"
+            rpc_password = "alpen"
+            network = "regtest"
+
+            [client]
+            rpc_host = "0.0.0.0"
+            rpc_port = 8432
+            l2_blocks_fetch_limit = 1000
+            datadir = "/path/to/data/directory"
+            sequencer_key = "/path/to/sequencer_key"
+            db_retry_count = 5
+
+            [sync]
+            l1_follow_distance = 6
+            max_reorg_depth = 4
+            client_poll_dur_ms = 200
+            client_checkpoint_interval = 10
+
+            [exec.reth]
+            rpc_url = "http://localhost:8551"
+            secret = "1234567890abcdef"
+
+            [relayer]
+            refresh_interval = 10
+            stale_duration = 120
+            relay_misc = true
+
+            [btcio.writer]
+            commit_fee_rate = { min = 2, max = 100, fallback = 10 }
+            reveal_fee_rate = 5
+
+            [btcio.writer.batching]
+            max_batch_blobs = 8
+            max_batch_bytes = 300000
+            max_wait_ms = 60000
+        "#;
+
+        let config = toml::from_str::<Config>(config_string).unwrap();
+        let writer = &config.btcio.writer;
+        assert!(matches!(
+            writer.commit_fee_rate,
+            Some(FeeRateConfig::Smart {
+                min: 2,
+                max: 100,
+                fallback: 10
+            })
+        ));
+        assert!(matches!(
+            writer.reveal_fee_rate,
+            Some(FeeRateConfig::Fixed(5))
+        ));
+        let batching = writer.batching.as_ref().unwrap();
+        assert_eq!(batching.max_batch_blobs, 8);
+        assert_eq!(batching.max_batch_bytes, 300_000);
+        assert_eq!(batching.max_wait_ms, 60_000);
+
+        // inverted bounds are only caught when building the policy
+        let bad_bounds = FeeRateConfig::Smart {
+            min: 100,
+            max: 2,
+            fallback: 10,
+        };
+        assert!(bad_bounds.to_fee_policy().is_err());
+    }
 }
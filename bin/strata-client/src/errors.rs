@@ -19,6 +19,9 @@ pub enum InitError {
     #[error("params: {0}")]
     MalformedParams(#[from] ParamsError),
 
+    #[error("failed to bind {addr}: {source}")]
+    RpcBind { addr: String, source: io::Error },
+
     #[error("{0}")]
     Anyhow(#[from] anyhow::Error),
 }
@@ -4,6 +4,7 @@ use alloy_rpc_types::engine::JwtSecret;
 use anyhow::Context;
 use bitcoin::{base58, bip32::Xpriv, Address, Network};
 use format_serde_error::SerdeError;
+use jsonrpsee::server::{Server, ServerBuilder};
 use rockbound::{rocksdb, OptimisticTransactionDB};
 use strata_btcio::rpc::{traits::Wallet, BitcoinClient};
 use strata_consensus_logic::{
@@ -17,11 +18,12 @@ use strata_primitives::{
     buf::Buf32,
     keys::ZeroizableXpriv,
     l1::L1Status,
-    params::{Params, RollupParams},
+    params::{Params, RollupParams, SyncParams},
 };
 use strata_rocksdb::{
-    broadcaster::db::BroadcastDb, l2::db::L2Db, sequencer::db::SequencerDB, ChainstateDb,
-    ClientStateDb, DbOpsConfig, L1BroadcastDb, L1Db, RBCheckpointDB, RBSeqBlobDb, SyncEventDb,
+    broadcaster::db::BroadcastDb, l2::db::L2Db, sequencer::db::SequencerDB, AtomicBatchDb,
+    ChainstateDb, ClientStateDb, DbOpsConfig, L1BroadcastDb, L1Db, RBCheckpointDB, RBSeqBlobDb,
+    SyncEventDb,
 };
 use strata_state::csm_status::CsmStatus;
 use strata_status::StatusChannel;
@@ -32,17 +34,31 @@ use zeroize::Zeroize;
 
 use crate::{args::Args, config::Config, errors::InitError, network};
 
-pub type CommonDb =
-    CommonDatabase<L1Db, L2Db, SyncEventDb, ClientStateDb, ChainstateDb, RBCheckpointDB>;
-
-pub fn init_core_dbs(rbdb: Arc<OptimisticTransactionDB>, ops_config: DbOpsConfig) -> Arc<CommonDb> {
+pub type CommonDb = CommonDatabase<
+    L1Db,
+    L2Db,
+    SyncEventDb,
+    ClientStateDb,
+    ChainstateDb,
+    RBCheckpointDB,
+    AtomicBatchDb,
+>;
+
+pub fn init_core_dbs(
+    rbdb: Arc<OptimisticTransactionDB>,
+    ops_config: DbOpsConfig,
+    sync_params: &SyncParams,
+) -> Arc<CommonDb> {
     // Initialize databases.
     let l1_db: Arc<_> = L1Db::new(rbdb.clone(), ops_config).into();
-    let l2_db: Arc<_> = L2Db::new(rbdb.clone(), ops_config).into();
+    let l2_db: Arc<_> = L2Db::new(rbdb.clone(), ops_config)
+        .with_max_block_size(sync_params.max_block_size)
+        .into();
     let sync_ev_db: Arc<_> = strata_rocksdb::SyncEventDb::new(rbdb.clone(), ops_config).into();
     let clientstate_db: Arc<_> = ClientStateDb::new(rbdb.clone(), ops_config).into();
     let chainstate_db: Arc<_> = ChainstateDb::new(rbdb.clone(), ops_config).into();
     let checkpoint_db: Arc<_> = RBCheckpointDB::new(rbdb.clone(), ops_config).into();
+    let batch_db: Arc<_> = AtomicBatchDb::new(rbdb.clone()).into();
     let database = CommonDatabase::new(
         l1_db,
         l2_db,
@@ -50,6 +66,7 @@ pub fn init_core_dbs(rbdb: Arc<OptimisticTransactionDB>, ops_config: DbOpsConfig
         clientstate_db,
         chainstate_db,
         checkpoint_db,
+        batch_db,
     );
 
     database.into()
@@ -132,15 +149,20 @@ fn load_rollup_params(path: &Path) -> Result<RollupParams, InitError> {
     Ok(rollup_params)
 }
 
-pub fn create_bitcoin_rpc_client(config: &Config) -> anyhow::Result<Arc<BitcoinClient>> {
+pub fn create_bitcoin_rpc_client(
+    config: &Config,
+    max_retries: u8,
+) -> anyhow::Result<Arc<BitcoinClient>> {
     // Set up Bitcoin client RPC.
     let bitcoind_url = format!("http://{}", config.bitcoind_rpc.rpc_url);
-    let btc_rpc = BitcoinClient::new(
+    let btc_rpc = BitcoinClient::new_with_pool_size(
         bitcoind_url,
         config.bitcoind_rpc.rpc_user.clone(),
         config.bitcoind_rpc.rpc_password.clone(),
+        config.bitcoind_rpc.rpc_pool_size,
     )
-    .map_err(anyhow::Error::from)?;
+    .map_err(anyhow::Error::from)?
+    .with_max_retries(max_retries);
 
     // TODO remove this
     if config.bitcoind_rpc.network != Network::Regtest {
@@ -277,3 +299,39 @@ pub async fn generate_sequencer_address(
         }
     })
 }
+
+/// Builds the RPC server listening on the given host and port.
+pub async fn build_rpc_server(host: &str, port: u16) -> Result<Server, InitError> {
+    let addr = format!("{host}:{port}");
+    ServerBuilder::new()
+        .build(addr.as_str())
+        .await
+        .map_err(|source| InitError::RpcBind { addr, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build_rpc_server_port_in_use() {
+        let server = build_rpc_server("127.0.0.1", 0).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let err = build_rpc_server("127.0.0.1", port)
+            .await
+            .err()
+            .expect("port should be in use");
+        assert!(matches!(err, InitError::RpcBind { .. }));
+
+        let msg = err.to_string();
+        assert!(
+            msg.starts_with(&format!("failed to bind 127.0.0.1:{port}: ")),
+            "unexpected error: {msg}"
+        );
+        assert!(
+            msg.to_lowercase().contains("address already in use"),
+            "{msg}"
+        );
+    }
+}
@@ -1,16 +1,30 @@
 use std::sync::Arc;
 
-use strata_btcio::{reader::query::bitcoin_data_reader_task, rpc::traits::Reader};
+use strata_btcio::{
+    reader::{
+        integrity::{resolve_start_height, verify_l1_chain_integrity},
+        progress::ReaderProgressTracker,
+        query::bitcoin_data_reader_task,
+    },
+    rpc::traits::Reader,
+};
 use strata_consensus_logic::{csm::ctl::CsmController, l1_handler::bitcoin_data_handler_task};
 use strata_db::traits::{Database, L1Database};
 use strata_primitives::params::Params;
 use strata_status::StatusChannel;
 use strata_tasks::TaskExecutor;
-use strata_tx_parser::messages::L1Event;
+use strata_tx_parser::{filter::default_tx_filters, messages::L1Event};
 use tokio::sync::mpsc;
+use tracing::*;
 
 use crate::config::Config;
 
+/// Starts the L1 reader and the task handling what it reads, returning the tracker the reader
+/// publishes its progress on.
+///
+/// The reader starts at `start_override` if set.  If that's within the stored chain, the stored
+/// blocks from there on are rolled back first so they're taken as new ones when rescanned.
+#[allow(clippy::too_many_arguments)]
 pub fn start_reader_tasks<D>(
     executor: &TaskExecutor,
     params: Arc<Params>,
@@ -19,7 +33,8 @@ pub fn start_reader_tasks<D>(
     db: Arc<D>,
     csm_ctl: Arc<CsmController>,
     status_channel: StatusChannel,
-) -> anyhow::Result<()>
+    start_override: Option<u64>,
+) -> anyhow::Result<Arc<ReaderProgressTracker>>
 where
     D: Database + Send + Sync + 'static,
 {
@@ -28,10 +43,32 @@ where
     // TODO switch to checking the L1 tip in the consensus/client state
     let l1_db = db.l1_db().clone();
     let horz_height = params.rollup().horizon_l1_height;
-    let target_next_block = l1_db.get_chain_tip()?.map(|i| i + 1).unwrap_or(horz_height);
+    let chain_tip = l1_db.get_chain_tip()?;
+    let buried_height = status_channel.l1_view().buried_l1_height();
+    if let Some(start_height) = start_override {
+        info!(%start_height, ?chain_tip, "overriding L1 scan start height");
+    }
+    let target_next_block =
+        resolve_start_height(l1_db.as_ref(), horz_height, buried_height, start_override)?;
     assert!(target_next_block >= horz_height);
 
+    if let Some(tip) = chain_tip.filter(|_| config.sync.verify_l1_chain_on_startup) {
+        info!(%horz_height, %tip, "verifying stored L1 chain");
+        verify_l1_chain_integrity(l1_db.as_ref(), horz_height, tip)?;
+    }
+
+    // The handler goes through the events in order, so the rollback is done before the reader
+    // sends anything.  The channel was just made, so there's room for it.
+    if let Some(tip) = chain_tip.filter(|tip| *tip >= target_next_block) {
+        let revert_to = target_next_block - 1;
+        info!(%tip, %revert_to, "rolling back stored L1 blocks to rescan them");
+        ev_tx
+            .try_send(L1Event::RevertTo(revert_to))
+            .map_err(|e| anyhow::anyhow!("queueing L1 rollback: {e}"))?;
+    }
+
     let reader_config = Arc::new(config.get_reader_config(params.clone()));
+    let progress = Arc::new(ReaderProgressTracker::new());
 
     executor.spawn_critical_async(
         "bitcoin_data_reader_task",
@@ -41,6 +78,8 @@ where
             target_next_block,
             reader_config,
             status_channel,
+            default_tx_filters(),
+            progress.clone(),
         ),
     );
 
@@ -50,5 +89,5 @@ where
     executor.spawn_critical("bitcoin_data_handler_task", move |_| {
         bitcoin_data_handler_task::<D>(l1db, csm_ctl, ev_rx, params)
     });
-    Ok(())
+    Ok(progress)
 }
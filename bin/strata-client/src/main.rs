@@ -3,12 +3,18 @@ use std::{str::FromStr, sync::Arc, time::Duration};
 use bitcoin::{hashes::Hash, Address, BlockHash};
 use config::{ClientMode, Config, SequencerConfig};
 use jsonrpsee::Methods;
+use rockbound::OptimisticTransactionDB;
 use rpc_client::sync_client;
 use strata_bridge_relay::relayer::RelayerHandle;
 use strata_btcio::{
     broadcaster::{spawn_broadcaster_task, L1BroadcastHandle},
-    rpc::{traits::Reader, BitcoinClient},
-    writer::{config::WriterConfig, start_inscription_task},
+    reader::progress::ReaderProgressTracker,
+    rpc::{
+        retry::{RetryConfig, RetryingL1Client},
+        traits::Reader,
+        BitcoinClient, DEFAULT_MAX_RETRIES,
+    },
+    writer::start_inscription_task,
 };
 use strata_common::logging;
 use strata_consensus_logic::{
@@ -85,16 +91,23 @@ fn main_inner(args: Args) -> anyhow::Result<()> {
     // Set up block params.
     let rparams = resolve_and_validate_rollup_params(args.rollup_params.as_deref())
         .map_err(anyhow::Error::from)?;
-    let params: Arc<_> = Params {
+    let params = Params {
         rollup: rparams,
         run: SyncParams {
             // FIXME these shouldn't be configurable here
             l1_follow_distance: config.sync.l1_follow_distance,
             client_checkpoint_interval: config.sync.client_checkpoint_interval,
             l2_blocks_fetch_limit: config.client.l2_blocks_fetch_limit,
+            allow_force_finalize: config.sync.allow_force_finalize,
+            max_block_size: config.sync.max_block_size,
+            max_reorg_depth: config.sync.max_l2_reorg_depth,
+            client_state_retention_interval: config.sync.client_state_retention_interval,
+            max_block_time_drift: config.sync.max_block_time_drift_ms,
+            el_check_timeout: config.sync.el_check_timeout_ms,
         },
-    }
-    .into();
+    };
+    params.validate()?;
+    let params = Arc::new(params);
 
     let mut methods = jsonrpsee::Methods::new();
 
@@ -103,11 +116,13 @@ fn main_inner(args: Args) -> anyhow::Result<()> {
     let ops_config = DbOpsConfig::new(config.client.db_retry_count);
 
     // initialize core databases
-    let database = init_core_dbs(rbdb.clone(), ops_config);
+    let database = init_core_dbs(rbdb.clone(), ops_config, params.run());
 
     // Init thread pool for batch jobs.
-    // TODO switch to num_cpus
-    let pool = threadpool::ThreadPool::with_name("strata-pool".to_owned(), 8);
+    if config.client.db_threads == 0 {
+        anyhow::bail!("config: db_threads must be nonzero");
+    }
+    let pool = strata_storage::pool::create_db_pool(config.client.db_threads);
 
     let task_manager = TaskManager::new(runtime.handle().clone());
     let executor = task_manager.executor();
@@ -121,7 +136,7 @@ fn main_inner(args: Args) -> anyhow::Result<()> {
     let checkpoint_manager: Arc<_> =
         CheckpointDbManager::new(pool.clone(), database.clone()).into();
     let checkpoint_handle: Arc<_> = CheckpointHandle::new(checkpoint_manager.clone()).into();
-    let bitcoin_client = create_bitcoin_rpc_client(&config)?;
+    let bitcoin_client = create_bitcoin_rpc_client(&config, DEFAULT_MAX_RETRIES)?;
 
     let l2_block_manager = Arc::new(L2BlockManager::new(pool.clone(), database.clone()));
 
@@ -144,6 +159,7 @@ fn main_inner(args: Args) -> anyhow::Result<()> {
         checkpoint_manager,
         bridge_msg_ops,
         bitcoin_client,
+        args.l1_start_height,
     )?;
 
     match &config.client.client_mode {
@@ -202,6 +218,7 @@ fn main_inner(args: Args) -> anyhow::Result<()> {
             task_manager.shutdown_signal(),
             config,
             checkpoint_handle,
+            rbdb,
             methods,
         ),
     );
@@ -247,6 +264,7 @@ pub struct CoreContext {
     pub engine: Arc<RpcExecEngineCtl<EngineRpcClient>>,
     pub relayer_handle: Arc<RelayerHandle>,
     pub bitcoin_client: Arc<BitcoinClient>,
+    pub reader_progress: Arc<ReaderProgressTracker>,
 }
 
 fn do_startup_checks(
@@ -320,6 +338,7 @@ fn start_core_tasks(
     checkpoint_manager: Arc<CheckpointDbManager>,
     bridge_msg_ops: Arc<BridgeMsgOps>,
     bitcoin_client: Arc<BitcoinClient>,
+    l1_start_height: Option<u64>,
 ) -> anyhow::Result<CoreContext> {
     // init status tasks
     let status_channel = init_status_channel(database.as_ref())?;
@@ -351,11 +370,12 @@ fn start_core_tasks(
         params.clone(),
         status_channel.clone(),
         checkpoint_manager,
+        Duration::from_millis(config.sync.slow_sync_event_ms),
     )?
     .into();
 
     // Start the L1 tasks to get that going.
-    l1_reader::start_reader_tasks(
+    let reader_progress = l1_reader::start_reader_tasks(
         executor,
         sync_manager.get_params(),
         config,
@@ -363,6 +383,7 @@ fn start_core_tasks(
         database.clone(),
         sync_manager.get_csm_ctl(),
         status_channel.clone(),
+        l1_start_height,
     )?;
 
     // Start relayer task.
@@ -383,6 +404,7 @@ fn start_core_tasks(
         engine,
         relayer_handle,
         bitcoin_client,
+        reader_progress,
     })
 }
 
@@ -429,15 +451,19 @@ fn start_sequencer_tasks(
     };
 
     // Spawn up writer
-    let writer_config = WriterConfig::new(
-        sequencer_bitcoin_address,
-        params.rollup().rollup_name.clone(),
-    )?;
+    let writer_config = config.get_writer_config(sequencer_bitcoin_address, params.rollup())?;
+
+    // The writer talks to the node a lot, don't let a blip fail a whole step of it.  The wrapper
+    // does the retrying, so the client under it doesn't retry on its own.
+    let writer_client = Arc::new(RetryingL1Client::new(
+        create_bitcoin_rpc_client(config, 0)?,
+        RetryConfig::default(),
+    ));
 
     // Start inscription tasks
     let inscription_handle = start_inscription_task(
         executor,
-        bitcoin_client,
+        writer_client,
         writer_config,
         seq_db,
         status_channel.clone(),
@@ -512,6 +538,7 @@ async fn start_rpc(
     shutdown_signal: ShutdownSignal,
     config: Config,
     checkpoint_handle: Arc<CheckpointHandle>,
+    rbdb: Arc<OptimisticTransactionDB>,
     mut methods: Methods,
 ) -> anyhow::Result<()> {
     let CoreContext {
@@ -520,6 +547,7 @@ async fn start_rpc(
         l2_block_manager,
         status_channel,
         relayer_handle,
+        reader_progress,
         ..
     } = ctx;
 
@@ -528,24 +556,23 @@ async fn start_rpc(
     // Init RPC impls.
     let strata_rpc = rpc_server::StrataRpcImpl::new(
         status_channel.clone(),
-        database,
-        sync_manager,
+        database.clone(),
+        sync_manager.clone(),
         l2_block_manager,
         checkpoint_handle,
         relayer_handle,
+        reader_progress,
+        config.client.enable_debug_rpc,
     );
     methods.merge(strata_rpc.into_rpc())?;
 
-    let admin_rpc = rpc_server::AdminServerImpl::new(stop_tx);
+    let admin_rpc = rpc_server::AdminServerImpl::new(stop_tx, rbdb, database, sync_manager);
     methods.merge(admin_rpc.into_rpc())?;
 
     let rpc_host = config.client.rpc_host;
     let rpc_port = config.client.rpc_port;
 
-    let rpc_server = jsonrpsee::server::ServerBuilder::new()
-        .build(format!("{rpc_host}:{rpc_port}"))
-        .await
-        .expect("init: build rpc server");
+    let rpc_server = build_rpc_server(&rpc_host, rpc_port).await?;
 
     let rpc_handle = rpc_server.start(methods);
 
@@ -41,6 +41,9 @@ fn get_deprecated_fallback() -> RollupParams {
     // TODO: load default params from a json during compile time
     RollupParams {
         rollup_name: "strata".to_string(),
+        inscription_tag: None,
+        deposit_magic_bytes: None,
+        batched_inscriptions: false,
         block_time: 1000,
         cred_rule: CredRule::Unchecked,
         horizon_l1_height: 3,
@@ -58,6 +61,7 @@ fn get_deprecated_fallback() -> RollupParams {
         target_l2_batch_size: 64,
         address_length: 20,
         deposit_amount: 1_000_000_000,
+        federation_script: None,
         rollup_vk: RollupVerifyingKey::SP1VerifyingKey(
             "0x00b01ae596b4e51843484ff71ccbd0dd1a030af70b255e6b9aad50b81d81266f"
                 .parse()
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use bitcoin::{
@@ -6,30 +6,40 @@ use bitcoin::{
     hashes::Hash,
     key::Parity,
     secp256k1::{PublicKey, XOnlyPublicKey},
-    Transaction as BTransaction, Txid,
+    FeeRate, Transaction as BTransaction, Txid,
 };
 use futures::TryFutureExt;
 use jsonrpsee::core::RpcResult;
+use rockbound::OptimisticTransactionDB;
 use strata_bridge_relay::relayer::RelayerHandle;
-use strata_btcio::{broadcaster::L1BroadcastHandle, writer::InscriptionHandle};
+use strata_btcio::{
+    broadcaster::L1BroadcastHandle,
+    reader::progress::ReaderProgressTracker,
+    writer::{builder::estimate_inscription_cost, InscriptionHandle},
+};
 use strata_consensus_logic::{
-    checkpoint::CheckpointHandle, l1_handler::verify_proof, sync_manager::SyncManager,
+    checkpoint::CheckpointHandle,
+    csm::{client_transition, replay::replay_event},
+    l1_handler::verify_proof,
+    sync_manager::SyncManager,
 };
 use strata_db::{
     traits::*,
-    types::{CheckpointProvingStatus, L1TxEntry, L1TxStatus},
+    types::{BlobL1Status, CheckpointProvingStatus, L1TxEntry, L1TxStatus},
 };
 use strata_primitives::{
     bridge::{OperatorIdx, PublickeyTable},
     buf::Buf32,
-    hash,
     params::Params,
 };
+use strata_rocksdb::{maintenance, stats::DbStats, STORE_COLUMN_FAMILIES};
 use strata_rpc_api::{StrataAdminApiServer, StrataApiServer, StrataSequencerApiServer};
 use strata_rpc_types::{
-    errors::RpcServerError as Error, DaBlob, HexBytes, HexBytes32, L2BlockStatus, RpcBlockHeader,
-    RpcBridgeDuties, RpcCheckpointInfo, RpcClientStatus, RpcDepositEntry, RpcExecUpdate,
-    RpcL1Status, RpcSyncStatus,
+    errors::RpcServerError as Error, ConsensusStateSummary, DaBlob, HexBytes, HexBytes32,
+    L2BlockStatus, RpcBlockHeader, RpcBridgeDuties, RpcCheckpointInfo, RpcClientStatus,
+    RpcDbCfStats, RpcDepositEntry, RpcExecUpdate, RpcInscriptionCost, RpcL1BlockManifest,
+    RpcL1Status, RpcPendingBlock, RpcStaleBlob, RpcSyncEventStats, RpcSyncStatus,
+    RpcUnfinalizedTree,
 };
 use strata_rpc_utils::to_jsonrpsee_error;
 use strata_state::{
@@ -37,6 +47,7 @@ use strata_state::{
     block::L2BlockBundle,
     bridge_duties::BridgeDuty,
     bridge_ops::WithdrawalIntent,
+    client_state::ClientState,
     da_blob::{BlobDest, BlobIntent},
     header::L2Header,
     id::L2BlockId,
@@ -62,6 +73,20 @@ fn fetch_l2blk<D: Database + Sync + Send + 'static>(
         .ok_or(Error::MissingL2Block(blkid))
 }
 
+/// Fetches the manifest stored for the L1 block at the height, if we have one.
+fn fetch_l1_manifest(
+    l1_db: &impl L1Database,
+    height: u64,
+) -> Result<Option<RpcL1BlockManifest>, Error> {
+    let Some(mf) = l1_db.get_block_manifest(height)? else {
+        return Ok(None);
+    };
+
+    RpcL1BlockManifest::from_manifest(height, &mf)
+        .map(Some)
+        .map_err(|e| Error::Other(format!("invalid L1 block manifest {height}: {e}")))
+}
+
 pub struct StrataRpcImpl<D> {
     status_channel: StatusChannel,
     database: Arc<D>,
@@ -69,6 +94,8 @@ pub struct StrataRpcImpl<D> {
     l2_block_manager: Arc<L2BlockManager>,
     checkpoint_handle: Arc<CheckpointHandle>,
     relayer_handle: Arc<RelayerHandle>,
+    reader_progress: Arc<ReaderProgressTracker>,
+    enable_debug_rpc: bool,
 }
 
 impl<D: Database + Sync + Send + 'static> StrataRpcImpl<D> {
@@ -80,6 +107,8 @@ impl<D: Database + Sync + Send + 'static> StrataRpcImpl<D> {
         l2_block_manager: Arc<L2BlockManager>,
         checkpoint_handle: Arc<CheckpointHandle>,
         relayer_handle: Arc<RelayerHandle>,
+        reader_progress: Arc<ReaderProgressTracker>,
+        enable_debug_rpc: bool,
     ) -> Self {
         Self {
             status_channel,
@@ -88,8 +117,29 @@ impl<D: Database + Sync + Send + 'static> StrataRpcImpl<D> {
             l2_block_manager,
             checkpoint_handle,
             relayer_handle,
+            reader_progress,
+            enable_debug_rpc,
         }
     }
+
+    /// Gets the client state after the sync event at `idx`, reconstructing it
+    /// if it isn't one of the recently computed ones.
+    async fn fetch_client_state(&self, idx: u64) -> Result<Arc<ClientState>, Error> {
+        let db = self.database.clone();
+        let state_cache = self.sync_manager.get_client_state_cache();
+
+        wait_blocking("fetch_client_state", move || {
+            let client_state_db = db.client_state_db();
+            if idx > client_state_db.get_last_write_idx()? {
+                return Err(Error::MissingClientState(idx));
+            }
+
+            state_cache
+                .get_or_reconstruct(client_state_db.as_ref(), idx)
+                .map_err(|e| Error::Other(e.to_string()))
+        })
+        .await
+    }
 }
 
 fn conv_blk_header_to_rpc(blk_header: &impl L2Header) -> RpcBlockHeader {
@@ -141,6 +191,14 @@ impl<D: Database + Send + Sync + 'static> StrataApiServer for StrataRpcImpl<D> {
         }
     }
 
+    async fn get_l1_block_manifest(&self, height: u64) -> RpcResult<Option<RpcL1BlockManifest>> {
+        let db = self.database.clone();
+        Ok(wait_blocking("l1_block_manifest", move || {
+            fetch_l1_manifest(db.l1_db().as_ref(), height)
+        })
+        .await?)
+    }
+
     async fn get_client_status(&self) -> RpcResult<RpcClientStatus> {
         let sync_state = self.status_channel.sync_state();
         let l1_view = self.status_channel.l1_view();
@@ -363,11 +421,14 @@ impl<D: Database + Send + Sync + 'static> StrataApiServer for StrataRpcImpl<D> {
 
     async fn sync_status(&self) -> RpcResult<RpcSyncStatus> {
         let sync_state = self.status_channel.sync_state();
+        let l1_progress = self.reader_progress.get();
         Ok(sync_state
             .map(|sync| RpcSyncStatus {
                 tip_height: sync.chain_tip_height(),
                 tip_block_id: *sync.chain_tip_blkid(),
                 finalized_block_id: *sync.finalized_blkid(),
+                l1_scanned_height: l1_progress.scanned_height,
+                l1_behind_by: l1_progress.behind_by,
             })
             .ok_or(Error::ClientNotStarted)?)
     }
@@ -560,7 +621,7 @@ impl<D: Database + Send + Sync + 'static> StrataApiServer for StrataRpcImpl<D> {
         Ok(ev)
     }
 
-    async fn get_last_sync_event_idx(&self) -> RpcResult<u64> {
+    async fn get_last_sync_event_idx(&self) -> RpcResult<Option<u64>> {
         let db = self.database.clone();
 
         let last = wait_blocking("fetch_last_sync_event_idx", move || {
@@ -568,9 +629,7 @@ impl<D: Database + Send + Sync + 'static> StrataApiServer for StrataRpcImpl<D> {
         })
         .await?;
 
-        // FIXME returning MAX if we haven't produced one yet, should figure
-        // something else out
-        Ok(last.unwrap_or(u64::MAX))
+        Ok(last)
     }
 
     // FIXME: possibly create a separate rpc type corresponding to ClientUpdateOutput
@@ -593,6 +652,83 @@ impl<D: Database + Send + Sync + 'static> StrataApiServer for StrataRpcImpl<D> {
 
         Ok(res)
     }
+
+    async fn get_consensus_state(&self, idx: u64) -> RpcResult<ConsensusStateSummary> {
+        let state = self.fetch_client_state(idx).await?;
+        Ok(ConsensusStateSummary::from_client_state(idx, &state))
+    }
+
+    async fn get_consensus_state_full(&self, idx: u64) -> RpcResult<HexBytes> {
+        if !self.enable_debug_rpc {
+            return Err(Error::DebugRpcDisabled.into());
+        }
+
+        let state = self.fetch_client_state(idx).await?;
+        let raw = borsh::to_vec(state.as_ref()).map_err(|e| Error::Other(e.to_string()))?;
+        Ok(HexBytes(raw))
+    }
+
+    async fn get_sync_event_stats(&self) -> RpcResult<Vec<RpcSyncEventStats>> {
+        if !self.enable_debug_rpc {
+            return Err(Error::DebugRpcDisabled.into());
+        }
+
+        Ok(self
+            .sync_manager
+            .event_timings()
+            .get_stats()
+            .into_iter()
+            .map(|s| RpcSyncEventStats {
+                kind: s.kind.to_owned(),
+                count: s.count,
+                slow_count: s.slow_count,
+                p50_us: s.p50.as_micros() as u64,
+                p99_us: s.p99.as_micros() as u64,
+            })
+            .collect())
+    }
+
+    async fn replay_sync_event(&self, idx: u64) -> RpcResult<ClientUpdateOutput> {
+        if !self.enable_debug_rpc {
+            return Err(Error::DebugRpcDisabled.into());
+        }
+
+        let db = self.database.clone();
+        let params = self.sync_manager.get_params();
+        let output = wait_blocking("replay_sync_event", move || {
+            replay_event(db.as_ref(), &params, idx).map_err(|e| Error::Other(e.to_string()))
+        })
+        .await?;
+
+        Ok(output)
+    }
+
+    async fn get_unfinalized_tree(&self) -> RpcResult<RpcUnfinalizedTree> {
+        if !self.enable_debug_rpc {
+            return Err(Error::DebugRpcDisabled.into());
+        }
+
+        // the fork choice manager doesn't answer until genesis
+        if self.status_channel.sync_state().is_none() {
+            return Err(Error::BeforeGenesis.into());
+        }
+
+        let dump = self
+            .sync_manager
+            .get_unfinalized_tree()
+            .await
+            .ok_or_else(|| Error::Other("fork choice manager not running".to_owned()))?;
+
+        Ok(RpcUnfinalizedTree {
+            finalized_tip: dump.finalized_tip,
+            pending_blocks: dump
+                .pending
+                .into_iter()
+                .map(|(block_id, parent)| RpcPendingBlock { block_id, parent })
+                .collect(),
+            tips: dump.tips,
+        })
+    }
 }
 
 /// Wrapper around [``tokio::task::spawn_blocking``] that handles errors in
@@ -611,20 +747,33 @@ where
     }
 }
 
-pub struct AdminServerImpl {
+pub struct AdminServerImpl<D> {
     stop_tx: Mutex<Option<oneshot::Sender<()>>>,
+    rbdb: Arc<OptimisticTransactionDB>,
+    db_stats: DbStats,
+    database: Arc<D>,
+    sync_manager: Arc<SyncManager>,
 }
 
-impl AdminServerImpl {
-    pub fn new(stop_tx: oneshot::Sender<()>) -> Self {
+impl<D: Database + Sync + Send + 'static> AdminServerImpl<D> {
+    pub fn new(
+        stop_tx: oneshot::Sender<()>,
+        rbdb: Arc<OptimisticTransactionDB>,
+        database: Arc<D>,
+        sync_manager: Arc<SyncManager>,
+    ) -> Self {
         Self {
             stop_tx: Mutex::new(Some(stop_tx)),
+            db_stats: DbStats::new(rbdb.clone(), STORE_COLUMN_FAMILIES),
+            rbdb,
+            database,
+            sync_manager,
         }
     }
 }
 
 #[async_trait]
-impl StrataAdminApiServer for AdminServerImpl {
+impl<D: Database + Sync + Send + 'static> StrataAdminApiServer for AdminServerImpl<D> {
     async fn stop(&self) -> RpcResult<()> {
         let mut opt = self.stop_tx.lock().await;
         if let Some(stop_tx) = opt.take() {
@@ -634,6 +783,65 @@ impl StrataAdminApiServer for AdminServerImpl {
         }
         Ok(())
     }
+
+    async fn trigger_compaction(&self) -> RpcResult<()> {
+        let rbdb = self.rbdb.clone();
+        info!("starting manual db compaction");
+        wait_blocking("trigger_compaction", move || {
+            Ok(maintenance::compact_column_families(
+                &rbdb,
+                STORE_COLUMN_FAMILIES,
+            )?)
+        })
+        .await?;
+        info!("finished manual db compaction");
+        Ok(())
+    }
+
+    async fn get_db_stats(&self) -> RpcResult<Vec<RpcDbCfStats>> {
+        let db_stats = self.db_stats.clone();
+        let stats = wait_blocking("get_db_stats", move || Ok(db_stats.get_all_cf_stats()?)).await?;
+
+        Ok(stats
+            .into_iter()
+            .map(|s| RpcDbCfStats {
+                name: s.name.to_owned(),
+                live_data_size: s.live_data_size,
+                sst_files_size: s.sst_files_size,
+                sst_file_count: s.sst_file_count,
+                num_keys: s.num_keys,
+            })
+            .collect())
+    }
+
+    async fn force_finalize(&self, blkid: L2BlockId) -> RpcResult<()> {
+        if !self.sync_manager.params().run().allow_force_finalize {
+            return Err(Error::ForceFinalizeDisabled.into());
+        }
+
+        // Check it against our current state here, the CSM just ignores it if
+        // it doesn't make sense by the time it gets there.
+        let sync_state = self
+            .sync_manager
+            .status_channel()
+            .sync_state()
+            .ok_or(Error::BeforeGenesis)?;
+        let db = self.database.clone();
+        wait_blocking("check_force_finalize", move || {
+            client_transition::check_unfinalized_ancestor(&sync_state, &blkid, db.as_ref())
+                .map_err(|e| Error::IncorrectParameters(e.to_string()))
+        })
+        .await?;
+
+        warn!(%blkid, "submitting forced finalization");
+        self.sync_manager
+            .csm_controller()
+            .submit_event_async(SyncEvent::ForceFinalize(blkid))
+            .await
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        Ok(())
+    }
 }
 
 pub struct SequencerServerImpl {
@@ -662,8 +870,11 @@ impl SequencerServerImpl {
 #[async_trait]
 impl StrataSequencerApiServer for SequencerServerImpl {
     async fn submit_da_blob(&self, blob: HexBytes) -> RpcResult<()> {
-        let commitment = hash::raw(&blob.0);
-        let blobintent = BlobIntent::new(BlobDest::L1, commitment, blob.0);
+        let blobintent = BlobIntent::builder()
+            .dest(BlobDest::L1)
+            .payload(blob.0)
+            .build()
+            .map_err(|e| Error::Other(e.to_string()))?;
         // NOTE: It would be nice to return reveal txid from the submit method. But creation of txs
         // is deferred to signer in the writer module
         if let Err(e) = self
@@ -676,6 +887,51 @@ impl StrataSequencerApiServer for SequencerServerImpl {
         Ok(())
     }
 
+    async fn estimate_inscription_cost(
+        &self,
+        payload_len: usize,
+        fee_rate: u64,
+    ) -> RpcResult<RpcInscriptionCost> {
+        let fee_rate = FeeRate::from_sat_per_vb(fee_rate)
+            .ok_or_else(|| Error::Other(format!("fee rate {fee_rate} sat/vB is too high")))?;
+        let cost = estimate_inscription_cost(
+            self.params.rollup().inscription_tag(),
+            payload_len,
+            fee_rate,
+        )
+        .map_err(|e| Error::Other(e.to_string()))?;
+        Ok(RpcInscriptionCost {
+            num_inscriptions: cost.num_inscriptions,
+            commit_vsize: cost.commit_vsize,
+            reveal_vsize: cost.reveal_vsize,
+            total_fee: cost.total_fee.to_sat(),
+        })
+    }
+
+    async fn get_stale_blobs(
+        &self,
+        status: BlobL1Status,
+        min_age_secs: u64,
+    ) -> RpcResult<Vec<RpcStaleBlob>> {
+        let blobs = self
+            .inscription_handle
+            .get_blobs_older_than_async(status, Duration::from_secs(min_age_secs))
+            .await
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        // unsigned entries don't have txids yet
+        let txid = |txid: Buf32| (txid != Buf32::zero()).then(|| Txid::from_byte_array(txid.0));
+        Ok(blobs
+            .into_iter()
+            .map(|(id, entry)| RpcStaleBlob {
+                id: HexBytes32(id.0),
+                created_at_ms: entry.created_at_ms,
+                commit_txid: txid(entry.commit_txid),
+                reveal_txid: txid(entry.reveal_txid),
+            })
+            .collect())
+    }
+
     async fn broadcast_raw_tx(&self, rawtx: HexBytes) -> RpcResult<Txid> {
         let tx: BTransaction = deserialize(&rawtx.0).map_err(|e| Error::Other(e.to_string()))?;
         let txid = tx.compute_txid();
@@ -739,3 +995,52 @@ impl StrataSequencerApiServer for SequencerServerImpl {
             .map_err(|e| Error::Other(e.to_string()))?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_primitives::l1::{L1BlockManifest, L1BlockRecord};
+    use strata_rocksdb::test_utils::get_common_db;
+    use strata_test_utils::bitcoin::get_btc_chain;
+
+    use super::*;
+
+    #[test]
+    fn test_fetch_l1_manifest() {
+        let db = get_common_db();
+        let l1_db = db.l1_db();
+        let chain = get_btc_chain();
+        let height = 40320;
+        let rec = chain.get_block_manifest(height as u32);
+        l1_db
+            .put_block_data(height, L1BlockManifest::new(rec.clone(), 3), Vec::new())
+            .unwrap();
+
+        let mf = fetch_l1_manifest(l1_db.as_ref(), height).unwrap().unwrap();
+        let header = chain.get_header(height as u32);
+        assert_eq!(mf.height, height);
+        assert_eq!(mf.blockid, rec.block_hash().0);
+        assert_eq!(mf.txs_root, rec.txs_root().0);
+        assert_eq!(mf.epoch, 3);
+        assert_eq!(
+            mf.header.prev_blockhash,
+            header.prev_blockhash.to_byte_array()
+        );
+        assert_eq!(mf.header.time, header.time);
+        assert_eq!(mf.header.nonce, header.nonce);
+
+        // heights we don't have anything for
+        assert!(fetch_l1_manifest(l1_db.as_ref(), height + 1)
+            .unwrap()
+            .is_none());
+        assert!(fetch_l1_manifest(l1_db.as_ref(), height - 1)
+            .unwrap()
+            .is_none());
+
+        // a manifest with a broken header is reported instead of skipped
+        let bad_rec = L1BlockRecord::new(rec.block_hash(), vec![0; 10], rec.txs_root());
+        l1_db
+            .put_block_data(height + 1, L1BlockManifest::new(bad_rec, 3), Vec::new())
+            .unwrap();
+        assert!(fetch_l1_manifest(l1_db.as_ref(), height + 1).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use strata_db::errors::DbError;
 use thiserror::Error;
 
+use crate::rpc::error::ClientError;
+
 #[derive(Debug, Error)]
 pub enum BroadcasterError {
     #[error("db: {0}")]
@@ -9,6 +11,9 @@ pub enum BroadcasterError {
     #[error("client: {0}")]
     Client(#[from] anyhow::Error),
 
+    #[error("rpc: {0}")]
+    Rpc(#[from] ClientError),
+
     #[error("expected tx not found in db. Idx {0}")]
     TxNotFound(u64),
 
@@ -16,4 +21,15 @@ pub enum BroadcasterError {
     Other(String),
 }
 
+impl BroadcasterError {
+    /// Returns if whatever failed might succeed when just tried again later, i.e. the node
+    /// couldn't be reached.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Rpc(err) => err.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
 pub(crate) type BroadcasterResult<T> = Result<T, BroadcasterError>;
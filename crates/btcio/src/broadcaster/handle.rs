@@ -49,6 +49,29 @@ impl L1BroadcastHandle {
     pub async fn get_tx_entry_by_id_async(&self, txid: Buf32) -> DbResult<Option<L1TxEntry>> {
         self.ops.get_tx_entry_by_id_async(txid).await
     }
+
+    /// Updates the entry of a tx already in the database and hands it to the broadcaster again,
+    /// so it's watched even if it was considered final before. Returns if the tx was found.
+    ///
+    /// The entry is looked up from the most recent one, so this is meant for recent txs.
+    pub async fn reopen_tx_entry(&self, txid: Buf32, txentry: L1TxEntry) -> DbResult<bool> {
+        let next_idx = self.ops.get_next_tx_idx_async().await?;
+        for idx in (0..next_idx).rev() {
+            if self.ops.get_txid_async(idx).await? != Some(txid) {
+                continue;
+            }
+
+            self.ops
+                .put_tx_entry_by_idx_async(idx, txentry.clone())
+                .await?;
+            if self.sender.send((idx, txentry)).await.is_err() {
+                warn!("L1 tx broadcast worker shutting down");
+            }
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
 }
 
 pub fn spawn_broadcaster_task<T>(
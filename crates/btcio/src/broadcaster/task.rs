@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use bitcoin::{hashes::Hash, Txid};
 use strata_db::types::{L1TxEntry, L1TxStatus};
@@ -12,14 +16,14 @@ use crate::{
         error::{BroadcasterError, BroadcasterResult},
         state::BroadcasterState,
     },
-    rpc::traits::{Broadcaster, Wallet},
+    rpc::traits::{Broadcaster, Reader, Wallet},
 };
 
 const BROADCAST_POLL_INTERVAL: u64 = 1_000; // millis
 
 /// Broadcasts the next blob to be sent
 pub async fn broadcaster_task(
-    rpc_client: Arc<impl Broadcaster + Wallet>,
+    rpc_client: Arc<impl Reader + Broadcaster + Wallet>,
     ops: Arc<l1tx_broadcast::BroadcastDbOps>,
     mut entry_receiver: Receiver<(u64, L1TxEntry)>,
     params: Arc<Params>,
@@ -28,6 +32,26 @@ pub async fn broadcaster_task(
     let interval = tokio::time::interval(Duration::from_millis(BROADCAST_POLL_INTERVAL));
     tokio::pin!(interval);
 
+    // The db might not reflect what was actually broadcast if we crashed last time, so fix that up
+    // before picking up the unfinalized entries.  Not being able to shouldn't stop the broadcaster,
+    // so it's retried while the node can't be reached and skipped on anything else.
+    loop {
+        match reconcile_broadcast_state(rpc_client.as_ref(), &ops, &params).await {
+            Ok(reconciled) => {
+                info!(%reconciled, "Reconciled broadcast entries with L1");
+                break;
+            }
+            Err(err) if err.is_retryable() => {
+                warn!(%err, "Could not reconcile broadcast entries, retrying");
+                tokio::time::sleep(Duration::from_millis(BROADCAST_POLL_INTERVAL)).await;
+            }
+            Err(err) => {
+                error!(%err, "Could not reconcile broadcast entries, skipping");
+                break;
+            }
+        }
+    }
+
     let mut state = BroadcasterState::initialize(&ops).await?;
 
     // Run indefinitely to watch/publish txs
@@ -65,6 +89,99 @@ pub async fn broadcaster_task(
     }
 }
 
+/// Checks the status of every non-final entry in the db against the mempool and confirmations
+/// reported by the bitcoin node, and updates the entries whose status drifted from it. Returns the
+/// number of entries updated.
+///
+/// This repairs the db after a crash, e.g. a tx that was published right before the crash but is
+/// still marked [`L1TxStatus::Unpublished`], or a [`L1TxStatus::Published`] one that got dropped
+/// from the mempool while we were down.
+///
+/// Failing to reach the node fails the whole reconciliation, so it can be retried.  Any other
+/// failure only skips the entry it happened on, leaving it as it is.
+pub async fn reconcile_broadcast_state(
+    rpc_client: &(impl Reader + Wallet),
+    ops: &BroadcastDbOps,
+    params: &Params,
+) -> BroadcasterResult<usize> {
+    let mempool: HashSet<Txid> = rpc_client.get_raw_mempool().await?.into_iter().collect();
+
+    let mut updated = 0;
+    let next_idx = ops.get_next_tx_idx_async().await?;
+    for idx in 0..next_idx {
+        match reconcile_entry(idx, rpc_client, ops, &mempool, params).await {
+            Ok(true) => updated += 1,
+            Ok(false) => {}
+            Err(err) if err.is_retryable() => return Err(err),
+            Err(err) => warn!(%idx, %err, "Could not reconcile tx entry, skipping"),
+        }
+    }
+
+    Ok(updated)
+}
+
+/// Reconciles the status of the entry at the index with the mempool and its confirmations.
+/// Returns if the entry was updated.
+async fn reconcile_entry(
+    idx: u64,
+    rpc_client: &(impl Reader + Wallet),
+    ops: &BroadcastDbOps,
+    mempool: &HashSet<Txid>,
+    params: &Params,
+) -> BroadcasterResult<bool> {
+    let mut txentry = ops
+        .get_tx_entry_async(idx)
+        .await?
+        .ok_or(BroadcasterError::TxNotFound(idx))?;
+    if txentry.is_finalized() || !txentry.is_valid() {
+        return Ok(false);
+    }
+
+    let txid = ops
+        .get_txid_async(idx)
+        .await?
+        .ok_or(BroadcasterError::TxNotFound(idx))?;
+    let txid =
+        Txid::from_slice(txid.0.as_slice()).map_err(|e| BroadcasterError::Other(e.to_string()))?;
+
+    let confirmations = match rpc_client.get_transaction(&txid).await {
+        Ok(info) => info.confirmations,
+        Err(e) if e.is_tx_not_found() => 0,
+        Err(e) => return Err(e.into()),
+    };
+    let status = reconciled_status(
+        confirmations,
+        mempool.contains(&txid),
+        params.rollup().l1_reorg_safe_depth.into(),
+    );
+    if status == txentry.status {
+        return Ok(false);
+    }
+
+    info!(%idx, %txid, old = ?txentry.status, new = ?status, "Reconciled tx entry status");
+    txentry.status = status;
+    ops.put_tx_entry_by_idx_async(idx, txentry).await?;
+    Ok(true)
+}
+
+/// Status a tx should have given its confirmations and whether it's in the mempool.
+pub(crate) fn reconciled_status(
+    confirmations: u64,
+    in_mempool: bool,
+    reorg_safe_depth: u64,
+) -> L1TxStatus {
+    if confirmations >= reorg_safe_depth {
+        L1TxStatus::Finalized { confirmations }
+    } else if confirmations > 0 {
+        L1TxStatus::Confirmed { confirmations }
+    } else if in_mempool {
+        L1TxStatus::Published
+    } else {
+        // Neither mined nor in the mempool, so it has to be broadcast again
+        L1TxStatus::Unpublished
+    }
+}
+
 /// Processes unfinalized entries and returns entries idxs that are finalized
 async fn process_unfinalized_entries(
     unfinalized_entries: &BTreeMap<u64, L1TxEntry>,
@@ -131,9 +248,14 @@ async fn handle_entry(
 
                     Ok(Some(L1TxStatus::InvalidInputs))
                 }
+                Err(err) if err.is_retryable() => {
+                    // Leave it unpublished, it's tried again on the next poll
+                    warn!(%idx, ?err, %txid, "could not reach node to broadcast tx, will retry");
+                    Ok(None)
+                }
                 Err(err) => {
                     warn!(%idx, ?err, %txid, "errored while broadcasting");
-                    Err(BroadcasterError::Other(err.to_string()))
+                    Err(err.into())
                 }
             }
         }
@@ -167,8 +289,11 @@ async fn handle_entry(
                     // published/confirmed, set it to unpublished.
                     if e.is_tx_not_found() {
                         L1TxStatus::Unpublished
+                    } else if e.is_retryable() {
+                        warn!(%idx, ?e, %txid, "could not reach node to check tx, will retry");
+                        return Ok(None);
                     } else {
-                        return Err(BroadcasterError::Other(e.to_string()));
+                        return Err(e.into());
                     }
                 }
             };
@@ -181,7 +306,7 @@ async fn handle_entry(
 
 #[cfg(test)]
 mod test {
-    use bitcoin::{consensus, Transaction};
+    use bitcoin::{consensus, OutPoint, Transaction};
     use strata_db::traits::BroadcastDatabase;
     use strata_rocksdb::{
         broadcaster::db::{BroadcastDb, L1BroadcastDb},
@@ -191,7 +316,10 @@ mod test {
     use strata_test_utils::l2::gen_params;
 
     use super::*;
-    use crate::test_utils::{TestBitcoinClient, SOME_TX};
+    use crate::{
+        rpc::error::ClientError,
+        test_utils::{MockL1Client, MockRpc, TestBitcoinClient, SOME_TX},
+    };
 
     fn get_db() -> Arc<impl BroadcastDatabase> {
         let (db, dbops) = get_rocksdb_tmp_instance().unwrap();
@@ -478,4 +606,168 @@ mod test {
             "published tx should be finalized"
         );
     }
+
+    #[tokio::test]
+    async fn test_handle_entry_client_errors() {
+        let ops = get_ops();
+        let params = get_params();
+        let unpublished = gen_entry_with_status(L1TxStatus::Unpublished);
+        let published = gen_entry_with_status(L1TxStatus::Published);
+        ops.put_tx_entry_async([1; 32].into(), unpublished.clone())
+            .await
+            .unwrap();
+        let client = MockL1Client::new();
+
+        // Transport errors keep the status so the entry is retried
+        let refused = ClientError::Connection("refused".to_string());
+        client.fail_always(MockRpc::SendRawTransaction, refused.clone());
+        client.fail_always(MockRpc::GetTransaction, refused);
+        for e in [&unpublished, &published] {
+            let res = handle_entry(&client, e, 0, ops.as_ref(), params.as_ref())
+                .await
+                .unwrap();
+            assert_eq!(res, None, "status should not change on transport error");
+        }
+        client.clear_failures();
+
+        // Mempool rejects are surfaced right away, the node doesn't know the tx's inputs
+        let res = handle_entry(&client, &unpublished, 0, ops.as_ref(), params.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(res, Some(L1TxStatus::InvalidInputs));
+
+        // Unknown txs get published again
+        let res = handle_entry(&client, &published, 0, ops.as_ref(), params.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(res, Some(L1TxStatus::Unpublished));
+
+        // Anything else is an error
+        let err = ClientError::Server(-32601, "Method not found".to_string());
+        client.fail_always(MockRpc::GetTransaction, err);
+        let res = handle_entry(&client, &published, 0, ops.as_ref(), params.as_ref()).await;
+        assert!(res.is_err());
+    }
+
+    /// Stores entries with the given statuses for txs spending the node's funding utxos, one
+    /// each, and returns the txs.
+    async fn put_funded_entries(
+        client: &MockL1Client,
+        ops: &BroadcastDbOps,
+        statuses: &[L1TxStatus],
+    ) -> Vec<Transaction> {
+        let utxos = client.get_utxos().await.unwrap();
+        assert!(utxos.len() >= statuses.len());
+
+        let mut txs = Vec::new();
+        for (utxo, st) in utxos.iter().zip(statuses) {
+            let tx = MockL1Client::build_spending_tx(&[OutPoint::new(utxo.txid, utxo.vout)]);
+            let mut entry = L1TxEntry::from_tx(&tx);
+            entry.status = st.clone();
+            ops.put_tx_entry_async(tx.compute_txid().into(), entry)
+                .await
+                .unwrap();
+            txs.push(tx);
+        }
+        txs
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_broadcast_state() {
+        let ops = get_ops();
+        let params = get_params();
+        let reorg_depth = params.rollup().l1_reorg_safe_depth as u64;
+        let client = MockL1Client::new();
+
+        // What the db thinks
+        let statuses = [
+            // published right before crashing, status not persisted
+            L1TxStatus::Unpublished,
+            // dropped from the mempool while down
+            L1TxStatus::Published,
+            // got more confirmations while down
+            L1TxStatus::Confirmed { confirmations: 1 },
+            // reorged out while down
+            L1TxStatus::Confirmed { confirmations: 1 },
+            // still the same
+            L1TxStatus::Published,
+            // final ones aren't touched
+            L1TxStatus::Finalized { confirmations: 1 },
+            L1TxStatus::InvalidInputs,
+        ];
+        let txs = put_funded_entries(&client, &ops, &statuses).await;
+
+        // What the node has
+        client.send_raw_transaction(&txs[2]).await.unwrap();
+        client.mine_blocks(reorg_depth);
+        client.send_raw_transaction(&txs[3]).await.unwrap();
+        client.mine_blocks(1);
+        client.disconnect_blocks(1);
+        for tx in [&txs[0], &txs[4]] {
+            client.send_raw_transaction(tx).await.unwrap();
+        }
+
+        let updated = reconcile_broadcast_state(&client, ops.as_ref(), params.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(updated, 4);
+
+        let expected = [
+            L1TxStatus::Published,
+            L1TxStatus::Unpublished,
+            L1TxStatus::Finalized {
+                confirmations: reorg_depth,
+            },
+            L1TxStatus::Published,
+            L1TxStatus::Published,
+            L1TxStatus::Finalized { confirmations: 1 },
+            L1TxStatus::InvalidInputs,
+        ];
+        for (idx, st) in expected.iter().enumerate() {
+            let entry = ops.get_tx_entry_async(idx as u64).await.unwrap().unwrap();
+            assert_eq!(&entry.status, st, "unexpected status at idx {idx}");
+        }
+
+        // Entries have converged, so running it again changes nothing
+        let updated = reconcile_broadcast_state(&client, ops.as_ref(), params.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(updated, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_broadcast_state_failures() {
+        let ops = get_ops();
+        let params = get_params();
+        let client = MockL1Client::new();
+
+        let statuses = [L1TxStatus::Unpublished, L1TxStatus::Unpublished];
+        let txs = put_funded_entries(&client, &ops, &statuses).await;
+        for tx in &txs {
+            client.send_raw_transaction(tx).await.unwrap();
+        }
+
+        // Not reaching the node fails it as a whole, so it's retried
+        for rpc in [MockRpc::GetRawMempool, MockRpc::GetTransaction] {
+            client.fail_next(rpc, 1, ClientError::Timeout);
+            let res = reconcile_broadcast_state(&client, ops.as_ref(), params.as_ref()).await;
+            assert!(res.unwrap_err().is_retryable(), "{rpc:?}");
+        }
+        let entry = ops.get_tx_entry_async(0).await.unwrap().unwrap();
+        assert_eq!(entry.status, L1TxStatus::Unpublished);
+
+        // Anything else only skips the entry it happened on
+        let err = ClientError::Server(-32601, "Method not found".to_string());
+        client.fail_next(MockRpc::GetTransaction, 1, err);
+        let updated = reconcile_broadcast_state(&client, ops.as_ref(), params.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(updated, 1);
+
+        let statuses = [L1TxStatus::Unpublished, L1TxStatus::Published];
+        for (idx, st) in statuses.iter().enumerate() {
+            let entry = ops.get_tx_entry_async(idx as u64).await.unwrap().unwrap();
+            assert_eq!(&entry.status, st, "unexpected status at idx {idx}");
+        }
+    }
 }
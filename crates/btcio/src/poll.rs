@@ -0,0 +1,88 @@
+//! Randomized poll intervals, so components that (re)start together don't hit the bitcoin node in
+//! lockstep.
+
+use std::time::Duration;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Default jitter on the reader's and writer's poll intervals, in percent.
+pub const DEFAULT_POLL_JITTER_PCT: u8 = 10;
+
+/// Produces poll intervals spread randomly within `jitter_pct` percent around a base interval.
+#[derive(Debug)]
+pub struct PollJitter<R = StdRng> {
+    base_ms: u64,
+    jitter_pct: u8,
+    rng: R,
+}
+
+impl PollJitter {
+    pub fn new(base_ms: u64, jitter_pct: u8) -> Self {
+        Self::with_rng(base_ms, jitter_pct, StdRng::from_entropy())
+    }
+}
+
+impl<R: Rng> PollJitter<R> {
+    /// Same as [`PollJitter::new`] but with the given rng, so the intervals can be made
+    /// deterministic by seeding it.
+    ///
+    /// Jitter above 100% is treated as 100%.
+    pub fn with_rng(base_ms: u64, jitter_pct: u8, rng: R) -> Self {
+        Self {
+            base_ms,
+            jitter_pct: jitter_pct.min(100),
+            rng,
+        }
+    }
+
+    /// Returns the duration to wait before the next poll.
+    pub fn next_interval(&mut self) -> Duration {
+        let spread = self.base_ms * self.jitter_pct as u64 / 100;
+        if spread == 0 {
+            return Duration::from_millis(self.base_ms);
+        }
+
+        let ms = self
+            .rng
+            .gen_range(self.base_ms - spread..=self.base_ms + spread);
+        Duration::from_millis(ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_within_jitter_bounds() {
+        for jitter_pct in [1, 10, 50, 100, 200] {
+            let mut jitter = PollJitter::with_rng(1_000, jitter_pct, StdRng::seed_from_u64(42));
+            let spread = 1_000 * jitter_pct.min(100) as u64 / 100;
+
+            for _ in 0..1_000 {
+                let ms = jitter.next_interval().as_millis() as u64;
+                assert!(
+                    (1_000 - spread..=1_000 + spread).contains(&ms),
+                    "{ms}ms out of bounds for {jitter_pct}% jitter"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_jitter() {
+        let mut jitter = PollJitter::new(1_000, 0);
+        for _ in 0..10 {
+            assert_eq!(jitter.next_interval(), Duration::from_millis(1_000));
+        }
+    }
+
+    #[test]
+    fn test_seeded_intervals_are_deterministic() {
+        let mut a = PollJitter::with_rng(1_000, 20, StdRng::seed_from_u64(7));
+        let mut b = PollJitter::with_rng(1_000, 20, StdRng::seed_from_u64(7));
+        for _ in 0..10 {
+            assert_eq!(a.next_interval(), b.next_interval());
+        }
+    }
+}
@@ -2,6 +2,13 @@ use std::sync::Arc;
 
 use strata_primitives::params::Params;
 
+use crate::poll::DEFAULT_POLL_JITTER_PCT;
+
+/// Default number of sync events the CSM worker can fall behind by before the
+/// reader stops scanning new blocks.  Kept below the size of the CSM channel so
+/// that submitting events doesn't block in the meantime.
+pub const DEFAULT_MAX_PENDING_SYNC_EVENTS: u64 = 32;
+
 #[derive(Clone, Debug)]
 pub struct ReaderConfig {
     /// This is the maximum depth we ever expect to reorg.
@@ -10,6 +17,14 @@ pub struct ReaderConfig {
     /// Time between polls to the L1 client, in millis.
     pub client_poll_dur_ms: u32,
 
+    /// How much the time between polls is randomly varied by, in percent of
+    /// `client_poll_dur_ms`.
+    pub client_poll_jitter_pct: u8,
+
+    /// Number of sync events the CSM worker can have left to process before we
+    /// pause scanning new blocks, no limit if unset.
+    pub max_pending_events: Option<u64>,
+
     /// params
     pub params: Arc<Params>,
 }
@@ -19,7 +34,19 @@ impl ReaderConfig {
         Self {
             max_reorg_depth,
             client_poll_dur_ms,
+            client_poll_jitter_pct: DEFAULT_POLL_JITTER_PCT,
+            max_pending_events: None,
             params,
         }
     }
+
+    pub fn with_poll_jitter_pct(mut self, jitter_pct: u8) -> Self {
+        self.client_poll_jitter_pct = jitter_pct;
+        self
+    }
+
+    pub fn with_max_pending_events(mut self, max_pending: u64) -> Self {
+        self.max_pending_events = Some(max_pending);
+        self
+    }
 }
@@ -0,0 +1,248 @@
+//! Checks of the L1 chain we stored, to catch corruption or a reorg that was
+//! interrupted halfway through before the reader builds on top of it.
+
+use bitcoin::hashes::Hash;
+use strata_db::{errors::DbError, traits::L1Database};
+use strata_primitives::{buf::Buf32, errors::ManifestDecodeError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IntegrityError {
+    #[error("missing stored L1 block {0}")]
+    MissingBlock(u64),
+
+    #[error("stored L1 block {0}: {1}")]
+    InvalidBlock(u64, ManifestDecodeError),
+
+    #[error(
+        "stored L1 block {height} has parent {found}, but the block stored below it is {expected}"
+    )]
+    BrokenLink {
+        height: u64,
+        expected: Buf32,
+        found: Buf32,
+    },
+
+    #[error("L1 start height {height} is below the horizon {horizon}")]
+    StartBelowHorizon { height: u64, horizon: u64 },
+
+    #[error("L1 start height {height} would roll back the buried block at {buried}")]
+    StartBelowBuried { height: u64, buried: u64 },
+
+    #[error("db: {0}")]
+    Db(#[from] DbError),
+}
+
+/// Checks that the L1 blocks stored between the heights, inclusive, are all
+/// there and that each one's header links to the block stored below it,
+/// reporting the first one that doesn't.
+pub fn verify_l1_chain_integrity(
+    l1_db: &impl L1Database,
+    from: u64,
+    to: u64,
+) -> Result<(), IntegrityError> {
+    let mut parent: Option<Buf32> = None;
+    for height in from..=to {
+        let mf = l1_db
+            .get_block_manifest(height)?
+            .ok_or(IntegrityError::MissingBlock(height))?;
+        let header = mf
+            .parse_header()
+            .map_err(|e| IntegrityError::InvalidBlock(height, e))?;
+
+        if let Some(expected) = parent {
+            let found = Buf32(header.prev_blockhash.to_byte_array());
+            if found != expected {
+                return Err(IntegrityError::BrokenLink {
+                    height,
+                    expected,
+                    found,
+                });
+            }
+        }
+
+        parent = Some(mf.block_hash());
+    }
+
+    Ok(())
+}
+
+/// Returns the height the reader should start scanning at, the one after the
+/// stored chain tip or the horizon if nothing's stored yet.
+///
+/// If the start height is overridden, like when importing a snapshot or
+/// re-indexing, it has to be at or above the horizon and the blocks just below
+/// it and at it, if stored, have to link up, so the first block scanned builds
+/// on the stored chain.  Starting within the stored chain means rolling it
+/// back to below the start height first, which can't undo the buried block.
+pub fn resolve_start_height(
+    l1_db: &impl L1Database,
+    horizon: u64,
+    buried: u64,
+    start_override: Option<u64>,
+) -> Result<u64, IntegrityError> {
+    let tip = l1_db.get_chain_tip()?;
+    let Some(height) = start_override else {
+        return Ok(tip.map(|h| h + 1).unwrap_or(horizon));
+    };
+
+    if height < horizon {
+        return Err(IntegrityError::StartBelowHorizon { height, horizon });
+    }
+
+    if tip.is_some_and(|tip| height <= tip) && height <= buried {
+        return Err(IntegrityError::StartBelowBuried { height, buried });
+    }
+
+    if height > horizon {
+        let to = match l1_db.get_block_manifest(height)? {
+            Some(_) => height,
+            None => height - 1,
+        };
+        verify_l1_chain_integrity(l1_db, height - 1, to)?;
+    }
+
+    Ok(height)
+}
+
+#[cfg(test)]
+mod tests {
+    use strata_primitives::l1::L1BlockManifest;
+    use strata_rocksdb::test_utils::get_common_db;
+    use strata_test_utils::bitcoin::get_btc_chain;
+
+    use super::*;
+
+    const START_HEIGHT: u64 = 40318;
+
+    /// Stores the blocks of the test chain at the heights, in order.
+    fn put_blocks(l1_db: &impl L1Database, blocks: &[(u64, u32)]) {
+        let chain = get_btc_chain();
+        for (height, chain_height) in blocks {
+            let mf = L1BlockManifest::new(chain.get_block_manifest(*chain_height), 0);
+            l1_db.put_block_data(*height, mf, Vec::new()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_intact_chain() {
+        let db = get_common_db();
+        let l1_db = db.l1_db().as_ref();
+        let blocks: Vec<_> = (START_HEIGHT..START_HEIGHT + 6)
+            .map(|h| (h, h as u32))
+            .collect();
+        put_blocks(l1_db, &blocks);
+
+        verify_l1_chain_integrity(l1_db, START_HEIGHT, START_HEIGHT + 5).unwrap();
+        verify_l1_chain_integrity(l1_db, START_HEIGHT + 2, START_HEIGHT + 3).unwrap();
+
+        let res = verify_l1_chain_integrity(l1_db, START_HEIGHT, START_HEIGHT + 6);
+        assert!(matches!(res, Err(IntegrityError::MissingBlock(h)) if h == START_HEIGHT + 6));
+    }
+
+    #[test]
+    fn test_broken_link() {
+        let db = get_common_db();
+        let l1_db = db.l1_db().as_ref();
+
+        // one block got lost, so everything above it is stored one height too
+        // low, but they still link to each other
+        let skipped = START_HEIGHT + 2;
+        let blocks: Vec<_> = (START_HEIGHT..START_HEIGHT + 6)
+            .map(|h| (h, if h < skipped { h as u32 } else { h as u32 + 1 }))
+            .collect();
+        put_blocks(l1_db, &blocks);
+
+        let res = verify_l1_chain_integrity(l1_db, START_HEIGHT, START_HEIGHT + 5);
+        let chain = get_btc_chain();
+        match res {
+            Err(IntegrityError::BrokenLink {
+                height,
+                expected,
+                found,
+            }) => {
+                assert_eq!(height, skipped);
+                assert_eq!(
+                    expected,
+                    chain.get_block_manifest(skipped as u32 - 1).block_hash()
+                );
+                assert_eq!(found, chain.get_block_manifest(skipped as u32).block_hash());
+            }
+            res => panic!("expected a broken link, got {res:?}"),
+        }
+
+        // the part above the break is consistent on its own
+        verify_l1_chain_integrity(l1_db, skipped, START_HEIGHT + 5).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_start_height() {
+        let db = get_common_db();
+        let l1_db = db.l1_db().as_ref();
+        let horizon = START_HEIGHT;
+
+        // nothing stored yet, so we start at the horizon
+        assert_eq!(
+            resolve_start_height(l1_db, horizon, horizon, None).unwrap(),
+            horizon
+        );
+        assert_eq!(
+            resolve_start_height(l1_db, horizon, horizon, Some(horizon)).unwrap(),
+            horizon
+        );
+
+        let blocks: Vec<_> = (START_HEIGHT..START_HEIGHT + 6)
+            .map(|h| (h, h as u32))
+            .collect();
+        put_blocks(l1_db, &blocks);
+        assert_eq!(
+            resolve_start_height(l1_db, horizon, horizon, None).unwrap(),
+            START_HEIGHT + 6
+        );
+
+        // overrides within the stored chain above the buried block, or right past it, are
+        // honored
+        for height in [horizon + 1, START_HEIGHT + 3, START_HEIGHT + 6] {
+            assert_eq!(
+                resolve_start_height(l1_db, horizon, horizon, Some(height)).unwrap(),
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_start_height_rejected() {
+        let db = get_common_db();
+        let l1_db = db.l1_db().as_ref();
+        let horizon = START_HEIGHT;
+
+        let skipped = START_HEIGHT + 3;
+        let blocks: Vec<_> = (START_HEIGHT..START_HEIGHT + 6)
+            .map(|h| (h, if h < skipped { h as u32 } else { h as u32 + 1 }))
+            .collect();
+        put_blocks(l1_db, &blocks);
+
+        let res = resolve_start_height(l1_db, horizon, horizon, Some(horizon - 1));
+        assert!(matches!(
+            res,
+            Err(IntegrityError::StartBelowHorizon { height, horizon: h })
+                if height == horizon - 1 && h == horizon
+        ));
+
+        // rescanning it would undo the buried block
+        let buried = START_HEIGHT + 2;
+        let res = resolve_start_height(l1_db, horizon, buried, Some(buried));
+        assert!(matches!(
+            res,
+            Err(IntegrityError::StartBelowBuried { height, buried: b }) if height == buried && b == buried
+        ));
+
+        // nothing stored below it to build on
+        let res = resolve_start_height(l1_db, horizon, horizon, Some(START_HEIGHT + 7));
+        assert!(matches!(res, Err(IntegrityError::MissingBlock(h)) if h == START_HEIGHT + 6));
+
+        // the block stored at it doesn't link to the one below
+        let res = resolve_start_height(l1_db, horizon, horizon, Some(skipped));
+        assert!(matches!(res, Err(IntegrityError::BrokenLink { height, .. }) if height == skipped));
+    }
+}
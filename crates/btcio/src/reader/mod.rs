@@ -1,3 +1,5 @@
 pub mod config;
+pub mod integrity;
+pub mod progress;
 pub mod query;
 mod state;
@@ -0,0 +1,66 @@
+use tokio::sync::watch;
+
+/// How far along the reader is in scanning the L1 chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReaderProgress {
+    /// Height of the last block the reader scanned.
+    pub scanned_height: u64,
+
+    /// Height of the tip of the chain as last seen from the client.
+    pub tip_height: u64,
+
+    /// Number of blocks left to scan to catch up with the tip.
+    pub behind_by: u64,
+}
+
+impl ReaderProgress {
+    fn new(scanned_height: u64, tip_height: u64) -> Self {
+        Self {
+            scanned_height,
+            tip_height,
+            behind_by: tip_height.saturating_sub(scanned_height),
+        }
+    }
+}
+
+/// Publishes the [`ReaderProgress`] of the reader task so it can be observed
+/// without going to the database.
+#[derive(Debug)]
+pub struct ReaderProgressTracker {
+    progress_tx: watch::Sender<ReaderProgress>,
+}
+
+impl ReaderProgressTracker {
+    pub fn new() -> Self {
+        let (progress_tx, _) = watch::channel(ReaderProgress::default());
+        Self { progress_tx }
+    }
+
+    /// Returns a receiver that sees every progress update from now on.
+    pub fn subscribe(&self) -> watch::Receiver<ReaderProgress> {
+        self.progress_tx.subscribe()
+    }
+
+    /// Returns the current progress.
+    pub fn get(&self) -> ReaderProgress {
+        *self.progress_tx.borrow()
+    }
+
+    pub(crate) fn update_tip(&self, tip_height: u64) {
+        self.progress_tx
+            .send_modify(|p| *p = ReaderProgress::new(p.scanned_height, tip_height));
+    }
+
+    pub(crate) fn update_scanned(&self, scanned_height: u64) {
+        self.progress_tx.send_modify(|p| {
+            // we could have scanned past the tip we last saw
+            *p = ReaderProgress::new(scanned_height, p.tip_height.max(scanned_height))
+        });
+    }
+}
+
+impl Default for ReaderProgressTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
     collections::VecDeque,
     sync::Arc,
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::bail;
@@ -13,7 +13,7 @@ use strata_state::l1::{
 };
 use strata_status::StatusChannel;
 use strata_tx_parser::{
-    filter::filter_protocol_op_tx_refs,
+    filter::{filter_protocol_op_tx_refs_with, TxFilter},
     filter_types::TxFilterConfig,
     messages::{BlockData, L1Event},
 };
@@ -21,7 +21,8 @@ use tokio::sync::mpsc;
 use tracing::*;
 
 use crate::{
-    reader::{config::ReaderConfig, state::ReaderState},
+    poll::PollJitter,
+    reader::{config::ReaderConfig, progress::ReaderProgressTracker, state::ReaderState},
     rpc::traits::Reader,
     status::{apply_status_updates, L1StatusUpdate},
 };
@@ -36,6 +37,10 @@ struct ReaderContext<R: Reader> {
     config: Arc<ReaderConfig>,
     /// Status transmitter
     status_channel: StatusChannel,
+    /// Filters picking out the relevant txs in each block
+    tx_filters: Vec<Box<dyn TxFilter>>,
+    /// Progress transmitter
+    progress: Arc<ReaderProgressTracker>,
 }
 
 /// The main task that initializes the reader state and starts reading from bitcoin.
@@ -45,12 +50,16 @@ pub async fn bitcoin_data_reader_task(
     target_next_block: u64,
     config: Arc<ReaderConfig>,
     status_channel: StatusChannel,
+    tx_filters: Vec<Box<dyn TxFilter>>,
+    progress: Arc<ReaderProgressTracker>,
 ) -> anyhow::Result<()> {
     let ctx = ReaderContext {
         client,
         event_tx,
         config,
         status_channel,
+        tx_filters,
+        progress,
     };
     do_reader_task(ctx, target_next_block).await
 }
@@ -62,13 +71,21 @@ async fn do_reader_task<R: Reader>(
 ) -> anyhow::Result<()> {
     info!(%target_next_block, "started L1 reader task!");
 
-    let poll_dur = Duration::from_millis(ctx.config.client_poll_dur_ms as u64);
+    let mut poll_jitter = PollJitter::new(
+        ctx.config.client_poll_dur_ms as u64,
+        ctx.config.client_poll_jitter_pct,
+    );
 
     let mut state = init_reader_state(&ctx, target_next_block).await?;
     let best_blkid = state.best_block();
     info!(%best_blkid, "initialized L1 reader state");
+    ctx.progress.update_scanned(state.best_block_idx());
 
     loop {
+        if let Some(max_pending) = ctx.config.max_pending_events {
+            wait_for_csm_catch_up(&ctx, max_pending).await?;
+        }
+
         let mut status_updates: Vec<L1StatusUpdate> = Vec::new();
         let cur_best_height = state.best_block_idx();
 
@@ -100,7 +117,7 @@ async fn do_reader_task<R: Reader>(
             }
         }
 
-        tokio::time::sleep(poll_dur).await;
+        tokio::time::sleep(poll_jitter.next_interval()).await;
 
         status_updates.push(L1StatusUpdate::LastUpdate(
             SystemTime::now()
@@ -113,6 +130,26 @@ async fn do_reader_task<R: Reader>(
     }
 }
 
+/// Waits for the CSM worker to get through its backlog of sync events if it
+/// fell too far behind, so that we don't keep piling up more for it.
+async fn wait_for_csm_catch_up<R: Reader>(
+    ctx: &ReaderContext<R>,
+    max_pending: u64,
+) -> anyhow::Result<()> {
+    let pending = ctx.status_channel.sync_event_progress().pending();
+    if pending <= max_pending {
+        return Ok(());
+    }
+
+    warn!(%pending, %max_pending, "CSM falling behind, pausing L1 scan");
+    let progress = ctx
+        .status_channel
+        .wait_for_pending_sync_events(max_pending)
+        .await?;
+    info!(pending = %progress.pending(), "CSM caught up, resuming L1 scan");
+    Ok(())
+}
+
 /// Reverts the reader state to the height where the last checkpoint is finalized.
 async fn handle_new_filter_rule<R: Reader>(
     ctx: &ReaderContext<R>,
@@ -224,6 +261,7 @@ async fn poll_for_new_blocks<R: Reader>(
 
     status_updates.push(L1StatusUpdate::CurHeight(client_height));
     status_updates.push(L1StatusUpdate::CurTip(fresh_best_block.to_string()));
+    ctx.progress.update_tip(client_height);
 
     if fresh_best_block == *state.best_block() {
         trace!("polled client, nothing to do");
@@ -235,6 +273,7 @@ async fn poll_for_new_blocks<R: Reader>(
         if pivot_height < state.best_block_idx() {
             info!(%pivot_height, %pivot_blkid, "found apparent reorg");
             state.rollback_to_height(pivot_height);
+            ctx.progress.update_scanned(pivot_height);
             let revert_ev = L1Event::RevertTo(pivot_height);
             if ctx.event_tx.send(revert_ev).await.is_err() {
                 warn!("unable to submit L1 reorg event, did persistence task exit?");
@@ -304,6 +343,7 @@ async fn fetch_and_process_block<R: Reader>(
 
     // Insert to new block, incrementing cur_height.
     let _deep = state.accept_new_block(l1blkid);
+    ctx.progress.update_scanned(height);
 
     Ok(l1blkid)
 }
@@ -319,7 +359,8 @@ async fn process_block<R: Reader>(
     let txs = block.txdata.len();
 
     let params = ctx.config.params.clone();
-    let filtered_txs = filter_protocol_op_tx_refs(&block, state.filter_config());
+    let filtered_txs =
+        filter_protocol_op_tx_refs_with(&block, state.filter_config(), &ctx.tx_filters);
     let block_data = BlockData::new(height, block, filtered_txs);
     let l1blkid = block_data.block().block_hash();
     trace!(%height, %l1blkid, %txs, "fetched block from client");
@@ -396,10 +437,11 @@ pub async fn get_verification_state(
 
 #[cfg(test)]
 mod test {
-    use bitcoin::Network;
+    use std::time::Duration;
+
     use strata_primitives::{
-        l1::{BitcoinAddress, L1Status},
-        params::DepositTxParams,
+        l1::L1Status,
+        params::{DepositTxParams, FederationScript},
         sorted_vec::SortedVec,
     };
     use strata_state::{
@@ -407,9 +449,11 @@ mod test {
         client_state::{ClientState, L1Checkpoint},
     };
     use strata_test_utils::{l2::gen_params, ArbitraryGenerator};
+    use strata_tx_parser::filter::default_tx_filters;
+    use tokio::time::timeout;
 
     use super::*;
-    use crate::test_utils::TestBitcoinClient;
+    use crate::{reader::progress::ReaderProgress, test_utils::TestBitcoinClient};
 
     const N_RECENT_BLOCKS: usize = 10;
 
@@ -425,6 +469,8 @@ mod test {
         let config = Arc::new(ReaderConfig {
             max_reorg_depth: 4,
             client_poll_dur_ms: 3000,
+            client_poll_jitter_pct: 0,
+            max_pending_events: None,
             params,
         });
         let client = Arc::new(TestBitcoinClient::new(1));
@@ -433,12 +479,15 @@ mod test {
             config,
             status_channel,
             client,
+            tx_filters: default_tx_filters(),
+            progress: Arc::new(ReaderProgressTracker::new()),
         }
     }
 
     fn get_filter_config(name: &str) -> TxFilterConfig {
         TxFilterConfig {
-            rollup_name: name.to_string(),
+            inscription_tag: name.to_string(),
+            batched_inscriptions: false,
             expected_addrs: SortedVec::new(),
             expected_blobs: SortedVec::new(),
             expected_outpoints: SortedVec::new(),
@@ -446,11 +495,7 @@ mod test {
                 magic_bytes: vec![1, 2],
                 address_length: 5,
                 deposit_amount: 100,
-                address: BitcoinAddress::parse(
-                    "bcrt1q8adlclrnm80yhz2kfwd8wzmmxevxfg8yutvp93", // random address
-                    Network::Regtest,
-                )
-                .unwrap(),
+                federation_script: FederationScript::Wsh(Buf32::zero()),
             },
         }
     }
@@ -526,4 +571,88 @@ mod test {
         // Check the reader state's next_height
         assert_eq!(state.next_height(), checkpoint_height + 1);
     }
+
+    #[tokio::test]
+    async fn test_reader_progress() {
+        let (event_tx, _event_rx) = mpsc::channel::<L1Event>(100);
+        let chstate: Chainstate = ArbitraryGenerator::new().generate();
+        let clstate: ClientState = ArbitraryGenerator::new().generate();
+        let ctx = get_reader_ctx(event_tx, chstate, clstate);
+        let mut progress_rx = ctx.progress.subscribe();
+
+        // the test client is always at height 100, with the same block at
+        // every height
+        let tip_blkid = ctx.client.get_block_hash(95).await.unwrap();
+        let mut state = ReaderState::new(
+            96,
+            N_RECENT_BLOCKS,
+            VecDeque::from([tip_blkid]),
+            get_filter_config("zkzkzk"),
+            ctx.status_channel.epoch().unwrap(),
+        );
+
+        let mut status_updates = Vec::new();
+        ctx.progress.update_tip(100);
+        let mut last_behind_by = 100;
+        for height in 96..=98 {
+            fetch_and_process_block(&ctx, height, &mut state, &mut status_updates)
+                .await
+                .unwrap();
+
+            assert!(progress_rx.has_changed().unwrap());
+            let progress = *progress_rx.borrow_and_update();
+            assert_eq!(progress.scanned_height, height);
+            assert_eq!(progress.tip_height, 100);
+            assert!(progress.behind_by < last_behind_by);
+            last_behind_by = progress.behind_by;
+        }
+        assert_eq!(last_behind_by, 2);
+
+        poll_for_new_blocks(&ctx, &mut state, &mut status_updates)
+            .await
+            .unwrap();
+        assert!(progress_rx.has_changed().unwrap());
+        assert_eq!(
+            *progress_rx.borrow_and_update(),
+            ReaderProgress {
+                scanned_height: 100,
+                tip_height: 100,
+                behind_by: 0,
+            }
+        );
+        assert_eq!(ctx.progress.get().scanned_height, state.best_block_idx());
+    }
+
+    #[tokio::test]
+    async fn test_pause_on_csm_backlog() {
+        let (event_tx, _event_rx) = mpsc::channel::<L1Event>(10);
+        let chstate: Chainstate = ArbitraryGenerator::new().generate();
+        let clstate: ClientState = ArbitraryGenerator::new().generate();
+        let ctx = get_reader_ctx(event_tx, chstate, clstate);
+        let status_channel = ctx.status_channel.clone();
+
+        // nothing to wait for while the worker keeps up
+        status_channel.update_sync_event_written(2);
+        timeout(Duration::from_millis(100), wait_for_csm_catch_up(&ctx, 2))
+            .await
+            .expect("paused without a backlog")
+            .unwrap();
+
+        // a slow worker falls behind
+        status_channel.update_sync_event_written(5);
+        let wait = wait_for_csm_catch_up(&ctx, 2);
+        tokio::pin!(wait);
+        assert!(timeout(Duration::from_millis(50), &mut wait).await.is_err());
+
+        // still too far behind
+        status_channel.update_sync_event_processed(2);
+        assert!(timeout(Duration::from_millis(50), &mut wait).await.is_err());
+
+        // caught up enough to resume
+        status_channel.update_sync_event_processed(3);
+        timeout(Duration::from_secs(5), &mut wait)
+            .await
+            .expect("didn't resume after catching up")
+            .unwrap();
+    }
 }
@@ -20,7 +20,7 @@ use serde_json::{
     json,
     value::{RawValue, Value},
 };
-use tokio::time::sleep;
+use tokio::{sync::Semaphore, time::sleep};
 use tracing::*;
 
 use crate::rpc::{
@@ -36,8 +36,11 @@ use crate::rpc::{
 /// This is an alias for the result type returned by the [`BitcoinClient`].
 pub type ClientResult<T> = Result<T, ClientError>;
 
-/// The maximum number of retries for a request.
-const MAX_RETRIES: u8 = 3;
+/// Default number of times a request that failed on a recoverable error is retried.
+pub const DEFAULT_MAX_RETRIES: u8 = 2;
+
+/// Default number of connections to `bitcoind` kept open for reuse.
+pub const DEFAULT_RPC_POOL_SIZE: usize = 8;
 
 /// Custom implementation to convert a value to a `Value` type.
 pub fn to_value<T>(value: T) -> ClientResult<Value>
@@ -57,6 +60,11 @@ pub struct BitcoinClient {
     client: Client,
     /// The ID of the current request.
     id: AtomicUsize,
+    /// Permits for requests in flight, one per pooled connection, so we never
+    /// open more connections than the pool keeps around.
+    conn_permits: Semaphore,
+    /// How many times a request that failed on a recoverable error is retried.
+    max_retries: u8,
 }
 
 /// Response returned by the `bitcoind` RPC server.
@@ -67,13 +75,42 @@ struct Response<R> {
     pub id: u64,
 }
 
+impl<R> Response<R> {
+    fn into_result(self) -> ClientResult<R> {
+        if let Some(err) = self.error {
+            return Err(ClientError::Server(err.code, err.message));
+        }
+        self.result
+            .ok_or_else(|| ClientError::Other("Empty data received".to_string()))
+    }
+}
+
 impl BitcoinClient {
     /// Creates a new [`BitcoinClient`] with the given URL, username, and password.
     pub fn new(url: String, username: String, password: String) -> ClientResult<Self> {
+        Self::new_with_pool_size(url, username, password, DEFAULT_RPC_POOL_SIZE)
+    }
+
+    /// Creates a new [`BitcoinClient`] that reuses up to `pool_size` connections to `bitcoind`.
+    ///
+    /// Calls beyond that many at once wait for one of the connections to free up instead of
+    /// opening another one.
+    pub fn new_with_pool_size(
+        url: String,
+        username: String,
+        password: String,
+        pool_size: usize,
+    ) -> ClientResult<Self> {
         if username.is_empty() || password.is_empty() {
             return Err(ClientError::MissingUserPassword);
         }
 
+        if pool_size == 0 {
+            return Err(ClientError::Other(
+                "RPC pool size must be nonzero".to_string(),
+            ));
+        }
+
         let user_pw = general_purpose::STANDARD.encode(format!("{username}:{password}"));
         let authorization = format!("Basic {user_pw}")
             .parse()
@@ -89,14 +126,31 @@ impl BitcoinClient {
 
         let client = Client::builder()
             .default_headers(headers)
+            .pool_max_idle_per_host(pool_size)
             .build()
             .map_err(|e| ClientError::Other(format!("Could not create client: {e}")))?;
 
         let id = AtomicUsize::new(0);
 
-        trace!(url = %url, "Created bitcoin client");
+        trace!(url = %url, %pool_size, "Created bitcoin client");
 
-        Ok(Self { url, client, id })
+        Ok(Self {
+            url,
+            client,
+            id,
+            conn_permits: Semaphore::new(pool_size),
+            max_retries: DEFAULT_MAX_RETRIES,
+        })
+    }
+
+    /// Sets how many times a request that failed on a recoverable error is retried.
+    ///
+    /// With `0` the error itself is returned rather than
+    /// [`ClientError::MaxRetriesExceeded`], so a caller doing its own retrying, like
+    /// [`RetryingL1Client`](super::retry::RetryingL1Client), can tell what went wrong.
+    pub fn with_max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
     }
 
     fn next_id(&self) -> usize {
@@ -108,41 +162,102 @@ impl BitcoinClient {
         method: &str,
         params: &[Value],
     ) -> ClientResult<T> {
+        trace!(%method, ?params, "Calling bitcoin client");
+        let data = self
+            .post::<Response<T>>(method, || {
+                json!({
+                    "jsonrpc": "1.0",
+                    "id": self.next_id(),
+                    "method": method,
+                    "params": params
+                })
+            })
+            .await?;
+        data.into_result()
+    }
+
+    /// Calls the method once for each of the params, all in a single batched request, returning
+    /// the results in the same order as the params.
+    ///
+    /// The outer error is for the batch as a whole, the inner ones for the individual calls.
+    async fn call_batch<T: de::DeserializeOwned + fmt::Debug>(
+        &self,
+        method: &str,
+        params: &[Vec<Value>],
+    ) -> ClientResult<Vec<ClientResult<T>>> {
+        if params.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        trace!(%method, ?params, "Calling bitcoin client in batch");
+        let ids: Vec<usize> = params.iter().map(|_| self.next_id()).collect();
+        let mut responses = self
+            .post::<Vec<Response<T>>>(method, || {
+                let reqs = ids
+                    .iter()
+                    .zip(params)
+                    .map(|(id, params)| {
+                        json!({
+                            "jsonrpc": "1.0",
+                            "id": id,
+                            "method": method,
+                            "params": params
+                        })
+                    })
+                    .collect();
+                Value::Array(reqs)
+            })
+            .await?;
+
+        // The responses can come back in any order, so match them up by id.
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            let pos = responses
+                .iter()
+                .position(|resp| resp.id == id as u64)
+                .ok_or_else(|| {
+                    ClientError::MalformedResponse(format!("no response to batched request {id}"))
+                })?;
+            results.push(responses.swap_remove(pos).into_result());
+        }
+
+        Ok(results)
+    }
+
+    /// Posts the request body, made anew for each attempt, and decodes the response body,
+    /// retrying the errors that might be recoverable.
+    async fn post<R: de::DeserializeOwned>(
+        &self,
+        method: &str,
+        make_body: impl Fn() -> Value,
+    ) -> ClientResult<R> {
         let mut retries = 0;
         loop {
-            trace!(%method, ?params, %retries, "Calling bitcoin client");
+            trace!(%method, %retries, "Posting bitcoin client request");
 
-            let id = self.next_id();
+            let body = make_body();
 
-            let response = self
-                .client
-                .post(&self.url)
-                .json(&json!({
-                    "jsonrpc": "1.0",
-                    "id": id,
-                    "method": method,
-                    "params": params
-                }))
-                .send()
-                .await;
+            // Held until the response body is read, which is when the connection goes back to
+            // the pool.
+            let _permit = self
+                .conn_permits
+                .acquire()
+                .await
+                .expect("btcio: connection permits never closed");
+
+            let response = self.client.post(&self.url).json(&body).send().await;
             trace!(?response, "Response received");
             match response {
                 Ok(resp) => {
-                    let data = resp
-                        .json::<Response<T>>()
+                    return resp
+                        .json::<R>()
                         .await
-                        .map_err(|e| ClientError::Parse(e.to_string()))?;
-                    if let Some(err) = data.error {
-                        return Err(ClientError::Server(err.code, err.message));
-                    }
-                    return data
-                        .result
-                        .ok_or_else(|| ClientError::Other("Empty data received".to_string()));
+                        .map_err(|e| ClientError::Parse(e.to_string()));
                 }
                 Err(err) => {
                     warn!(err = %err, "Error calling bitcoin client");
 
-                    if err.is_body() {
+                    let e = if err.is_body() {
                         // Body error is unrecoverable
                         return Err(ClientError::Body(err.to_string()));
                     } else if err.is_status() {
@@ -154,20 +269,16 @@ impl BitcoinClient {
                         return Err(e);
                     } else if err.is_decode() {
                         // Error decoding response, might be recoverable
-                        let e = ClientError::MalformedResponse(err.to_string());
-                        warn!(%e, "decoding error, retrying...");
+                        ClientError::MalformedResponse(err.to_string())
                     } else if err.is_connect() {
                         // Connection error, might be recoverable
-                        let e = ClientError::Connection(err.to_string());
-                        warn!(%e, "connection error, retrying...");
+                        ClientError::Connection(err.to_string())
                     } else if err.is_timeout() {
                         // Timeout error, might be recoverable
-                        let e = ClientError::Timeout;
-                        warn!(%e, "timeout error, retrying...");
+                        ClientError::Timeout
                     } else if err.is_request() {
                         // General request error, might be recoverable
-                        let e = ClientError::Request(err.to_string());
-                        warn!(%e, "request error, retrying...");
+                        ClientError::Request(err.to_string())
                     } else if err.is_builder() {
                         // Request builder error is unrecoverable
                         return Err(ClientError::ReqBuilder(err.to_string()));
@@ -177,13 +288,18 @@ impl BitcoinClient {
                     } else {
                         // Unknown error is unrecoverable
                         return Err(ClientError::Other("Unknown error".to_string()));
+                    };
+
+                    if self.max_retries == 0 {
+                        return Err(e);
+                    }
+                    if retries >= self.max_retries {
+                        return Err(ClientError::MaxRetriesExceeded(self.max_retries));
                     }
+                    warn!(%e, %retries, "recoverable error, retrying...");
                 }
             }
             retries += 1;
-            if retries >= MAX_RETRIES {
-                return Err(ClientError::MaxRetriesExceeded(MAX_RETRIES));
-            }
             sleep(Duration::from_millis(1_000)).await;
         }
     }
@@ -270,7 +386,7 @@ impl Broadcaster for BitcoinClient {
                 -27 => Ok(tx.compute_txid()), // Tx already in chain
                 _ => Err(ClientError::Server(i, s)),
             },
-            Err(e) => Err(ClientError::Other(e.to_string())),
+            Err(e) => Err(e),
         }
     }
 }
@@ -293,6 +409,18 @@ impl Wallet for BitcoinClient {
             .await?)
     }
 
+    async fn get_transactions_batch(
+        &self,
+        txids: &[Txid],
+    ) -> ClientResult<Vec<ClientResult<GetTransaction>>> {
+        let params = txids
+            .iter()
+            .map(|txid| Ok(vec![to_value(txid.to_string())?]))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.call_batch::<GetTransaction>("gettransaction", &params)
+            .await
+    }
+
     async fn get_utxos(&self) -> ClientResult<Vec<ListUnspent>> {
         let resp = self.call::<Vec<ListUnspent>>("listunspent", &[]).await?;
         trace!(?resp, "Got UTXOs");
@@ -387,11 +515,15 @@ impl Signer for BitcoinClient {
 
 #[cfg(test)]
 mod test {
-    use std::env::set_var;
+    use std::{env::set_var, sync::Arc};
 
     use bitcoin::{consensus, hashes::Hash, NetworkKind};
     use corepc_node::BitcoinD;
     use strata_common::logging;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::{TcpListener, TcpStream},
+    };
 
     use super::*;
 
@@ -539,4 +671,207 @@ mod test {
         let expected = vec![ImportDescriptorResult { success: true }];
         assert_eq!(expected, got);
     }
+
+    /// Connections a [`spawn_rpc_server`] accepted, the most it had open at once, and the HTTP
+    /// requests it served.
+    #[derive(Debug, Default)]
+    struct ConnStats {
+        accepted: AtomicUsize,
+        open: AtomicUsize,
+        max_open: AtomicUsize,
+        requests: AtomicUsize,
+    }
+
+    type Responder = fn(&Value) -> Value;
+
+    /// Answers with a block count of 100.
+    fn block_count_responder(req: &Value) -> Value {
+        json!({ "result": 100, "error": null, "id": req["id"] })
+    }
+
+    /// Spawns a minimal JSON-RPC server answering every request with the responder after the
+    /// delay, keeping connections alive.
+    async fn spawn_rpc_server(delay: Duration, respond: Responder) -> (String, Arc<ConnStats>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let stats = Arc::new(ConnStats::default());
+
+        let server_stats = stats.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let stats = server_stats.clone();
+                stats.accepted.fetch_add(1, Ordering::SeqCst);
+                let open = stats.open.fetch_add(1, Ordering::SeqCst) + 1;
+                stats.max_open.fetch_max(open, Ordering::SeqCst);
+
+                tokio::spawn(async move {
+                    serve_conn(stream, delay, respond, &stats).await;
+                    stats.open.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        (url, stats)
+    }
+
+    async fn serve_conn(stream: TcpStream, delay: Duration, respond: Responder, stats: &ConnStats) {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut content_len = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                    return;
+                }
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_len = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_len];
+            if reader.read_exact(&mut body).await.is_err() {
+                return;
+            }
+
+            stats.requests.fetch_add(1, Ordering::SeqCst);
+            sleep(delay).await;
+            let req: Value = serde_json::from_slice(&body).unwrap();
+            let resp_body = respond(&req).to_string();
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{resp_body}",
+                resp_body.len()
+            );
+            if reader.get_mut().write_all(resp.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connections_reused() {
+        let (url, stats) = spawn_rpc_server(Duration::ZERO, block_count_responder).await;
+        let client = BitcoinClient::new(url, "user".into(), "pass".into()).unwrap();
+
+        for _ in 0..5 {
+            assert_eq!(client.get_block_count().await.unwrap(), 100);
+        }
+
+        assert_eq!(stats.accepted.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pool_size_bound() {
+        let (url, stats) = spawn_rpc_server(Duration::from_millis(50), block_count_responder).await;
+        let client = Arc::new(
+            BitcoinClient::new_with_pool_size(url, "user".into(), "pass".into(), 2).unwrap(),
+        );
+
+        let calls: Vec<_> = (0..6)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.get_block_count().await })
+            })
+            .collect();
+        for call in calls {
+            assert_eq!(call.await.unwrap().unwrap(), 100);
+        }
+
+        assert!(stats.max_open.load(Ordering::SeqCst) <= 2);
+    }
+
+    /// Answers a batch of `gettransaction` calls in reverse order, with the first byte of each
+    /// txid as its confirmations and txids of all `0xff` not found.
+    fn gettransaction_batch_responder(req: &Value) -> Value {
+        let tx = Transaction {
+            version: bitcoin::transaction::Version(2),
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![bitcoin::TxIn::default()],
+            output: vec![],
+        };
+
+        let resps = req
+            .as_array()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|req| {
+                let txid: Txid = req["params"][0].as_str().unwrap().parse().unwrap();
+                if txid == Txid::from_byte_array([0xff; 32]) {
+                    let err =
+                        json!({ "code": -5, "message": "Invalid or non-wallet transaction id" });
+                    return json!({ "result": null, "error": err, "id": req["id"] });
+                }
+
+                let info = json!({
+                    "amount": 1.5,
+                    "confirmations": txid.to_byte_array()[0],
+                    "txid": txid.to_string(),
+                    "wtxid": txid.to_string(),
+                    "walletconflicts": [],
+                    "time": 0,
+                    "timereceived": 0,
+                    "bip125-replaceable": "no",
+                    "details": [],
+                    "hex": serialize_hex(&tx),
+                });
+                json!({ "result": info, "error": null, "id": req["id"] })
+            })
+            .collect();
+        Value::Array(resps)
+    }
+
+    #[tokio::test]
+    async fn test_get_transactions_batch() {
+        let (url, stats) = spawn_rpc_server(Duration::ZERO, gettransaction_batch_responder).await;
+        let client = BitcoinClient::new(url, "user".into(), "pass".into()).unwrap();
+
+        let txids = [[3; 32], [0xff; 32], [7; 32]].map(Txid::from_byte_array);
+        let txs = client.get_transactions_batch(&txids).await.unwrap();
+
+        // all of them in a single request, in the order asked for
+        assert_eq!(stats.requests.load(Ordering::SeqCst), 1);
+        assert_eq!(txs.len(), 3);
+        assert_eq!(txs[0].as_ref().unwrap().txid, txids[0]);
+        assert_eq!(txs[0].as_ref().unwrap().confirmations, 3);
+        assert!(txs[1].as_ref().unwrap_err().is_tx_not_found());
+        assert_eq!(txs[2].as_ref().unwrap().txid, txids[2]);
+        assert_eq!(txs[2].as_ref().unwrap().confirmations, 7);
+
+        // nothing to ask for doesn't need a request
+        assert!(client.get_transactions_batch(&[]).await.unwrap().is_empty());
+        assert_eq!(stats.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_retries_returns_error() {
+        // nothing listening there anymore
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = BitcoinClient::new(url, "user".into(), "pass".into())
+            .unwrap()
+            .with_max_retries(0);
+        let res = client.get_block_count().await;
+        assert!(matches!(res, Err(ClientError::Connection(_))));
+        assert!(res.unwrap_err().is_retryable());
+    }
+
+    #[test]
+    fn test_zero_pool_size() {
+        let res = BitcoinClient::new_with_pool_size(
+            "http://localhost:18443".into(),
+            "user".into(),
+            "pass".into(),
+            0,
+        );
+        assert!(matches!(res, Err(ClientError::Other(_))));
+    }
 }
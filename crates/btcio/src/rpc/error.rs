@@ -104,6 +104,86 @@ impl ClientError {
     pub fn is_missing_or_invalid_input(&self) -> bool {
         matches!(self, Self::Server(-26, _)) || matches!(self, Self::Server(-25, _))
     }
+
+    /// Returns if the node rejected the tx because it already has it, either in its mempool or in
+    /// the chain, i.e. it was published after all.
+    pub fn is_tx_already_known(&self) -> bool {
+        const REASONS: [&str; 4] = [
+            "txn-already-in-mempool",
+            "txn-already-known",
+            "already in block chain",
+            "already in utxo set",
+        ];
+        match self {
+            Self::Server(-27, _) => true,
+            Self::Server(-26 | -25, reason) => REASONS.iter().any(|r| reason.contains(r)),
+            _ => false,
+        }
+    }
+
+    /// Returns if the node rejected the tx because its inputs are missing, already spent or
+    /// spent by a conflicting tx in its mempool, so it can only go through if signed again over
+    /// other inputs.
+    pub fn is_input_conflict(&self) -> bool {
+        const REASONS: [&str; 5] = [
+            "bad-txns-inputs-missingorspent",
+            "missing-inputs",
+            "Missing inputs",
+            "txn-mempool-conflict",
+            "bad-txns-spends-conflicting-tx",
+        ];
+        match self {
+            Self::Server(-26 | -25, reason) => REASONS.iter().any(|r| reason.contains(r)),
+            _ => false,
+        }
+    }
+
+    /// Classifies the error by how callers should react to it.
+    pub fn kind(&self) -> ClientErrorKind {
+        match self {
+            Self::Network(_)
+            | Self::Connection(_)
+            | Self::Timeout
+            | Self::Request(_)
+            | Self::MaxRetriesExceeded(_) => ClientErrorKind::Transport,
+            Self::MissingUserPassword => ClientErrorKind::Auth,
+            Self::Status(code, _) if code.starts_with("401") || code.starts_with("403") => {
+                ClientErrorKind::Auth
+            }
+            Self::Server(-32601, _) => ClientErrorKind::MethodNotFound,
+            Self::Server(-26 | -25, reason) => ClientErrorKind::MempoolReject(reason.clone()),
+            Self::Server(-5, _) => ClientErrorKind::NotFound,
+            _ => ClientErrorKind::Other,
+        }
+    }
+
+    /// Returns if the request might succeed when retried as is, i.e. the node couldn't be
+    /// reached, as opposed to it rejecting the request.
+    pub fn is_retryable(&self) -> bool {
+        self.kind() == ClientErrorKind::Transport
+    }
+}
+
+/// Broad categories of [`ClientError`]s, see [`ClientError::kind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientErrorKind {
+    /// The node could not be reached or did not respond in time.
+    Transport,
+
+    /// The node rejected our credentials.
+    Auth,
+
+    /// The node does not support the RPC method, e.g. the wallet is disabled.
+    MethodNotFound,
+
+    /// The transaction was rejected from the mempool for the given reason.
+    MempoolReject(String),
+
+    /// The requested transaction or block is unknown to the node.
+    NotFound,
+
+    /// Anything else, like malformed responses.
+    Other,
 }
 
 impl From<SerdeJsonError> for ClientError {
@@ -180,3 +260,91 @@ impl fmt::Display for UnexpectedServerVersionError {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_error_kind() {
+        let cases = [
+            (
+                ClientError::Connection("refused".into()),
+                ClientErrorKind::Transport,
+            ),
+            (ClientError::Timeout, ClientErrorKind::Transport),
+            (
+                ClientError::MaxRetriesExceeded(3),
+                ClientErrorKind::Transport,
+            ),
+            (ClientError::MissingUserPassword, ClientErrorKind::Auth),
+            (
+                ClientError::Status("401 Unauthorized".into(), "".into()),
+                ClientErrorKind::Auth,
+            ),
+            (
+                ClientError::Server(-32601, "Method not found".into()),
+                ClientErrorKind::MethodNotFound,
+            ),
+            (
+                ClientError::Server(-26, "min relay fee not met".into()),
+                ClientErrorKind::MempoolReject("min relay fee not met".into()),
+            ),
+            (
+                ClientError::Server(-5, "No such mempool or blockchain transaction".into()),
+                ClientErrorKind::NotFound,
+            ),
+            (
+                ClientError::Status("500 Internal Server Error".into(), "".into()),
+                ClientErrorKind::Other,
+            ),
+            (
+                ClientError::Parse("bad json".into()),
+                ClientErrorKind::Other,
+            ),
+        ];
+
+        for (err, kind) in cases {
+            assert_eq!(err.kind(), kind, "{err}");
+            assert_eq!(
+                err.is_retryable(),
+                kind == ClientErrorKind::Transport,
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mempool_reject_reasons() {
+        let known = [
+            ClientError::Server(-26, "txn-already-in-mempool".into()),
+            ClientError::Server(-26, "txn-already-known".into()),
+            ClientError::Server(-25, "Transaction already in block chain".into()),
+            ClientError::Server(-27, "Transaction outputs already in utxo set".into()),
+        ];
+        for err in known {
+            assert!(err.is_tx_already_known(), "{err}");
+            assert!(!err.is_input_conflict(), "{err}");
+        }
+
+        let conflicts = [
+            ClientError::Server(-25, "bad-txns-inputs-missingorspent".into()),
+            ClientError::Server(-25, "Missing inputs".into()),
+            ClientError::Server(-26, "txn-mempool-conflict".into()),
+        ];
+        for err in conflicts {
+            assert!(err.is_input_conflict(), "{err}");
+            assert!(!err.is_tx_already_known(), "{err}");
+        }
+
+        let others = [
+            ClientError::Server(-26, "min relay fee not met".into()),
+            ClientError::Server(-5, "txn-already-known".into()),
+            ClientError::Timeout,
+        ];
+        for err in others {
+            assert!(!err.is_tx_already_known(), "{err}");
+            assert!(!err.is_input_conflict(), "{err}");
+        }
+    }
+}
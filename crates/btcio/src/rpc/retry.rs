@@ -0,0 +1,287 @@
+//! Retrying RPC calls that failed on transport errors, so a short blip talking
+//! to the node doesn't fail whatever was calling it.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bitcoin::{bip32::Xpriv, Address, Block, BlockHash, Network, Transaction, Txid};
+use tokio::time::sleep;
+use tracing::*;
+
+use super::{
+    client::ClientResult,
+    traits::{Broadcaster, Reader, Signer, Wallet},
+    types::{
+        GetBlockchainInfo, GetTransaction, ImportDescriptor, ImportDescriptorResult,
+        ListTransactions, ListUnspent, SignRawTransactionWithWallet,
+    },
+};
+
+/// How many times and how far apart calls are retried.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Most times a call is made, including the first one.
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubling with each one after.
+    pub base_delay: Duration,
+
+    /// Longest delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl RetryConfig {
+    /// Returns the delay before the given retry, counting from 1.
+    fn delay(&self, retry: u32) -> Duration {
+        let mult = 1u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(mult).min(self.max_delay)
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Wraps a client to retry calls that failed on transport errors, see
+/// [`ClientError::is_retryable`](super::error::ClientError::is_retryable).  Any
+/// other error is returned right away, as is the last one once the attempts
+/// run out.
+///
+/// A wrapped [`BitcoinClient`](super::BitcoinClient) should have its own
+/// retries turned off with
+/// [`with_max_retries(0)`](super::BitcoinClient::with_max_retries), otherwise
+/// the attempts multiply.
+///
+/// # Note
+///
+/// If a broadcast reached the node but the response didn't make it back, the
+/// retry is rejected as already being in the mempool.
+#[derive(Debug)]
+pub struct RetryingL1Client<C> {
+    inner: Arc<C>,
+    config: RetryConfig,
+}
+
+impl<C> RetryingL1Client<C> {
+    pub fn new(inner: Arc<C>, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    async fn retry<T, F, Fut>(&self, method: &str, call: F) -> ClientResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Err(err) if err.is_retryable() && attempt < self.config.max_attempts => {
+                    let delay = self.config.delay(attempt);
+                    warn!(%method, %attempt, ?delay, %err, "L1 client call failed, retrying");
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<C: Reader + Send + Sync> Reader for RetryingL1Client<C> {
+    async fn estimate_smart_fee(&self, conf_target: u16) -> ClientResult<u64> {
+        self.retry("estimate_smart_fee", || {
+            self.inner.estimate_smart_fee(conf_target)
+        })
+        .await
+    }
+
+    async fn get_block(&self, hash: &BlockHash) -> ClientResult<Block> {
+        self.retry("get_block", || self.inner.get_block(hash)).await
+    }
+
+    async fn get_block_at(&self, height: u64) -> ClientResult<Block> {
+        self.retry("get_block_at", || self.inner.get_block_at(height))
+            .await
+    }
+
+    async fn get_block_count(&self) -> ClientResult<u64> {
+        self.retry("get_block_count", || self.inner.get_block_count())
+            .await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> ClientResult<BlockHash> {
+        self.retry("get_block_hash", || self.inner.get_block_hash(height))
+            .await
+    }
+
+    async fn get_blockchain_info(&self) -> ClientResult<GetBlockchainInfo> {
+        self.retry("get_blockchain_info", || self.inner.get_blockchain_info())
+            .await
+    }
+
+    async fn get_raw_mempool(&self) -> ClientResult<Vec<Txid>> {
+        self.retry("get_raw_mempool", || self.inner.get_raw_mempool())
+            .await
+    }
+
+    async fn network(&self) -> ClientResult<Network> {
+        self.retry("network", || self.inner.network()).await
+    }
+}
+
+#[async_trait]
+impl<C: Broadcaster + Send + Sync> Broadcaster for RetryingL1Client<C> {
+    async fn send_raw_transaction(&self, tx: &Transaction) -> ClientResult<Txid> {
+        self.retry("send_raw_transaction", || {
+            self.inner.send_raw_transaction(tx)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl<C: Wallet + Send + Sync> Wallet for RetryingL1Client<C> {
+    async fn get_new_address(&self) -> ClientResult<Address> {
+        self.retry("get_new_address", || self.inner.get_new_address())
+            .await
+    }
+
+    async fn get_transaction(&self, txid: &Txid) -> ClientResult<GetTransaction> {
+        self.retry("get_transaction", || self.inner.get_transaction(txid))
+            .await
+    }
+
+    async fn get_transactions_batch(
+        &self,
+        txids: &[Txid],
+    ) -> ClientResult<Vec<ClientResult<GetTransaction>>> {
+        self.retry("get_transactions_batch", || {
+            self.inner.get_transactions_batch(txids)
+        })
+        .await
+    }
+
+    async fn get_utxos(&self) -> ClientResult<Vec<ListUnspent>> {
+        self.retry("get_utxos", || self.inner.get_utxos()).await
+    }
+
+    async fn list_transactions(&self, count: Option<usize>) -> ClientResult<Vec<ListTransactions>> {
+        self.retry("list_transactions", || self.inner.list_transactions(count))
+            .await
+    }
+
+    async fn list_wallets(&self) -> ClientResult<Vec<String>> {
+        self.retry("list_wallets", || self.inner.list_wallets())
+            .await
+    }
+}
+
+#[async_trait]
+impl<C: Signer + Send + Sync> Signer for RetryingL1Client<C> {
+    async fn sign_raw_transaction_with_wallet(
+        &self,
+        tx: &Transaction,
+    ) -> ClientResult<SignRawTransactionWithWallet> {
+        self.retry("sign_raw_transaction_with_wallet", || {
+            self.inner.sign_raw_transaction_with_wallet(tx)
+        })
+        .await
+    }
+
+    async fn get_xpriv(&self) -> ClientResult<Option<Xpriv>> {
+        self.retry("get_xpriv", || self.inner.get_xpriv()).await
+    }
+
+    async fn import_descriptors(
+        &self,
+        descriptors: Vec<ImportDescriptor>,
+        wallet_name: String,
+    ) -> ClientResult<Vec<ImportDescriptorResult>> {
+        self.retry("import_descriptors", || {
+            self.inner
+                .import_descriptors(descriptors.clone(), wallet_name.clone())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::error::ClientError,
+        test_utils::{MockL1Client, MockRpc},
+    };
+
+    fn config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transport_errors() {
+        let inner = Arc::new(MockL1Client::new());
+        inner.fail_next(MockRpc::GetBlockCount, 2, ClientError::Timeout);
+        let client = RetryingL1Client::new(inner.clone(), config(3));
+
+        assert_eq!(client.get_block_count().await.unwrap(), 0);
+        assert_eq!(inner.calls(MockRpc::GetBlockCount), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let inner = Arc::new(MockL1Client::new());
+        inner.fail_next(
+            MockRpc::GetBlockCount,
+            3,
+            ClientError::Connection("refused".into()),
+        );
+        let client = RetryingL1Client::new(inner.clone(), config(3));
+
+        let res = client.get_block_count().await;
+        assert!(matches!(res, Err(ClientError::Connection(_))));
+        assert_eq!(inner.calls(MockRpc::GetBlockCount), 3);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_errors_not_retried() {
+        let err = ClientError::Server(-32601, "Method not found".into());
+        let inner = Arc::new(MockL1Client::new());
+        inner.fail_next(MockRpc::GetBlockCount, 1, err.clone());
+        let client = RetryingL1Client::new(inner.clone(), config(3));
+
+        assert_eq!(client.get_block_count().await, Err(err));
+        assert_eq!(inner.calls(MockRpc::GetBlockCount), 1);
+    }
+
+    #[test]
+    fn test_delay_backoff() {
+        let config = RetryConfig {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+        };
+
+        assert_eq!(config.delay(1), Duration::from_millis(100));
+        assert_eq!(config.delay(2), Duration::from_millis(200));
+        assert_eq!(config.delay(3), Duration::from_millis(400));
+        assert_eq!(config.delay(4), Duration::from_millis(500));
+        assert_eq!(config.delay(40), Duration::from_millis(500));
+    }
+}
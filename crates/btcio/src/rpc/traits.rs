@@ -114,6 +114,26 @@ pub trait Wallet {
     /// client's wallet.
     async fn get_transaction(&self, txid: &Txid) -> ClientResult<GetTransaction>;
 
+    /// Gets information related to each of the transactions, in the same
+    /// order, in as few round trips to the node as the client can manage.
+    ///
+    /// The outer error is for the request as a whole, the inner ones for the
+    /// individual transactions, e.g. if one isn't in the wallet.
+    ///
+    /// # Note
+    ///
+    /// By default this just calls [`Self::get_transaction`] for each of them.
+    async fn get_transactions_batch(
+        &self,
+        txids: &[Txid],
+    ) -> ClientResult<Vec<ClientResult<GetTransaction>>> {
+        let mut txs = Vec::with_capacity(txids.len());
+        for txid in txids {
+            txs.push(self.get_transaction(txid).await);
+        }
+        Ok(txs)
+    }
+
     /// Gets all Unspent Transaction Outputs (UTXOs) for the underlying Bitcoin
     /// client's wallet.
     async fn get_utxos(&self) -> ClientResult<Vec<ListUnspent>>;
@@ -1,4 +1,7 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use bitcoin::Txid;
+use strata_primitives::{buf::Buf32, l1::InscriptionStage};
 use strata_status::StatusChannel;
 
 #[derive(Debug, Clone)]
@@ -10,6 +13,8 @@ pub enum L1StatusUpdate {
     CurTip(String),
     LastPublishedTxid(Txid),
     IncrementInscriptionCount,
+    /// Blob at the index, inscribed with the reveal txid, reached the stage.
+    InscriptionStage(u64, Buf32, InscriptionStage),
 }
 
 pub async fn apply_status_updates(st_updates: &[L1StatusUpdate], st_chan: &StatusChannel) {
@@ -22,11 +27,22 @@ pub async fn apply_status_updates(st_updates: &[L1StatusUpdate], st_chan: &Statu
             L1StatusUpdate::RpcError(err_string) => {
                 l1_status.last_rpc_error = Some(err_string.clone())
             }
-            L1StatusUpdate::CurTip(tip) => l1_status.cur_tip_blkid = tip.clone(),
+            L1StatusUpdate::CurTip(tip) => {
+                if l1_status.cur_tip_blkid != *tip {
+                    l1_status.last_seen_block_time_ms = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as u64;
+                }
+                l1_status.cur_tip_blkid = tip.clone()
+            }
             L1StatusUpdate::LastPublishedTxid(txid) => {
                 l1_status.last_published_txid = Some(Into::into(*txid))
             }
             L1StatusUpdate::IncrementInscriptionCount => l1_status.published_inscription_count += 1,
+            L1StatusUpdate::InscriptionStage(idx, reveal_txid, stage) => {
+                l1_status.inscriptions.update(*idx, *reveal_txid, *stage)
+            }
         }
     }
 
@@ -20,6 +20,10 @@ use crate::{
     writer::builder::{build_reveal_transaction, generate_inscription_script, InscriptionError},
 };
 
+mod mock_client;
+
+pub use mock_client::{MockL1Client, MockRpc};
+
 /// A test implementation of a Bitcoin client.
 #[derive(Debug, Clone)]
 pub struct TestBitcoinClient {
@@ -0,0 +1,663 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use bitcoin::{
+    absolute::LockTime,
+    bip32::Xpriv,
+    block::{Header, Version as BVersion},
+    consensus,
+    hashes::Hash,
+    transaction::Version,
+    Address, Amount, Block, BlockHash, CompactTarget, Network, OutPoint, SignedAmount, Transaction,
+    TxIn, TxMerkleNode, TxOut, Txid, Work,
+};
+
+use crate::rpc::{
+    error::ClientError,
+    traits::{Broadcaster, Reader, Signer, Wallet},
+    types::{
+        GetBlockchainInfo, GetTransaction, ImportDescriptor, ImportDescriptorResult,
+        ListTransactions, ListUnspent, SignRawTransactionWithWallet,
+    },
+    ClientResult,
+};
+
+/// Address the funding utxos of the wallet are locked to.
+const WALLET_ADDR: &str = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+
+/// Number of funding utxos the wallet starts with.
+const NUM_FUNDING_UTXOS: u8 = 10;
+
+/// Fee rate estimated until set otherwise, in sat/vB.
+const DEFAULT_FEE_ESTIMATE: u64 = 3;
+
+/// Fake bitcoin node with a mempool and a chain of blocks that tests drive by
+/// hand, so the writer and reader can be tested end to end without regtest.
+///
+/// The wallet starts out with a few confirmed funding utxos.  Txs are accepted
+/// into the mempool if all their inputs exist and aren't spent by another tx,
+/// and are mined in the order they were accepted with [`Self::mine_blocks`].
+/// Reorgs are done by disconnecting blocks with [`Self::disconnect_blocks`],
+/// which puts their txs back in the mempool like `invalidateblock` does.
+///
+/// Calls can be made to fail with [`Self::fail_next`] and [`Self::fail_always`]
+/// to test how callers deal with a flaky or misbehaving node, and are counted
+/// for [`Self::calls`].
+#[derive(Debug)]
+pub struct MockL1Client {
+    chain: Mutex<MockChain>,
+    rpcs: Mutex<MockRpcs>,
+}
+
+/// RPC methods of [`MockL1Client`], to make them fail or count their calls.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MockRpc {
+    EstimateSmartFee,
+    GetBlock,
+    GetBlockAt,
+    GetBlockCount,
+    GetBlockHash,
+    GetBlockchainInfo,
+    GetRawMempool,
+    Network,
+    SendRawTransaction,
+    GetNewAddress,
+    GetTransaction,
+    GetUtxos,
+    ListTransactions,
+    ListWallets,
+    SignRawTransactionWithWallet,
+    GetXpriv,
+    ImportDescriptors,
+}
+
+#[derive(Debug, Default)]
+struct MockRpcs {
+    /// Number of times each method was called, including failed calls.
+    calls: HashMap<MockRpc, u32>,
+
+    /// Methods that fail, with the number of calls left to fail or `None` if
+    /// they keep failing.
+    failures: HashMap<MockRpc, (Option<u32>, ClientError)>,
+}
+
+#[derive(Debug)]
+struct MockChain {
+    /// Blocks of the main chain, by height.
+    blocks: Vec<Block>,
+
+    /// Txs in the mempool, in the order they were accepted.
+    mempool: Vec<Transaction>,
+
+    /// Height of the block each tx of the main chain was mined in.
+    tx_heights: HashMap<Txid, u64>,
+
+    /// Utxos the wallet was funded with.
+    funding_utxos: HashMap<OutPoint, TxOut>,
+
+    /// Number of blocks mined so far, including disconnected ones.
+    num_mined: u32,
+
+    /// Fee rate answered to estimations, in sat/vB.
+    fee_estimate: u64,
+}
+
+impl MockL1Client {
+    /// Creates a node with only a genesis block and a funded wallet.
+    pub fn new() -> Self {
+        let wallet_spk = parse_addr(WALLET_ADDR).script_pubkey();
+        let funding_utxos = (1..=NUM_FUNDING_UTXOS)
+            .map(|i| {
+                let outpoint = OutPoint::new(Txid::from_byte_array([i; 32]), 0);
+                let txout = TxOut {
+                    value: Amount::from_btc(100.0).unwrap(),
+                    script_pubkey: wallet_spk.clone(),
+                };
+                (outpoint, txout)
+            })
+            .collect();
+
+        let mut chain = MockChain {
+            blocks: Vec::new(),
+            mempool: Vec::new(),
+            tx_heights: HashMap::new(),
+            funding_utxos,
+            num_mined: 0,
+            fee_estimate: DEFAULT_FEE_ESTIMATE,
+        };
+        chain.mine_block();
+
+        Self {
+            chain: Mutex::new(chain),
+            rpcs: Mutex::new(MockRpcs::default()),
+        }
+    }
+
+    /// Makes the next `n` calls of the method fail with the error.
+    pub fn fail_next(&self, rpc: MockRpc, n: u32, err: ClientError) {
+        let mut rpcs = self.rpcs.lock().unwrap();
+        rpcs.failures.insert(rpc, (Some(n), err));
+    }
+
+    /// Makes every call of the method fail with the error, until
+    /// [`Self::clear_failures`].
+    pub fn fail_always(&self, rpc: MockRpc, err: ClientError) {
+        let mut rpcs = self.rpcs.lock().unwrap();
+        rpcs.failures.insert(rpc, (None, err));
+    }
+
+    /// Makes every method work again.
+    pub fn clear_failures(&self) {
+        self.rpcs.lock().unwrap().failures.clear();
+    }
+
+    /// Returns the number of times the method was called, including failed
+    /// calls.
+    pub fn calls(&self, rpc: MockRpc) -> u32 {
+        let rpcs = self.rpcs.lock().unwrap();
+        rpcs.calls.get(&rpc).copied().unwrap_or(0)
+    }
+
+    /// Counts a call of the method, failing it if it's set to fail.
+    fn enter(&self, rpc: MockRpc) -> ClientResult<()> {
+        let mut rpcs = self.rpcs.lock().unwrap();
+        *rpcs.calls.entry(rpc).or_default() += 1;
+
+        let Some((left, err)) = rpcs.failures.get_mut(&rpc) else {
+            return Ok(());
+        };
+        let err = err.clone();
+        match left {
+            None => Err(err),
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                Err(err)
+            }
+            Some(_) => {
+                rpcs.failures.remove(&rpc);
+                Ok(())
+            }
+        }
+    }
+
+    /// Builds a tx spending the given outpoints to the wallet address, which
+    /// the node accepts as long as they exist and aren't spent yet.
+    pub fn build_spending_tx(inputs: &[OutPoint]) -> Transaction {
+        Transaction {
+            version: Version(2),
+            lock_time: LockTime::ZERO,
+            input: inputs
+                .iter()
+                .map(|outpoint| TxIn {
+                    previous_output: *outpoint,
+                    ..Default::default()
+                })
+                .collect(),
+            output: vec![TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: parse_addr(WALLET_ADDR).script_pubkey(),
+            }],
+        }
+    }
+
+    /// Mines `n` blocks on top of the tip, the first one including the whole
+    /// mempool.  Returns the hash of the new tip.
+    pub fn mine_blocks(&self, n: u64) -> BlockHash {
+        let mut chain = self.chain.lock().unwrap();
+        for _ in 0..n {
+            chain.mine_block();
+        }
+        chain.tip().block_hash()
+    }
+
+    /// Disconnects the `n` blocks at the tip, putting the txs in them back in
+    /// the mempool.
+    ///
+    /// # Panics
+    ///
+    /// If that would disconnect the genesis block.
+    pub fn disconnect_blocks(&self, n: u64) {
+        let mut chain = self.chain.lock().unwrap();
+        assert!(
+            (n as usize) < chain.blocks.len(),
+            "mock: can't disconnect genesis"
+        );
+
+        let mut reorged_txs = Vec::new();
+        for _ in 0..n {
+            let block = chain.blocks.pop().unwrap();
+            for tx in &block.txdata {
+                chain.tx_heights.remove(&tx.compute_txid());
+            }
+            reorged_txs.splice(0..0, block.txdata);
+        }
+
+        // they were accepted before anything still in the mempool
+        reorged_txs.append(&mut chain.mempool);
+        chain.mempool = reorged_txs;
+    }
+
+    /// Sets the fee rate estimations are answered with, in sat/vB.
+    pub fn set_fee_estimate(&self, fee_rate: u64) {
+        self.chain.lock().unwrap().fee_estimate = fee_rate;
+    }
+
+    /// Returns if the tx is in the mempool.
+    pub fn in_mempool(&self, txid: &Txid) -> bool {
+        self.chain.lock().unwrap().mempool_idx(txid).is_some()
+    }
+
+    /// Returns the number of confirmations of the tx, 0 if it's not mined.
+    pub fn confirmations(&self, txid: &Txid) -> u64 {
+        let chain = self.chain.lock().unwrap();
+        chain
+            .tx_heights
+            .get(txid)
+            .map_or(0, |h| chain.height() - h + 1)
+    }
+}
+
+impl Default for MockL1Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockChain {
+    fn height(&self) -> u64 {
+        self.blocks.len() as u64 - 1
+    }
+
+    fn tip(&self) -> &Block {
+        self.blocks.last().unwrap()
+    }
+
+    fn mine_block(&mut self) {
+        let height = self.blocks.len() as u64;
+        let prev_blockhash = self
+            .blocks
+            .last()
+            .map_or(BlockHash::all_zeros(), Block::block_hash);
+
+        let txdata = std::mem::take(&mut self.mempool);
+        for tx in &txdata {
+            self.tx_heights.insert(tx.compute_txid(), height);
+        }
+
+        let mut block = Block {
+            header: Header {
+                version: BVersion::ONE,
+                prev_blockhash,
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: height as u32,
+                bits: CompactTarget::from_consensus(0x207fffff),
+                // keeps the hashes of blocks replacing disconnected ones apart
+                nonce: self.num_mined,
+            },
+            txdata,
+        };
+        if let Some(root) = block.compute_merkle_root() {
+            block.header.merkle_root = root;
+        }
+        self.blocks.push(block);
+        self.num_mined += 1;
+    }
+
+    fn mempool_idx(&self, txid: &Txid) -> Option<usize> {
+        self.mempool
+            .iter()
+            .position(|tx| tx.compute_txid() == *txid)
+    }
+
+    /// Iterates over the txs of the main chain and then the mempool.
+    fn known_txs(&self) -> impl Iterator<Item = &Transaction> {
+        self.blocks
+            .iter()
+            .flat_map(|b| b.txdata.iter())
+            .chain(self.mempool.iter())
+    }
+
+    fn get_tx(&self, txid: &Txid) -> Option<&Transaction> {
+        self.known_txs().find(|tx| tx.compute_txid() == *txid)
+    }
+
+    fn spent_outpoints(&self) -> HashSet<OutPoint> {
+        self.known_txs()
+            .flat_map(|tx| tx.input.iter().map(|inp| inp.previous_output))
+            .collect()
+    }
+
+    fn output_exists(&self, outpoint: &OutPoint) -> bool {
+        self.funding_utxos.contains_key(outpoint)
+            || self
+                .get_tx(&outpoint.txid)
+                .is_some_and(|tx| (outpoint.vout as usize) < tx.output.len())
+    }
+
+    fn accept_tx(&mut self, tx: &Transaction) -> ClientResult<Txid> {
+        let txid = tx.compute_txid();
+        if self.get_tx(&txid).is_some() {
+            return Ok(txid);
+        }
+
+        let spent = self.spent_outpoints();
+        for inp in &tx.input {
+            if !self.output_exists(&inp.previous_output) {
+                return Err(ClientError::Server(
+                    -25,
+                    "bad-txns-inputs-missingorspent".to_string(),
+                ));
+            }
+            if spent.contains(&inp.previous_output) {
+                return Err(ClientError::Server(-26, "txn-mempool-conflict".to_string()));
+            }
+        }
+
+        self.mempool.push(tx.clone());
+        Ok(txid)
+    }
+}
+
+#[async_trait]
+impl Reader for MockL1Client {
+    async fn estimate_smart_fee(&self, _conf_target: u16) -> ClientResult<u64> {
+        self.enter(MockRpc::EstimateSmartFee)?;
+        Ok(self.chain.lock().unwrap().fee_estimate)
+    }
+
+    async fn get_block(&self, hash: &BlockHash) -> ClientResult<Block> {
+        self.enter(MockRpc::GetBlock)?;
+        let chain = self.chain.lock().unwrap();
+        chain
+            .blocks
+            .iter()
+            .find(|b| b.block_hash() == *hash)
+            .cloned()
+            .ok_or_else(|| ClientError::Server(-5, "Block not found".to_string()))
+    }
+
+    async fn get_block_at(&self, height: u64) -> ClientResult<Block> {
+        self.enter(MockRpc::GetBlockAt)?;
+        let chain = self.chain.lock().unwrap();
+        chain
+            .blocks
+            .get(height as usize)
+            .cloned()
+            .ok_or_else(|| ClientError::Server(-8, "Block height out of range".to_string()))
+    }
+
+    async fn get_block_count(&self) -> ClientResult<u64> {
+        self.enter(MockRpc::GetBlockCount)?;
+        Ok(self.chain.lock().unwrap().height())
+    }
+
+    async fn get_block_hash(&self, height: u64) -> ClientResult<BlockHash> {
+        self.enter(MockRpc::GetBlockHash)?;
+        let chain = self.chain.lock().unwrap();
+        chain
+            .blocks
+            .get(height as usize)
+            .map(Block::block_hash)
+            .ok_or_else(|| ClientError::Server(-8, "Block height out of range".to_string()))
+    }
+
+    async fn get_blockchain_info(&self) -> ClientResult<GetBlockchainInfo> {
+        self.enter(MockRpc::GetBlockchainInfo)?;
+        let chain = self.chain.lock().unwrap();
+        Ok(GetBlockchainInfo {
+            chain: "regtest".to_string(),
+            blocks: chain.height(),
+            headers: chain.height(),
+            best_block_hash: chain.tip().block_hash().to_string(),
+            difficulty: 1.0,
+            median_time: chain.tip().header.time as u64,
+            verification_progress: 1.0,
+            initial_block_download: false,
+            chain_work: Work::from_be_bytes([0; 32]).to_string(),
+            size_on_disk: 1_000_000,
+            pruned: false,
+            prune_height: None,
+            automatic_pruning: None,
+            prune_target_size: None,
+        })
+    }
+
+    async fn get_raw_mempool(&self) -> ClientResult<Vec<Txid>> {
+        self.enter(MockRpc::GetRawMempool)?;
+        let chain = self.chain.lock().unwrap();
+        Ok(chain.mempool.iter().map(|tx| tx.compute_txid()).collect())
+    }
+
+    async fn network(&self) -> ClientResult<Network> {
+        self.enter(MockRpc::Network)?;
+        Ok(Network::Regtest)
+    }
+}
+
+#[async_trait]
+impl Broadcaster for MockL1Client {
+    async fn send_raw_transaction(&self, tx: &Transaction) -> ClientResult<Txid> {
+        self.enter(MockRpc::SendRawTransaction)?;
+        self.chain.lock().unwrap().accept_tx(tx)
+    }
+}
+
+#[async_trait]
+impl Wallet for MockL1Client {
+    async fn get_new_address(&self) -> ClientResult<Address> {
+        self.enter(MockRpc::GetNewAddress)?;
+        Ok(parse_addr(WALLET_ADDR))
+    }
+
+    async fn get_transaction(&self, txid: &Txid) -> ClientResult<GetTransaction> {
+        self.enter(MockRpc::GetTransaction)?;
+        let chain = self.chain.lock().unwrap();
+        let Some(tx) = chain.get_tx(txid) else {
+            return Err(ClientError::Server(
+                -5,
+                "Invalid or non-wallet transaction id".to_string(),
+            ));
+        };
+
+        let blockheight = chain.tx_heights.get(txid).copied();
+        let confirmations = blockheight.map_or(0, |h| chain.height() - h + 1);
+        Ok(GetTransaction {
+            amount: SignedAmount::ZERO,
+            confirmations,
+            generated: None,
+            trusted: None,
+            blockhash: blockheight.map(|h| chain.blocks[h as usize].block_hash().to_string()),
+            blockheight,
+            blockindex: None,
+            blocktime: None,
+            txid: *txid,
+            wtxid: tx.compute_wtxid().to_string(),
+            walletconflicts: vec![],
+            replaced_by_txid: None,
+            replaces_txid: None,
+            comment: None,
+            to: None,
+            time: 0,
+            timereceived: 0,
+            bip125_replaceable: "false".to_string(),
+            details: vec![],
+            hex: tx.clone(),
+        })
+    }
+
+    async fn get_utxos(&self) -> ClientResult<Vec<ListUnspent>> {
+        self.enter(MockRpc::GetUtxos)?;
+        let chain = self.chain.lock().unwrap();
+        let spent = chain.spent_outpoints();
+        let mut utxos: Vec<_> = chain
+            .funding_utxos
+            .iter()
+            .filter(|(outpoint, _)| !spent.contains(outpoint))
+            .map(|(outpoint, txout)| ListUnspent {
+                txid: outpoint.txid,
+                vout: outpoint.vout,
+                address: WALLET_ADDR.parse().unwrap(),
+                label: None,
+                script_pubkey: txout.script_pubkey.to_hex_string(),
+                amount: txout.value,
+                confirmations: chain.height() as u32 + 1,
+                spendable: true,
+                solvable: true,
+                safe: true,
+            })
+            .collect();
+        utxos.sort_by_key(|u| u.txid);
+        Ok(utxos)
+    }
+
+    async fn list_transactions(
+        &self,
+        _count: Option<usize>,
+    ) -> ClientResult<Vec<ListTransactions>> {
+        self.enter(MockRpc::ListTransactions)?;
+        Ok(vec![])
+    }
+
+    async fn list_wallets(&self) -> ClientResult<Vec<String>> {
+        self.enter(MockRpc::ListWallets)?;
+        Ok(vec![])
+    }
+}
+
+#[async_trait]
+impl Signer for MockL1Client {
+    async fn sign_raw_transaction_with_wallet(
+        &self,
+        tx: &Transaction,
+    ) -> ClientResult<SignRawTransactionWithWallet> {
+        self.enter(MockRpc::SignRawTransactionWithWallet)?;
+        Ok(SignRawTransactionWithWallet {
+            hex: consensus::encode::serialize_hex(tx),
+            complete: true,
+            errors: None,
+        })
+    }
+
+    async fn get_xpriv(&self) -> ClientResult<Option<Xpriv>> {
+        self.enter(MockRpc::GetXpriv)?;
+        Ok(None)
+    }
+
+    async fn import_descriptors(
+        &self,
+        _descriptors: Vec<ImportDescriptor>,
+        _wallet_name: String,
+    ) -> ClientResult<Vec<ImportDescriptorResult>> {
+        self.enter(MockRpc::ImportDescriptors)?;
+        Ok(vec![ImportDescriptorResult { success: true }])
+    }
+}
+
+fn parse_addr(addr: &str) -> Address {
+    addr.parse::<Address<_>>()
+        .unwrap()
+        .require_network(Network::Regtest)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_spending_tx(inputs: &[OutPoint]) -> Transaction {
+        MockL1Client::build_spending_tx(inputs)
+    }
+
+    #[tokio::test]
+    async fn test_mock_client_lifecycle() {
+        let client = MockL1Client::new();
+        assert_eq!(client.get_block_count().await.unwrap(), 0);
+
+        let utxos = client.get_utxos().await.unwrap();
+        assert_eq!(utxos.len(), NUM_FUNDING_UTXOS as usize);
+        let funding = OutPoint::new(utxos[0].txid, utxos[0].vout);
+
+        let tx = build_spending_tx(&[funding]);
+        let txid = client.send_raw_transaction(&tx).await.unwrap();
+        assert!(client.in_mempool(&txid));
+        assert_eq!(
+            client.get_transaction(&txid).await.unwrap().confirmations,
+            0
+        );
+        assert_eq!(
+            client.get_utxos().await.unwrap().len(),
+            NUM_FUNDING_UTXOS as usize - 1
+        );
+
+        // double spends and missing inputs are rejected
+        let mut conflict = build_spending_tx(&[funding]);
+        conflict.output[0].value = Amount::from_sat(999);
+        let err = client.send_raw_transaction(&conflict).await.unwrap_err();
+        assert!(err.is_missing_or_invalid_input());
+        let orphan = build_spending_tx(&[OutPoint::new(Txid::all_zeros(), 0)]);
+        let err = client.send_raw_transaction(&orphan).await.unwrap_err();
+        assert!(err.is_missing_or_invalid_input());
+
+        // children of mempool txs are fine
+        let child = build_spending_tx(&[OutPoint::new(txid, 0)]);
+        let child_txid = client.send_raw_transaction(&child).await.unwrap();
+
+        let tip = client.mine_blocks(3);
+        assert_eq!(client.get_block_count().await.unwrap(), 3);
+        assert_eq!(client.get_block_hash(3).await.unwrap(), tip);
+        assert_eq!(client.get_block_at(1).await.unwrap().txdata.len(), 2);
+        assert!(client.get_raw_mempool().await.unwrap().is_empty());
+        assert_eq!(client.confirmations(&txid), 3);
+        assert_eq!(
+            client
+                .get_transaction(&child_txid)
+                .await
+                .unwrap()
+                .confirmations,
+            3
+        );
+
+        // reorg out the block with the txs, they go back to the mempool in
+        // order
+        client.disconnect_blocks(3);
+        assert_eq!(client.get_block_count().await.unwrap(), 0);
+        assert_eq!(
+            client.get_raw_mempool().await.unwrap(),
+            vec![txid, child_txid]
+        );
+        assert_eq!(client.confirmations(&txid), 0);
+
+        let new_tip = client.mine_blocks(3);
+        assert_ne!(new_tip, tip);
+        assert_eq!(client.confirmations(&child_txid), 3);
+        assert!(client.get_block(&tip).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_client_failures() {
+        let client = MockL1Client::new();
+
+        client.fail_next(MockRpc::GetBlockCount, 2, ClientError::Timeout);
+        for _ in 0..2 {
+            assert_eq!(client.get_block_count().await, Err(ClientError::Timeout));
+        }
+        assert_eq!(client.get_block_count().await, Ok(0));
+        assert_eq!(client.calls(MockRpc::GetBlockCount), 3);
+
+        // other methods are left alone
+        assert!(client.get_raw_mempool().await.is_ok());
+        assert_eq!(client.calls(MockRpc::GetRawMempool), 1);
+
+        let err = ClientError::Server(-32601, "Method not found".into());
+        client.fail_always(MockRpc::GetRawMempool, err.clone());
+        for _ in 0..3 {
+            assert_eq!(client.get_raw_mempool().await, Err(err.clone()));
+        }
+        client.clear_failures();
+        assert!(client.get_raw_mempool().await.is_ok());
+        assert_eq!(client.calls(MockRpc::GetRawMempool), 5);
+    }
+}
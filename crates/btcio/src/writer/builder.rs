@@ -21,21 +21,29 @@ use bitcoin::{
         TaprootSpendInfo,
     },
     transaction::Version,
-    Address, Amount, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid,
-    Witness,
+    Address, Amount, FeeRate, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Txid, Witness,
 };
 use rand::{rngs::OsRng, RngCore};
-use strata_state::tx::InscriptionData;
-use strata_tx_parser::inscription::{BATCH_DATA_TAG, ROLLUP_NAME_TAG, VERSION_TAG};
+use strata_primitives::buf::Buf32;
+use strata_state::{
+    da_blob::MAX_BLOB_PAYLOAD_SIZE,
+    tx::{InscriptionChunk, InscriptionData},
+};
+use strata_tx_parser::inscription::{BATCH_DATA_TAG, CHUNK_TAG, ROLLUP_NAME_TAG, VERSION_TAG};
 use thiserror::Error;
-use tracing::trace;
+use tracing::{trace, warn};
 
 use crate::{
     rpc::{
         traits::{Reader, Signer, Wallet},
         types::ListUnspent,
     },
-    writer::config::{InscriptionFeePolicy, WriterConfig},
+    writer::{
+        chunk::MAX_CHUNK_SIZE,
+        config::{InscriptionFeePolicy, WriterConfig},
+        error::WriterResult,
+    },
 };
 
 const BITCOIN_DUST_LIMIT: u64 = 546;
@@ -50,6 +58,17 @@ pub enum InscriptionError {
     #[error("Error building taproot")]
     Taproot(#[from] TaprootBuilderError),
 
+    #[error("output {vout} of tx {txid} is dust ({value} sats, need at least {min_value} sats)")]
+    DustOutput {
+        txid: Txid,
+        vout: usize,
+        value: u64,
+        min_value: u64,
+    },
+
+    #[error("blob payload too large (max {0} bytes, got {1})")]
+    PayloadTooLarge(usize, usize),
+
     #[error("{0}")]
     Other(#[from] anyhow::Error),
 }
@@ -59,45 +78,83 @@ pub enum InscriptionError {
 // dependencies on `tx-parser`, we include {btcio, feature="strata_test_utils"} , so cyclic
 // dependency doesn't happen
 pub async fn build_inscription_txs(
-    payload: &[u8],
+    insc_data: Vec<InscriptionData>,
     rpc_client: &Arc<impl Reader + Wallet + Signer>,
     config: &WriterConfig,
-) -> anyhow::Result<(Transaction, Transaction)> {
+) -> WriterResult<(Transaction, Transaction)> {
     let network = rpc_client.network().await?;
     let utxos = rpc_client.get_utxos().await?;
 
-    let fee_rate = match config.inscription_fee_policy {
-        InscriptionFeePolicy::Smart => rpc_client.estimate_smart_fee(1).await? * 2,
-        InscriptionFeePolicy::Fixed(val) => val,
+    let commit_fee_rate = get_fee_rate(rpc_client.as_ref(), &config.inscription_fee_policy).await;
+    let reveal_fee_rate = match &config.reveal_fee_policy {
+        Some(policy) => get_fee_rate(rpc_client.as_ref(), policy).await,
+        None => commit_fee_rate,
     };
-    create_inscription_transactions(
-        &config.rollup_name,
-        payload,
+    let txs = create_inscription_transactions(
+        &config.inscription_tag,
+        insc_data,
         utxos,
         config.sequencer_address.clone(),
         config.amount_for_reveal_txn,
-        fee_rate,
+        InscriptionFeeRates {
+            commit: commit_fee_rate,
+            reveal: reveal_fee_rate,
+        },
         network,
-    )
-    .map_err(|e| anyhow::anyhow!(e.to_string()))
+    )?;
+    Ok(txs)
 }
 
+/// Determines the fee rate in sat/vB to inscribe with.
+async fn get_fee_rate(rpc_client: &impl Reader, policy: &InscriptionFeePolicy) -> u64 {
+    match policy {
+        InscriptionFeePolicy::Smart(bounds) => match rpc_client.estimate_smart_fee(1).await {
+            Ok(fee_rate) => bounds.clamp(fee_rate * 2),
+            Err(err) => {
+                warn!(?err, fallback = %bounds.fallback, "could not estimate fee rate, using fallback");
+                bounds.fallback
+            }
+        },
+        InscriptionFeePolicy::Fixed(val) => *val,
+    }
+}
+
+/// Fee rates, in sat/vB, the commit and reveal txns each pay for their own size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InscriptionFeeRates {
+    pub commit: u64,
+    pub reveal: u64,
+}
+
+impl InscriptionFeeRates {
+    /// Same fee rate for both txns.
+    pub fn uniform(fee_rate: u64) -> Self {
+        Self {
+            commit: fee_rate,
+            reveal: fee_rate,
+        }
+    }
+}
+
+/// Builds the commit txn, paying for its own size at the commit fee rate, whose
+/// output funds the reveal txn including its fee at the reveal fee rate.
+///
+/// Each of the inscription datas gets its own envelope in the reveal script, so
+/// several blobs can be inscribed in a single reveal txn.
 #[allow(clippy::too_many_arguments)]
 pub fn create_inscription_transactions(
     rollup_name: &str,
-    write_intent: &[u8],
+    insc_data: Vec<InscriptionData>,
     utxos: Vec<ListUnspent>,
     recipient: Address,
     reveal_value: u64,
-    fee_rate: u64,
+    fee_rates: InscriptionFeeRates,
     network: Network,
 ) -> Result<(Transaction, Transaction), InscriptionError> {
     // Create commit key
     let key_pair = generate_key_pair()?;
     let public_key = XOnlyPublicKey::from_keypair(&key_pair).0;
 
-    let insc_data = InscriptionData::new(write_intent.to_vec());
-
     // Start creating inscription content
     let reveal_script =
         build_reveal_script(rollup_name, &public_key, insc_data, INSCRIPTION_VERSION)?;
@@ -120,7 +177,7 @@ pub fn create_inscription_transactions(
     let commit_value = calculate_commit_output_value(
         &recipient,
         reveal_value,
-        fee_rate,
+        fee_rates.reveal,
         &reveal_script,
         &taproot_spend_info,
     );
@@ -131,17 +188,17 @@ pub fn create_inscription_transactions(
         reveal_address.clone(),
         recipient.clone(),
         commit_value,
-        fee_rate,
+        fee_rates.commit,
     )?;
 
     let output_to_reveal = unsigned_commit_tx.output[0].clone();
 
-    // Build reveal tx
+    // Build reveal tx, this fails if the commit output doesn't cover its fee
     let mut reveal_tx = build_reveal_transaction(
         unsigned_commit_tx.clone(),
         recipient,
         reveal_value,
-        fee_rate,
+        fee_rates.reveal,
         &reveal_script,
         &taproot_spend_info
             .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
@@ -163,6 +220,124 @@ pub fn create_inscription_transactions(
     Ok((unsigned_commit_tx, reveal_tx))
 }
 
+/// Checks that none of the tx's outputs is below the dust limit of its script type, which would get
+/// the tx rejected from the mempool.
+pub fn check_dust_outputs(tx: &Transaction) -> Result<(), InscriptionError> {
+    for (vout, output) in tx.output.iter().enumerate() {
+        let min_value = output.script_pubkey.minimal_non_dust();
+        if output.value < min_value {
+            return Err(InscriptionError::DustOutput {
+                txid: tx.compute_txid(),
+                vout,
+                value: output.value.to_sat(),
+                min_value: min_value.to_sat(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Projected size and fees of inscribing a payload, see [`estimate_inscription_cost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InscriptionCost {
+    /// Number of commit/reveal txn pairs, more than one if the payload has to be chunked.
+    pub num_inscriptions: u32,
+
+    /// Total vsize of the commit txns.
+    pub commit_vsize: u64,
+
+    /// Total vsize of the reveal txns.
+    pub reveal_vsize: u64,
+
+    /// Total fee paid by all the txns.
+    pub total_fee: Amount,
+}
+
+/// Estimates what inscribing a payload of the given size costs at the fee rate, without building
+/// the actual txns.  Payloads over [`MAX_BLOB_PAYLOAD_SIZE`] are rejected, like they would be when
+/// submitted.
+///
+/// Like the fees the writer pays, this assumes each commit txn spends a single taproot utxo and
+/// sends the change back to a taproot address.
+pub fn estimate_inscription_cost(
+    inscription_tag: &str,
+    payload_len: usize,
+    fee_rate: FeeRate,
+) -> Result<InscriptionCost, InscriptionError> {
+    if payload_len > MAX_BLOB_PAYLOAD_SIZE {
+        return Err(InscriptionError::PayloadTooLarge(
+            MAX_BLOB_PAYLOAD_SIZE,
+            payload_len,
+        ));
+    }
+
+    let chunk_lens: Vec<usize> = if payload_len <= MAX_CHUNK_SIZE {
+        vec![payload_len]
+    } else {
+        (0..payload_len)
+            .step_by(MAX_CHUNK_SIZE)
+            .map(|start| MAX_CHUNK_SIZE.min(payload_len - start))
+            .collect()
+    };
+    let total = chunk_lens.len() as u32;
+
+    // Any key does, only the sizes matter
+    let key_pair = UntweakedKeypair::from_seckey_slice(SECP256K1, &[1; 32]).expect("valid key");
+    let public_key = XOnlyPublicKey::from_keypair(&key_pair).0;
+    let taproot_output = TxOut {
+        script_pubkey: ScriptBuf::new_p2tr(SECP256K1, public_key, None),
+        value: Amount::ZERO,
+    };
+
+    let mut commit_vsize = 0;
+    let mut reveal_vsize = 0;
+    for (index, len) in chunk_lens.into_iter().enumerate() {
+        let payload = vec![0; len];
+        let insc_data = if total > 1 {
+            let chunk = InscriptionChunk::new(Buf32::zero(), index as u32, total);
+            InscriptionData::new_chunk(payload, chunk)
+        } else {
+            InscriptionData::new(payload)
+        };
+
+        let reveal_script = build_reveal_script(
+            inscription_tag,
+            &public_key,
+            vec![insc_data],
+            INSCRIPTION_VERSION,
+        )
+        .expect("inscription script for payload");
+        let control_block = TaprootBuilder::new()
+            .add_leaf(0, reveal_script.clone())
+            .expect("single leaf")
+            .finalize(SECP256K1, public_key)
+            .expect("single leaf tree")
+            .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+            .expect("control block for the leaf");
+
+        commit_vsize += get_size(
+            &default_txin(),
+            &[taproot_output.clone(), taproot_output.clone()],
+            None,
+            None,
+        ) as u64;
+        reveal_vsize += get_size(
+            &default_txin(),
+            &[taproot_output.clone()],
+            Some(&reveal_script),
+            Some(&control_block),
+        ) as u64;
+    }
+
+    let fee_rate = fee_rate.to_sat_per_vb_ceil();
+    Ok(InscriptionCost {
+        num_inscriptions: total,
+        commit_vsize,
+        reveal_vsize,
+        total_fee: Amount::from_sat((commit_vsize + reveal_vsize) * fee_rate),
+    })
+}
+
 fn get_size(
     inputs: &[TxIn],
     outputs: &[TxOut],
@@ -396,12 +571,12 @@ pub fn generate_key_pair() -> Result<UntweakedKeypair, anyhow::Error> {
     Ok(UntweakedKeypair::from_seckey_slice(SECP256K1, &rand_bytes)?)
 }
 
-/// Builds reveal script such that it contains opcodes for verifying the internal key as well as the
-/// inscription block
+/// Builds reveal script such that it contains opcodes for verifying the internal key as well as an
+/// inscription block for each of the inscription datas, in order
 fn build_reveal_script(
     rollup_name: &str,
     taproot_public_key: &XOnlyPublicKey,
-    insc_data: InscriptionData,
+    insc_data: Vec<InscriptionData>,
     version: u8,
 ) -> Result<ScriptBuf, anyhow::Error> {
     let mut script_bytes = script::Builder::new()
@@ -409,8 +584,10 @@ fn build_reveal_script(
         .push_opcode(OP_CHECKSIG)
         .into_script()
         .into_bytes();
-    let script = generate_inscription_script(insc_data, rollup_name, version)?;
-    script_bytes.extend(script.into_bytes());
+    for data in insc_data {
+        let script = generate_inscription_script(data, rollup_name, version)?;
+        script_bytes.extend(script.into_bytes());
+    }
     Ok(ScriptBuf::from(script_bytes))
 }
 
@@ -504,7 +681,15 @@ pub fn generate_inscription_script(
         .push_slice(PushBytesBuf::try_from(ROLLUP_NAME_TAG.to_vec())?)
         .push_slice(PushBytesBuf::try_from(rollup_name.as_bytes().to_vec())?)
         .push_slice(PushBytesBuf::try_from(VERSION_TAG.to_vec())?)
-        .push_slice(PushBytesBuf::from([version]))
+        .push_slice(PushBytesBuf::from([version]));
+
+    if let Some(chunk) = inscription_data.chunk() {
+        builder = builder
+            .push_slice(PushBytesBuf::try_from(CHUNK_TAG.to_vec())?)
+            .push_slice(PushBytesBuf::from(chunk.to_bytes()));
+    }
+
+    builder = builder
         .push_slice(PushBytesBuf::try_from(BATCH_DATA_TAG.to_vec())?)
         .push_int(inscription_data.batch_data().len() as i64);
 
@@ -527,9 +712,15 @@ mod tests {
         taproot::ControlBlock, Address, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
         TxOut, Witness,
     };
+    use strata_test_utils::l2::gen_params;
+    use strata_tx_parser::inscription::{parse_inscription, InscribedBlob, InscriptionConfig};
 
     use super::*;
-    use crate::{rpc::types::ListUnspent, writer::builder::InscriptionError};
+    use crate::{
+        rpc::error::ClientError,
+        test_utils::{MockL1Client, MockRpc},
+        writer::{builder::InscriptionError, config::SmartFeeBounds},
+    };
 
     const BTC_TO_SATS: u64 = 100_000_000;
     const REVEAL_OUTPUT_AMOUNT: u64 = BITCOIN_DUST_LIMIT;
@@ -726,11 +917,11 @@ mod tests {
         let write_intent = vec![0u8; 100];
         let (commit, reveal) = super::create_inscription_transactions(
             rollup_name,
-            &write_intent,
+            vec![InscriptionData::new(write_intent)],
             utxos.to_vec(),
             address.clone(),
             REVEAL_OUTPUT_AMOUNT,
-            10,
+            InscriptionFeeRates::uniform(10),
             bitcoin::Network::Bitcoin,
         )
         .unwrap();
@@ -766,5 +957,196 @@ mod tests {
         );
     }
 
-    // TODO: make the tests more comprehensive
+    #[test]
+    fn test_check_dust_outputs() {
+        let (_, _, _, _, address, utxos) = get_mock_data();
+        let mut tx = get_txn_from_utxo(&utxos[0], &address);
+        let min_value = tx.output[0].script_pubkey.minimal_non_dust().to_sat();
+
+        tx.output[0].value = Amount::from_sat(min_value);
+        check_dust_outputs(&tx).unwrap();
+
+        tx.output[0].value = Amount::from_sat(min_value + 1);
+        check_dust_outputs(&tx).unwrap();
+
+        tx.output[0].value = Amount::from_sat(min_value - 1);
+        let res = check_dust_outputs(&tx);
+        assert!(matches!(
+            res,
+            Err(InscriptionError::DustOutput { vout: 0, value, min_value: min })
+                if value == min_value - 1 && min == min_value
+        ));
+    }
+
+    #[test]
+    fn test_commit_reveal_fee_split() {
+        let (rollup_name, _, _, _, address, utxos) = get_mock_data();
+
+        let fee_rates = InscriptionFeeRates {
+            commit: 3,
+            reveal: 25,
+        };
+        let (commit, reveal) = super::create_inscription_transactions(
+            rollup_name,
+            vec![InscriptionData::new(vec![0u8; 2000])],
+            utxos.to_vec(),
+            address.clone(),
+            REVEAL_OUTPUT_AMOUNT,
+            fee_rates,
+            bitcoin::Network::Bitcoin,
+        )
+        .unwrap();
+
+        // the commit output pays exactly for the reveal output and fee
+        let reveal_fee = reveal.vsize() as u64 * fee_rates.reveal;
+        assert_eq!(
+            commit.output[0].value.to_sat(),
+            REVEAL_OUTPUT_AMOUNT + reveal_fee
+        );
+
+        // and the commit pays for its own size at its own rate
+        let commit_out: u64 = commit.output.iter().map(|o| o.value.to_sat()).sum();
+        let commit_size = super::get_size(&commit.input, &commit.output, None, None) as u64;
+        assert_eq!(
+            utxos[2].amount.to_sat() - commit_out,
+            commit_size * fee_rates.commit
+        );
+    }
+
+    #[test]
+    fn test_estimate_inscription_cost() {
+        let (rollup_name, _, _, _, address, utxos) = get_mock_data();
+        let fee_rate = 10;
+
+        for payload_len in [1, 100, 1_000, 20_000] {
+            let estimate = estimate_inscription_cost(
+                rollup_name,
+                payload_len,
+                FeeRate::from_sat_per_vb(fee_rate).unwrap(),
+            )
+            .unwrap();
+
+            let (commit, reveal) = super::create_inscription_transactions(
+                rollup_name,
+                vec![InscriptionData::new(vec![0u8; payload_len])],
+                utxos.clone(),
+                address.clone(),
+                REVEAL_OUTPUT_AMOUNT,
+                InscriptionFeeRates::uniform(fee_rate),
+                bitcoin::Network::Bitcoin,
+            )
+            .unwrap();
+
+            assert_eq!(estimate.num_inscriptions, 1);
+            assert!(
+                estimate.reveal_vsize.abs_diff(reveal.vsize() as u64) <= 2,
+                "reveal vsize {} for {payload_len} bytes, estimated {}",
+                reveal.vsize(),
+                estimate.reveal_vsize
+            );
+
+            // fees the built txns actually pay
+            let commit_out: u64 = commit.output.iter().map(|o| o.value.to_sat()).sum();
+            let commit_fee = utxos[2].amount.to_sat() - commit_out;
+            let reveal_fee = commit.output[0].value.to_sat() - reveal.output[0].value.to_sat();
+            let actual_fee = commit_fee + reveal_fee;
+            assert!(
+                estimate.total_fee.to_sat().abs_diff(actual_fee) <= 4 * fee_rate,
+                "fee {actual_fee} for {payload_len} bytes, estimated {}",
+                estimate.total_fee
+            );
+        }
+    }
+
+    #[test]
+    fn test_estimate_chunked_inscription_cost() {
+        let fee_rate = FeeRate::from_sat_per_vb(10).unwrap();
+        let single = estimate_inscription_cost("test_rollup", MAX_CHUNK_SIZE, fee_rate).unwrap();
+        assert_eq!(single.num_inscriptions, 1);
+
+        // one full chunk more, and a small one for the rest
+        let chunked =
+            estimate_inscription_cost("test_rollup", 2 * MAX_CHUNK_SIZE + 10, fee_rate).unwrap();
+        assert_eq!(chunked.num_inscriptions, 3);
+        assert_eq!(chunked.commit_vsize, 3 * single.commit_vsize);
+        assert!(chunked.reveal_vsize > 2 * single.reveal_vsize);
+        assert!(chunked.total_fee > single.total_fee * 2);
+
+        // the largest payload that can be submitted is fine, anything over it isn't
+        let max =
+            estimate_inscription_cost("test_rollup", MAX_BLOB_PAYLOAD_SIZE, fee_rate).unwrap();
+        assert_eq!(
+            max.num_inscriptions as usize,
+            MAX_BLOB_PAYLOAD_SIZE.div_ceil(MAX_CHUNK_SIZE)
+        );
+        assert!(matches!(
+            estimate_inscription_cost("test_rollup", MAX_BLOB_PAYLOAD_SIZE + 1, fee_rate),
+            Err(InscriptionError::PayloadTooLarge(..))
+        ));
+        assert!(matches!(
+            estimate_inscription_cost("test_rollup", usize::MAX, fee_rate),
+            Err(InscriptionError::PayloadTooLarge(..))
+        ));
+    }
+
+    #[test]
+    fn test_inscription_parsed_with_same_params() {
+        let (_, _, _, _, address, utxos) = get_mock_data();
+        let mut params = gen_params();
+        params.rollup.inscription_tag = Some("strata-test".to_string());
+        let config = WriterConfig::new(address.clone(), params.rollup()).unwrap();
+
+        let payload = vec![7u8; 300];
+        let (_, reveal) = super::create_inscription_transactions(
+            &config.inscription_tag,
+            vec![InscriptionData::new(payload.clone())],
+            utxos,
+            address,
+            REVEAL_OUTPUT_AMOUNT,
+            InscriptionFeeRates::uniform(10),
+            bitcoin::Network::Bitcoin,
+        )
+        .unwrap();
+
+        let parsed = parse_inscription(&reveal, &InscriptionConfig::derive_from(params.rollup()));
+        assert_eq!(
+            parsed,
+            Some(InscribedBlob {
+                payload,
+                chunk: None
+            })
+        );
+
+        // a reader derived from params with another tag ignores it
+        let other_params = gen_params();
+        let other_config = InscriptionConfig::derive_from(other_params.rollup());
+        assert_eq!(parse_inscription(&reveal, &other_config), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_fee_rate() {
+        let bounds = SmartFeeBounds::new(2, 100, 10).unwrap();
+        let policy = InscriptionFeePolicy::Smart(bounds);
+
+        let client = MockL1Client::new();
+
+        // estimates are doubled, then clamped
+        let cases = [(20, 40), (0, 2), (1, 2), (50, 100), (400, 100)];
+        for (estimate, expected) in cases {
+            client.set_fee_estimate(estimate);
+            let rate = get_fee_rate(&client, &policy).await;
+            assert_eq!(rate, expected, "estimate = {estimate}");
+        }
+
+        let err = ClientError::Server(-32603, "no estimate".into());
+        client.fail_always(MockRpc::EstimateSmartFee, err);
+        assert_eq!(get_fee_rate(&client, &policy).await, 10);
+        client.clear_failures();
+
+        // fixed rates aren't estimated at all
+        let estimations = client.calls(MockRpc::EstimateSmartFee);
+        let policy = InscriptionFeePolicy::Fixed(7);
+        assert_eq!(get_fee_rate(&client, &policy).await, 7);
+        assert_eq!(client.calls(MockRpc::EstimateSmartFee), estimations);
+    }
 }
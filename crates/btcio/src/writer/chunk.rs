@@ -0,0 +1,217 @@
+//! Splitting of blobs too large for a single inscription into chunks, and putting them back
+//! together from the stored entries or from the inscriptions read back from L1.
+
+use strata_db::types::{BlobChunkInfo, BlobEntry};
+use strata_primitives::{buf::Buf32, hash};
+use strata_tx_parser::inscription::InscribedBlob;
+
+/// Max size of a chunk payload, one chunk is inscribed per commit/reveal pair.
+///
+/// Standard txs can be at most 400k weight units and witness bytes count as 1
+/// WU each, so this leaves some room for the inscription envelope and the rest
+/// of the reveal tx.
+pub const MAX_CHUNK_SIZE: usize = 390_000;
+
+/// Returns the id the chunk at `index` of the blob with `commitment` is stored with.
+///
+/// The first chunk is stored with the blob commitment itself so that duplicate intents can be
+/// detected without knowing how many chunks the blob was split into.
+pub fn chunk_id(commitment: &Buf32, index: u32) -> Buf32 {
+    if index == 0 {
+        return *commitment;
+    }
+
+    let mut buf = commitment.as_slice().to_vec();
+    buf.extend_from_slice(&index.to_be_bytes());
+    hash::raw(&buf)
+}
+
+/// Splits the payload into unsigned [`BlobEntry`]s of at most `max_chunk_size` bytes each,
+/// returned in order along with the ids to store them with.
+///
+/// Payloads that fit in a single chunk produce a single plain entry without chunk info.
+pub fn split_into_entries(
+    commitment: Buf32,
+    payload: &[u8],
+    max_chunk_size: usize,
+) -> Vec<(Buf32, BlobEntry)> {
+    if payload.len() <= max_chunk_size {
+        return vec![(commitment, BlobEntry::new_unsigned(payload.to_vec()))];
+    }
+
+    let chunks: Vec<_> = payload.chunks(max_chunk_size).collect();
+    let total = chunks.len() as u32;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let index = i as u32;
+            let info = BlobChunkInfo::new(commitment, index, total);
+            let entry = BlobEntry::new_unsigned_chunk(chunk.to_vec(), info);
+            (chunk_id(&commitment, index), entry)
+        })
+        .collect()
+}
+
+/// Position of a chunk in its blob, as the blob commitment, the chunk index and the total number
+/// of chunks.
+type ChunkPos = (Buf32, u32, u32);
+
+/// Reassembles the blob payload from its entries, which have to be in order.
+///
+/// Returns `None` if the entries aren't the complete ordered set of chunks of a single blob.
+pub fn reassemble(entries: &[BlobEntry]) -> Option<Vec<u8>> {
+    reassemble_parts(entries, |entry| {
+        let pos = entry
+            .chunk
+            .as_ref()
+            .map(|info| (info.blob_commitment, info.index, info.total));
+        (entry.blob.as_slice(), pos)
+    })
+}
+
+/// Reassembles the blob payload from the inscriptions its chunks were read back from, which have
+/// to be in order.
+///
+/// Returns `None` if the blobs aren't the complete ordered set of chunks of a single blob.
+pub fn reassemble_inscribed(blobs: &[InscribedBlob]) -> Option<Vec<u8>> {
+    reassemble_parts(blobs, |blob| {
+        let pos = blob
+            .chunk
+            .as_ref()
+            .map(|chunk| (chunk.blob_commitment, chunk.index, chunk.total));
+        (blob.payload.as_slice(), pos)
+    })
+}
+
+fn reassemble_parts<T>(
+    parts: &[T],
+    split: impl Fn(&T) -> (&[u8], Option<ChunkPos>),
+) -> Option<Vec<u8>> {
+    let (first_payload, first_pos) = split(parts.first()?);
+    let Some((commitment, _, total)) = first_pos else {
+        return (parts.len() == 1).then(|| first_payload.to_vec());
+    };
+
+    if parts.len() != total as usize {
+        return None;
+    }
+
+    let mut payload = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let (chunk, pos) = split(part);
+        let (chunk_commitment, index, _) = pos?;
+        if index as usize != i || chunk_commitment != commitment {
+            return None;
+        }
+        payload.extend_from_slice(chunk);
+    }
+
+    Some(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use strata_state::{da_blob::MAX_BLOB_PAYLOAD_SIZE, tx::InscriptionChunk};
+
+    use super::*;
+
+    #[test]
+    fn test_max_payload_is_chunked() {
+        let commitment = Buf32::from([1; 32]);
+        let payload = vec![7; MAX_BLOB_PAYLOAD_SIZE];
+
+        let entries = split_into_entries(commitment, &payload, MAX_CHUNK_SIZE);
+
+        assert_eq!(
+            entries.len(),
+            MAX_BLOB_PAYLOAD_SIZE.div_ceil(MAX_CHUNK_SIZE)
+        );
+        assert!(entries.len() > 1);
+    }
+
+    #[test]
+    fn test_small_payload_single_entry() {
+        let commitment = Buf32::from([1; 32]);
+        let payload = vec![7; 100];
+
+        let entries = split_into_entries(commitment, &payload, MAX_CHUNK_SIZE);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, commitment);
+        assert!(entries[0].1.chunk.is_none());
+        assert!(entries[0].1.is_last_chunk());
+    }
+
+    #[test]
+    fn test_split_and_reassemble() {
+        let commitment = Buf32::from([1; 32]);
+        let payload: Vec<u8> = (0..2500u32).map(|i| i as u8).collect();
+
+        let entries = split_into_entries(commitment, &payload, 1000);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0, commitment);
+        for (i, (id, entry)) in entries.iter().enumerate() {
+            let info = entry.chunk.as_ref().unwrap();
+            assert_eq!(*id, chunk_id(&commitment, i as u32));
+            assert_eq!(info.index, i as u32);
+            assert_eq!(info.total, 3);
+            assert_eq!(entry.is_last_chunk(), i == 2);
+        }
+        assert_eq!(entries[2].1.blob.len(), 500);
+
+        let entries: Vec<_> = entries.into_iter().map(|(_, e)| e).collect();
+        assert_eq!(reassemble(&entries), Some(payload));
+    }
+
+    #[test]
+    fn test_reassemble_rejects_incomplete_or_unordered() {
+        let commitment = Buf32::from([1; 32]);
+        let payload = vec![3; 2500];
+
+        let mut entries: Vec<_> = split_into_entries(commitment, &payload, 1000)
+            .into_iter()
+            .map(|(_, e)| e)
+            .collect();
+
+        assert_eq!(reassemble(&entries[..2]), None);
+
+        entries.swap(0, 1);
+        assert_eq!(reassemble(&entries), None);
+    }
+
+    #[test]
+    fn test_reassemble_inscribed() {
+        let commitment = Buf32::from([9; 32]);
+        let payload: Vec<u8> = (0..2500u32).map(|i| i as u8).collect();
+
+        let blobs: Vec<_> = split_into_entries(commitment, &payload, 1000)
+            .into_iter()
+            .map(|(_, entry)| {
+                let info = entry.chunk.unwrap();
+                InscribedBlob {
+                    payload: entry.blob,
+                    chunk: Some(InscriptionChunk::new(
+                        info.blob_commitment,
+                        info.index,
+                        info.total,
+                    )),
+                }
+            })
+            .collect();
+
+        assert_eq!(reassemble_inscribed(&blobs), Some(payload));
+        assert_eq!(reassemble_inscribed(&blobs[1..]), None);
+
+        let mut unordered = blobs.clone();
+        unordered.swap(0, 2);
+        assert_eq!(reassemble_inscribed(&unordered), None);
+
+        let single = InscribedBlob {
+            payload: vec![5; 10],
+            chunk: None,
+        };
+        assert_eq!(reassemble_inscribed(&[single]), Some(vec![5; 10]));
+    }
+}
@@ -1,41 +1,206 @@
 use bitcoin::Address;
+use strata_primitives::params::RollupParams;
+use tracing::*;
+
+use super::chunk::MAX_CHUNK_SIZE;
+use crate::poll::DEFAULT_POLL_JITTER_PCT;
 
 #[derive(Debug, Clone)]
 pub struct WriterConfig {
     /// The sequencer change_address. This is where the reveal txn spends it's utxo to
     pub(super) sequencer_address: Address,
 
-    /// The rollup name
-    pub(super) rollup_name: String,
+    /// Tag the rollup's inscriptions are marked with
+    pub(super) inscription_tag: String,
 
     /// Time between each processing queue item, in millis
     pub(super) poll_duration_ms: u64,
 
+    /// How much the time between each processing queue item is randomly varied by, in percent of
+    /// `poll_duration_ms`
+    pub(super) poll_jitter_pct: u8,
+
     /// How should the inscription fee be determined
     pub(super) inscription_fee_policy: InscriptionFeePolicy,
 
+    /// How should the fee of the reveal txn be determined, if differently from the commit txn's
+    pub(super) reveal_fee_policy: Option<InscriptionFeePolicy>,
+
     /// How much amount(in sats) to send to reveal address
     pub(super) amount_for_reveal_txn: u64,
+
+    /// How many times a blob is resigned before giving up on it
+    pub(super) max_resigns: u32,
+
+    /// Confirmations after which a blob's txs are considered final
+    pub(super) reorg_safe_depth: u64,
+
+    /// Whether the rollup params allow inscribing several blobs in the same reveal txn
+    pub(super) batched_inscriptions: bool,
+
+    /// How to batch blobs into the same commit/reveal txns, if at all
+    pub(super) batching: Option<BlobBatchingConfig>,
 }
 
 impl WriterConfig {
-    pub fn new(sequencer_address: Address, rollup_name: String) -> anyhow::Result<Self> {
+    pub fn new(sequencer_address: Address, rollup_params: &RollupParams) -> anyhow::Result<Self> {
         Ok(Self {
             sequencer_address,
-            rollup_name,
+            inscription_tag: rollup_params.inscription_tag().to_string(),
             // TODO: get these from config as well
-            inscription_fee_policy: InscriptionFeePolicy::Smart,
+            inscription_fee_policy: InscriptionFeePolicy::Smart(SmartFeeBounds::default()),
+            reveal_fee_policy: None,
             poll_duration_ms: 1_000,
+            poll_jitter_pct: DEFAULT_POLL_JITTER_PCT,
             amount_for_reveal_txn: 1_000,
+            max_resigns: 10,
+            reorg_safe_depth: rollup_params.l1_reorg_safe_depth.into(),
+            batched_inscriptions: rollup_params.batched_inscriptions,
+            batching: None,
+        })
+    }
+
+    pub fn with_max_resigns(mut self, max_resigns: u32) -> Self {
+        self.max_resigns = max_resigns;
+        self
+    }
+
+    pub fn with_poll_jitter_pct(mut self, jitter_pct: u8) -> Self {
+        self.poll_jitter_pct = jitter_pct;
+        self
+    }
+
+    pub fn with_inscription_fee_policy(mut self, policy: InscriptionFeePolicy) -> Self {
+        self.inscription_fee_policy = policy;
+        self
+    }
+
+    /// Pays the reveal txn's fee according to its own policy instead of the commit txn's.
+    pub fn with_reveal_fee_policy(mut self, policy: InscriptionFeePolicy) -> Self {
+        self.reveal_fee_policy = Some(policy);
+        self
+    }
+
+    /// Inscribes pending blobs together in a single commit/reveal txn pair instead of one pair
+    /// each, if the rollup params allow it.  Other nodes wouldn't pick up the blobs after the
+    /// first one otherwise.
+    pub fn with_batching(mut self, batching: BlobBatchingConfig) -> Self {
+        if !self.batched_inscriptions {
+            warn!("rollup params don't allow batched inscriptions, not batching blobs");
+            return self;
+        }
+        self.batching = Some(batching);
+        self
+    }
+}
+
+/// Thresholds for inscribing several blobs in the same reveal txn.
+///
+/// Pending blobs are held back until either they reach one of the batch limits or the oldest of
+/// them has waited long enough, whichever comes first.
+#[derive(Debug, Clone)]
+pub struct BlobBatchingConfig {
+    /// Most blobs to inscribe in a single reveal txn.
+    pub(super) max_batch_blobs: usize,
+
+    /// Most bytes of blob data to inscribe in a single reveal txn.
+    pub(super) max_batch_bytes: usize,
+
+    /// Longest a blob is held back waiting for others to batch with, in millis.
+    pub(super) max_wait_ms: u64,
+}
+
+impl BlobBatchingConfig {
+    /// Creates the batching thresholds, failing if a full batch wouldn't fit in a standard reveal
+    /// txn.
+    pub fn new(
+        max_batch_blobs: usize,
+        max_batch_bytes: usize,
+        max_wait_ms: u64,
+    ) -> anyhow::Result<Self> {
+        if max_batch_blobs == 0 {
+            anyhow::bail!("max blobs per batch must be at least 1");
+        }
+        if max_batch_bytes > MAX_CHUNK_SIZE {
+            anyhow::bail!(
+                "max batch size {max_batch_bytes} bytes is above the {MAX_CHUNK_SIZE} bytes that fit in a standard reveal txn"
+            );
+        }
+        Ok(Self {
+            max_batch_blobs,
+            max_batch_bytes,
+            max_wait_ms,
         })
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum InscriptionFeePolicy {
-    /// Use estimatesmartfee.
-    Smart,
+    /// Use estimatesmartfee, within the given bounds.
+    Smart(SmartFeeBounds),
 
     /// Fixed fee in sat/vB.
     Fixed(u64),
 }
+
+/// Bounds for the fee rates estimated by the node, all in sat/vB.
+#[derive(Debug, Clone)]
+pub struct SmartFeeBounds {
+    /// Lowest fee rate to pay.
+    pub(super) min: u64,
+
+    /// Highest fee rate to pay, so a fee spike doesn't drain the wallet.
+    pub(super) max: u64,
+
+    /// Fee rate to pay if the node is unable to estimate one.
+    pub(super) fallback: u64,
+}
+
+impl SmartFeeBounds {
+    /// Creates the bounds, failing if the lowest fee rate is above the highest.
+    pub fn new(min: u64, max: u64, fallback: u64) -> anyhow::Result<Self> {
+        if min > max {
+            anyhow::bail!("min fee rate {min} sat/vB is above max fee rate {max} sat/vB");
+        }
+        Ok(Self { min, max, fallback })
+    }
+
+    /// Clamps the estimated fee rate to the bounds.
+    pub fn clamp(&self, fee_rate: u64) -> u64 {
+        fee_rate.clamp(self.min, self.max)
+    }
+}
+
+impl Default for SmartFeeBounds {
+    fn default() -> Self {
+        Self {
+            min: 1,
+            max: 500,
+            fallback: 10,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smart_fee_bounds() {
+        let bounds = SmartFeeBounds::new(2, 100, 10).unwrap();
+        assert_eq!(bounds.clamp(1), 2);
+        assert_eq!(bounds.clamp(50), 50);
+        assert_eq!(bounds.clamp(400), 100);
+
+        // a single allowed rate is fine, inverted bounds aren't
+        assert_eq!(SmartFeeBounds::new(5, 5, 5).unwrap().clamp(400), 5);
+        assert!(SmartFeeBounds::new(100, 2, 10).is_err());
+    }
+
+    #[test]
+    fn test_blob_batching_config() {
+        assert!(BlobBatchingConfig::new(8, MAX_CHUNK_SIZE, 60_000).is_ok());
+        assert!(BlobBatchingConfig::new(8, MAX_CHUNK_SIZE + 1, 60_000).is_err());
+        assert!(BlobBatchingConfig::new(0, 1_000, 60_000).is_err());
+    }
+}
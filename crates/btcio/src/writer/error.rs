@@ -0,0 +1,138 @@
+use bitcoin::Txid;
+use strata_db::errors::DbError;
+use thiserror::Error;
+
+use super::builder::InscriptionError;
+use crate::rpc::error::{ClientError, ClientErrorKind};
+
+#[derive(Debug, Error)]
+pub enum WriterError {
+    #[error("rpc: {0}")]
+    Rpc(ClientError),
+
+    #[error("signing: {0}")]
+    Signing(String),
+
+    #[error("db: {0}")]
+    Db(#[from] DbError),
+
+    #[error("output {vout} of tx {txid} is dust ({value} sats, need at least {min_value} sats)")]
+    DustOutput {
+        txid: Txid,
+        vout: usize,
+        value: u64,
+        min_value: u64,
+    },
+
+    #[error("insufficient funds for tx (need {0} sats, have {1} sats)")]
+    InsufficientFunds(u64, u64),
+
+    #[error("rejected from mempool: {0}")]
+    MempoolReject(String),
+}
+
+impl WriterError {
+    /// Returns if whatever failed might succeed when just tried again later, i.e. the node
+    /// couldn't be reached or there weren't enough funds yet.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Rpc(err) => err.is_retryable(),
+            Self::InsufficientFunds(..) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<ClientError> for WriterError {
+    fn from(err: ClientError) -> Self {
+        match err.kind() {
+            ClientErrorKind::MempoolReject(reason) => Self::MempoolReject(reason),
+            _ => Self::Rpc(err),
+        }
+    }
+}
+
+impl From<InscriptionError> for WriterError {
+    fn from(err: InscriptionError) -> Self {
+        match err {
+            InscriptionError::NotEnoughUtxos(required, available) => {
+                Self::InsufficientFunds(required, available)
+            }
+            InscriptionError::DustOutput {
+                txid,
+                vout,
+                value,
+                min_value,
+            } => Self::DustOutput {
+                txid,
+                vout,
+                value,
+                min_value,
+            },
+            InscriptionError::Taproot(_) | InscriptionError::Other(_) => {
+                Self::Signing(err.to_string())
+            }
+        }
+    }
+}
+
+pub(crate) type WriterResult<T> = Result<T, WriterError>;
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+
+    use super::*;
+
+    #[test]
+    fn test_client_errors() {
+        let err = WriterError::from(ClientError::Timeout);
+        assert!(matches!(err, WriterError::Rpc(ClientError::Timeout)));
+        assert!(err.is_retryable());
+
+        let err = WriterError::from(ClientError::Server(-26, "txn-mempool-conflict".into()));
+        assert!(
+            matches!(err, WriterError::MempoolReject(ref reason) if reason == "txn-mempool-conflict")
+        );
+        assert!(!err.is_retryable());
+
+        let err = WriterError::from(ClientError::Server(-32601, "Method not found".into()));
+        assert!(matches!(err, WriterError::Rpc(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_inscription_errors() {
+        let err = WriterError::from(InscriptionError::NotEnoughUtxos(1_000, 10));
+        assert!(matches!(err, WriterError::InsufficientFunds(1_000, 10)));
+        assert!(err.is_retryable());
+
+        let err = WriterError::from(InscriptionError::DustOutput {
+            txid: Txid::from_byte_array([1; 32]),
+            vout: 0,
+            value: 1,
+            min_value: 330,
+        });
+        assert!(matches!(
+            err,
+            WriterError::DustOutput {
+                vout: 0,
+                value: 1,
+                min_value: 330,
+                ..
+            }
+        ));
+        assert!(!err.is_retryable());
+
+        let err = WriterError::from(InscriptionError::Other(anyhow::anyhow!("no control block")));
+        assert!(matches!(err, WriterError::Signing(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_db_errors() {
+        let err = WriterError::from(DbError::Busy);
+        assert!(matches!(err, WriterError::Db(DbError::Busy)));
+        assert!(!err.is_retryable());
+    }
+}
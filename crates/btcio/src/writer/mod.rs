@@ -1,5 +1,7 @@
 pub mod builder;
+pub mod chunk;
 pub mod config;
+pub mod error;
 mod signer;
 mod task;
 
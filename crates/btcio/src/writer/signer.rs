@@ -1,75 +1,192 @@
 use std::sync::Arc;
 
 use bitcoin::{consensus, Transaction};
-use strata_db::types::{BlobEntry, L1TxEntry};
+use strata_db::{
+    errors::DbError,
+    types::{BlobEntry, BlobL1Status, L1TxEntry, L1TxStatus},
+};
 use strata_primitives::buf::Buf32;
+use strata_state::tx::{InscriptionChunk, InscriptionData};
 use tracing::*;
 
 use super::{
-    builder::{build_inscription_txs, InscriptionError},
+    builder::{build_inscription_txs, check_dust_outputs},
     config::WriterConfig,
+    error::{WriterError, WriterResult},
+    task::determine_blob_next_status,
 };
 use crate::{
     broadcaster::L1BroadcastHandle,
-    rpc::traits::{Reader, Signer, Wallet},
+    rpc::{
+        error::ClientError,
+        traits::{Broadcaster, Reader, Signer, Wallet},
+    },
 };
 
 type BlobIdx = u64;
 
-/// Create inscription transactions corresponding to a [`BlobEntry`].
+/// Create inscription transactions corresponding to [`BlobEntry`]s, all revealed by the same
+/// reveal txn when they're batched.
 ///
 /// This is used during one of the cases:
-/// 1. A new blob intent needs to be signed
-/// 2. A signed intent needs to be resigned because somehow its inputs were spent/missing
+/// 1. New blob intents need to be signed
+/// 2. Signed intents need to be resigned because somehow their inputs were spent/missing
 /// 3. A confirmed block that includes the tx gets reorged
+///
+/// The signed transactions are submitted to the node's mempool right away, returns their txids
+/// along with the status the blobs should be in after that.
 pub async fn create_and_sign_blob_inscriptions(
-    blobentry: &BlobEntry,
+    blobentries: &[BlobEntry],
     broadcast_handle: &L1BroadcastHandle,
-    client: Arc<impl Reader + Wallet + Signer>,
+    client: Arc<impl Reader + Broadcaster + Wallet + Signer>,
     config: &WriterConfig,
-) -> Result<(Buf32, Buf32), InscriptionError> {
-    trace!("Creating and signing blob inscriptions");
-    let (commit, reveal) = build_inscription_txs(&blobentry.blob, &client, config).await?;
+) -> WriterResult<(Buf32, Buf32, BlobL1Status)> {
+    trace!(blobs = %blobentries.len(), "Creating and signing blob inscriptions");
+    let insc_data = blobentries.iter().map(to_inscription_data).collect();
+    let (commit, reveal) = build_inscription_txs(insc_data, &client, config).await?;
+
+    // The mempool would reject these anyway, better not to sign and broadcast them
+    check_dust_outputs(&commit)?;
+    check_dust_outputs(&reveal)?;
 
     let ctxid = commit.compute_txid();
     debug!(commit_txid = ?ctxid, "Signing commit transaction");
     let signed_commit = client
         .sign_raw_transaction_with_wallet(&commit)
         .await
-        .expect("could not sign commit tx")
+        .map_err(signing_error)?
         .hex;
 
     let signed_commit: Transaction = consensus::encode::deserialize_hex(&signed_commit)
-        .expect("could not deserialize transaction");
+        .map_err(|e| WriterError::Signing(format!("wallet returned invalid tx: {e}")))?;
     let cid: Buf32 = signed_commit.compute_txid().into();
     let rid: Buf32 = reveal.compute_txid().into();
 
-    let centry = L1TxEntry::from_tx(&signed_commit);
-    let rentry = L1TxEntry::from_tx(&reveal);
+    let mut centry = L1TxEntry::from_tx(&signed_commit);
+    let mut rentry = L1TxEntry::from_tx(&reveal);
+
+    // The reveal spends the commit, so there's no point in trying it if the commit didn't make it
+    centry.status = publish_tx(client.as_ref(), &signed_commit).await;
+    if centry.status == L1TxStatus::Published {
+        rentry.status = publish_tx(client.as_ref(), &reveal).await;
+    }
+    let status = determine_blob_next_status(&centry.status, &rentry.status);
 
     // These don't need to be atomic. It will be handled by writer task if it does not find both
     // commit-reveal txs in db by triggering re-signing.
-    let _ = broadcast_handle
-        .put_tx_entry(cid, centry)
-        .await
-        .map_err(|e| InscriptionError::Other(e.into()))?;
-    let _ = broadcast_handle
-        .put_tx_entry(rid, rentry)
-        .await
-        .map_err(|e| InscriptionError::Other(e.into()))?;
-    Ok((cid, rid))
+    let _ = broadcast_handle.put_tx_entry(cid, centry).await?;
+    let _ = broadcast_handle.put_tx_entry(rid, rentry).await?;
+    Ok((cid, rid, status))
+}
+
+/// Failing to sign with the wallet is only worth retrying if the node couldn't be reached.
+fn signing_error(err: ClientError) -> WriterError {
+    if err.is_retryable() {
+        WriterError::Rpc(err)
+    } else {
+        WriterError::Signing(err.to_string())
+    }
+}
+
+/// Submits the blob's commit and reveal txs to the node's mempool again if their entries are still
+/// [`L1TxStatus::Unpublished`], updating the entries with how that went.
+///
+/// Entries that are missing are left alone, the blob is signed again when its status is checked.
+pub async fn rebroadcast_blob_txs(
+    blobentry: &BlobEntry,
+    broadcast_handle: &L1BroadcastHandle,
+    client: &impl Broadcaster,
+) -> WriterResult<()> {
+    let cid = blobentry.commit_txid;
+    let rid = blobentry.reveal_txid;
+    let (Some(mut centry), Some(mut rentry)) = (
+        broadcast_handle.get_tx_entry_by_id_async(cid).await?,
+        broadcast_handle.get_tx_entry_by_id_async(rid).await?,
+    ) else {
+        return Ok(());
+    };
+
+    if centry.status == L1TxStatus::Unpublished {
+        debug!(commit_txid = %cid, "Rebroadcasting commit transaction");
+        centry.status = publish_tx(client, &stored_tx(&centry)?).await;
+        broadcast_handle.put_tx_entry(cid, centry.clone()).await?;
+    }
+
+    // The reveal spends the commit, so there's no point in trying it if the commit didn't make it
+    let commit_out = !matches!(
+        centry.status,
+        L1TxStatus::Unpublished | L1TxStatus::InvalidInputs
+    );
+    if commit_out && rentry.status == L1TxStatus::Unpublished {
+        debug!(reveal_txid = %rid, "Rebroadcasting reveal transaction");
+        rentry.status = publish_tx(client, &stored_tx(&rentry)?).await;
+        broadcast_handle.put_tx_entry(rid, rentry).await?;
+    }
+
+    Ok(())
+}
+
+/// Decodes the tx stored in the entry.
+fn stored_tx(txentry: &L1TxEntry) -> WriterResult<Transaction> {
+    txentry
+        .try_to_tx()
+        .map_err(|e| DbError::CodecError(e.to_string()).into())
+}
+
+/// Submits the tx to the node's mempool and returns the status its [`L1TxEntry`] should be stored
+/// with.
+///
+/// A tx the node already has is published, and one whose inputs are gone or conflict with another
+/// tx is [`L1TxStatus::InvalidInputs`].  Any other failure, like the node being unreachable or
+/// the fee being too low, isn't an error here, the tx is left [`L1TxStatus::Unpublished`] for the
+/// broadcaster to publish later.
+async fn publish_tx(client: &impl Broadcaster, tx: &Transaction) -> L1TxStatus {
+    let txid = tx.compute_txid();
+    match client.send_raw_transaction(tx).await {
+        Ok(_) => {
+            debug!(%txid, "Published tx");
+            L1TxStatus::Published
+        }
+        Err(err) if err.is_tx_already_known() => {
+            debug!(%txid, %err, "tx already known to the node");
+            L1TxStatus::Published
+        }
+        Err(err) if err.is_input_conflict() => {
+            warn!(%txid, %err, "tx inputs are missing or conflicting");
+            L1TxStatus::InvalidInputs
+        }
+        Err(err) => {
+            warn!(%txid, ?err, "could not publish tx, leaving it to the broadcaster");
+            L1TxStatus::Unpublished
+        }
+    }
+}
+
+/// Builds the data to inscribe for the entry, which includes its position if it's a chunk.
+fn to_inscription_data(blobentry: &BlobEntry) -> InscriptionData {
+    let blob = blobentry.blob.clone();
+    match &blobentry.chunk {
+        Some(info) => InscriptionData::new_chunk(
+            blob,
+            InscriptionChunk::new(info.blob_commitment, info.index, info.total),
+        ),
+        None => InscriptionData::new(blob),
+    }
 }
 
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
 
+    use async_trait::async_trait;
+    use bitcoin::Txid;
     use strata_db::types::{BlobEntry, BlobL1Status};
     use strata_primitives::hash;
 
     use super::*;
     use crate::{
-        test_utils::TestBitcoinClient,
+        rpc::{error::ClientError, ClientResult},
+        test_utils::{TestBitcoinClient, SOME_TX},
         writer::test_utils::{get_broadcast_handle, get_config, get_inscription_ops},
     };
 
@@ -92,15 +209,190 @@ mod test {
             .await
             .unwrap();
 
-        let (cid, rid) =
-            create_and_sign_blob_inscriptions(&entry, bcast_handle.as_ref(), client, &config)
-                .await
-                .unwrap();
+        let (cid, rid, status) = create_and_sign_blob_inscriptions(
+            &[entry.clone()],
+            bcast_handle.as_ref(),
+            client,
+            &config,
+        )
+        .await
+        .unwrap();
 
         // Check if corresponding txs exist in db
         let ctx = bcast_handle.get_tx_entry_by_id_async(cid).await.unwrap();
         let rtx = bcast_handle.get_tx_entry_by_id_async(rid).await.unwrap();
         assert!(ctx.is_some());
         assert!(rtx.is_some());
+
+        // The test client accepts every tx
+        assert_eq!(ctx.unwrap().status, L1TxStatus::Published);
+        assert_eq!(rtx.unwrap().status, L1TxStatus::Published);
+        assert_eq!(status, BlobL1Status::Published);
+    }
+
+    #[tokio::test]
+    async fn test_sign_failure_paths() {
+        let bcast_handle = get_broadcast_handle();
+        let client = Arc::new(TestBitcoinClient::new(1));
+        let entries = [BlobEntry::new_unsigned([1; 100].to_vec())];
+
+        // The wallet can't fund the reveal output
+        let mut config = get_config();
+        config.amount_for_reveal_txn = 21_000_000 * 100_000_000;
+        let res =
+            create_and_sign_blob_inscriptions(&entries, &bcast_handle, client.clone(), &config)
+                .await;
+        assert!(matches!(res, Err(WriterError::InsufficientFunds(..))));
+
+        // The reveal output would be dust
+        let mut config = get_config();
+        config.amount_for_reveal_txn = 1;
+        let res = create_and_sign_blob_inscriptions(&entries, &bcast_handle, client, &config).await;
+        assert!(matches!(res, Err(WriterError::DustOutput { value: 1, .. })));
+
+        // Failing to sign is only retried if the node couldn't be reached
+        let err = signing_error(ClientError::Timeout);
+        assert!(matches!(err, WriterError::Rpc(ClientError::Timeout)));
+        assert!(err.is_retryable());
+
+        let err = signing_error(ClientError::Server(-4, "Private keys are disabled".into()));
+        assert!(matches!(err, WriterError::Signing(_)));
+        assert!(!err.is_retryable());
+    }
+
+    /// Client whose node fails every broadcast with the given error.
+    struct RejectingClient(ClientError);
+
+    #[async_trait]
+    impl Broadcaster for RejectingClient {
+        async fn send_raw_transaction(&self, _tx: &Transaction) -> ClientResult<Txid> {
+            Err(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_tx() {
+        let tx: Transaction = consensus::encode::deserialize_hex(SOME_TX).unwrap();
+
+        let status = publish_tx(&TestBitcoinClient::new(0), &tx).await;
+        assert_eq!(status, L1TxStatus::Published);
+
+        let cases = [
+            (
+                ClientError::Server(-26, "txn-already-in-mempool".into()),
+                L1TxStatus::Published,
+            ),
+            (
+                ClientError::Server(-26, "txn-already-known".into()),
+                L1TxStatus::Published,
+            ),
+            (
+                ClientError::Server(-27, "Transaction already in block chain".into()),
+                L1TxStatus::Published,
+            ),
+            (
+                ClientError::Server(-25, "bad-txns-inputs-missingorspent".into()),
+                L1TxStatus::InvalidInputs,
+            ),
+            (
+                ClientError::Server(-26, "txn-mempool-conflict".into()),
+                L1TxStatus::InvalidInputs,
+            ),
+            (
+                ClientError::Server(-26, "min relay fee not met".into()),
+                L1TxStatus::Unpublished,
+            ),
+            (ClientError::Timeout, L1TxStatus::Unpublished),
+        ];
+        for (err, expected) in cases {
+            let client = RejectingClient(err.clone());
+            let status = publish_tx(&client, &tx).await;
+            assert_eq!(status, expected, "{err}");
+        }
+    }
+
+    #[test]
+    fn test_rejected_blob_needs_resign() {
+        let status =
+            determine_blob_next_status(&L1TxStatus::InvalidInputs, &L1TxStatus::Unpublished);
+        assert_eq!(status, BlobL1Status::NeedsResign);
+
+        let status = determine_blob_next_status(&L1TxStatus::Published, &L1TxStatus::InvalidInputs);
+        assert_eq!(status, BlobL1Status::NeedsResign);
+    }
+
+    async fn set_unpublished(bcast_handle: &L1BroadcastHandle, txids: &[Buf32]) {
+        for txid in txids {
+            let mut txentry = bcast_handle
+                .get_tx_entry_by_id_async(*txid)
+                .await
+                .unwrap()
+                .unwrap();
+            txentry.status = L1TxStatus::Unpublished;
+            bcast_handle.put_tx_entry(*txid, txentry).await.unwrap();
+        }
+    }
+
+    async fn tx_statuses(
+        bcast_handle: &L1BroadcastHandle,
+        cid: Buf32,
+        rid: Buf32,
+    ) -> (L1TxStatus, L1TxStatus) {
+        (
+            bcast_handle.get_tx_status(cid).await.unwrap().unwrap(),
+            bcast_handle.get_tx_status(rid).await.unwrap().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rebroadcast_blob_txs() {
+        let bcast_handle = get_broadcast_handle();
+        let client = Arc::new(TestBitcoinClient::new(1));
+        let mut entry = BlobEntry::new_unsigned([1; 100].to_vec());
+        let (cid, rid, _) = create_and_sign_blob_inscriptions(
+            &[entry.clone()],
+            bcast_handle.as_ref(),
+            client,
+            &get_config(),
+        )
+        .await
+        .unwrap();
+        entry.commit_txid = cid;
+        entry.reveal_txid = rid;
+
+        // Signed, but neither went out
+        set_unpublished(&bcast_handle, &[cid, rid]).await;
+
+        // The node is still unreachable
+        let client = RejectingClient(ClientError::Timeout);
+        rebroadcast_blob_txs(&entry, &bcast_handle, &client)
+            .await
+            .unwrap();
+        assert_eq!(
+            tx_statuses(&bcast_handle, cid, rid).await,
+            (L1TxStatus::Unpublished, L1TxStatus::Unpublished)
+        );
+
+        // The commit's inputs are gone, so the reveal isn't tried
+        let client = RejectingClient(ClientError::Server(
+            -25,
+            "bad-txns-inputs-missingorspent".into(),
+        ));
+        rebroadcast_blob_txs(&entry, &bcast_handle, &client)
+            .await
+            .unwrap();
+        assert_eq!(
+            tx_statuses(&bcast_handle, cid, rid).await,
+            (L1TxStatus::InvalidInputs, L1TxStatus::Unpublished)
+        );
+
+        set_unpublished(&bcast_handle, &[cid, rid]).await;
+        rebroadcast_blob_txs(&entry, &bcast_handle, &TestBitcoinClient::new(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            tx_statuses(&bcast_handle, cid, rid).await,
+            (L1TxStatus::Published, L1TxStatus::Published)
+        );
     }
 }
@@ -1,9 +1,16 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
+use bitcoin::{hashes::Hash, Txid};
 use strata_db::{
+    errors::DbError,
     traits::SequencerDatabase,
-    types::{BlobEntry, BlobL1Status, L1TxStatus},
+    types::{BlobEntry, BlobL1Status, BlobStatusTransition, L1TxStatus},
 };
+use strata_primitives::{buf::Buf32, l1::InscriptionStage};
 use strata_state::da_blob::{BlobDest, BlobIntent};
 use strata_status::StatusChannel;
 use strata_storage::ops::inscription::{Context, InscriptionDataOps};
@@ -12,10 +19,15 @@ use tracing::*;
 
 use super::config::WriterConfig;
 use crate::{
-    broadcaster::L1BroadcastHandle,
-    rpc::traits::{Reader, Signer, Wallet},
+    broadcaster::{task::reconciled_status, L1BroadcastHandle},
+    poll::PollJitter,
+    rpc::traits::{Broadcaster, Reader, Signer, Wallet},
     status::{apply_status_updates, L1StatusUpdate},
-    writer::{builder::InscriptionError, signer::create_and_sign_blob_inscriptions},
+    writer::{
+        chunk::{chunk_id, reassemble, split_into_entries, MAX_CHUNK_SIZE},
+        error::{WriterError, WriterResult},
+        signer::{create_and_sign_blob_inscriptions, rebroadcast_blob_txs},
+    },
 };
 
 /// A handle to the Inscription task.
@@ -28,13 +40,14 @@ impl InscriptionHandle {
         Self { ops }
     }
 
+    /// Stores the intent's payload to be inscribed, split across as many [`BlobEntry`]s as
+    /// needed to fit the per-inscription size limit.
     pub fn submit_intent(&self, intent: BlobIntent) -> anyhow::Result<()> {
         if intent.dest() != BlobDest::L1 {
             warn!(commitment = %intent.commitment(), "Received intent not meant for L1");
             return Ok(());
         }
 
-        let entry = BlobEntry::new_unsigned(intent.payload().to_vec());
         debug!(commitment = %intent.commitment(), "Received intent");
         if self
             .ops
@@ -45,9 +58,10 @@ impl InscriptionHandle {
             return Ok(());
         }
 
-        Ok(self
-            .ops
-            .put_blob_entry_blocking(*intent.commitment(), entry)?)
+        let entries = split_into_entries(*intent.commitment(), intent.payload(), MAX_CHUNK_SIZE);
+        debug!(commitment = %intent.commitment(), chunks = %entries.len(), "Storing blob entries");
+        self.ops.put_blob_entries_blocking(entries)?;
+        Ok(())
     }
 
     pub async fn submit_intent_async(&self, intent: BlobIntent) -> anyhow::Result<()> {
@@ -56,7 +70,6 @@ impl InscriptionHandle {
             return Ok(());
         }
 
-        let entry = BlobEntry::new_unsigned(intent.payload().to_vec());
         debug!(commitment = %intent.commitment(), "Received intent");
 
         if self
@@ -68,11 +81,91 @@ impl InscriptionHandle {
             warn!(commitment = %intent.commitment(), "Received duplicate intent");
             return Ok(());
         }
+
+        let entries = split_into_entries(*intent.commitment(), intent.payload(), MAX_CHUNK_SIZE);
+        debug!(commitment = %intent.commitment(), chunks = %entries.len(), "Storing blob entries");
+        self.ops.put_blob_entries_async(entries).await?;
+        Ok(())
+    }
+
+    /// Fetches all the entries for the blob with the given commitment, in chunk order.
+    pub async fn get_blob_entries_async(
+        &self,
+        commitment: Buf32,
+    ) -> anyhow::Result<Option<Vec<BlobEntry>>> {
+        let Some(first) = self.ops.get_blob_entry_async(commitment).await? else {
+            return Ok(None);
+        };
+
+        let total = first.chunk.as_ref().map_or(1, |c| c.total);
+        let mut entries = vec![first];
+        for i in 1..total {
+            let id = chunk_id(&commitment, i);
+            let entry = self
+                .ops
+                .get_blob_entry_async(id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing chunk {i} of blob {commitment}"))?;
+            entries.push(entry);
+        }
+
+        Ok(Some(entries))
+    }
+
+    /// Reassembles the payload of the blob with the given commitment from its chunks.
+    pub async fn get_blob_payload_async(
+        &self,
+        commitment: Buf32,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(entries) = self.get_blob_entries_async(commitment).await? else {
+            return Ok(None);
+        };
+
+        let payload = reassemble(&entries)
+            .ok_or_else(|| anyhow::anyhow!("inconsistent chunks for blob {commitment}"))?;
+        Ok(Some(payload))
+    }
+
+    /// Returns the status of the blob as a whole, which is the status of its least advanced
+    /// chunk.  The blob is only finalized once all of its chunks are.
+    pub async fn get_blob_status_async(
+        &self,
+        commitment: Buf32,
+    ) -> anyhow::Result<Option<BlobL1Status>> {
+        let Some(entries) = self.get_blob_entries_async(commitment).await? else {
+            return Ok(None);
+        };
+
+        let status = entries
+            .into_iter()
+            .map(|e| e.status)
+            .find(|s| *s != BlobL1Status::Finalized)
+            .unwrap_or(BlobL1Status::Finalized);
+        Ok(Some(status))
+    }
+
+    /// Fetches the entries that have been in the status since they were created longer than
+    /// `age` ago, oldest first, along with their ids.  Used to alert on blobs that got stuck.
+    pub async fn get_blobs_older_than_async(
+        &self,
+        status: BlobL1Status,
+        age: Duration,
+    ) -> anyhow::Result<Vec<(Buf32, BlobEntry)>> {
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        let before_ms = now_ms.saturating_sub(age.as_millis() as u64);
         Ok(self
             .ops
-            .put_blob_entry_async(*intent.commitment(), entry)
+            .get_blobs_created_before_async(status, before_ms)
             .await?)
     }
+
+    /// Fetches the log of status transitions of the entry, oldest first.
+    pub async fn get_blob_transitions_async(
+        &self,
+        id: Buf32,
+    ) -> anyhow::Result<Vec<BlobStatusTransition>> {
+        Ok(self.ops.get_blob_transitions_async(id).await?)
+    }
 }
 
 /// Starts the inscription task.
@@ -85,7 +178,7 @@ impl InscriptionHandle {
 /// [`Result<InscriptionHandle>`](anyhow::Result)
 pub fn start_inscription_task<D: SequencerDatabase + Send + Sync + 'static>(
     executor: &TaskExecutor,
-    bitcoin_client: Arc<impl Reader + Wallet + Signer + Send + Sync + 'static>,
+    bitcoin_client: Arc<impl Reader + Broadcaster + Wallet + Signer + Send + Sync + 'static>,
     config: WriterConfig,
     db: Arc<D>,
     status_channel: StatusChannel,
@@ -112,8 +205,8 @@ pub fn start_inscription_task<D: SequencerDatabase + Send + Sync + 'static>(
     Ok(inscription_handle)
 }
 
-/// Looks into the database from descending index order till it reaches 0 or `Finalized`
-/// [`BlobEntry`] from which the rest of the [`BlobEntry`]s should be watched.
+/// Looks into the database from descending index order till it reaches 0 or a `Finalized` or
+/// `Failed` [`BlobEntry`] from which the rest of the [`BlobEntry`]s should be watched.
 fn get_next_blobidx_to_watch(insc_ops: &InscriptionDataOps) -> anyhow::Result<u64> {
     let mut next_idx = insc_ops.get_next_blob_idx_blocking()?;
 
@@ -121,7 +214,7 @@ fn get_next_blobidx_to_watch(insc_ops: &InscriptionDataOps) -> anyhow::Result<u6
         let Some(blob) = insc_ops.get_blob_entry_by_idx_blocking(next_idx - 1)? else {
             break;
         };
-        if blob.status == BlobL1Status::Finalized {
+        if blob.status.is_terminal() {
             break;
         };
         next_idx -= 1;
@@ -139,161 +232,1120 @@ fn get_next_blobidx_to_watch(insc_ops: &InscriptionDataOps) -> anyhow::Result<u6
 /// [`BlobL1Status::Finalized`]
 pub async fn watcher_task(
     next_blbidx_to_watch: u64,
-    bitcoin_client: Arc<impl Reader + Wallet + Signer>,
+    bitcoin_client: Arc<impl Reader + Broadcaster + Wallet + Signer>,
     config: WriterConfig,
     insc_ops: Arc<InscriptionDataOps>,
     broadcast_handle: Arc<L1BroadcastHandle>,
     status_channel: StatusChannel,
 ) -> anyhow::Result<()> {
     info!("Starting L1 writer's watcher task");
-    let interval = tokio::time::interval(Duration::from_millis(config.poll_duration_ms));
-    tokio::pin!(interval);
+    let mut poll_jitter = PollJitter::new(config.poll_duration_ms, config.poll_jitter_pct);
 
-    let mut curr_blobidx = next_blbidx_to_watch;
+    // A reorg while we were down could have undone blobs we consider finalized.  Not being able
+    // to check that shouldn't stop the watcher, so it's retried while the node can't be reached
+    // and skipped on anything else.
+    let demoted_blobidx = loop {
+        match reconcile_finalized_blobs(
+            bitcoin_client.as_ref(),
+            &insc_ops,
+            &broadcast_handle,
+            config.reorg_safe_depth,
+        )
+        .await
+        {
+            Ok(idx) => break idx,
+            Err(err) if err.is_retryable() => {
+                warn!(%err, "Could not reconcile finalized blobs, retrying");
+                tokio::time::sleep(poll_jitter.next_interval()).await;
+            }
+            Err(err) => {
+                error!(%err, "Could not reconcile finalized blobs, skipping");
+                break None;
+            }
+        }
+    };
+    let mut curr_blobidx =
+        demoted_blobidx.map_or(next_blbidx_to_watch, |idx| idx.min(next_blbidx_to_watch));
     loop {
-        interval.as_mut().tick().await;
-
-        if let Some(blobentry) = insc_ops.get_blob_entry_by_idx_async(curr_blobidx).await? {
-            match blobentry.status {
-                // If unsigned or needs resign, create new signed commit/reveal txs and update the
-                // entry
-                BlobL1Status::Unsigned | BlobL1Status::NeedsResign => {
-                    debug!(?blobentry.status, %curr_blobidx, "Processing unsigned blobentry");
-                    match create_and_sign_blob_inscriptions(
-                        &blobentry,
-                        &broadcast_handle,
-                        bitcoin_client.clone(),
-                        &config,
-                    )
-                    .await
-                    {
-                        Ok((cid, rid)) => {
-                            let mut updated_entry = blobentry.clone();
-                            updated_entry.status = BlobL1Status::Unpublished;
-                            updated_entry.commit_txid = cid;
-                            updated_entry.reveal_txid = rid;
-                            update_existing_entry(curr_blobidx, updated_entry, &insc_ops).await?;
-
-                            debug!(%curr_blobidx, "Signed blob");
-                        }
-                        Err(InscriptionError::NotEnoughUtxos(required, available)) => {
-                            // Just wait till we have enough utxos and let the status be `Unsigned`
-                            // or `NeedsResign`
-                            // Maybe send an alert
-                            error!(%required, %available, "Not enough utxos available to create commit/reveal transaction");
-                        }
-                        e => {
-                            e?;
-                        }
+        tokio::time::sleep(poll_jitter.next_interval()).await;
+
+        let outcome = watcher_step(
+            curr_blobidx,
+            bitcoin_client.clone(),
+            &config,
+            &insc_ops,
+            &broadcast_handle,
+            &status_channel,
+        )
+        .await?;
+
+        if matches!(outcome, StepOutcome::Finalized | StepOutcome::Failed) {
+            curr_blobidx += 1;
+        }
+    }
+}
+
+/// Rechecks the txs of the most recently finalized blobs against L1, demoting the blobs, and their
+/// txs, that aren't final anymore so they're watched again. Returns the lowest index of the
+/// demoted blobs, if any.
+///
+/// Blobs are checked from the latest down to the first one that's still final, since the ones
+/// before it are buried even deeper.
+pub(crate) async fn reconcile_finalized_blobs(
+    bitcoin_client: &(impl Reader + Wallet),
+    insc_ops: &InscriptionDataOps,
+    broadcast_handle: &L1BroadcastHandle,
+    reorg_safe_depth: u64,
+) -> WriterResult<Option<u64>> {
+    let mempool: HashSet<Txid> = bitcoin_client
+        .get_raw_mempool()
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut demoted_blobidx = None;
+    let mut idx = insc_ops.get_next_blob_idx_async().await?;
+    while idx > 0 {
+        idx -= 1;
+        let Some(blobentry) = insc_ops.get_blob_entry_by_idx_async(idx).await? else {
+            break;
+        };
+        if blobentry.status != BlobL1Status::Finalized {
+            continue;
+        }
+
+        // Both txs are looked up in a single round trip.
+        let txids = [blobentry.commit_txid, blobentry.reveal_txid];
+        let btc_txids = txids.map(|txid| Txid::from_byte_array(txid.0));
+        let tx_infos = bitcoin_client.get_transactions_batch(&btc_txids).await?;
+
+        let mut tx_statuses = Vec::with_capacity(2);
+        for (txid, info) in txids.into_iter().zip(tx_infos) {
+            let confirmations = match info {
+                Ok(info) => info.confirmations,
+                Err(e) if e.is_tx_not_found() => 0,
+                Err(e) => return Err(e.into()),
+            };
+            let status = reconcile_tx_status(
+                broadcast_handle,
+                txid,
+                confirmations,
+                &mempool,
+                reorg_safe_depth,
+            )
+            .await?;
+            tx_statuses.push(status);
+        }
+
+        let new_status = determine_blob_next_status(&tx_statuses[0], &tx_statuses[1]);
+        if new_status == BlobL1Status::Finalized {
+            break;
+        }
+
+        warn!(%idx, ?new_status, "Finalized blob was reorged out, watching it again");
+        let mut updated_entry = blobentry;
+        updated_entry.status = new_status;
+        update_existing_entry(
+            idx,
+            updated_entry,
+            "reorged out after finalization",
+            insc_ops,
+        )
+        .await?;
+        demoted_blobidx = Some(idx);
+    }
+
+    Ok(demoted_blobidx)
+}
+
+/// Determines the status of the tx from its confirmations on L1 and updates its broadcast entry to
+/// match.
+async fn reconcile_tx_status(
+    broadcast_handle: &L1BroadcastHandle,
+    txid: Buf32,
+    confirmations: u64,
+    mempool: &HashSet<Txid>,
+    reorg_safe_depth: u64,
+) -> WriterResult<L1TxStatus> {
+    let btc_txid = Txid::from_byte_array(txid.0);
+    let status = reconciled_status(confirmations, mempool.contains(&btc_txid), reorg_safe_depth);
+
+    if let Some(mut txentry) = broadcast_handle.get_tx_entry_by_id_async(txid).await? {
+        if txentry.status != status {
+            txentry.status = status.clone();
+            broadcast_handle.reopen_tx_entry(txid, txentry).await?;
+        }
+    }
+
+    Ok(status)
+}
+
+/// What a [`watcher_step`] made of the blob entry it processed.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StepOutcome {
+    /// There's no entry at the index yet.
+    NoEntry,
+
+    /// The entry is still being watched and is now in the given status.
+    Pending(BlobL1Status),
+
+    /// The entry is finalized, so the next one should be watched.
+    Finalized,
+
+    /// The entry was given up on, so the next one should be watched.
+    Failed,
+}
+
+/// Processes the blob entry at `curr_blobidx` once, signing it or updating its status from the
+/// status of its commit/reveal txs.
+pub(crate) async fn watcher_step(
+    curr_blobidx: u64,
+    bitcoin_client: Arc<impl Reader + Broadcaster + Wallet + Signer>,
+    config: &WriterConfig,
+    insc_ops: &InscriptionDataOps,
+    broadcast_handle: &L1BroadcastHandle,
+    status_channel: &StatusChannel,
+) -> WriterResult<StepOutcome> {
+    let Some(blobentry) = insc_ops.get_blob_entry_by_idx_async(curr_blobidx).await? else {
+        // No blob exists, just wait for blob's presence in db
+        info!(%curr_blobidx, "Waiting for blobentry to be present in db");
+        return Ok(StepOutcome::NoEntry);
+    };
+
+    match blobentry.status {
+        // If unsigned or needs resign, create new signed commit/reveal txs and update the
+        // entry
+        BlobL1Status::Unsigned | BlobL1Status::NeedsResign => {
+            let is_resign = blobentry.status == BlobL1Status::NeedsResign;
+            if is_resign && blobentry.resign_count >= config.max_resigns {
+                // Most likely the funding utxo is gone for good, don't hold up the blobs after it
+                error!(%curr_blobidx, resign_count = %blobentry.resign_count, "Giving up on blob after too many resigns");
+                let mut updated_entry = blobentry.clone();
+                updated_entry.status = BlobL1Status::Failed;
+                update_l1_status(
+                    curr_blobidx,
+                    &updated_entry,
+                    &BlobL1Status::Failed,
+                    status_channel,
+                )
+                .await;
+                let reason = "too many resigns";
+                update_entry_with_batch(
+                    curr_blobidx,
+                    updated_entry,
+                    reason,
+                    insc_ops,
+                    status_channel,
+                )
+                .await?;
+                return Ok(StepOutcome::Failed);
+            }
+
+            let Some(batch) = collect_batch(curr_blobidx, &blobentry, config, insc_ops).await?
+            else {
+                debug!(%curr_blobidx, "Waiting for more blobs to batch with");
+                return Ok(StepOutcome::Pending(blobentry.status));
+            };
+
+            debug!(?blobentry.status, %curr_blobidx, blobs = %batch.len(), "Processing unsigned blobentry");
+            let entries: Vec<_> = batch.iter().map(|(_, entry)| entry.clone()).collect();
+            match create_and_sign_blob_inscriptions(
+                &entries,
+                broadcast_handle,
+                bitcoin_client,
+                config,
+            )
+            .await
+            {
+                Ok((cid, rid, status)) => {
+                    let mut updated_entry = blobentry.clone();
+                    updated_entry.status = status.clone();
+                    updated_entry.commit_txid = cid;
+                    updated_entry.reveal_txid = rid;
+                    if is_resign {
+                        updated_entry.resign_count += 1;
+                    }
+                    if batch.len() > 1 {
+                        updated_entry.batch = Some(batch.iter().map(|(id, _)| *id).collect());
                     }
+                    update_l1_status(curr_blobidx, &updated_entry, &status, status_channel).await;
+                    let reason = if is_resign { "resigned" } else { "signed" };
+                    update_entry_with_batch(
+                        curr_blobidx,
+                        updated_entry,
+                        reason,
+                        insc_ops,
+                        status_channel,
+                    )
+                    .await?;
+
+                    debug!(%curr_blobidx, ?status, "Signed blob");
+                    Ok(StepOutcome::Pending(status))
                 }
-                // If finalized, nothing to do, move on to process next entry
-                BlobL1Status::Finalized => {
-                    curr_blobidx += 1;
+                Err(WriterError::InsufficientFunds(required, available)) => {
+                    // Just wait till we have enough utxos and let the status be `Unsigned`
+                    // or `NeedsResign`
+                    // Maybe send an alert
+                    error!(%required, %available, "Not enough utxos available to create commit/reveal transaction");
+                    Ok(StepOutcome::Pending(blobentry.status))
                 }
-                // If entry is signed but not finalized or excluded yet, check broadcast txs status
-                BlobL1Status::Published | BlobL1Status::Confirmed | BlobL1Status::Unpublished => {
-                    debug!(%curr_blobidx, "Checking blobentry's broadcast status");
-                    let commit_tx = broadcast_handle
-                        .get_tx_entry_by_id_async(blobentry.commit_txid)
-                        .await?;
-                    let reveal_tx = broadcast_handle
-                        .get_tx_entry_by_id_async(blobentry.reveal_txid)
-                        .await?;
-
-                    match (commit_tx, reveal_tx) {
-                        (Some(ctx), Some(rtx)) => {
-                            let new_status = determine_blob_next_status(&ctx.status, &rtx.status);
-                            debug!(?new_status, "The next status for blob");
-
-                            update_l1_status(&blobentry, &new_status, &status_channel).await;
-
-                            // Update blobentry with new status
-                            let mut updated_entry = blobentry.clone();
-                            updated_entry.status = new_status.clone();
-                            update_existing_entry(curr_blobidx, updated_entry, &insc_ops).await?;
-
-                            if new_status == BlobL1Status::Finalized {
-                                curr_blobidx += 1;
-                            }
-                        }
-                        _ => {
-                            warn!(%curr_blobidx, "Corresponding commit/reveal entry for blobentry not found in broadcast db. Sign and create transactions again.");
-                            let mut updated_entry = blobentry.clone();
-                            updated_entry.status = BlobL1Status::Unsigned;
-                            update_existing_entry(curr_blobidx, updated_entry, &insc_ops).await?;
-                        }
-                    }
+                Err(e) if e.is_retryable() => {
+                    // The node is unreachable, try again on the next tick
+                    warn!(%curr_blobidx, %e, "Could not reach L1 client to sign blob, will retry");
+                    Ok(StepOutcome::Pending(blobentry.status))
                 }
+                Err(e) => Err(e),
             }
-        } else {
-            // No blob exists, just continue the loop to wait for blob's presence in db
-            info!(%curr_blobidx, "Waiting for blobentry to be present in db");
+        }
+        // If finalized, nothing to do, move on to process next entry
+        BlobL1Status::Finalized => Ok(StepOutcome::Finalized),
+        // If given up on, also nothing to do
+        BlobL1Status::Failed => Ok(StepOutcome::Failed),
+        // If signed but never made it out, e.g. the node was down or we restarted before it went
+        // out, broadcast it again before checking on it
+        BlobL1Status::Unpublished => {
+            debug!(%curr_blobidx, "Rebroadcasting unpublished blobentry");
+            rebroadcast_blob_txs(&blobentry, broadcast_handle, bitcoin_client.as_ref()).await?;
+            check_and_update_blobentry_status(
+                curr_blobidx,
+                blobentry,
+                insc_ops,
+                broadcast_handle,
+                status_channel,
+            )
+            .await
+        }
+        // If entry is signed but not finalized or excluded yet, check broadcast txs status
+        BlobL1Status::Published | BlobL1Status::Confirmed => {
+            check_and_update_blobentry_status(
+                curr_blobidx,
+                blobentry,
+                insc_ops,
+                broadcast_handle,
+                status_channel,
+            )
+            .await
         }
     }
 }
 
+/// Updates the blob entry's status from the status of its commit/reveal txs in the broadcaster,
+/// having it signed again if they're missing.
+async fn check_and_update_blobentry_status(
+    curr_blobidx: u64,
+    blobentry: BlobEntry,
+    insc_ops: &InscriptionDataOps,
+    broadcast_handle: &L1BroadcastHandle,
+    status_channel: &StatusChannel,
+) -> WriterResult<StepOutcome> {
+    debug!(%curr_blobidx, "Checking blobentry's broadcast status");
+    let commit_tx = broadcast_handle
+        .get_tx_entry_by_id_async(blobentry.commit_txid)
+        .await?;
+    let reveal_tx = broadcast_handle
+        .get_tx_entry_by_id_async(blobentry.reveal_txid)
+        .await?;
+
+    match (commit_tx, reveal_tx) {
+        (Some(ctx), Some(rtx)) => {
+            let new_status = determine_blob_next_status(&ctx.status, &rtx.status);
+            debug!(?new_status, "The next status for blob");
+
+            update_l1_status(curr_blobidx, &blobentry, &new_status, status_channel).await;
+
+            // Update blobentry with new status, along with the others inscribed by the same txs
+            let mut updated_entry = blobentry.clone();
+            updated_entry.status = new_status.clone();
+            let reason = format!("commit tx {:?}, reveal tx {:?}", ctx.status, rtx.status);
+            update_entry_with_batch(
+                curr_blobidx,
+                updated_entry,
+                &reason,
+                insc_ops,
+                status_channel,
+            )
+            .await?;
+
+            if new_status == BlobL1Status::Finalized {
+                // Chunks are watched in order, so once the last one is
+                // finalized the whole blob is.
+                if let Some(chunk) = blobentry.chunk.as_ref().filter(|c| c.is_last()) {
+                    info!(commitment = %chunk.blob_commitment, chunks = %chunk.total, "Chunked blob finalized");
+                }
+                return Ok(StepOutcome::Finalized);
+            }
+            Ok(StepOutcome::Pending(new_status))
+        }
+        _ => {
+            warn!(%curr_blobidx, "Corresponding commit/reveal entry for blobentry not found in broadcast db. Sign and create transactions again.");
+            let mut updated_entry = blobentry.clone();
+            updated_entry.status = BlobL1Status::Unsigned;
+            let reason = "commit/reveal txs missing from broadcast db";
+            update_entry_with_batch(
+                curr_blobidx,
+                updated_entry,
+                reason,
+                insc_ops,
+                status_channel,
+            )
+            .await?;
+            Ok(StepOutcome::Pending(BlobL1Status::Unsigned))
+        }
+    }
+}
+
+/// Collects the entries to sign together with the one at `curr_blobidx`, in order, along with
+/// their ids.
+///
+/// An entry that was already batched is signed again with the rest of its batch. Otherwise, if
+/// batching is enabled, the unsigned entries right after it are added while they fit, and `None`
+/// is returned if there's still room for more and the oldest entry can wait for them.
+async fn collect_batch(
+    curr_blobidx: u64,
+    blobentry: &BlobEntry,
+    config: &WriterConfig,
+    insc_ops: &InscriptionDataOps,
+) -> WriterResult<Option<Vec<(Buf32, BlobEntry)>>> {
+    let id = get_blob_entry_id(curr_blobidx, insc_ops).await?;
+
+    if let Some(ids) = &blobentry.batch {
+        let first_idx = batch_first_idx(curr_blobidx, id, ids)?;
+        let mut batch = Vec::with_capacity(ids.len());
+        for (offset, id) in ids.iter().enumerate() {
+            let idx = first_idx + offset as u64;
+            let entry = insc_ops
+                .get_blob_entry_by_idx_async(idx)
+                .await?
+                .ok_or_else(|| missing_entry(idx))?;
+            batch.push((*id, entry));
+        }
+        return Ok(Some(batch));
+    }
+
+    let mut batch = vec![(id, blobentry.clone())];
+
+    let Some(batching) = config
+        .batching
+        .as_ref()
+        .filter(|_| blobentry.status == BlobL1Status::Unsigned)
+    else {
+        return Ok(Some(batch));
+    };
+
+    let mut size = blobentry.blob.len();
+    let mut closed = false;
+    let mut idx = curr_blobidx + 1;
+    while let Some(entry) = insc_ops.get_blob_entry_by_idx_async(idx).await? {
+        // Only fresh entries can join, and they have to stay in order
+        if batch.len() >= batching.max_batch_blobs
+            || entry.status != BlobL1Status::Unsigned
+            || entry.batch.is_some()
+            || size + entry.blob.len() > batching.max_batch_bytes
+        {
+            closed = true;
+            break;
+        }
+        size += entry.blob.len();
+        batch.push((get_blob_entry_id(idx, insc_ops).await?, entry));
+        idx += 1;
+    }
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let waited_ms = now_ms.saturating_sub(blobentry.created_at_ms);
+    let has_room = batch.len() < batching.max_batch_blobs && size < batching.max_batch_bytes;
+    if !closed && has_room && waited_ms < batching.max_wait_ms {
+        return Ok(None);
+    }
+
+    Ok(Some(batch))
+}
+
+/// Stores the updated entry along with the other entries of its batch, brought to the same status
+/// and txs so the whole batch moves along together.  They're all written at once.
+async fn update_entry_with_batch(
+    blobidx: u64,
+    updated_entry: BlobEntry,
+    reason: &str,
+    insc_ops: &InscriptionDataOps,
+    status_channel: &StatusChannel,
+) -> WriterResult<()> {
+    let id = get_blob_entry_id(blobidx, insc_ops).await?;
+    let mut updates = Vec::new();
+    if let Some(ids) = &updated_entry.batch {
+        let first_idx = batch_first_idx(blobidx, id, ids)?;
+        for (idx, member_id) in (first_idx..).zip(ids).filter(|(idx, _)| *idx != blobidx) {
+            let Some(mut entry) = insc_ops.get_blob_entry_async(*member_id).await? else {
+                warn!(%idx, %blobidx, "Batched blobentry missing from db");
+                continue;
+            };
+            entry.status = updated_entry.status.clone();
+            entry.commit_txid = updated_entry.commit_txid;
+            entry.reveal_txid = updated_entry.reveal_txid;
+            entry.resign_count = updated_entry.resign_count;
+            entry.batch = updated_entry.batch.clone();
+
+            update_l1_status(idx, &entry, &updated_entry.status, status_channel).await;
+            updates.push((*member_id, entry));
+        }
+    }
+    updates.insert(0, (id, updated_entry));
+
+    insc_ops
+        .update_blob_entries_async(updates, reason.to_string())
+        .await?;
+    Ok(())
+}
+
+/// Returns the index of the first entry of the batch, given the index and id of one of its
+/// entries. The entries of a batch are always stored one after the other.
+fn batch_first_idx(blobidx: u64, id: Buf32, batch: &[Buf32]) -> WriterResult<u64> {
+    let pos = batch
+        .iter()
+        .position(|batch_id| *batch_id == id)
+        .ok_or_else(|| {
+            DbError::Other(format!("blobentry {blobidx} is not part of its own batch"))
+        })?;
+    Ok(blobidx - pos as u64)
+}
+
+async fn get_blob_entry_id(idx: u64, insc_ops: &InscriptionDataOps) -> WriterResult<Buf32> {
+    insc_ops
+        .get_blob_entry_id_async(idx)
+        .await?
+        .ok_or_else(|| missing_entry(idx))
+}
+
 async fn update_l1_status(
+    idx: u64,
     blobentry: &BlobEntry,
     new_status: &BlobL1Status,
     status_channel: &StatusChannel,
 ) {
+    let stage = match new_status {
+        BlobL1Status::Confirmed => InscriptionStage::Confirmed,
+        BlobL1Status::Finalized => InscriptionStage::Finalized,
+        BlobL1Status::Failed => InscriptionStage::Failed,
+        _ => InscriptionStage::Pending,
+    };
+    let mut status_updates = vec![L1StatusUpdate::InscriptionStage(
+        idx,
+        blobentry.reveal_txid,
+        stage,
+    )];
+
     // Update L1 status. Since we are processing one blobentry at a time, if the entry is
     // finalized/confirmed, then it means it is published as well
     if *new_status == BlobL1Status::Published
         || *new_status == BlobL1Status::Confirmed
         || *new_status == BlobL1Status::Finalized
     {
-        let status_updates = [
+        status_updates.extend([
             L1StatusUpdate::LastPublishedTxid(blobentry.reveal_txid.into()),
             L1StatusUpdate::IncrementInscriptionCount,
-        ];
-        apply_status_updates(&status_updates, status_channel).await;
+        ]);
     }
+
+    // Applied at once so readers never see the summary and the last txid out of sync
+    apply_status_updates(&status_updates, status_channel).await;
 }
 
+/// Stores the updated entry, logging the transition along with it if its status changed.
 async fn update_existing_entry(
     idx: u64,
     updated_entry: BlobEntry,
+    reason: &str,
     insc_ops: &InscriptionDataOps,
-) -> anyhow::Result<()> {
-    let msg = format!("Expect to find blobentry {idx} in db");
-    let id = insc_ops.get_blob_entry_id_async(idx).await?.expect(&msg);
-    Ok(insc_ops.put_blob_entry_async(id, updated_entry).await?)
+) -> WriterResult<()> {
+    let id = get_blob_entry_id(idx, insc_ops).await?;
+    insc_ops
+        .update_blob_entries_async(vec![(id, updated_entry)], reason.to_string())
+        .await?;
+    Ok(())
+}
+
+fn missing_entry(idx: u64) -> WriterError {
+    DbError::Other(format!("missing blobentry {idx}")).into()
 }
 
 /// Determine the status of the `BlobEntry` based on the status of its commit and reveal
 /// transactions in bitcoin.
-fn determine_blob_next_status(
+///
+/// The reveal spends the commit, so it can't be any deeper in L1 than the commit is.  If it looks
+/// like it is, e.g. because the commit was reorged out and the reveal's status wasn't caught up
+/// yet, the blob is only as far along as the commit.
+pub(super) fn determine_blob_next_status(
     commit_status: &L1TxStatus,
     reveal_status: &L1TxStatus,
 ) -> BlobL1Status {
-    match (&commit_status, &reveal_status) {
-        // If reveal is finalized, both are finalized
-        (_, L1TxStatus::Finalized { .. }) => BlobL1Status::Finalized,
-        // If reveal is confirmed, both are confirmed
-        (_, L1TxStatus::Confirmed { .. }) => BlobL1Status::Confirmed,
-        // If reveal is published regardless of commit, the blob is published
-        (_, L1TxStatus::Published) => BlobL1Status::Published,
-        // if commit has invalid inputs, needs resign
-        (L1TxStatus::InvalidInputs, _) => BlobL1Status::NeedsResign,
-        // If commit is unpublished, both are upublished
-        (L1TxStatus::Unpublished, _) => BlobL1Status::Unpublished,
-        // If commit is published but not reveal, the blob is unpublished
-        (_, L1TxStatus::Unpublished) => BlobL1Status::Unpublished,
+    match (tx_progress(commit_status), tx_progress(reveal_status)) {
         // If reveal has invalid inputs, these need resign because we can do nothing with just
         // commit tx confirmed. This should not occur in practice
-        (_, L1TxStatus::InvalidInputs) => BlobL1Status::NeedsResign,
+        (_, None) => BlobL1Status::NeedsResign,
+        // if commit has invalid inputs and the reveal never made it out, needs resign
+        (None, Some(0)) => BlobL1Status::NeedsResign,
+        // The reveal was seen so the commit made it in after all, but we don't know how deep
+        (None, Some(_)) => BlobL1Status::Published,
+        (Some(commit), Some(reveal)) => {
+            let least_advanced = if commit <= reveal {
+                commit_status
+            } else {
+                reveal_status
+            };
+            match least_advanced {
+                L1TxStatus::Unpublished => BlobL1Status::Unpublished,
+                L1TxStatus::Published => BlobL1Status::Published,
+                L1TxStatus::Confirmed { .. } => BlobL1Status::Confirmed,
+                L1TxStatus::Finalized { .. } => BlobL1Status::Finalized,
+                L1TxStatus::InvalidInputs => BlobL1Status::NeedsResign,
+            }
+        }
+    }
+}
+
+/// Returns how far the tx got towards being finalized in L1, higher being further along, or
+/// `None` if it can't get in because its inputs are invalid.
+fn tx_progress(status: &L1TxStatus) -> Option<u8> {
+    match status {
+        L1TxStatus::Unpublished => Some(0),
+        L1TxStatus::Published => Some(1),
+        L1TxStatus::Confirmed { .. } => Some(2),
+        L1TxStatus::Finalized { .. } => Some(3),
+        L1TxStatus::InvalidInputs => None,
     }
 }
 
 #[cfg(test)]
 mod test {
-    use strata_primitives::buf::Buf32;
-    use strata_test_utils::ArbitraryGenerator;
+    use strata_primitives::l1::L1Status;
+    use strata_state::client_state::ClientState;
+    use strata_test_utils::{l2::gen_params, ArbitraryGenerator};
+    use strata_tx_parser::inscription::{parse_inscriptions, InscriptionConfig};
 
     use super::*;
-    use crate::writer::test_utils::get_inscription_ops;
+    use crate::{
+        broadcaster::task::reconcile_broadcast_state,
+        test_utils::{MockL1Client, TestBitcoinClient},
+        writer::{
+            config::BlobBatchingConfig,
+            test_utils::{
+                get_broadcast_handle, get_broadcast_ops, get_config, get_inscription_ops,
+            },
+        },
+    };
+
+    fn get_status_channel() -> StatusChannel {
+        let cls: ClientState = ArbitraryGenerator::new().generate();
+        StatusChannel::new(cls, L1Status::default(), None)
+    }
+
+    /// Runs a single watcher step on the first blob entry.
+    async fn step(
+        iops: &InscriptionDataOps,
+        bcast_handle: &L1BroadcastHandle,
+        status_channel: &StatusChannel,
+    ) -> StepOutcome {
+        let client = Arc::new(TestBitcoinClient::new(1));
+        watcher_step(0, client, &get_config(), iops, bcast_handle, status_channel)
+            .await
+            .unwrap()
+    }
+
+    /// Sets the status of the blob's commit and reveal txs, like the broadcaster would.
+    async fn set_tx_statuses(
+        iops: &InscriptionDataOps,
+        bcast_handle: &L1BroadcastHandle,
+        status: L1TxStatus,
+    ) {
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        for txid in [blob.commit_txid, blob.reveal_txid] {
+            let mut txentry = bcast_handle
+                .get_tx_entry_by_id_async(txid)
+                .await
+                .unwrap()
+                .unwrap();
+            txentry.status = status.clone();
+            bcast_handle.put_tx_entry(txid, txentry).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watcher_step_fails_after_max_resigns() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+        let max_resigns = get_config().max_resigns;
+
+        let entry = BlobEntry::new_unsigned([1; 100].to_vec());
+        iops.put_blob_entry_async(Buf32::from([1; 32]), entry)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Pending(BlobL1Status::Published)
+        );
+
+        // The inputs keep getting spent from under us
+        for i in 1..=max_resigns {
+            set_tx_statuses(&iops, &bcast_handle, L1TxStatus::InvalidInputs).await;
+            assert_eq!(
+                step(&iops, &bcast_handle, &status_channel).await,
+                StepOutcome::Pending(BlobL1Status::NeedsResign)
+            );
+            assert_eq!(
+                step(&iops, &bcast_handle, &status_channel).await,
+                StepOutcome::Pending(BlobL1Status::Published)
+            );
+            let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+            assert_eq!(blob.resign_count, i);
+        }
+
+        set_tx_statuses(&iops, &bcast_handle, L1TxStatus::InvalidInputs).await;
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Pending(BlobL1Status::NeedsResign)
+        );
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Failed
+        );
+
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(blob.status, BlobL1Status::Failed);
+        assert_eq!(blob.resign_count, max_resigns);
+
+        let summary = status_channel.l1_status().inscriptions;
+        assert_eq!(summary.failed_idxs().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(summary.pending_count(), 0);
+
+        // Stays failed
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Failed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watcher_step_blob_lifecycle() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::NoEntry
+        );
+
+        let entry = BlobEntry::new_unsigned([1; 100].to_vec());
+        iops.put_blob_entry_async(Buf32::from([1; 32]), entry)
+            .await
+            .unwrap();
+
+        // Signing publishes the txs right away
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Pending(BlobL1Status::Published)
+        );
+
+        // The txs got dropped from the mempool and are published again
+        let transitions = [
+            (L1TxStatus::Unpublished, BlobL1Status::Unpublished),
+            (L1TxStatus::Published, BlobL1Status::Published),
+            (
+                L1TxStatus::Confirmed { confirmations: 1 },
+                BlobL1Status::Confirmed,
+            ),
+        ];
+        for (tx_status, blob_status) in transitions {
+            set_tx_statuses(&iops, &bcast_handle, tx_status).await;
+            assert_eq!(
+                step(&iops, &bcast_handle, &status_channel).await,
+                StepOutcome::Pending(blob_status.clone())
+            );
+            let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+            assert_eq!(blob.status, blob_status);
+
+            let summary = status_channel.l1_status().inscriptions;
+            let confirmed = blob_status == BlobL1Status::Confirmed;
+            assert_eq!(summary.pending_count(), u64::from(!confirmed));
+            assert_eq!(summary.confirmed_count(), u64::from(confirmed));
+            assert_eq!(
+                summary.unfinalized_txids().collect::<Vec<_>>(),
+                vec![(0, blob.reveal_txid)]
+            );
+        }
+
+        set_tx_statuses(
+            &iops,
+            &bcast_handle,
+            L1TxStatus::Finalized { confirmations: 6 },
+        )
+        .await;
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Finalized
+        );
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(blob.status, BlobL1Status::Finalized);
+
+        let summary = status_channel.l1_status().inscriptions;
+        assert_eq!(summary.pending_count(), 0);
+        assert_eq!(summary.confirmed_count(), 0);
+        assert_eq!(summary.finalized_count(), 1);
+
+        // Stays finalized
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Finalized
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unpublished_blob_is_rebroadcast() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+
+        let id = Buf32::from([1; 32]);
+        let entry = BlobEntry::new_unsigned([1; 100].to_vec());
+        iops.put_blob_entry_async(id, entry).await.unwrap();
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Pending(BlobL1Status::Published)
+        );
+
+        // Signed but never made it out, like if we restarted before broadcasting
+        set_tx_statuses(&iops, &bcast_handle, L1TxStatus::Unpublished).await;
+        let mut blob = iops.get_blob_entry_async(id).await.unwrap().unwrap();
+        blob.status = BlobL1Status::Unpublished;
+        iops.put_blob_entry_async(id, blob.clone()).await.unwrap();
+
+        assert_eq!(
+            step(&iops, &bcast_handle, &status_channel).await,
+            StepOutcome::Pending(BlobL1Status::Published)
+        );
+        for txid in [blob.commit_txid, blob.reveal_txid] {
+            assert_eq!(
+                bcast_handle.get_tx_status(txid).await.unwrap(),
+                Some(L1TxStatus::Published)
+            );
+        }
+        let blob = iops.get_blob_entry_async(id).await.unwrap().unwrap();
+        assert_eq!(blob.status, BlobL1Status::Published);
+    }
+
+    #[tokio::test]
+    async fn test_blob_transition_log() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+
+        let id = Buf32::from([1; 32]);
+        let entry = BlobEntry::new_unsigned([1; 100].to_vec());
+        iops.put_blob_entry_async(id, entry).await.unwrap();
+        assert!(iops
+            .get_blob_transitions_async(id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        step(&iops, &bcast_handle, &status_channel).await;
+        set_tx_statuses(&iops, &bcast_handle, L1TxStatus::InvalidInputs).await;
+        step(&iops, &bcast_handle, &status_channel).await;
+        step(&iops, &bcast_handle, &status_channel).await;
+        set_tx_statuses(
+            &iops,
+            &bcast_handle,
+            L1TxStatus::Confirmed { confirmations: 1 },
+        )
+        .await;
+        step(&iops, &bcast_handle, &status_channel).await;
+
+        // steps that don't change the status aren't recorded
+        step(&iops, &bcast_handle, &status_channel).await;
+
+        set_tx_statuses(
+            &iops,
+            &bcast_handle,
+            L1TxStatus::Finalized { confirmations: 6 },
+        )
+        .await;
+        step(&iops, &bcast_handle, &status_channel).await;
+
+        let transitions = iops.get_blob_transitions_async(id).await.unwrap();
+        let statuses: Vec<_> = transitions
+            .iter()
+            .map(|t| (t.from.clone(), t.to.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (BlobL1Status::Unsigned, BlobL1Status::Published),
+                (BlobL1Status::Published, BlobL1Status::NeedsResign),
+                (BlobL1Status::NeedsResign, BlobL1Status::Published),
+                (BlobL1Status::Published, BlobL1Status::Confirmed),
+                (BlobL1Status::Confirmed, BlobL1Status::Finalized),
+            ]
+        );
+        assert_eq!(transitions[0].reason, "signed");
+        assert_eq!(transitions[2].reason, "resigned");
+        assert!(transitions
+            .windows(2)
+            .all(|w| w[0].timestamp_ms <= w[1].timestamp_ms));
+    }
+
+    #[tokio::test]
+    async fn test_blob_lifecycle_with_mock_client() {
+        let client = Arc::new(MockL1Client::new());
+        let iops = get_inscription_ops();
+        let bcast_ops = get_broadcast_ops();
+        let (sender, _receiver) = tokio::sync::mpsc::channel(64);
+        let bcast_handle = L1BroadcastHandle::new(sender, bcast_ops.clone());
+        let status_channel = get_status_channel();
+        let config = get_config();
+        let params = gen_params();
+        let finality_depth = params.rollup().l1_reorg_safe_depth as u64;
+
+        let handle = InscriptionHandle::new(iops.clone());
+        let commitment = Buf32::from([7; 32]);
+        let intent = BlobIntent::new(BlobDest::L1, commitment, vec![1; 100]);
+        handle.submit_intent_async(intent).await.unwrap();
+
+        // Each round the node moves along, then the broadcaster and the watcher catch up
+        let expected = [
+            (0, BlobL1Status::Published),
+            (1, BlobL1Status::Confirmed),
+            (finality_depth - 2, BlobL1Status::Confirmed),
+        ];
+        for (new_blocks, blob_status) in expected {
+            client.mine_blocks(new_blocks);
+            reconcile_broadcast_state(client.as_ref(), &bcast_ops, &params)
+                .await
+                .unwrap();
+            let outcome = watcher_step(
+                0,
+                client.clone(),
+                &config,
+                &iops,
+                &bcast_handle,
+                &status_channel,
+            )
+            .await
+            .unwrap();
+            assert_eq!(outcome, StepOutcome::Pending(blob_status));
+        }
+
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        let commit_txid = Txid::from_byte_array(blob.commit_txid.0);
+        let reveal_txid = Txid::from_byte_array(blob.reveal_txid.0);
+        assert_eq!(client.confirmations(&commit_txid), finality_depth - 1);
+        assert_eq!(client.confirmations(&reveal_txid), finality_depth - 1);
+
+        // Reorg the txs back into the mempool right before they'd be final
+        client.disconnect_blocks(finality_depth - 1);
+        assert!(client.in_mempool(&reveal_txid));
+        reconcile_broadcast_state(client.as_ref(), &bcast_ops, &params)
+            .await
+            .unwrap();
+        let outcome = watcher_step(
+            0,
+            client.clone(),
+            &config,
+            &iops,
+            &bcast_handle,
+            &status_channel,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, StepOutcome::Pending(BlobL1Status::Published));
+
+        client.mine_blocks(finality_depth);
+        reconcile_broadcast_state(client.as_ref(), &bcast_ops, &params)
+            .await
+            .unwrap();
+        let outcome = watcher_step(
+            0,
+            client.clone(),
+            &config,
+            &iops,
+            &bcast_handle,
+            &status_channel,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, StepOutcome::Finalized);
+
+        // The same txs made it in, no resigning happened
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(Txid::from_byte_array(blob.reveal_txid.0), reveal_txid);
+        assert_eq!(blob.resign_count, 0);
+        assert_eq!(
+            handle.get_blob_status_async(commitment).await.unwrap(),
+            Some(BlobL1Status::Finalized)
+        );
+        assert_eq!(status_channel.l1_status().inscriptions.finalized_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reorged_finalized_blob_is_demoted() {
+        let client = Arc::new(MockL1Client::new());
+        let iops = get_inscription_ops();
+        let bcast_ops = get_broadcast_ops();
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(64);
+        let bcast_handle = L1BroadcastHandle::new(sender, bcast_ops.clone());
+        let status_channel = get_status_channel();
+        let config = get_config();
+        let params = gen_params();
+        let finality_depth = config.reorg_safe_depth;
+
+        let handle = InscriptionHandle::new(iops.clone());
+        let intent = BlobIntent::new(BlobDest::L1, Buf32::from([8; 32]), vec![1; 100]);
+        handle.submit_intent_async(intent).await.unwrap();
+
+        // Sign the blob and bury its txs deep enough to be final
+        let mut outcomes = Vec::new();
+        for new_blocks in [0, finality_depth] {
+            client.mine_blocks(new_blocks);
+            reconcile_broadcast_state(client.as_ref(), &bcast_ops, &params)
+                .await
+                .unwrap();
+            let outcome = watcher_step(
+                0,
+                client.clone(),
+                &config,
+                &iops,
+                &bcast_handle,
+                &status_channel,
+            )
+            .await
+            .unwrap();
+            outcomes.push(outcome);
+        }
+        assert_eq!(
+            outcomes,
+            vec![
+                StepOutcome::Pending(BlobL1Status::Published),
+                StepOutcome::Finalized
+            ]
+        );
+
+        // Nothing happened on L1, so it stays final
+        let demoted =
+            reconcile_finalized_blobs(client.as_ref(), &iops, &bcast_handle, finality_depth)
+                .await
+                .unwrap();
+        assert_eq!(demoted, None);
+        while receiver.try_recv().is_ok() {}
+
+        // A reorg deeper than expected puts the txs back into the mempool
+        client.disconnect_blocks(finality_depth);
+        let demoted =
+            reconcile_finalized_blobs(client.as_ref(), &iops, &bcast_handle, finality_depth)
+                .await
+                .unwrap();
+        assert_eq!(demoted, Some(0));
+
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(blob.status, BlobL1Status::Published);
+        for txid in [blob.commit_txid, blob.reveal_txid] {
+            let status = bcast_handle.get_tx_status(txid).await.unwrap();
+            assert_eq!(status, Some(L1TxStatus::Published));
+        }
+
+        // and the broadcaster is told to watch them again
+        let mut reopened = Vec::new();
+        while let Ok((_, txentry)) = receiver.try_recv() {
+            reopened.push(txentry.status);
+        }
+        assert_eq!(reopened, vec![L1TxStatus::Published; 2]);
+
+        // Once buried again, the same txs finalize it again
+        client.mine_blocks(finality_depth);
+        reconcile_broadcast_state(client.as_ref(), &bcast_ops, &params)
+            .await
+            .unwrap();
+        let outcome = watcher_step(
+            0,
+            client.clone(),
+            &config,
+            &iops,
+            &bcast_handle,
+            &status_channel,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, StepOutcome::Finalized);
+
+        let reburied = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(reburied.reveal_txid, blob.reveal_txid);
+        assert_eq!(reburied.resign_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_submit_oversized_intent_is_chunked() {
+        let iops = get_inscription_ops();
+        let handle = InscriptionHandle::new(iops.clone());
+
+        let payload: Vec<u8> = (0..(2 * MAX_CHUNK_SIZE + 10)).map(|i| i as u8).collect();
+        let intent = BlobIntent::new(BlobDest::L1, Buf32::from([5; 32]), payload.clone());
+        handle.submit_intent_async(intent.clone()).await.unwrap();
+
+        assert_eq!(iops.get_next_blob_idx_async().await.unwrap(), 3);
+        let entries = handle
+            .get_blob_entries_async(*intent.commitment())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|e| e.blob.len() <= MAX_CHUNK_SIZE));
+
+        let reassembled = handle
+            .get_blob_payload_async(*intent.commitment())
+            .await
+            .unwrap();
+        assert_eq!(reassembled, Some(payload));
+
+        // resubmitting doesn't duplicate the chunks
+        handle.submit_intent_async(intent).await.unwrap();
+        assert_eq!(iops.get_next_blob_idx_async().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_blob_status() {
+        let iops = get_inscription_ops();
+        let handle = InscriptionHandle::new(iops.clone());
+
+        let commitment = Buf32::from([6; 32]);
+        let intent = BlobIntent::builder()
+            .dest(BlobDest::L1)
+            .commitment(commitment)
+            .payload(vec![1; MAX_CHUNK_SIZE + 1])
+            .build()
+            .unwrap();
+        handle.submit_intent_async(intent).await.unwrap();
+
+        let mut first = iops
+            .get_blob_entry_async(commitment)
+            .await
+            .unwrap()
+            .unwrap();
+        first.status = BlobL1Status::Finalized;
+        iops.put_blob_entry_async(commitment, first).await.unwrap();
+
+        let status = handle.get_blob_status_async(commitment).await.unwrap();
+        assert_eq!(status, Some(BlobL1Status::Unsigned));
+
+        let id = chunk_id(&commitment, 1);
+        let mut second = iops.get_blob_entry_async(id).await.unwrap().unwrap();
+        second.status = BlobL1Status::Finalized;
+        iops.put_blob_entry_async(id, second).await.unwrap();
+
+        let status = handle.get_blob_status_async(commitment).await.unwrap();
+        assert_eq!(status, Some(BlobL1Status::Finalized));
+    }
 
     #[test]
     fn test_initialize_writer_state_no_last_blob_idx() {
@@ -375,4 +1427,189 @@ mod test {
         let next = determine_blob_next_status(&commit_status, &reveal_status);
         assert_eq!(next, BlobL1Status::NeedsResign);
     }
+
+    #[test]
+    fn test_blob_status_follows_less_advanced_tx() {
+        let conf = L1TxStatus::Confirmed { confirmations: 3 };
+        let fin = L1TxStatus::Finalized { confirmations: 6 };
+
+        // The commit was reorged out but the reveal still looks confirmed
+        let next = determine_blob_next_status(&L1TxStatus::Published, &conf);
+        assert_eq!(next, BlobL1Status::Published);
+        let next = determine_blob_next_status(&L1TxStatus::Unpublished, &fin);
+        assert_eq!(next, BlobL1Status::Unpublished);
+
+        // Only finalized once both are
+        let next = determine_blob_next_status(&conf, &fin);
+        assert_eq!(next, BlobL1Status::Confirmed);
+        let next = determine_blob_next_status(&fin, &conf);
+        assert_eq!(next, BlobL1Status::Confirmed);
+
+        // Commit reached the mempool but the reveal didn't yet
+        let next = determine_blob_next_status(&L1TxStatus::Published, &L1TxStatus::Unpublished);
+        assert_eq!(next, BlobL1Status::Unpublished);
+
+        // A commit with invalid inputs is only resigned if its reveal never made it out
+        let next = determine_blob_next_status(&L1TxStatus::InvalidInputs, &conf);
+        assert_eq!(next, BlobL1Status::Published);
+        let next = determine_blob_next_status(&L1TxStatus::InvalidInputs, &L1TxStatus::Unpublished);
+        assert_eq!(next, BlobL1Status::NeedsResign);
+    }
+
+    #[tokio::test]
+    async fn test_batched_blobs_finalize_together() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+        let client = Arc::new(TestBitcoinClient::new(1));
+        let config = get_config().with_batching(BlobBatchingConfig::new(8, 300, 60_000).unwrap());
+        let batch_step = |idx| {
+            watcher_step(
+                idx,
+                client.clone(),
+                &config,
+                &iops,
+                &bcast_handle,
+                &status_channel,
+            )
+        };
+
+        let ids: Vec<_> = (1..=3u8).map(|i| Buf32::from([i; 32])).collect();
+        for (i, id) in ids.iter().take(2).enumerate() {
+            let entry = BlobEntry::new_unsigned(vec![i as u8; 100]);
+            iops.put_blob_entry_async(*id, entry).await.unwrap();
+        }
+
+        // There's still room in the batch, so it waits for more
+        assert_eq!(
+            batch_step(0).await.unwrap(),
+            StepOutcome::Pending(BlobL1Status::Unsigned)
+        );
+
+        // The third one fills it up, so all of them are signed together
+        let entry = BlobEntry::new_unsigned(vec![2; 100]);
+        iops.put_blob_entry_async(ids[2], entry).await.unwrap();
+        assert_eq!(
+            batch_step(0).await.unwrap(),
+            StepOutcome::Pending(BlobL1Status::Published)
+        );
+
+        let first = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(first.batch.as_ref(), Some(&ids));
+        for idx in 0..3 {
+            let blob = iops
+                .get_blob_entry_by_idx_async(idx)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(blob.status, BlobL1Status::Published);
+            assert_eq!(blob.commit_txid, first.commit_txid);
+            assert_eq!(blob.reveal_txid, first.reveal_txid);
+            assert_eq!(blob.batch, first.batch);
+        }
+
+        // The single reveal inscribes all of them, in order
+        let reveal = bcast_handle
+            .get_tx_entry_by_id_async(first.reveal_txid)
+            .await
+            .unwrap()
+            .unwrap()
+            .try_to_tx()
+            .unwrap();
+        let insc_config = InscriptionConfig {
+            inscription_tag: config.inscription_tag.clone(),
+            batched_inscriptions: true,
+        };
+        let payloads: Vec<_> = parse_inscriptions(&reveal, &insc_config)
+            .into_iter()
+            .map(|blob| blob.payload)
+            .collect();
+        assert_eq!(payloads, vec![vec![0; 100], vec![1; 100], vec![2; 100]]);
+
+        // All of them are finalized as soon as the shared txs are
+        set_tx_statuses(
+            &iops,
+            &bcast_handle,
+            L1TxStatus::Finalized { confirmations: 6 },
+        )
+        .await;
+        assert_eq!(batch_step(0).await.unwrap(), StepOutcome::Finalized);
+        for idx in 0..3 {
+            let blob = iops
+                .get_blob_entry_by_idx_async(idx)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(blob.status, BlobL1Status::Finalized);
+            assert_eq!(batch_step(idx).await.unwrap(), StepOutcome::Finalized);
+        }
+        let summary = status_channel.l1_status().inscriptions;
+        assert_eq!(summary.finalized_count(), 3);
+        assert_eq!(summary.unfinalized_txids().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_signed_after_max_wait() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+        let client = Arc::new(TestBitcoinClient::new(1));
+        let config = get_config().with_batching(BlobBatchingConfig::new(8, 1_000, 60_000).unwrap());
+
+        // Waited long enough for others to come along, so it goes out on its own
+        let mut entry = BlobEntry::new_unsigned(vec![1; 100]);
+        entry.created_at_ms -= 60_000;
+        iops.put_blob_entry_async(Buf32::from([1; 32]), entry)
+            .await
+            .unwrap();
+
+        let outcome = watcher_step(0, client, &config, &iops, &bcast_handle, &status_channel)
+            .await
+            .unwrap();
+        assert_eq!(outcome, StepOutcome::Pending(BlobL1Status::Published));
+        let blob = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(blob.batch, None);
+    }
+
+    #[tokio::test]
+    async fn test_batch_closed_at_max_blobs() {
+        let iops = get_inscription_ops();
+        let bcast_handle = get_broadcast_handle();
+        let status_channel = get_status_channel();
+        let client = Arc::new(TestBitcoinClient::new(1));
+        let config = get_config().with_batching(BlobBatchingConfig::new(2, 1_000, 60_000).unwrap());
+
+        let ids: Vec<_> = (1..=3u8).map(|i| Buf32::from([i; 32])).collect();
+        for (i, id) in ids.iter().enumerate() {
+            let entry = BlobEntry::new_unsigned(vec![i as u8; 100]);
+            iops.put_blob_entry_async(*id, entry).await.unwrap();
+        }
+
+        // Plenty of bytes left, but the batch is full so it's signed right away
+        let outcome = watcher_step(0, client, &config, &iops, &bcast_handle, &status_channel)
+            .await
+            .unwrap();
+        assert_eq!(outcome, StepOutcome::Pending(BlobL1Status::Published));
+
+        let first = iops.get_blob_entry_by_idx_async(0).await.unwrap().unwrap();
+        assert_eq!(first.batch.as_deref(), Some(&ids[..2]));
+        let last = iops.get_blob_entry_by_idx_async(2).await.unwrap().unwrap();
+        assert_eq!(last.status, BlobL1Status::Unsigned);
+        assert_eq!(last.batch, None);
+    }
+
+    #[test]
+    fn test_batching_needs_params() {
+        let batching = BlobBatchingConfig::new(8, 1_000, 60_000).unwrap();
+        assert!(get_config()
+            .with_batching(batching.clone())
+            .batching
+            .is_some());
+
+        let config = WriterConfig {
+            batched_inscriptions: false,
+            ..get_config()
+        };
+        assert!(config.with_batching(batching).batching.is_none());
+    }
 }
@@ -6,10 +6,14 @@ use strata_rocksdb::{
     broadcaster::db::BroadcastDb, sequencer::db::SequencerDB, test_utils::get_rocksdb_tmp_instance,
     L1BroadcastDb, RBSeqBlobDb,
 };
-use strata_storage::ops::{
-    inscription::{Context, InscriptionDataOps},
-    l1tx_broadcast::Context as BContext,
+use strata_storage::{
+    ops::{
+        inscription::{Context, InscriptionDataOps},
+        l1tx_broadcast::Context as BContext,
+    },
+    BroadcastDbOps,
 };
+use strata_test_utils::l2::gen_params;
 
 use crate::{
     broadcaster::L1BroadcastHandle,
@@ -38,13 +42,17 @@ pub fn get_broadcast_db() -> Arc<impl BroadcastDatabase> {
     Arc::new(BroadcastDb::new(bcastdb))
 }
 
-/// Returns `Arc` of `L1BroadcastHandle` for testing
-pub fn get_broadcast_handle() -> Arc<L1BroadcastHandle> {
+/// Returns `Arc` of `BroadcastDbOps` for testing
+pub fn get_broadcast_ops() -> Arc<BroadcastDbOps> {
     let pool = threadpool::Builder::new().num_threads(2).build();
     let db = get_broadcast_db();
-    let ops = BContext::new(db).into_ops(pool);
+    Arc::new(BContext::new(db).into_ops(pool))
+}
+
+/// Returns `Arc` of `L1BroadcastHandle` for testing
+pub fn get_broadcast_handle() -> Arc<L1BroadcastHandle> {
     let (sender, _) = tokio::sync::mpsc::channel::<(u64, L1TxEntry)>(64);
-    let handle = L1BroadcastHandle::new(sender, Arc::new(ops));
+    let handle = L1BroadcastHandle::new(sender, get_broadcast_ops());
     Arc::new(handle)
 }
 
@@ -57,9 +65,15 @@ pub fn get_config() -> WriterConfig {
         .unwrap();
     WriterConfig {
         sequencer_address: addr,
-        rollup_name: "strata".to_string(),
+        inscription_tag: "strata".to_string(),
         inscription_fee_policy: InscriptionFeePolicy::Fixed(100),
+        reveal_fee_policy: None,
         poll_duration_ms: 1000,
+        poll_jitter_pct: 0,
         amount_for_reveal_txn: 1000,
+        max_resigns: 3,
+        reorg_safe_depth: gen_params().rollup().l1_reorg_safe_depth.into(),
+        batched_inscriptions: true,
+        batching: None,
     }
 }
@@ -58,6 +58,11 @@ pub fn process_block(
     let ready_withdrawals = process_execution_update(state, body.exec_segment().update())?;
     process_deposit_updates(state, ready_withdrawals, &mut rng, params)?;
 
+    #[cfg(debug_assertions)]
+    if let Err(e) = state.state().check_invariants() {
+        panic!("transition: broken chainstate invariant: {e}");
+    }
+
     Ok(())
 }
 
@@ -9,6 +9,7 @@ use strata_primitives::prelude::*;
 use strata_state::{
     batch::{BatchCheckpoint, BatchInfo},
     block,
+    block_validation::check_block_credential,
     client_state::*,
     header::L2Header,
     id::L2BlockId,
@@ -18,7 +19,13 @@ use strata_state::{
 };
 use tracing::*;
 
-use crate::{errors::*, genesis::make_genesis_block, l1_handler::verify_proof};
+use crate::{
+    errors::*,
+    genesis::make_genesis_block,
+    l1_handler::verify_proof,
+    reorg::{self, Reorg},
+    unfinalized_tracker::UnfinalizedBlockTracker,
+};
 
 /// Processes the event given the current consensus state, producing some
 /// output.  This can return database errors.
@@ -177,10 +184,36 @@ pub fn process_event<D: Database>(
                             .collect(),
                     ));
 
+                    // The L2 blocks the checkpoints end at are now confirmed by
+                    // data posted on L1, as long as we know about them
+                    for checkpt in &proof_verified_checkpoints {
+                        let blkid = *checkpt.batch_info().l2_blockid();
+                        if l2_db.get_block_data(blkid)?.is_some() {
+                            writes.push(ClientStateWrite::MarkDaConfirmed(*height, blkid));
+                        } else {
+                            warn!(%height, %blkid, "DA batch checkpoint ends at unknown L2 block");
+                        }
+                    }
+
                     actions.push(SyncAction::WriteCheckpoints(
                         *height,
                         proof_verified_checkpoints,
                     ));
+
+                    // The batch could have been posted deep enough already, in which case
+                    // there's no later event that would finalize it.
+                    let safe_depth = params.rollup().l1_reorg_safe_depth as u64;
+                    let maturable_height = state
+                        .l1_view()
+                        .next_expected_block()
+                        .saturating_sub(safe_depth);
+                    if *height < maturable_height {
+                        let mut next_state = state.clone();
+                        apply_writes_to_state(&mut next_state, writes.iter().cloned());
+                        let (wrs, acts) = handle_mature_l1_height(*height, &next_state, database);
+                        writes.extend(wrs);
+                        actions.extend(acts);
+                    }
                 }
             } else {
                 // TODO we can expand this later to make more sense
@@ -195,6 +228,42 @@ pub fn process_event<D: Database>(
                 .get_block_data(*blkid)?
                 .ok_or(Error::MissingL2Block(*blkid))?;
 
+            // Only blocks signed according to the credential rule can extend the tip.
+            if !check_block_credential(block.header(), params.rollup()) {
+                warn!(?blkid, "rejecting tip block with invalid credential");
+                actions.push(SyncAction::MarkInvalid(*blkid));
+                return Ok(ClientUpdateOutput::new(writes, actions));
+            }
+
+            // Check how the block links up to our current tip.  We can't do
+            // anything with it until we have its parent.
+            let ss = state.sync().ok_or(Error::MissingClientSyncState)?;
+            let parent = *block.header().parent();
+            if l2_db.get_block_data(parent)?.is_none() {
+                warn!(?blkid, ?parent, "new tip block has unknown parent");
+                actions.push(SyncAction::FetchBlock(parent));
+                return Ok(ClientUpdateOutput::new(writes, actions));
+            }
+
+            if parent != *ss.chain_tip_blkid() {
+                let reorg = match compute_tip_reorg(ss, blkid, database) {
+                    Ok(reorg) => reorg,
+                    Err(Error::ForkBelowFinalized(_)) => {
+                        // Nothing we can do with it, we won't revert finalized blocks.
+                        warn!(
+                            ?blkid,
+                            "ignoring tip block forking off below finalized block"
+                        );
+                        return Ok(ClientUpdateOutput::new(Vec::new(), Vec::new()));
+                    }
+                    Err(e) => return Err(e),
+                };
+                if reorg.revert_iter().next().is_some() {
+                    debug!(?blkid, pivot = ?reorg.pivot(), "new tip block is on a fork");
+                    actions.push(SyncAction::RevertTip(*reorg.pivot()));
+                }
+            }
+
             // TODO: get chainstate idx from blkid OR pass correct idx in sync event
             let block_idx = block.header().blockidx();
             let chainstate_db = database.chain_state_db();
@@ -231,11 +300,185 @@ pub fn process_event<D: Database>(
             writes.extend(wrs);
             actions.extend(acts);
         }
+
+        SyncEvent::ForceFinalize(blkid) => {
+            // Whether this is allowed at all is checked when it's submitted,
+            // we only make sure it still makes sense on top of our state.
+            let Some(ss) = state.sync() else {
+                warn!(%blkid, "ignoring forced finalization before genesis");
+                return Ok(ClientUpdateOutput::new(writes, actions));
+            };
+
+            match check_unfinalized_ancestor(ss, blkid, database) {
+                Ok(()) => {}
+                Err(e @ (Error::NotUnfinalizedAncestor(_) | Error::MissingL2Block(_))) => {
+                    warn!(%blkid, %e, "ignoring forced finalization");
+                    return Ok(ClientUpdateOutput::new(writes, actions));
+                }
+                Err(e) => return Err(e),
+            }
+
+            warn!(%blkid, "force finalizing block");
+            writes.push(ClientStateWrite::UpdateFinalized(*blkid));
+            actions.push(SyncAction::FinalizeBlock(*blkid));
+        }
+
+        SyncEvent::L1Checkpoint { idx, l2_tip } => {
+            // Unlike the ones in `L1DABatch` these checkpoints aren't verified,
+            // so they only finalize anything if they match one that we've
+            // verified already.  Otherwise we only check they're consistent
+            // with what we're following.
+            let Some(ss) = state.sync() else {
+                warn!(%idx, %l2_tip, "ignoring L1 checkpoint before genesis");
+                return Ok(ClientUpdateOutput::new(writes, actions));
+            };
+
+            let verified = state
+                .l1_view()
+                .verified_checkpoints()
+                .iter()
+                .find(|cp| cp.batch_info.idx() == *idx);
+            if let Some(verified) = verified {
+                let verified_tip = verified.batch_info.l2_blockid;
+                if verified_tip != *l2_tip {
+                    warn!(%idx, %l2_tip, %verified_tip, "ignoring L1 checkpoint that conflicts with verified checkpoint");
+                    return Ok(ClientUpdateOutput::new(writes, actions));
+                }
+
+                match check_unfinalized_ancestor(ss, l2_tip, database) {
+                    Ok(()) => {
+                        info!(%idx, %l2_tip, "L1 checkpoint matches verified checkpoint, finalizing");
+                        writes.push(ClientStateWrite::UpdateFinalized(*l2_tip));
+                        actions.push(SyncAction::FinalizeBlock(*l2_tip));
+                    }
+                    Err(e @ (Error::NotUnfinalizedAncestor(_) | Error::MissingL2Block(_))) => {
+                        warn!(%idx, %l2_tip, %e, "ignoring verified L1 checkpoint");
+                    }
+                    Err(e) => return Err(e),
+                }
+                return Ok(ClientUpdateOutput::new(writes, actions));
+            }
+
+            let expected_idx = next_checkpoint_idx(state);
+            if *idx != expected_idx {
+                warn!(%idx, %expected_idx, "ignoring L1 checkpoint with unexpected index");
+                return Ok(ClientUpdateOutput::new(writes, actions));
+            }
+
+            match check_unfinalized_ancestor(ss, l2_tip, database) {
+                Ok(()) => {
+                    info!(%idx, %l2_tip, "observed L1 checkpoint, waiting for it to be verified");
+                }
+                Err(e @ (Error::NotUnfinalizedAncestor(_) | Error::MissingL2Block(_))) => {
+                    warn!(%idx, %l2_tip, %e, "ignoring L1 checkpoint");
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     Ok(ClientUpdateOutput::new(writes, actions))
 }
 
+/// Returns the index of the checkpoint we expect to see next, following the
+/// last one we've verified or finalized.
+fn next_checkpoint_idx(state: &ClientState) -> u64 {
+    let l1_view = state.l1_view();
+    l1_view
+        .verified_checkpoints()
+        .last()
+        .or(l1_view.last_finalized_checkpoint())
+        .map(|cp| cp.batch_info.idx() + 1)
+        .unwrap_or(0)
+}
+
+/// Checks that `blkid` is the tip or one of its ancestors above the finalized
+/// block, by walking back from the tip.  This is what a block has to be to be
+/// force finalized.
+pub fn check_unfinalized_ancestor(
+    ss: &SyncState,
+    blkid: &L2BlockId,
+    database: &impl Database,
+) -> Result<(), Error> {
+    let l2_db = database.l2_db();
+    let target_height = l2_db
+        .get_block_data(*blkid)?
+        .ok_or(Error::MissingL2Block(*blkid))?
+        .header()
+        .blockidx();
+
+    let mut cur = *ss.chain_tip_blkid();
+    loop {
+        if cur == *ss.finalized_blkid() {
+            return Err(Error::NotUnfinalizedAncestor(*blkid));
+        }
+
+        if cur == *blkid {
+            return Ok(());
+        }
+
+        let block = l2_db
+            .get_block_data(cur)?
+            .ok_or(Error::MissingL2Block(cur))?;
+        if block.header().blockidx() <= target_height {
+            return Err(Error::NotUnfinalizedAncestor(*blkid));
+        }
+        cur = *block.header().parent();
+    }
+}
+
+/// Computes the reorg from our current tip to a new tip block.  Both have to
+/// descend from the finalized block.
+fn compute_tip_reorg(
+    ss: &SyncState,
+    new_tip: &L2BlockId,
+    database: &impl Database,
+) -> Result<Reorg, Error> {
+    let tracker = load_unfinalized_tracker(ss, &[ss.chain_tip_blkid(), new_tip], database)?;
+    reorg::compute_reorg(ss.chain_tip_blkid(), new_tip, usize::MAX, &tracker)
+        .ok_or(Error::UnableToFindReorg(*ss.chain_tip_blkid(), *new_tip))
+}
+
+/// Builds a tracker of the unfinalized blocks on the chains down from the tips
+/// to the finalized block.
+fn load_unfinalized_tracker(
+    ss: &SyncState,
+    tips: &[&L2BlockId],
+    database: &impl Database,
+) -> Result<UnfinalizedBlockTracker, Error> {
+    let l2_db = database.l2_db();
+    let finalized = *ss.finalized_blkid();
+    let finalized_height = l2_db
+        .get_block_data(finalized)?
+        .ok_or(Error::MissingL2Block(finalized))?
+        .header()
+        .blockidx();
+
+    let mut tracker = UnfinalizedBlockTracker::new_empty(finalized);
+    for tip in tips {
+        // Walk down until we reach a block we already have in the tracker,
+        // then attach the blocks on the way bottom up.
+        let mut headers = Vec::new();
+        let mut cur = **tip;
+        while cur != finalized && tracker.get_parent(&cur).is_none() {
+            let block = l2_db
+                .get_block_data(cur)?
+                .ok_or(Error::MissingL2Block(cur))?;
+            if block.header().blockidx() <= finalized_height {
+                return Err(Error::ForkBelowFinalized(**tip));
+            }
+            headers.push((cur, block.header().clone()));
+            cur = *block.header().parent();
+        }
+
+        for (blkid, header) in headers.into_iter().rev() {
+            tracker.attach_block(blkid, &header)?;
+        }
+    }
+
+    Ok(tracker)
+}
+
 /// Handles the maturation of L1 height by finalizing checkpoints and emitting
 /// sync actions.
 ///
@@ -456,12 +699,18 @@ pub fn filter_verified_checkpoints(
 mod tests {
     use bitcoin::params::MAINNET;
     use strata_db::traits::L1Database;
-    use strata_primitives::{block_credential, l1::L1BlockRecord};
+    use strata_primitives::{block_credential, l1::L1BlockRecord, proof::RollupVerifyingKey};
     use strata_rocksdb::test_utils::get_common_db;
-    use strata_state::{l1::L1BlockId, operation};
+    use strata_state::{
+        block::{L2Block, L2BlockBundle},
+        header::SignedL2BlockHeader,
+        l1::L1BlockId,
+        operation,
+        state_op::WriteBatch,
+    };
     use strata_test_utils::{
         bitcoin::{gen_l1_chain, get_btc_chain},
-        l2::{gen_client_state, gen_params},
+        l2::{gen_client_state, gen_l2_chain, gen_params, get_genesis_chainstate},
         ArbitraryGenerator,
     };
 
@@ -732,10 +981,455 @@ mod tests {
                     expected_writes: &[ClientStateWrite::RollbackL1BlocksTo(genesis)],
                     expected_actions: &[],
                 }],
-                state_assertions: Box::new({ move |state| {} }),
+                state_assertions: Box::new({
+                    let l1_chain = l1_chain.clone();
+                    move |state| {
+                        assert_eq!(state.next_exp_l1_block(), genesis + 1);
+                        assert_eq!(
+                            state.most_recent_l1_block(),
+                            Some(&l1_chain[(genesis - horizon) as usize].block_hash().into())
+                        );
+                    }
+                }),
             },
         ];
 
         run_test_cases(&test_cases, &mut state, database.as_ref(), &params);
     }
+
+    #[test]
+    fn test_force_finalize() {
+        let database = get_common_db();
+        let params = gen_params();
+
+        // g - a1 - a2 - a3
+        //      \
+        //       b2
+        let chain = gen_l2_chain(None, 3);
+        let fork = gen_l2_chain(Some(chain[1].header().clone()), 1);
+        let l2_db = database.l2_db();
+        for b in chain.iter().chain(fork.iter()) {
+            l2_db.put_block_data(b.clone()).unwrap();
+        }
+        let blkids: Vec<L2BlockId> = chain.iter().map(|b| b.header().get_blockid()).collect();
+        let fork_blkid = fork[0].header().get_blockid();
+
+        let mut state = gen_client_state(Some(&params));
+        state.set_sync_state(SyncState::from_genesis_blkid(blkids[0]));
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::AcceptL2Block(blkids[3], 3)].into_iter(),
+        );
+
+        let ev = SyncEvent::ForceFinalize(blkids[2]);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert_eq!(
+            output.writes(),
+            &[ClientStateWrite::UpdateFinalized(blkids[2])]
+        );
+        assert_eq!(output.actions(), &[SyncAction::FinalizeBlock(blkids[2])]);
+
+        operation::apply_writes_to_state(&mut state, output.writes().iter().cloned());
+        assert_eq!(state.sync().unwrap().finalized_blkid(), &blkids[2]);
+
+        // not on the tip's chain
+        let res = check_unfinalized_ancestor(state.sync().unwrap(), &fork_blkid, database.as_ref());
+        assert!(matches!(res, Err(Error::NotUnfinalizedAncestor(id)) if id == fork_blkid));
+        let ev = SyncEvent::ForceFinalize(fork_blkid);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output.writes().is_empty());
+        assert!(output.actions().is_empty());
+
+        // already below the finalized block
+        let res = check_unfinalized_ancestor(state.sync().unwrap(), &blkids[1], database.as_ref());
+        assert!(matches!(res, Err(Error::NotUnfinalizedAncestor(id)) if id == blkids[1]));
+        let ev = SyncEvent::ForceFinalize(blkids[1]);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output.writes().is_empty());
+        assert!(output.actions().is_empty());
+    }
+
+    #[test]
+    fn test_l1_checkpoint() {
+        let database = get_common_db();
+        let params = gen_params();
+
+        // g - a1 - a2 - a3
+        //      \
+        //       b2
+        let chain = gen_l2_chain(None, 3);
+        let fork = gen_l2_chain(Some(chain[1].header().clone()), 1);
+        let l2_db = database.l2_db();
+        for b in chain.iter().chain(fork.iter()) {
+            l2_db.put_block_data(b.clone()).unwrap();
+        }
+        let blkids: Vec<L2BlockId> = chain.iter().map(|b| b.header().get_blockid()).collect();
+        let fork_blkid = fork[0].header().get_blockid();
+
+        let mut state = gen_client_state(Some(&params));
+        state.set_sync_state(SyncState::from_genesis_blkid(blkids[0]));
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::AcceptL2Block(blkids[3], 3)].into_iter(),
+        );
+
+        let unknown: L2BlockId = ArbitraryGenerator::new().generate();
+        let events = [
+            // a checkpoint on the chain we follow
+            SyncEvent::L1Checkpoint {
+                idx: 0,
+                l2_tip: blkids[2],
+            },
+            // not the index we expect next
+            SyncEvent::L1Checkpoint {
+                idx: 1,
+                l2_tip: blkids[2],
+            },
+            // a block we don't know about
+            SyncEvent::L1Checkpoint {
+                idx: 0,
+                l2_tip: unknown,
+            },
+            // a block that isn't on the chain we follow
+            SyncEvent::L1Checkpoint {
+                idx: 0,
+                l2_tip: fork_blkid,
+            },
+        ];
+
+        // none of them finalize anything or fail the event
+        for ev in &events {
+            let output = process_event(&state, ev, database.as_ref(), &params).unwrap();
+            assert!(output.writes().is_empty(), "writes for {ev}");
+            assert!(output.actions().is_empty(), "actions for {ev}");
+        }
+
+        assert_eq!(next_checkpoint_idx(&state), 0);
+
+        // once we've verified the checkpoint, seeing it on L1 finalizes its tip
+        let checkpt = make_first_checkpoint(blkids[2]);
+        let verified = L1Checkpoint::new(
+            checkpt.batch_info().clone(),
+            checkpt.bootstrap_state().clone(),
+            true,
+            params.rollup().horizon_l1_height + 1,
+        );
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::CheckpointsReceived(vec![verified])].into_iter(),
+        );
+        assert_eq!(next_checkpoint_idx(&state), 1);
+
+        let ev = SyncEvent::L1Checkpoint {
+            idx: 0,
+            l2_tip: blkids[2],
+        };
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert_eq!(
+            output.writes(),
+            &[ClientStateWrite::UpdateFinalized(blkids[2])]
+        );
+        assert_eq!(output.actions(), &[SyncAction::FinalizeBlock(blkids[2])]);
+
+        let events = [
+            // a different tip than the verified checkpoint's
+            SyncEvent::L1Checkpoint {
+                idx: 0,
+                l2_tip: blkids[1],
+            },
+            // the next checkpoint, which isn't verified yet
+            SyncEvent::L1Checkpoint {
+                idx: 1,
+                l2_tip: blkids[3],
+            },
+        ];
+        for ev in &events {
+            let output = process_event(&state, ev, database.as_ref(), &params).unwrap();
+            assert!(output.writes().is_empty(), "writes for {ev}");
+            assert!(output.actions().is_empty(), "actions for {ev}");
+        }
+
+        // and once it's finalized, seeing it again doesn't do anything
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::UpdateFinalized(blkids[2])].into_iter(),
+        );
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output.writes().is_empty());
+        assert!(output.actions().is_empty());
+    }
+
+    /// Stores the blocks along with a chainstate for every height up to the highest one, which
+    /// is what accepting them as the tip needs.
+    fn store_l2_blocks(database: &impl Database, blocks: &[L2BlockBundle]) {
+        let l2_db = database.l2_db();
+        for b in blocks {
+            l2_db.put_block_data(b.clone()).unwrap();
+        }
+
+        let chs_db = database.chain_state_db();
+        chs_db
+            .write_genesis_state(&get_genesis_chainstate())
+            .unwrap();
+        let max_idx = blocks.iter().map(|b| b.header().blockidx()).max().unwrap();
+        for idx in 1..=max_idx {
+            chs_db
+                .write_state_update(idx, &WriteBatch::new_empty())
+                .unwrap();
+        }
+    }
+
+    /// Returns the block with its header signature replaced.
+    fn with_sig(bundle: &L2BlockBundle, sig: Buf64) -> L2BlockBundle {
+        let header = SignedL2BlockHeader::new(bundle.header().header().clone(), sig);
+        let block = L2Block::new(header, bundle.body().clone());
+        L2BlockBundle::new(block, bundle.accessory().clone())
+    }
+
+    #[test]
+    fn test_new_tip_block_credential() {
+        let database = get_common_db();
+        let mut params = gen_params();
+        let mut state = gen_client_state(Some(&params));
+
+        let sk = secp256k1::SecretKey::new(&mut secp256k1::rand::thread_rng());
+        let (pk, _) = sk.x_only_public_key(secp256k1::SECP256K1);
+        params.rollup.cred_rule = block_credential::CredRule::SchnorrKey(pk.serialize().into());
+
+        // g - a1 - a2, where a1 is validly signed and a2 is signed with another key
+        let chain = gen_l2_chain(None, 2);
+        let sign = |bundle: &L2BlockBundle, sk: &[u8; 32]| {
+            let sighash = bundle.header().header().get_sighash();
+            with_sig(
+                bundle,
+                strata_crypto::sign_schnorr_sig(&sighash, &Buf32::from(*sk)),
+            )
+        };
+        let other_sk = secp256k1::SecretKey::new(&mut secp256k1::rand::thread_rng());
+        let blocks = [
+            chain[0].clone(),
+            sign(&chain[1], &sk.secret_bytes()),
+            sign(&chain[2], &other_sk.secret_bytes()),
+        ];
+        store_l2_blocks(database.as_ref(), &blocks);
+        let good = blocks[1].header().get_blockid();
+        let bad = blocks[2].header().get_blockid();
+        state.set_sync_state(SyncState::from_genesis_blkid(
+            blocks[0].header().get_blockid(),
+        ));
+
+        let ev = SyncEvent::NewTipBlock(good);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output
+            .writes()
+            .contains(&ClientStateWrite::AcceptL2Block(good, 1)));
+        assert!(output.actions().contains(&SyncAction::UpdateTip(good)));
+
+        let ev = SyncEvent::NewTipBlock(bad);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output.writes().is_empty());
+        assert_eq!(output.actions(), &[SyncAction::MarkInvalid(bad)]);
+
+        // nothing is checked with the unchecked rule
+        params.rollup.cred_rule = block_credential::CredRule::Unchecked;
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::AcceptL2Block(good, 1)].into_iter(),
+        );
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output.actions().contains(&SyncAction::UpdateTip(bad)));
+    }
+
+    #[test]
+    fn test_new_tip_block_linkage() {
+        let database = get_common_db();
+        let params = gen_params();
+
+        // g - a1 - a2 - a3
+        //      \
+        //       b2 - b3
+        let chain = gen_l2_chain(None, 3);
+        let fork = gen_l2_chain(Some(chain[1].header().clone()), 2);
+        let orphan = gen_l2_chain(Some(fork[1].header().clone()), 2);
+        let mut blocks = chain.clone();
+        blocks.extend(fork.iter().cloned());
+        // only the second orphan block is stored, its parent is missing
+        blocks.push(orphan[1].clone());
+        store_l2_blocks(database.as_ref(), &blocks);
+
+        let a: Vec<L2BlockId> = chain.iter().map(|b| b.header().get_blockid()).collect();
+        let b: Vec<L2BlockId> = fork.iter().map(|b| b.header().get_blockid()).collect();
+
+        let mut state = gen_client_state(Some(&params));
+        state.set_sync_state(SyncState::from_genesis_blkid(a[0]));
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::AcceptL2Block(a[2], 2)].into_iter(),
+        );
+
+        // linear extension of the tip
+        let output = process_event(
+            &state,
+            &SyncEvent::NewTipBlock(a[3]),
+            database.as_ref(),
+            &params,
+        )
+        .unwrap();
+        assert!(output
+            .writes()
+            .contains(&ClientStateWrite::AcceptL2Block(a[3], 3)));
+        assert_eq!(output.actions()[0], SyncAction::UpdateTip(a[3]));
+
+        // a fork that wins reverts to the fork point before extending
+        let output = process_event(
+            &state,
+            &SyncEvent::NewTipBlock(b[1]),
+            database.as_ref(),
+            &params,
+        )
+        .unwrap();
+        assert!(output
+            .writes()
+            .contains(&ClientStateWrite::AcceptL2Block(b[1], 3)));
+        assert_eq!(
+            &output.actions()[..2],
+            &[SyncAction::RevertTip(a[1]), SyncAction::UpdateTip(b[1])]
+        );
+
+        // an orphan isn't accepted, we go fetch its parent instead
+        let orphan_blkid = orphan[1].header().get_blockid();
+        let missing = orphan[0].header().get_blockid();
+        let output = process_event(
+            &state,
+            &SyncEvent::NewTipBlock(orphan_blkid),
+            database.as_ref(),
+            &params,
+        )
+        .unwrap();
+        assert!(output.writes().is_empty());
+        assert_eq!(output.actions(), &[SyncAction::FetchBlock(missing)]);
+
+        // forking off below the finalized block is ignored
+        operation::apply_writes_to_state(
+            &mut state,
+            [ClientStateWrite::UpdateFinalized(a[2])].into_iter(),
+        );
+        let output = process_event(
+            &state,
+            &SyncEvent::NewTipBlock(b[1]),
+            database.as_ref(),
+            &params,
+        )
+        .unwrap();
+        assert!(output.writes().is_empty());
+        assert!(output.actions().is_empty());
+    }
+
+    /// Makes the first checkpoint, ending at the given L2 block.
+    fn make_first_checkpoint(blkid: L2BlockId) -> BatchCheckpoint {
+        let mut arb = ArbitraryGenerator::new();
+        let mut batch_info: BatchInfo = arb.generate();
+        batch_info.idx = 0;
+        batch_info.l2_blockid = blkid;
+        BatchCheckpoint::new(batch_info, arb.generate(), arb.generate())
+    }
+
+    #[test]
+    fn test_l1_da_batch() {
+        let database = get_common_db();
+        let mut params = gen_params();
+        // so that any proof checks out
+        params.rollup.rollup_vk = RollupVerifyingKey::NativeVerifyingKey(Buf32::zero());
+
+        let chain = gen_l2_chain(None, 2);
+        let l2_db = database.l2_db();
+        for b in &chain {
+            l2_db.put_block_data(b.clone()).unwrap();
+        }
+        let blkids: Vec<L2BlockId> = chain.iter().map(|b| b.header().get_blockid()).collect();
+
+        let mut state = gen_client_state(Some(&params));
+        state.set_sync_state(SyncState::from_genesis_blkid(blkids[0]));
+        let horizon = params.rollup().horizon_l1_height;
+        let da_height = horizon + 1;
+
+        // the batch lands in a recent L1 block, the block is confirmed but not finalized
+        let checkpt = make_first_checkpoint(blkids[2]);
+        let ev = SyncEvent::L1DABatch(da_height, vec![checkpt.clone()]);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert_eq!(
+            output.writes(),
+            &[
+                ClientStateWrite::CheckpointsReceived(vec![L1Checkpoint::new(
+                    checkpt.batch_info().clone(),
+                    checkpt.bootstrap_state().clone(),
+                    !checkpt.proof().is_empty(),
+                    da_height,
+                )]),
+                ClientStateWrite::MarkDaConfirmed(da_height, blkids[2]),
+            ]
+        );
+        assert_eq!(
+            output.actions(),
+            &[SyncAction::WriteCheckpoints(
+                da_height,
+                vec![checkpt.clone()]
+            )]
+        );
+
+        let mut confirmed_state = state.clone();
+        operation::apply_writes_to_state(&mut confirmed_state, output.writes().iter().cloned());
+        let ss = confirmed_state.sync().unwrap();
+        assert_eq!(ss.confirmed_checkpoint_blocks(), &[(da_height, blkids[2])]);
+        assert_eq!(
+            ss.get_confirmed_checkpt_block_at(da_height),
+            Some(blkids[2])
+        );
+        assert_eq!(ss.finalized_blkid(), &blkids[0]);
+
+        // reverting the L1 block drops the confirmation
+        operation::apply_writes_to_state(
+            &mut confirmed_state,
+            [ClientStateWrite::RollbackL1BlocksTo(horizon)].into_iter(),
+        );
+        assert!(confirmed_state
+            .sync()
+            .unwrap()
+            .confirmed_checkpoint_blocks()
+            .is_empty());
+
+        // a checkpoint ending at a block we don't know isn't marked
+        let unknown = gen_l2_chain(Some(chain[2].header().clone()), 1)[0]
+            .header()
+            .get_blockid();
+        let ev = SyncEvent::L1DABatch(da_height, vec![make_first_checkpoint(unknown)]);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(!output
+            .writes()
+            .iter()
+            .any(|w| matches!(w, ClientStateWrite::MarkDaConfirmed(..))));
+
+        // the batch lands in a buried enough L1 block, it gets finalized right away
+        let mut arb = ArbitraryGenerator::new();
+        let accept_writes = (0..5).map(|_| ClientStateWrite::AcceptL1Block(arb.generate()));
+        operation::apply_writes_to_state(&mut state, accept_writes);
+        let ev = SyncEvent::L1DABatch(da_height, vec![checkpt.clone()]);
+        let output = process_event(&state, &ev, database.as_ref(), &params).unwrap();
+        assert!(output
+            .writes()
+            .contains(&ClientStateWrite::MarkDaConfirmed(da_height, blkids[2])));
+        assert_eq!(
+            output.writes().last(),
+            Some(&ClientStateWrite::CheckpointFinalized(da_height))
+        );
+        assert_eq!(
+            output.actions().last(),
+            Some(&SyncAction::FinalizeBlock(blkids[2]))
+        );
+
+        operation::apply_writes_to_state(&mut state, output.writes().iter().cloned());
+        let ss = state.sync().unwrap();
+        assert_eq!(ss.finalized_blkid(), &blkids[2]);
+        assert!(ss.confirmed_checkpoint_blocks().is_empty());
+    }
 }
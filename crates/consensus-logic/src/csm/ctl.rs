@@ -1,17 +1,44 @@
-use std::sync::Arc;
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
-use strata_db::{errors::DbError, traits::*};
+use strata_db::{errors::DbError, traits::*, DbResult};
 use strata_state::sync_event::SyncEvent;
-use tokio::sync::{mpsc, oneshot};
+use strata_status::StatusChannel;
+use strata_storage::pool::check_blocking_outside_db_pool;
+use tokio::sync::{
+    mpsc::{
+        self,
+        error::{SendTimeoutError, TrySendError},
+    },
+    oneshot,
+};
 use tracing::*;
 
 use super::message::CsmMessage;
+use crate::errors::SubmitError;
+
+/// Default time we wait for the CSM worker to accept a new event notification.
+pub const DEFAULT_SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often we retry notifying the CSM worker from blocking contexts.
+const SUBMIT_RETRY_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Controller handle for the consensus state machine.  Used to submit new sync
 /// events for persistence and processing.
 pub struct CsmController {
     submit_event_shim: SubmitEventShim,
     csm_tx: mpsc::Sender<CsmMessage>,
+    submit_timeout: Duration,
+    status_channel: StatusChannel,
+
+    /// Height of the last L1 block or revert we submitted, to make sure the
+    /// L1 events reach the CSM in order and blocks the reader re-scans aren't
+    /// submitted again.  Picked up from the sync events written before we
+    /// started.
+    last_l1_height: Mutex<Option<u64>>,
 }
 
 impl CsmController {
@@ -19,27 +46,67 @@ impl CsmController {
         database: Arc<D>,
         pool: threadpool::ThreadPool,
         csm_tx: mpsc::Sender<CsmMessage>,
-    ) -> Self {
+        status_channel: StatusChannel,
+    ) -> Result<Self, DbError> {
+        let last_l1_height = find_last_l1_event_height(database.sync_event_db().as_ref())?;
         let submit_event_shim = make_write_event_shim(database, pool);
-        Self {
+        Ok(Self {
             submit_event_shim,
             csm_tx,
-        }
+            submit_timeout: DEFAULT_SUBMIT_TIMEOUT,
+            status_channel,
+            last_l1_height: Mutex::new(last_l1_height),
+        })
+    }
+
+    /// Sets how long we wait for the CSM worker to accept an event
+    /// notification before giving up on it.
+    pub fn with_submit_timeout(mut self, submit_timeout: Duration) -> Self {
+        self.submit_timeout = submit_timeout;
+        self
     }
 
     /// Writes a sync event to the database and updates the watch channel to
     /// trigger the CSM executor to process the event.
-    pub fn submit_event(&self, sync_event: SyncEvent) -> anyhow::Result<()> {
+    ///
+    /// If the worker doesn't accept the notification within the submit
+    /// timeout, the event stays in the database and this returns
+    /// [`SubmitError::WorkerUnresponsive`].
+    ///
+    /// L1 blocks have to be submitted in height order, an [`SyncEvent::L1Block`]
+    /// that isn't past the last L1 block or revert is rejected with
+    /// [`SubmitError::OutOfOrderL1Event`] without being written.
+    pub fn submit_event(&self, sync_event: SyncEvent) -> Result<(), SubmitError> {
         trace!(?sync_event, "Writing sync event");
+        self.check_l1_event_order(&sync_event)?;
         let ev_idx = self
             .submit_event_shim
             .submit_event_blocking(sync_event.clone())?;
-        let msg = CsmMessage::EventInput(ev_idx);
+        self.note_l1_event(&sync_event);
+        self.status_channel.update_sync_event_written(ev_idx);
+        let mut msg = CsmMessage::EventInput(ev_idx);
         trace!(?sync_event, ?ev_idx, "sending csm event input");
-        if self.csm_tx.blocking_send(msg).is_err() {
-            warn!(%ev_idx, "sync event receiver closed when submitting sync event");
-        } else {
-            trace!(%ev_idx, "sent csm event input");
+
+        // There's no blocking send with a timeout, so we have to poll.
+        let deadline = Instant::now() + self.submit_timeout;
+        loop {
+            match self.csm_tx.try_send(msg) {
+                Ok(()) => {
+                    trace!(%ev_idx, "sent csm event input");
+                    break;
+                }
+                Err(TrySendError::Closed(_)) => {
+                    warn!(%ev_idx, "sync event receiver closed when submitting sync event");
+                    break;
+                }
+                Err(TrySendError::Full(m)) => {
+                    if Instant::now() >= deadline {
+                        return Err(self.worker_unresponsive(ev_idx));
+                    }
+                    msg = m;
+                    thread::sleep(SUBMIT_RETRY_INTERVAL);
+                }
+            }
         }
 
         Ok(())
@@ -47,15 +114,84 @@ impl CsmController {
 
     /// Writes a sync event to the database and updates the watch channel to
     /// trigger the CSM executor to process the event.
-    pub async fn submit_event_async(&self, sync_event: SyncEvent) -> anyhow::Result<()> {
-        let ev_idx = self.submit_event_shim.submit_event(sync_event).await?;
+    ///
+    /// See [`Self::submit_event`].
+    pub async fn submit_event_async(&self, sync_event: SyncEvent) -> Result<(), SubmitError> {
+        self.check_l1_event_order(&sync_event)?;
+        let ev_idx = self
+            .submit_event_shim
+            .submit_event(sync_event.clone())
+            .await?;
+        self.note_l1_event(&sync_event);
+        self.status_channel.update_sync_event_written(ev_idx);
         let msg = CsmMessage::EventInput(ev_idx);
-        if self.csm_tx.send(msg).await.is_err() {
-            warn!(%ev_idx, "sync event receiver closed when submitting sync event");
+        match self.csm_tx.send_timeout(msg, self.submit_timeout).await {
+            Ok(()) => {}
+            Err(SendTimeoutError::Closed(_)) => {
+                warn!(%ev_idx, "sync event receiver closed when submitting sync event");
+            }
+            Err(SendTimeoutError::Timeout(_)) => return Err(self.worker_unresponsive(ev_idx)),
         }
 
         Ok(())
     }
+
+    /// Checks that an L1 block comes after the last L1 event we submitted.  A
+    /// reorg always comes as a revert first, so a block at or below the last
+    /// height means something reordered or resent the events.
+    fn check_l1_event_order(&self, ev: &SyncEvent) -> Result<(), SubmitError> {
+        let SyncEvent::L1Block(height, blkid) = ev else {
+            return Ok(());
+        };
+
+        let last = *self.last_l1_height.lock().expect("csmctl: l1 height lock");
+        match last {
+            Some(last) if *height <= last => {
+                warn!(%height, %last, %blkid, "rejecting out of order L1 block");
+                Err(SubmitError::OutOfOrderL1Event(*height, last))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Remembers the height of an L1 event that was written, so later ones can
+    /// be checked against it.
+    fn note_l1_event(&self, ev: &SyncEvent) {
+        let Some(height) = l1_event_height(ev) else {
+            return;
+        };
+        *self.last_l1_height.lock().expect("csmctl: l1 height lock") = Some(height);
+    }
+
+    fn worker_unresponsive(&self, ev_idx: u64) -> SubmitError {
+        warn!(%ev_idx, timeout = ?self.submit_timeout, "CSM worker unresponsive, sync event left in db");
+        SubmitError::WorkerUnresponsive(ev_idx, self.submit_timeout)
+    }
+}
+
+/// Returns the height of an L1 block or revert event.
+fn l1_event_height(ev: &SyncEvent) -> Option<u64> {
+    match ev {
+        SyncEvent::L1Block(height, _) | SyncEvent::L1Revert(height) => Some(*height),
+        _ => None,
+    }
+}
+
+/// Finds the height of the last L1 block or revert that was written, looking
+/// back from the most recent sync event.
+fn find_last_l1_event_height(sync_event_db: &impl SyncEventDatabase) -> DbResult<Option<u64>> {
+    let mut idx = sync_event_db.get_last_idx()?.unwrap_or(0);
+    while idx > 0 {
+        // The older ones might have been cleared
+        let Some(ev) = sync_event_db.get_sync_event(idx)? else {
+            break;
+        };
+        if let Some(height) = l1_event_height(&ev) {
+            return Ok(Some(height));
+        }
+        idx -= 1;
+    }
+    Ok(None)
 }
 
 struct SubmitEventShim {
@@ -82,6 +218,7 @@ struct EventSubmitHandle {
 
 impl EventSubmitHandle {
     pub fn wait_blocking(self) -> Result<u64, DbError> {
+        check_blocking_outside_db_pool();
         match self.resp_rx.blocking_recv() {
             Ok(v) => v,
             Err(e) => Err(DbError::Other(format!("{e}"))),
@@ -119,3 +256,165 @@ fn make_write_event_shim<D: Database + Sync + Send + 'static>(
         handle: Box::new(fun),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use strata_primitives::l1::L1Status;
+    use strata_rocksdb::test_utils::get_common_db;
+    use strata_state::l1::L1BlockId;
+    use strata_test_utils::l2::gen_client_state;
+
+    use super::*;
+
+    fn make_status_channel() -> StatusChannel {
+        StatusChannel::new(gen_client_state(None), L1Status::default(), None)
+    }
+
+    #[test]
+    fn test_submit_event_worker_unresponsive() {
+        let database = get_common_db();
+        let pool = threadpool::ThreadPool::new(1);
+
+        // a channel that's already full and never drained
+        let (csm_tx, _csm_rx) = mpsc::channel(1);
+        csm_tx.try_send(CsmMessage::EventInput(0)).unwrap();
+
+        let timeout = Duration::from_millis(50);
+        let status_channel = make_status_channel();
+        let csm_ctl = CsmController::new(database.clone(), pool, csm_tx, status_channel.clone())
+            .unwrap()
+            .with_submit_timeout(timeout);
+
+        let ev = SyncEvent::L1Block(1, L1BlockId::default());
+        let res = csm_ctl.submit_event(ev.clone());
+        assert!(matches!(
+            res,
+            Err(SubmitError::WorkerUnresponsive(1, t)) if t == timeout
+        ));
+
+        // the event is still persisted for the worker to pick up later
+        let sync_ev_db = database.sync_event_db();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(1));
+        assert_eq!(sync_ev_db.get_sync_event(1).unwrap(), Some(ev));
+        let progress = status_channel.sync_event_progress();
+        assert_eq!(progress.last_written_idx, 1);
+        assert_eq!(progress.pending(), 1);
+    }
+
+    #[test]
+    fn test_l1_events_in_order() {
+        let database = get_common_db();
+        let pool = threadpool::ThreadPool::new(1);
+        let (csm_tx, _csm_rx) = mpsc::channel(16);
+        let csm_ctl =
+            CsmController::new(database.clone(), pool, csm_tx, make_status_channel()).unwrap();
+
+        let events = [
+            SyncEvent::L1Block(10, L1BlockId::default()),
+            SyncEvent::L1Block(11, L1BlockId::default()),
+            SyncEvent::L1DABatch(11, vec![]),
+            SyncEvent::L1Block(13, L1BlockId::default()),
+            // a reorg reverts first, so the replacement blocks can follow
+            SyncEvent::L1Revert(11),
+            SyncEvent::L1Block(12, L1BlockId::default()),
+        ];
+        for ev in &events {
+            csm_ctl.submit_event(ev.clone()).unwrap();
+        }
+
+        let sync_ev_db = database.sync_event_db();
+        assert_eq!(
+            sync_ev_db.get_last_idx().unwrap(),
+            Some(events.len() as u64)
+        );
+    }
+
+    #[test]
+    fn test_l1_events_out_of_order() {
+        let database = get_common_db();
+        let pool = threadpool::ThreadPool::new(1);
+        let (csm_tx, _csm_rx) = mpsc::channel(16);
+        let csm_ctl =
+            CsmController::new(database.clone(), pool, csm_tx, make_status_channel()).unwrap();
+
+        csm_ctl
+            .submit_event(SyncEvent::L1Block(11, L1BlockId::default()))
+            .unwrap();
+
+        // an older block and a resent one are both rejected
+        let res = csm_ctl.submit_event(SyncEvent::L1Block(10, L1BlockId::default()));
+        assert!(matches!(res, Err(SubmitError::OutOfOrderL1Event(10, 11))));
+        let res = csm_ctl.submit_event(SyncEvent::L1Block(11, L1BlockId::default()));
+        assert!(matches!(res, Err(SubmitError::OutOfOrderL1Event(11, 11))));
+
+        // and neither got written
+        let sync_ev_db = database.sync_event_db();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(1));
+
+        // the async path checks the same way
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let res =
+            rt.block_on(csm_ctl.submit_event_async(SyncEvent::L1Block(9, L1BlockId::default())));
+        assert!(matches!(res, Err(SubmitError::OutOfOrderL1Event(9, 11))));
+        rt.block_on(csm_ctl.submit_event_async(SyncEvent::L1Block(12, L1BlockId::default())))
+            .unwrap();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_l1_event_order_kept_across_restarts() {
+        let database = get_common_db();
+        let (csm_tx, _csm_rx) = mpsc::channel(16);
+        let csm_ctl = CsmController::new(
+            database.clone(),
+            threadpool::ThreadPool::new(1),
+            csm_tx.clone(),
+            make_status_channel(),
+        )
+        .unwrap();
+        csm_ctl
+            .submit_event(SyncEvent::L1Block(11, L1BlockId::default()))
+            .unwrap();
+        csm_ctl
+            .submit_event(SyncEvent::L1DABatch(11, vec![]))
+            .unwrap();
+
+        // a new controller over the same db still knows which blocks went in
+        let csm_ctl = CsmController::new(
+            database.clone(),
+            threadpool::ThreadPool::new(1),
+            csm_tx,
+            make_status_channel(),
+        )
+        .unwrap();
+        let res = csm_ctl.submit_event(SyncEvent::L1Block(11, L1BlockId::default()));
+        assert!(matches!(res, Err(SubmitError::OutOfOrderL1Event(11, 11))));
+        csm_ctl
+            .submit_event(SyncEvent::L1Block(12, L1BlockId::default()))
+            .unwrap();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_submit_event_from_pool_worker() {
+        let database = get_common_db();
+        let pool = strata_storage::pool::create_db_pool(1);
+        let (csm_tx, _csm_rx) = mpsc::channel(1);
+        let csm_ctl =
+            CsmController::new(database, pool.clone(), csm_tx, make_status_channel()).unwrap();
+
+        // the write gets queued behind the job waiting on it, so this would
+        // hang forever without the guard
+        let (res_tx, res_rx) = std::sync::mpsc::channel();
+        pool.execute(move || {
+            let ev = SyncEvent::L1Block(1, L1BlockId::default());
+            let res = std::panic::catch_unwind(AssertUnwindSafe(|| csm_ctl.submit_event(ev)));
+            res_tx.send(res.is_err()).unwrap();
+        });
+
+        let panicked = res_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(panicked, "guard didn't fire");
+    }
+}
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
 use strata_state::{client_state::ClientState, id::L2BlockId, operation::ClientUpdateOutput};
+use tokio::sync::oneshot;
+
+use crate::unfinalized_tracker::TreeDump;
 
 /// Sync control message.
 #[derive(Copy, Clone, Debug)]
@@ -10,10 +13,14 @@ pub enum CsmMessage {
 }
 
 /// Message about a new block the fork choice manager might do something with.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub enum ForkChoiceMessage {
     /// New block coming in from over the network to be considered.
     NewBlock(L2BlockId),
+
+    /// Query for a snapshot of the unfinalized block tree, sent back on the
+    /// channel.
+    DumpUnfinalizedTree(oneshot::Sender<TreeDump>),
 }
 
 /// Package describing a new consensus state produced from a new sync event.
@@ -0,0 +1,240 @@
+//! Timing of the sync events processed by the CSM, to find out which kinds of
+//! events are expensive.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use strata_state::sync_event::SyncEvent;
+use tracing::*;
+
+/// Default duration after which processing an event is considered slow, in
+/// milliseconds.
+pub const DEFAULT_SLOW_EVENT_THRESHOLD_MS: u64 = 500;
+
+/// Number of most recent durations kept per event kind to compute the
+/// percentiles from.
+const MAX_SAMPLES: usize = 1024;
+
+/// Source of the time events are measured with, so that tests can control it.
+pub trait Clock: Send + Sync + 'static {
+    fn now(&self) -> Instant;
+}
+
+/// Clock reading the system's monotonic time.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Aggregate processing time stats for one kind of sync event.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventKindStats {
+    /// Name of the event kind.
+    pub kind: &'static str,
+
+    /// Number of events of this kind processed.
+    pub count: u64,
+
+    /// Number of those that took longer than the slow threshold.
+    pub slow_count: u64,
+
+    /// Median processing duration over the recent events.
+    pub p50: Duration,
+
+    /// 99th percentile processing duration over the recent events.
+    pub p99: Duration,
+}
+
+#[derive(Default)]
+struct KindTimings {
+    count: u64,
+    slow_count: u64,
+    recent: VecDeque<Duration>,
+}
+
+impl KindTimings {
+    fn record(&mut self, dur: Duration, slow: bool) {
+        self.count += 1;
+        if slow {
+            self.slow_count += 1;
+        }
+
+        if self.recent.len() == MAX_SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(dur);
+    }
+
+    fn to_stats(&self, kind: &'static str) -> EventKindStats {
+        let mut sorted: Vec<_> = self.recent.iter().copied().collect();
+        sorted.sort();
+        EventKindStats {
+            kind,
+            count: self.count,
+            slow_count: self.slow_count,
+            p50: percentile(&sorted, 50),
+            p99: percentile(&sorted, 99),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted durations.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn event_kind(ev: &SyncEvent) -> &'static str {
+    match ev {
+        SyncEvent::L1Block(..) => "l1block",
+        SyncEvent::L1Revert(..) => "l1revert",
+        SyncEvent::L1DABatch(..) => "l1da",
+        SyncEvent::L1BlockGenesis(..) => "l1genesis",
+        SyncEvent::NewTipBlock(..) => "newtip",
+        SyncEvent::ForceFinalize(..) => "forcefinalize",
+        SyncEvent::L1Checkpoint { .. } => "l1checkpoint",
+    }
+}
+
+/// Tracks how long processing sync events takes, logging the ones that take
+/// longer than a threshold.  Shared between the CSM worker and whoever wants
+/// to read the stats.
+pub struct EventTimings {
+    clock: Arc<dyn Clock>,
+    slow_threshold: Duration,
+    timings: Mutex<BTreeMap<&'static str, KindTimings>>,
+}
+
+impl EventTimings {
+    pub fn new(slow_threshold: Duration) -> Self {
+        Self::new_with_clock(slow_threshold, Arc::new(SystemClock))
+    }
+
+    pub fn new_with_clock(slow_threshold: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            slow_threshold,
+            timings: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Runs the processing of an event, recording how long it took.
+    pub fn time_event<R>(&self, ev_idx: u64, ev: &SyncEvent, f: impl FnOnce() -> R) -> R {
+        let start = self.clock.now();
+        let res = f();
+        let dur = self.clock.now().saturating_duration_since(start);
+
+        let kind = event_kind(ev);
+        let slow = dur > self.slow_threshold;
+        if slow {
+            warn!(%ev_idx, %kind, ?dur, "slow sync event");
+        }
+
+        self.timings
+            .lock()
+            .expect("csm: event timings lock")
+            .entry(kind)
+            .or_default()
+            .record(dur, slow);
+
+        res
+    }
+
+    /// Returns the stats of each kind of event processed so far, ordered by
+    /// kind.
+    pub fn get_stats(&self) -> Vec<EventKindStats> {
+        self.timings
+            .lock()
+            .expect("csm: event timings lock")
+            .iter()
+            .map(|(kind, t)| t.to_stats(kind))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    /// Clock that only moves when told to.
+    struct ManualClock(Mutex<Instant>);
+
+    impl ManualClock {
+        fn advance(&self, dur: Duration) {
+            *self.0.lock().unwrap() += dur;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_event_timings() {
+        let clock = Arc::new(ManualClock(Mutex::new(Instant::now())));
+        let timings = EventTimings::new_with_clock(Duration::from_millis(100), clock.clone());
+
+        let mut arb = ArbitraryGenerator::new();
+        let tip_ev = SyncEvent::NewTipBlock(arb.generate());
+        let revert_ev = SyncEvent::L1Revert(10);
+
+        for i in 1..=10 {
+            let res = timings.time_event(i, &tip_ev, || {
+                clock.advance(Duration::from_millis(i));
+                i
+            });
+            assert_eq!(res, i);
+        }
+        let stats = timings.get_stats();
+        assert_eq!(
+            stats,
+            vec![EventKindStats {
+                kind: "newtip",
+                count: 10,
+                slow_count: 0,
+                p50: Duration::from_millis(5),
+                p99: Duration::from_millis(10),
+            }]
+        );
+
+        // one slow event
+        timings.time_event(11, &revert_ev, || clock.advance(Duration::from_millis(250)));
+        let stats = timings.get_stats();
+        assert_eq!(stats.len(), 2);
+        let revert_stats = &stats[0];
+        assert_eq!(revert_stats.kind, "l1revert");
+        assert_eq!(revert_stats.count, 1);
+        assert_eq!(revert_stats.slow_count, 1);
+        assert_eq!(revert_stats.p50, Duration::from_millis(250));
+        assert_eq!(revert_stats.p99, Duration::from_millis(250));
+
+        // the other kind is unaffected
+        assert_eq!(stats[1].count, 10);
+        assert_eq!(stats[1].slow_count, 0);
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+
+        let durs: Vec<_> = (1..=200).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&durs, 50), Duration::from_millis(100));
+        assert_eq!(percentile(&durs, 99), Duration::from_millis(198));
+        assert_eq!(percentile(&durs[..1], 99), Duration::from_millis(1));
+    }
+}
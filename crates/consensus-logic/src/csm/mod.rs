@@ -10,5 +10,7 @@ pub mod client_transition;
 pub mod config;
 pub mod ctl;
 pub mod message;
+pub mod metrics;
+pub mod replay;
 pub mod state_tracker;
 pub mod worker;
@@ -0,0 +1,376 @@
+//! Consistency checking of the persisted consensus outputs by replaying the
+//! sync events they were computed from, and re-driving the EL with the actions
+//! it might have missed.
+
+use strata_db::{errors::DbError, traits::*};
+use strata_eectl::engine::ExecEngineCtl;
+use strata_primitives::params::Params;
+use strata_state::operation::{self, ClientUpdateOutput, SyncAction};
+use thiserror::Error;
+use tracing::*;
+
+use super::{client_transition, state_tracker::reconstruct_state};
+use crate::errors::Error;
+
+/// Where replaying the sync events first diverged from the stored outputs.
+#[derive(Debug, Error)]
+#[error("consensus replay diverged at sync event {idx}: {kind}")]
+pub struct DivergenceReport {
+    /// Index of the first sync event that diverged.
+    pub idx: u64,
+
+    /// How it diverged.
+    pub kind: DivergenceKind,
+}
+
+impl DivergenceReport {
+    fn new(idx: u64, kind: DivergenceKind) -> Self {
+        Self { idx, kind }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DivergenceKind {
+    #[error("replayed output differs from the stored one")]
+    OutputMismatch {
+        stored: ClientUpdateOutput,
+        replayed: ClientUpdateOutput,
+    },
+
+    #[error("missing stored {0}")]
+    MissingData(&'static str),
+
+    #[error("processing event failed: {0}")]
+    ProcessingFailed(String),
+
+    #[error("db: {0}")]
+    Db(#[from] DbError),
+}
+
+/// Re-runs [`process_event`](client_transition::process_event) over the
+/// persisted sync events starting from the genesis client state, comparing
+/// each produced output against the stored one.
+///
+/// Since processing events reads from the current database, this is only
+/// meaningful on a node that hasn't pruned or reverted any data since.
+pub fn verify_consensus_replay(
+    database: &impl Database,
+    params: &Params,
+) -> Result<(), DivergenceReport> {
+    let sync_event_db = database.sync_event_db();
+    let client_state_db = database.client_state_db();
+
+    let mut state = client_state_db
+        .get_state_checkpoint(0)
+        .map_err(|e| DivergenceReport::new(0, e.into()))?
+        .ok_or(DivergenceReport::new(
+            0,
+            DivergenceKind::MissingData("genesis client state"),
+        ))?;
+
+    let last_idx = match client_state_db.get_last_write_idx() {
+        Ok(idx) => idx,
+        // nothing to replay
+        Err(DbError::NotBootstrapped) => return Ok(()),
+        Err(e) => return Err(DivergenceReport::new(0, e.into())),
+    };
+
+    for idx in 1..=last_idx {
+        let report = |kind| DivergenceReport::new(idx, kind);
+
+        let ev = sync_event_db
+            .get_sync_event(idx)
+            .map_err(|e| report(e.into()))?
+            .ok_or_else(|| report(DivergenceKind::MissingData("sync event")))?;
+
+        let writes = client_state_db
+            .get_client_state_writes(idx)
+            .map_err(|e| report(e.into()))?;
+        let actions = client_state_db
+            .get_client_update_actions(idx)
+            .map_err(|e| report(e.into()))?;
+        let (Some(writes), Some(actions)) = (writes, actions) else {
+            return Err(report(DivergenceKind::MissingData("client update output")));
+        };
+        let stored = ClientUpdateOutput::new(writes, actions);
+
+        let replayed = client_transition::process_event(&state, &ev, database, params)
+            .map_err(|e| report(DivergenceKind::ProcessingFailed(e.to_string())))?;
+
+        if replayed != stored {
+            warn!(%idx, "consensus replay diverged");
+            return Err(report(DivergenceKind::OutputMismatch { stored, replayed }));
+        }
+
+        operation::apply_writes_to_state(&mut state, stored.writes().iter().cloned());
+    }
+
+    debug!(%last_idx, "consensus replay matches stored outputs");
+    Ok(())
+}
+
+/// Re-runs [`process_event`](client_transition::process_event) for the sync
+/// event at `idx` against the client state right before it, returning the
+/// output without persisting anything.
+///
+/// Like [`verify_consensus_replay`], this reads from the current database, so
+/// events that depend on data changed since can produce a different output.
+pub fn replay_event(
+    database: &impl Database,
+    params: &Params,
+    idx: u64,
+) -> anyhow::Result<ClientUpdateOutput> {
+    // the genesis state isn't produced by an event
+    if idx == 0 {
+        return Err(Error::MissingSyncEvent(idx).into());
+    }
+
+    let ev = database
+        .sync_event_db()
+        .get_sync_event(idx)?
+        .ok_or(Error::MissingSyncEvent(idx))?;
+    let state = reconstruct_state(database.client_state_db().as_ref(), idx - 1)?;
+
+    debug!(%idx, %ev, "replaying sync event");
+    Ok(client_transition::process_event(
+        &state, &ev, database, params,
+    )?)
+}
+
+/// Re-issues the EL updates among the actions output after the last acked
+/// index, which we might have crashed before applying, acking them as we go.
+/// Returns the number of updates re-issued.
+///
+/// If no index was ever acked only the last output is replayed, since the EL
+/// just has to end up at the most recent tip and finalized block.
+pub fn replay_unacked_actions(
+    database: &impl Database,
+    engine: &impl ExecEngineCtl,
+) -> anyhow::Result<usize> {
+    let client_state_db = database.client_state_db();
+    let last_idx = match client_state_db.get_last_write_idx() {
+        Ok(idx) => idx,
+        // nothing to replay
+        Err(DbError::NotBootstrapped) => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let first_idx = match client_state_db.get_last_acked_action_idx()? {
+        Some(acked_idx) => acked_idx + 1,
+        None => last_idx,
+    };
+
+    let mut replayed = 0;
+    for idx in first_idx..=last_idx {
+        let actions = client_state_db
+            .get_client_update_actions(idx)?
+            .ok_or(Error::MissingConsensusWrites(idx))?;
+
+        for action in actions {
+            match action {
+                SyncAction::UpdateTip(blkid) => engine.update_safe_block(blkid)?,
+                SyncAction::FinalizeBlock(blkid) => engine.update_finalized_block(blkid)?,
+                _ => continue,
+            }
+            debug!(%idx, ?action, "replayed unacked action");
+            replayed += 1;
+        }
+
+        client_state_db.put_last_acked_action_idx(idx)?;
+    }
+
+    if replayed > 0 {
+        info!(%first_idx, %last_idx, %replayed, "replayed unacked EL updates");
+    }
+    Ok(replayed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use strata_eectl::stub::{StubController, StubFcUpdate};
+    use strata_primitives::l1::L1BlockManifest;
+    use strata_rocksdb::test_utils::get_common_db;
+    use strata_state::{id::L2BlockId, l1::L1BlockId, sync_event::SyncEvent};
+    use strata_test_utils::{
+        bitcoin::get_btc_chain,
+        l2::{gen_client_state, gen_params},
+        ArbitraryGenerator,
+    };
+
+    use super::*;
+
+    /// Stores the genesis client state and a few L1 blocks, returning the
+    /// events accepting them.
+    fn setup_l1_events(database: &impl Database, params: &Params) -> Vec<SyncEvent> {
+        let horizon = params.rollup().horizon_l1_height;
+        let l1_chain = get_btc_chain().get_block_manifests(horizon as u32, 4);
+        let l1_db = database.l1_db();
+        for (i, b) in l1_chain.iter().enumerate() {
+            l1_db
+                .put_block_data(
+                    i as u64 + horizon,
+                    L1BlockManifest::new(b.clone(), 0),
+                    Vec::new(),
+                )
+                .unwrap();
+        }
+
+        database
+            .client_state_db()
+            .write_client_state_checkpoint(0, gen_client_state(Some(params)))
+            .unwrap();
+
+        l1_chain
+            .iter()
+            .enumerate()
+            .map(|(i, b)| SyncEvent::L1Block(i as u64 + horizon, L1BlockId::from(b.block_hash())))
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_reports_altered_output() {
+        let database = get_common_db();
+        let params = gen_params();
+        let events = setup_l1_events(database.as_ref(), &params);
+        let client_state_db = database.client_state_db();
+
+        // store the events along with their outputs, altering the third one
+        let mut cur_state = gen_client_state(Some(&params));
+        for ev in events {
+            let idx = database
+                .sync_event_db()
+                .write_sync_event(ev.clone())
+                .unwrap();
+
+            let output =
+                client_transition::process_event(&cur_state, &ev, database.as_ref(), &params)
+                    .unwrap();
+            operation::apply_writes_to_state(&mut cur_state, output.writes().iter().cloned());
+
+            let output = if idx == 3 {
+                ClientUpdateOutput::new(Vec::new(), output.actions().to_vec())
+            } else {
+                output
+            };
+            client_state_db
+                .write_client_update_output(idx, output)
+                .unwrap();
+
+            // everything before the altered output checks out
+            if idx == 2 {
+                verify_consensus_replay(database.as_ref(), &params).unwrap();
+            }
+        }
+
+        let report = verify_consensus_replay(database.as_ref(), &params).unwrap_err();
+        assert_eq!(report.idx, 3);
+        assert!(matches!(
+            report.kind,
+            DivergenceKind::OutputMismatch { ref stored, .. } if stored.writes().is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_replay_event() {
+        let database = get_common_db();
+        let params = gen_params();
+        let events = setup_l1_events(database.as_ref(), &params);
+        let client_state_db = database.client_state_db();
+
+        // process the events the way the worker does
+        let mut cur_state = gen_client_state(Some(&params));
+        let mut stored = Vec::new();
+        for ev in events {
+            let idx = database
+                .sync_event_db()
+                .write_sync_event(ev.clone())
+                .unwrap();
+            let output =
+                client_transition::process_event(&cur_state, &ev, database.as_ref(), &params)
+                    .unwrap();
+            operation::apply_writes_to_state(&mut cur_state, output.writes().iter().cloned());
+            client_state_db
+                .write_client_update_output(idx, output.clone())
+                .unwrap();
+            stored.push(output);
+        }
+
+        for (i, output) in stored.iter().enumerate() {
+            let idx = i as u64 + 1;
+            let replayed = replay_event(database.as_ref(), &params, idx).unwrap();
+            assert_eq!(&replayed, output, "event {idx}");
+        }
+
+        // replaying doesn't persist anything
+        let last_idx = client_state_db.get_last_write_idx().unwrap();
+        replay_event(database.as_ref(), &params, 2).unwrap();
+        assert_eq!(client_state_db.get_last_write_idx().unwrap(), last_idx);
+
+        assert!(replay_event(database.as_ref(), &params, 0).is_err());
+        assert!(replay_event(database.as_ref(), &params, last_idx + 1).is_err());
+    }
+
+    #[test]
+    fn test_replay_unacked_actions() {
+        let database = get_common_db();
+        let client_state_db = database.client_state_db();
+        let engine = StubController::new(Duration::ZERO);
+
+        // nothing written yet
+        assert_eq!(
+            replay_unacked_actions(database.as_ref(), &engine).unwrap(),
+            0
+        );
+
+        let mut arb = ArbitraryGenerator::new();
+        let blkids: Vec<L2BlockId> = (0..3).map(|_| arb.generate()).collect();
+        let outputs = [
+            vec![SyncAction::UpdateTip(blkids[0])],
+            vec![
+                SyncAction::UpdateTip(blkids[1]),
+                SyncAction::FetchBlock(blkids[2]),
+            ],
+            vec![
+                SyncAction::UpdateTip(blkids[2]),
+                SyncAction::FinalizeBlock(blkids[1]),
+            ],
+        ];
+        for (i, actions) in outputs.iter().enumerate() {
+            client_state_db
+                .write_client_update_output(
+                    i as u64 + 1,
+                    ClientUpdateOutput::new(Vec::new(), actions.clone()),
+                )
+                .unwrap();
+        }
+
+        // the worker applied the first output before crashing
+        client_state_db.put_last_acked_action_idx(1).unwrap();
+
+        assert_eq!(
+            replay_unacked_actions(database.as_ref(), &engine).unwrap(),
+            3
+        );
+        assert_eq!(
+            engine.take_fc_updates(),
+            vec![
+                StubFcUpdate::Safe(blkids[1]),
+                StubFcUpdate::Safe(blkids[2]),
+                StubFcUpdate::Finalized(blkids[1]),
+            ]
+        );
+        assert_eq!(
+            client_state_db.get_last_acked_action_idx().unwrap(),
+            Some(3)
+        );
+
+        // restarting again doesn't re-issue them
+        assert_eq!(
+            replay_unacked_actions(database.as_ref(), &engine).unwrap(),
+            0
+        );
+        assert!(engine.take_fc_updates().is_empty());
+    }
+}
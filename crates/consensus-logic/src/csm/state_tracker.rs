@@ -1,9 +1,13 @@
 //! Tracker to manage authoritative consensus states as we compute the
 //! transition outputs.
 
-use std::sync::Arc;
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
 
-use strata_db::traits::*;
+use lru::LruCache;
+use strata_db::{errors::DbError, traits::*};
 use strata_primitives::params::Params;
 use strata_state::{
     client_state::ClientState,
@@ -11,9 +15,73 @@ use strata_state::{
 };
 use tracing::*;
 
-use super::client_transition;
+use super::{client_transition, metrics::EventTimings};
 use crate::errors::Error;
 
+/// Number of the most recently computed states kept in memory.
+const RECENT_STATES_CACHE_SIZE: usize = 16;
+
+/// The most recently computed client states, by event index.
+///
+/// Filled by the [`StateTracker`] as it advances and shared with whatever
+/// looks up past states, so that looking up a recent one doesn't have to
+/// replay writes from the last checkpoint.  The state at an index never
+/// changes once it's computed, so entries only ever have to be evicted, not
+/// invalidated.
+pub struct ClientStateCache {
+    states: Mutex<LruCache<u64, Arc<ClientState>>>,
+}
+
+impl ClientStateCache {
+    pub fn new() -> Self {
+        let cache_size =
+            NonZeroUsize::new(RECENT_STATES_CACHE_SIZE).expect("csm: nonzero cache size");
+        Self {
+            states: Mutex::new(LruCache::new(cache_size)),
+        }
+    }
+
+    /// Gets the state after the event at the index if it's one of the recent
+    /// ones.
+    pub fn get(&self, idx: u64) -> Option<Arc<ClientState>> {
+        self.states
+            .lock()
+            .expect("csm: client state cache lock")
+            .get(&idx)
+            .cloned()
+    }
+
+    fn insert(&self, idx: u64, state: Arc<ClientState>) {
+        self.states
+            .lock()
+            .expect("csm: client state cache lock")
+            .put(idx, state);
+    }
+
+    /// Gets the state after the event at the index, from memory if it's one of
+    /// the recent ones, otherwise reconstructing it from the database.
+    pub fn get_or_reconstruct(
+        &self,
+        cs_db: &impl ClientStateDatabase,
+        idx: u64,
+    ) -> anyhow::Result<Arc<ClientState>> {
+        if let Some(state) = self.get(idx) {
+            return Ok(state);
+        }
+
+        trace!(%idx, "client state not cached, loading from db");
+        let state = Arc::new(reconstruct_state(cs_db, idx)?);
+        self.insert(idx, state.clone());
+        Ok(state)
+    }
+}
+
+impl Default for ClientStateCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct StateTracker<D: Database> {
     params: Arc<Params>,
     database: Arc<D>,
@@ -21,6 +89,11 @@ pub struct StateTracker<D: Database> {
     cur_state_idx: u64,
 
     cur_state: Arc<ClientState>,
+
+    /// States we've recently computed, shared with the readers of past states.
+    state_cache: Arc<ClientStateCache>,
+
+    event_timings: Arc<EventTimings>,
 }
 
 impl<D: Database> StateTracker<D> {
@@ -29,12 +102,18 @@ impl<D: Database> StateTracker<D> {
         database: Arc<D>,
         cur_state_idx: u64,
         cur_state: Arc<ClientState>,
+        state_cache: Arc<ClientStateCache>,
+        event_timings: Arc<EventTimings>,
     ) -> Self {
+        state_cache.insert(cur_state_idx, cur_state.clone());
+
         Self {
             params,
             database,
             cur_state_idx,
             cur_state,
+            state_cache,
+            event_timings,
         }
     }
 
@@ -48,9 +127,13 @@ impl<D: Database> StateTracker<D> {
 
     /// Given the next event index, computes the state application if the
     /// requisite data is available.  Returns the output and the new state.
+    ///
+    /// The output is queued in the provided batch, it's up to the caller to
+    /// commit it and then call [`Self::commit_advance`] with the new state.
     pub fn advance_consensus_state(
-        &mut self,
+        &self,
         ev_idx: u64,
+        batch: &mut impl AtomicBatch,
     ) -> anyhow::Result<(ClientUpdateOutput, Arc<ClientState>)> {
         let prev_ev_idx = ev_idx - 1;
         if prev_ev_idx != self.cur_state_idx {
@@ -60,7 +143,6 @@ impl<D: Database> StateTracker<D> {
         // Load the event from the database.
         let db = self.database.as_ref();
         let sync_event_db = db.sync_event_db();
-        let client_state_db = db.client_state_db();
         let ev = sync_event_db
             .get_sync_event(ev_idx)?
             .ok_or(Error::MissingSyncEvent(ev_idx))?;
@@ -68,22 +150,29 @@ impl<D: Database> StateTracker<D> {
         debug!(?ev, "Processing event");
 
         // Compute the state transition.
-        let outp = client_transition::process_event(&self.cur_state, &ev, db, &self.params)?;
+        let outp = self.event_timings.time_event(ev_idx, &ev, || {
+            client_transition::process_event(&self.cur_state, &ev, db, &self.params)
+        })?;
 
         // Clone the state and apply the operations to it.
         let mut new_state = self.cur_state.as_ref().clone();
         operation::apply_writes_to_state(&mut new_state, outp.writes().iter().cloned());
 
-        // Update bookkeeping.
-        self.cur_state = Arc::new(new_state);
-        self.cur_state_idx = ev_idx;
+        // Queue the outputs.
+        // TODO ideally avoid clone
+        batch.put_client_update_output(ev_idx, outp.clone())?;
         debug!(%ev_idx, "computed new consensus state");
 
-        // Store the outputs.
-        // TODO ideally avoid clone
-        client_state_db.write_client_update_output(ev_idx, outp.clone())?;
+        Ok((outp, Arc::new(new_state)))
+    }
 
-        Ok((outp, self.cur_state.clone()))
+    /// Moves the tracker to the state computed for the event at the index,
+    /// once its output has been committed.
+    pub fn commit_advance(&mut self, ev_idx: u64, new_state: Arc<ClientState>) {
+        assert_eq!(ev_idx, self.cur_state_idx + 1, "csm: advanced out of order");
+        self.state_cache.insert(ev_idx, new_state.clone());
+        self.cur_state = new_state;
+        self.cur_state_idx = ev_idx;
     }
 
     /// Writes the current state to the database as a new checkpoint.
@@ -144,13 +233,15 @@ pub fn reconstruct_state(
     cs_db: &impl ClientStateDatabase,
     idx: u64,
 ) -> anyhow::Result<ClientState> {
-    match cs_db.get_state_checkpoint(idx)? {
-        Some(cl) => {
+    match cs_db.get_state_checkpoint(idx) {
+        Ok(Some(cl)) => {
             // if the checkpoint was created at the idx itself, return the checkpoint
             debug!(%idx, "no writes to replay");
             Ok(cl)
         }
-        None => {
+        Err(e) if !matches!(e, DbError::ClientStatePruned(_)) => Err(e.into()),
+        // pruned checkpoints get recomputed like any other index between checkpoints
+        _ => {
             // get the previously written checkpoint
             let prev_ckpt_idx = cs_db.get_prev_checkpoint_at(idx)?;
 
@@ -175,19 +266,190 @@ pub fn reconstruct_state(
     }
 }
 
+/// Exports the borsh-serialized [`ClientState`] at `idx`, so that it can be analyzed offline or
+/// used to seed another node with [`import_state`].
+pub fn export_state(cs_db: &impl ClientStateDatabase, idx: u64) -> anyhow::Result<Vec<u8>> {
+    let state = reconstruct_state(cs_db, idx)?;
+    Ok(borsh::to_vec(&state)?)
+}
+
+/// Imports a [`ClientState`] exported with [`export_state`] by writing it as a checkpoint at `idx`.
+///
+/// The index can't be ahead of the last client update output written, and if none were written
+/// yet the state is imported as the bootstrap state at index 0.
+pub fn import_state(
+    cs_db: &impl ClientStateDatabase,
+    idx: u64,
+    raw_state: &[u8],
+) -> anyhow::Result<ClientState> {
+    let state: ClientState = borsh::from_slice(raw_state).map_err(|_| Error::Deserialization)?;
+
+    let last_write_idx = match cs_db.get_last_write_idx() {
+        Ok(last_write_idx) => Some(last_write_idx),
+        Err(DbError::NotBootstrapped) => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    if idx > last_write_idx.unwrap_or(0) {
+        return Err(Error::InconsistentImportIdx(idx, last_write_idx).into());
+    }
+
+    cs_db.write_client_state_checkpoint(idx, state.clone())?;
+    debug!(%idx, "imported client state");
+
+    Ok(state)
+}
+
 #[cfg(test)]
 mod tests {
-    use strata_db::traits::{ClientStateDatabase, Database};
+    use std::{sync::Arc, time::Duration};
+
+    use strata_db::traits::{
+        AtomicBatch, ClientStateDatabase, Database, L1Database, SyncEventDatabase,
+    };
+    use strata_primitives::l1::L1BlockManifest;
     use strata_rocksdb::test_utils::get_common_db;
     use strata_state::{
         block::L2Block,
         client_state::{ClientState, SyncState},
         header::L2Header,
+        l1::L1BlockId,
         operation::{apply_writes_to_state, ClientStateWrite, ClientUpdateOutput, SyncAction},
+        sync_event::SyncEvent,
+    };
+    use strata_test_utils::{
+        bitcoin::get_btc_chain,
+        l2::{gen_client_state, gen_params},
+        ArbitraryGenerator,
     };
-    use strata_test_utils::ArbitraryGenerator;
 
-    use super::reconstruct_state;
+    use super::{export_state, import_state, reconstruct_state, ClientStateCache, StateTracker};
+    use crate::{csm::metrics::EventTimings, errors::Error};
+
+    #[test]
+    fn test_advance_only_after_commit() {
+        let database = get_common_db();
+        let params = Arc::new(gen_params());
+        let client_state_db = database.client_state_db();
+
+        // an L1 block to accept, as the event to process
+        let horizon = params.rollup().horizon_l1_height;
+        let block = &get_btc_chain().get_block_manifests(horizon as u32, 1)[0];
+        database
+            .l1_db()
+            .put_block_data(horizon, L1BlockManifest::new(block.clone(), 0), Vec::new())
+            .unwrap();
+        database
+            .sync_event_db()
+            .write_sync_event(SyncEvent::L1Block(
+                horizon,
+                L1BlockId::from(block.block_hash()),
+            ))
+            .unwrap();
+
+        let genesis_state = Arc::new(gen_client_state(Some(&params)));
+        client_state_db
+            .write_client_state_checkpoint(0, genesis_state.as_ref().clone())
+            .unwrap();
+
+        let state_cache = Arc::new(ClientStateCache::new());
+        let mut tracker = StateTracker::new(
+            params,
+            database.clone(),
+            0,
+            genesis_state.clone(),
+            state_cache.clone(),
+            Arc::new(EventTimings::new(Duration::from_secs(1))),
+        );
+
+        // if the batch is never committed the tracker stays where it was
+        {
+            let mut batch = database.atomic_batch();
+            tracker.advance_consensus_state(1, &mut batch).unwrap();
+        }
+        assert_eq!(tracker.cur_state_idx(), 0);
+        assert!(Arc::ptr_eq(tracker.cur_state(), &genesis_state));
+        assert!(state_cache.get(1).is_none());
+
+        // so the event can be processed again
+        let mut batch = database.atomic_batch();
+        let (_, new_state) = tracker.advance_consensus_state(1, &mut batch).unwrap();
+        batch.commit().unwrap();
+        tracker.commit_advance(1, new_state.clone());
+
+        assert_eq!(tracker.cur_state_idx(), 1);
+        assert_eq!(
+            reconstruct_state(client_state_db.as_ref(), 1).unwrap(),
+            *new_state
+        );
+    }
+
+    #[test]
+    fn test_recent_states_cache() {
+        let database = get_common_db();
+        let params = Arc::new(gen_params());
+        let client_state_db = database.client_state_db();
+
+        // a few L1 blocks to accept, as the events to process
+        let horizon = params.rollup().horizon_l1_height;
+        let l1_chain = get_btc_chain().get_block_manifests(horizon as u32, 4);
+        for (i, b) in l1_chain.iter().enumerate() {
+            let height = i as u64 + horizon;
+            database
+                .l1_db()
+                .put_block_data(height, L1BlockManifest::new(b.clone(), 0), Vec::new())
+                .unwrap();
+            database
+                .sync_event_db()
+                .write_sync_event(SyncEvent::L1Block(height, L1BlockId::from(b.block_hash())))
+                .unwrap();
+        }
+
+        let genesis_state = Arc::new(gen_client_state(Some(&params)));
+        client_state_db
+            .write_client_state_checkpoint(0, genesis_state.as_ref().clone())
+            .unwrap();
+
+        let state_cache = Arc::new(ClientStateCache::new());
+        let mut tracker = StateTracker::new(
+            params,
+            database.clone(),
+            0,
+            genesis_state.clone(),
+            state_cache.clone(),
+            Arc::new(EventTimings::new(Duration::from_secs(1))),
+        );
+        assert!(Arc::ptr_eq(&state_cache.get(0).unwrap(), &genesis_state));
+
+        let mut computed = Vec::new();
+        for ev_idx in 1..=4 {
+            let mut batch = database.atomic_batch();
+            let (_, new_state) = tracker.advance_consensus_state(ev_idx, &mut batch).unwrap();
+            batch.commit().unwrap();
+            tracker.commit_advance(ev_idx, new_state.clone());
+            computed.push(new_state);
+        }
+
+        // the cached states are the ones that were persisted
+        for (i, state) in computed.iter().enumerate() {
+            let idx = i as u64 + 1;
+            let cached = state_cache
+                .get_or_reconstruct(client_state_db.as_ref(), idx)
+                .unwrap();
+            assert!(Arc::ptr_eq(&cached, state), "{idx}");
+            let stored = reconstruct_state(client_state_db.as_ref(), idx).unwrap();
+            assert_eq!(cached.as_ref(), &stored, "{idx}");
+        }
+
+        // states that aren't cached are loaded from the db, and cached from then on
+        let state_cache = ClientStateCache::new();
+        assert!(state_cache.get(2).is_none());
+        let loaded = state_cache
+            .get_or_reconstruct(client_state_db.as_ref(), 2)
+            .unwrap();
+        assert_eq!(loaded, computed[1]);
+        assert!(Arc::ptr_eq(&state_cache.get(2).unwrap(), &loaded));
+    }
 
     #[test]
     fn test_reconstruct_state() {
@@ -230,5 +492,82 @@ mod tests {
             let client_state = reconstruct_state(client_state_db.as_ref(), i).unwrap();
             assert_eq!(client_state_list[(i + 1) as usize], client_state);
         }
+
+        // pruned checkpoints are recomputed from the ones that are kept
+        client_state_db.prune_client_states_before(16, 8).unwrap();
+        for i in [4, 12, 13] {
+            let client_state = reconstruct_state(client_state_db.as_ref(), i).unwrap();
+            assert_eq!(client_state_list[(i + 1) as usize], client_state);
+        }
+    }
+
+    #[test]
+    fn test_export_import_state() {
+        let database = get_common_db();
+        let client_state_db = database.client_state_db();
+        let mut state: ClientState = ArbitraryGenerator::new().generate();
+        client_state_db
+            .write_client_state_checkpoint(0, state.clone())
+            .unwrap();
+
+        for idx in 1..=6 {
+            let ss: SyncState = ArbitraryGenerator::new().generate();
+            let output =
+                ClientUpdateOutput::new(vec![ClientStateWrite::ReplaceSync(Box::new(ss))], vec![]);
+            apply_writes_to_state(&mut state, output.writes().to_vec().into_iter());
+            client_state_db
+                .write_client_update_output(idx, output)
+                .unwrap();
+        }
+
+        let raw_state = export_state(client_state_db.as_ref(), 6).unwrap();
+
+        // importing at an index with writes makes it a checkpoint there
+        let imported = import_state(client_state_db.as_ref(), 6, &raw_state).unwrap();
+        assert_eq!(imported, state);
+        assert_eq!(
+            client_state_db.get_state_checkpoint(6).unwrap(),
+            Some(state.clone())
+        );
+        assert_eq!(
+            export_state(client_state_db.as_ref(), 6).unwrap(),
+            raw_state
+        );
+
+        // can't overwrite the checkpoint
+        assert!(import_state(client_state_db.as_ref(), 6, &raw_state).is_err());
+
+        // can't import ahead of the writes
+        let res = import_state(client_state_db.as_ref(), 7, &raw_state);
+        assert!(matches!(
+            res.unwrap_err().downcast_ref::<Error>(),
+            Some(Error::InconsistentImportIdx(7, Some(6)))
+        ));
+    }
+
+    #[test]
+    fn test_import_state_into_new_db() {
+        let database = get_common_db();
+        let client_state_db = database.client_state_db();
+        let state: ClientState = ArbitraryGenerator::new().generate();
+        let raw_state = borsh::to_vec(&state).unwrap();
+
+        let res = import_state(client_state_db.as_ref(), 3, &raw_state);
+        assert!(matches!(
+            res.unwrap_err().downcast_ref::<Error>(),
+            Some(Error::InconsistentImportIdx(3, None))
+        ));
+
+        import_state(client_state_db.as_ref(), 0, &raw_state).unwrap();
+        assert_eq!(
+            export_state(client_state_db.as_ref(), 0).unwrap(),
+            raw_state
+        );
+
+        let res = import_state(client_state_db.as_ref(), 0, &raw_state[1..]);
+        assert!(matches!(
+            res.unwrap_err().downcast_ref::<Error>(),
+            Some(Error::Deserialization)
+        ));
     }
 }
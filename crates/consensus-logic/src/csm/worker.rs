@@ -8,7 +8,7 @@ use strata_db::{
     traits::*,
     types::{CheckpointConfStatus, CheckpointEntry, CheckpointProvingStatus},
 };
-use strata_eectl::engine::ExecEngineCtl;
+use strata_eectl::{engine::ExecEngineCtl, errors::EngineResult};
 use strata_primitives::prelude::*;
 use strata_state::{client_state::ClientState, csm_status::CsmStatus, operation::SyncAction};
 use strata_status::StatusChannel;
@@ -23,7 +23,8 @@ use tracing::*;
 use super::{
     config::CsmExecConfig,
     message::{ClientUpdateNotif, CsmMessage},
-    state_tracker,
+    metrics::EventTimings,
+    replay, state_tracker,
 };
 use crate::{errors::Error, genesis};
 
@@ -65,6 +66,8 @@ impl<D: Database> WorkerState<D> {
         l2_block_manager: Arc<L2BlockManager>,
         cupdate_tx: broadcast::Sender<Arc<ClientUpdateNotif>>,
         checkpoint_manager: Arc<CheckpointDbManager>,
+        state_cache: Arc<state_tracker::ClientStateCache>,
+        event_timings: Arc<EventTimings>,
     ) -> anyhow::Result<Self> {
         let client_state_db = database.client_state_db().as_ref();
         let (cur_state_idx, cur_state) = state_tracker::reconstruct_cur_state(client_state_db)?;
@@ -73,6 +76,8 @@ impl<D: Database> WorkerState<D> {
             database.clone(),
             cur_state_idx,
             Arc::new(cur_state),
+            state_cache,
+            event_timings,
         );
 
         // TODO make configurable
@@ -119,6 +124,14 @@ pub fn client_worker_task<D: Database, E: ExecEngineCtl>(
     mut msg_rx: mpsc::Receiver<CsmMessage>,
     status_channel: StatusChannel,
 ) -> Result<(), Error> {
+    status_channel.update_sync_event_processed(state.cur_event_idx());
+
+    // We could have crashed after persisting an output but before the EL heard
+    // about it, so catch it up before processing anything new.
+    if let Err(e) = replay::replay_unacked_actions(state.database.as_ref(), engine.as_ref()) {
+        error!(err = %e, "failed to replay unacked actions");
+    }
+
     while let Some(msg) = msg_rx.blocking_recv() {
         if let Err(e) = process_msg(
             &mut state,
@@ -228,22 +241,52 @@ fn handle_sync_event<D: Database>(
     ev_idx: u64,
     status_channel: &StatusChannel,
 ) -> anyhow::Result<()> {
+    // Everything we persist for the event goes in a single batch, so that we
+    // can't crash with only some of it written.
+    let mut batch = state.database.atomic_batch();
+
     // Perform the main step of deciding what the output we're operating on.
-    let (outp, new_state) = state.state_tracker.advance_consensus_state(ev_idx)?;
+    let (outp, new_state) = state
+        .state_tracker
+        .advance_consensus_state(ev_idx, &mut batch)?;
     let outp = Arc::new(outp);
 
-    // Apply the actions produced from the state transition.
     for action in outp.actions() {
-        apply_action(action.clone(), state, engine, status_channel)?;
+        if let SyncAction::MarkInvalid(blkid) = action {
+            batch.put_block_status(*blkid, BlockStatus::Invalid)?;
+        }
     }
 
-    // Make sure that the new state index is set as expected.
-    assert_eq!(state.state_tracker.cur_state_idx(), ev_idx);
-
     // Write the client state checkpoint periodically based on the event idx..
     if ev_idx % state.params.run.client_checkpoint_interval as u64 == 0 {
-        let client_state_db = state.database.client_state_db();
-        client_state_db.write_client_state_checkpoint(ev_idx, new_state.as_ref().clone())?;
+        batch.put_client_state_checkpoint(ev_idx, new_state.as_ref().clone())?;
+    }
+
+    batch.commit()?;
+
+    // Only now that it's persisted do we move on to the new state.
+    state
+        .state_tracker
+        .commit_advance(ev_idx, new_state.clone());
+
+    // Apply the actions produced from the state transition.
+    for action in outp.actions() {
+        apply_action(action.clone(), state, engine, status_channel)?;
+    }
+
+    // Mark them as applied so they aren't replayed on restart.
+    let client_state_db = state.database.client_state_db();
+    client_state_db.put_last_acked_action_idx(ev_idx)?;
+
+    // Once a block gets finalized we don't need the older states anymore,
+    // other than the ones we keep at the retention interval.
+    let finalized = outp
+        .actions()
+        .iter()
+        .any(|a| matches!(a, SyncAction::FinalizeBlock(_)));
+    if finalized {
+        let keep_interval = state.params.run.client_state_retention_interval;
+        client_state_db.prune_client_states_before(ev_idx, keep_interval)?;
     }
 
     // FIXME clean this up
@@ -252,6 +295,7 @@ fn handle_sync_event<D: Database>(
     status.update_from_client_state(new_state.as_ref());
 
     status_channel.update_client_state(new_state.as_ref().clone());
+    status_channel.update_sync_event_processed(ev_idx);
 
     trace!(?new_state, "sending client update notif");
     let update = ClientUpdateNotif::new(ev_idx, outp, new_state);
@@ -272,17 +316,27 @@ fn apply_action<D: Database>(
         SyncAction::UpdateTip(blkid) => {
             // Tell the EL that this block does indeed look good.
             debug!(?blkid, "updating EL safe block");
-            engine.update_safe_block(blkid)?;
+            send_engine_update(&action, engine, &state.config)?;
 
             // TODO update the tip we report in RPCs and whatnot
         }
 
+        SyncAction::RevertTip(blkid) => {
+            // The tip of the fork we're switching to comes right after this, so
+            // there isn't anything to tell the EL yet.
+            info!(?blkid, "reverting tip to fork point");
+        }
+
+        SyncAction::FetchBlock(blkid) => {
+            // TODO ask the L2 sync worker for the block instead of relying on it
+            // to fetch missing parents on its own
+            warn!(?blkid, "missing L2 block needs to be fetched");
+        }
+
         SyncAction::MarkInvalid(blkid) => {
             // TODO not sure what this should entail yet
+            // The status itself was already written along with the output.
             warn!(?blkid, "marking block invalid!");
-            state
-                .l2_block_manager
-                .put_block_status_blocking(&blkid, BlockStatus::Invalid)?;
         }
 
         SyncAction::FinalizeBlock(blkid) => {
@@ -291,7 +345,7 @@ fn apply_action<D: Database>(
             // TODO we should probably emit a state checkpoint here if we
             // aren't already
             info!(?blkid, "finalizing block");
-            engine.update_finalized_block(blkid)?;
+            send_engine_update(&action, engine, &state.config)?;
         }
 
         SyncAction::L2Genesis(l1blkid) => {
@@ -346,3 +400,83 @@ fn apply_action<D: Database>(
 
     Ok(())
 }
+
+/// Sends the EL update the action corresponds to, if any, retrying with
+/// backoff until the engine acks it by returning successfully.  If it never
+/// does the action is left unacked, to be replayed on restart.
+fn send_engine_update(
+    action: &SyncAction,
+    engine: &impl ExecEngineCtl,
+    config: &CsmExecConfig,
+) -> EngineResult<()> {
+    let mut tries = 0;
+    let mut wait_dur = config.retry_base_dur;
+
+    loop {
+        tries += 1;
+
+        let res = match action {
+            SyncAction::UpdateTip(blkid) => engine.update_safe_block(*blkid),
+            SyncAction::FinalizeBlock(blkid) => engine.update_finalized_block(*blkid),
+            _ => return Ok(()),
+        };
+
+        let Err(e) = res else {
+            trace!(?action, %tries, "EL acked update");
+            return Ok(());
+        };
+
+        if tries > config.retry_cnt_max {
+            error!(err = %e, ?action, %tries, "EL didn't ack update, hit tries limit");
+            return Err(e);
+        }
+
+        warn!(err = %e, ?action, %tries, "EL didn't ack update, retrying...");
+        thread::sleep(wait_dur);
+        wait_dur = config.compute_retry_backoff(wait_dur);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use strata_eectl::stub::{StubController, StubFcUpdate};
+    use strata_state::id::L2BlockId;
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    #[test]
+    fn test_send_engine_update_retries() {
+        let config = CsmExecConfig {
+            retry_base_dur: Duration::from_millis(1),
+            retry_cnt_max: 3,
+            retry_backoff_mult: 1024,
+        };
+        let engine = StubController::new(Duration::ZERO);
+        let mut arb = ArbitraryGenerator::new();
+        let [acked, flaky, dead]: [L2BlockId; 3] = [arb.generate(), arb.generate(), arb.generate()];
+        engine.drop_fc_updates(flaky, 2);
+        engine.drop_fc_updates(dead, u32::MAX);
+
+        send_engine_update(&SyncAction::UpdateTip(acked), &engine, &config).unwrap();
+        assert_eq!(engine.take_fc_updates(), vec![StubFcUpdate::Safe(acked)]);
+
+        // retried until the EL acks it
+        send_engine_update(&SyncAction::FinalizeBlock(flaky), &engine, &config).unwrap();
+        assert_eq!(
+            engine.take_fc_updates(),
+            vec![StubFcUpdate::Finalized(flaky); 3]
+        );
+
+        // given up on after the tries limit
+        let res = send_engine_update(&SyncAction::UpdateTip(dead), &engine, &config);
+        assert!(res.is_err());
+        assert_eq!(engine.take_fc_updates(), vec![StubFcUpdate::Safe(dead); 4]);
+
+        // actions that don't go to the EL don't need acking
+        send_engine_update(&SyncAction::FetchBlock(dead), &engine, &config).unwrap();
+        assert!(engine.take_fc_updates().is_empty());
+    }
+}
@@ -4,7 +4,6 @@
 use std::{sync::Arc, thread, time};
 
 use bitcoin::Transaction;
-use strata_crypto::sign_schnorr_sig;
 use strata_db::traits::{
     ChainstateDatabase, ClientStateDatabase, Database, L1Database, L2BlockDatabase,
 };
@@ -14,7 +13,7 @@ use strata_eectl::{
     messages::{ExecPayloadData, PayloadEnv},
 };
 use strata_primitives::{
-    buf::{Buf32, Buf64},
+    buf::Buf32,
     params::{Params, RollupParams},
 };
 use strata_state::{
@@ -22,6 +21,7 @@ use strata_state::{
     bridge_ops::DepositIntent,
     chain_state::Chainstate,
     client_state::{ClientState, LocalL1State},
+    cred_rule,
     exec_update::{
         construct_ops_from_deposit_intents, ELDepositData, ExecUpdate, Op, UpdateOutput,
     },
@@ -33,7 +33,7 @@ use strata_state::{
 };
 use tracing::*;
 
-use super::types::*;
+use super::{signer::BlockSigner, types::*};
 use crate::errors::Error;
 
 /// Max number of L1 block entries to include per L2Block.
@@ -139,7 +139,11 @@ pub(super) fn sign_and_store_block<D: Database, E: ExecEngineCtl>(
     let new_state_root = post_state.compute_state_root();
 
     let header = L2BlockHeader::new(slot, ts, prev_block_id, &body, new_state_root);
-    let header_sig = sign_header(&header, ik);
+    let header_sig = ik.sign_header(&header);
+    if !cred_rule::verify(&params.rollup().cred_rule, &header, &header_sig) {
+        error!(%slot, "signed block fails the credential rule, not storing it");
+        return Err(Error::SignedBlockFailsCredential(slot));
+    }
     let signed_header = SignedL2BlockHeader::new(header, header_sig);
 
     let blkid = signed_header.get_blockid();
@@ -418,14 +422,6 @@ fn compute_post_state(
     Ok((post_state, wb))
 }
 
-/// Signs the L2BlockHeader and returns the signature
-fn sign_header(header: &L2BlockHeader, ik: &IdentityKey) -> Buf64 {
-    let msg = header.get_sighash();
-    match ik {
-        IdentityKey::Sequencer(sk) => sign_schnorr_sig(&msg, sk),
-    }
-}
-
 /// Returns the current unix time as milliseconds.
 // TODO maybe we should use a time source that is possibly more consistent with
 // the rest of the network for this?
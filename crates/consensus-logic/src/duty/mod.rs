@@ -1,5 +1,6 @@
 pub mod block_assembly;
 pub(crate) mod checkpoint;
 pub mod extractor;
+pub mod signer;
 pub mod types;
 pub mod worker;
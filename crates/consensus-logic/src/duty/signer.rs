@@ -0,0 +1,90 @@
+//! Signing the headers of the blocks we produce, so they pass the rollup's
+//! credential rule.
+
+use secp256k1::{Keypair, SecretKey, SECP256K1};
+use strata_crypto::sign_schnorr_sig;
+use strata_primitives::{
+    block_credential::CredRule,
+    buf::{Buf32, Buf64},
+};
+use strata_state::header::L2BlockHeader;
+
+use super::types::IdentityKey;
+
+/// Signs the headers of the blocks the sequencer produces.
+pub trait BlockSigner {
+    /// Signs the header's sighash.
+    fn sign_header(&self, header: &L2BlockHeader) -> Buf64;
+}
+
+/// Signer holding the sequencer's keypair in memory.
+#[derive(Clone, Debug)]
+pub struct InMemorySigner {
+    keypair: Keypair,
+}
+
+impl InMemorySigner {
+    pub fn new(sk: SecretKey) -> Self {
+        Self {
+            keypair: Keypair::from_secret_key(SECP256K1, &sk),
+        }
+    }
+
+    /// Returns the x-only pubkey the signatures are checked against.
+    pub fn pubkey(&self) -> Buf32 {
+        self.keypair.x_only_public_key().0.serialize().into()
+    }
+
+    /// Returns the credential rule the blocks we sign pass.
+    pub fn cred_rule(&self) -> CredRule {
+        CredRule::SchnorrKey(self.pubkey())
+    }
+}
+
+impl BlockSigner for InMemorySigner {
+    fn sign_header(&self, header: &L2BlockHeader) -> Buf64 {
+        let sk = Buf32::from(self.keypair.secret_bytes());
+        sign_schnorr_sig(&header.get_sighash(), &sk)
+    }
+}
+
+impl BlockSigner for IdentityKey {
+    fn sign_header(&self, header: &L2BlockHeader) -> Buf64 {
+        match self {
+            IdentityKey::Sequencer(sk) => sign_schnorr_sig(&header.get_sighash(), sk),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strata_state::cred_rule;
+    use strata_test_utils::l2::gen_l2_chain;
+
+    use super::*;
+
+    #[test]
+    fn test_signed_header_passes_cred_rule() {
+        let header = gen_l2_chain(None, 1)[1].header().header().clone();
+
+        let sk = SecretKey::new(&mut secp256k1::rand::thread_rng());
+        let signer = InMemorySigner::new(sk);
+
+        let sig = signer.sign_header(&header);
+        assert!(cred_rule::verify(&signer.cred_rule(), &header, &sig));
+
+        // the sequencer's identity key with the same secret signs the same way
+        let ik = IdentityKey::Sequencer(Buf32::from(sk.secret_bytes()));
+        let ik_sig = ik.sign_header(&header);
+        assert!(cred_rule::verify(&signer.cred_rule(), &header, &ik_sig));
+
+        // a signature from another key doesn't
+        let other = InMemorySigner::new(SecretKey::new(&mut secp256k1::rand::thread_rng()));
+        let other_sig = other.sign_header(&header);
+        assert!(!cred_rule::verify(&signer.cred_rule(), &header, &other_sig));
+
+        // and neither does the signature of a different header
+        let other_header = gen_l2_chain(None, 1)[1].header().header().clone();
+        assert!(!cred_rule::verify(&signer.cred_rule(), &other_header, &sig));
+    }
+}
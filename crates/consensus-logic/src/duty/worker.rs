@@ -428,7 +428,12 @@ fn perform_duty<D: Database, E: ExecEngineCtl>(
 
             let payload =
                 borsh::to_vec(&signed_checkpoint).map_err(|e| Error::Other(e.to_string()))?;
-            let blob_intent = BlobIntent::new(BlobDest::L1, checkpoint_sighash, payload);
+            let blob_intent = BlobIntent::builder()
+                .dest(BlobDest::L1)
+                .commitment(checkpoint_sighash)
+                .payload(payload)
+                .build()
+                .map_err(|e| Error::Other(e.to_string()))?;
 
             info!(signed_checkpoint = ?signed_checkpoint, "signed checkpoint");
             info!(blob_intent = ?blob_intent, "sending blob intent");
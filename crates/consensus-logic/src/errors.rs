@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use strata_chaintsn::errors::TsnError;
 use strata_eectl::errors::EngineError;
 use strata_state::{id::L2BlockId, l1::L1BlockId};
@@ -34,6 +36,9 @@ pub enum Error {
     #[error("missing expected state checkpoint at {0}")]
     MissingCheckpoint(u64),
 
+    #[error("can't import state at {0} (last write at {1:?})")]
+    InconsistentImportIdx(u64, Option<u64>),
+
     #[error("unable to find reorg {0:?} -> {1:?})")]
     UnableToFindReorg(L2BlockId, L2BlockId),
 
@@ -43,6 +48,18 @@ pub enum Error {
     #[error("invalid state transition on block {0:?}: {1}")]
     InvalidStateTsn(L2BlockId, TsnError),
 
+    #[error("block {0:?} forks off below the finalized block")]
+    ForkBelowFinalized(L2BlockId),
+
+    #[error("block we signed for slot {0} fails the credential rule, wrong sequencer key?")]
+    SignedBlockFailsCredential(u64),
+
+    #[error("block {0:?} timestamp {1} before its parent's {2}")]
+    BlockTimestampBeforeParent(L2BlockId, u64, u64),
+
+    #[error("block {0:?} timestamp {1} too far past our clock {2}")]
+    BlockTimestampTooFarAhead(L2BlockId, u64, u64),
+
     #[error("client sync state unset")]
     MissingClientSyncState,
 
@@ -89,10 +106,29 @@ pub enum Error {
     #[error("chain is not active yet")]
     ChainInactive,
 
+    #[error("L2 block {0:?} is not an unfinalized ancestor of the tip")]
+    NotUnfinalizedAncestor(L2BlockId),
+
     #[error("{0}")]
     Other(String),
 }
 
+#[derive(Debug, Error)]
+pub enum SubmitError {
+    #[error("db: {0}")]
+    Db(#[from] strata_db::errors::DbError),
+
+    /// The event was persisted but the CSM worker didn't accept the
+    /// notification in time, it can still pick it up by index later.
+    #[error("CSM worker unresponsive for {1:?} when submitting sync event {0}")]
+    WorkerUnresponsive(u64, Duration),
+
+    /// An L1 block came at or below the height of the last L1 event submitted,
+    /// without a revert in between.
+    #[error("out of order L1 block {0} (last L1 event at {1})")]
+    OutOfOrderL1Event(u64, u64),
+}
+
 #[derive(Debug, Error)]
 pub enum ChainTipError {
     #[error("tried to attach blkid {0:?} but missing parent blkid {1:?}")]
@@ -100,4 +136,7 @@ pub enum ChainTipError {
 
     #[error("tried to finalize unknown block {0:?}")]
     MissingBlock(L2BlockId),
+
+    #[error("reorg would revert {depth} blocks (max {max})")]
+    ReorgTooDeep { depth: u64, max: u64 },
 }
@@ -1,13 +1,17 @@
 //! Fork choice manager. Used to talk to the EL and pick the new fork choice.
 
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use strata_chaintsn::transition::process_block;
 use strata_db::{
     errors::DbError,
     traits::{BlockStatus, ChainstateDatabase, Database},
 };
-use strata_eectl::{engine::ExecEngineCtl, messages::ExecPayloadData};
+use strata_eectl::{engine::ExecEngineCtl, errors::EngineError, messages::ExecPayloadData};
 use strata_primitives::params::Params;
 use strata_state::{
     block::L2BlockBundle, block_validation::validate_block_segments, chain_state::Chainstate,
@@ -49,6 +53,10 @@ pub struct ForkChoiceManager<D: Database> {
 
     /// Current best block index.
     cur_index: u64,
+
+    /// Blocks whose EL check timed out or whose timestamp was too far ahead,
+    /// waiting to be checked again.
+    el_check_retries: ElCheckRetries,
 }
 
 impl<D: Database> ForkChoiceManager<D> {
@@ -70,6 +78,7 @@ impl<D: Database> ForkChoiceManager<D> {
             chain_tracker,
             cur_best_block,
             cur_index,
+            el_check_retries: ElCheckRetries::default(),
         }
     }
 
@@ -106,6 +115,79 @@ impl<D: Database> ForkChoiceManager<D> {
     }
 }
 
+/// Delay before a block whose EL check timed out is checked again, doubling
+/// with each further timeout.
+const EL_CHECK_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest delay between two EL checks of the same block.
+const EL_CHECK_RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Most times a block's EL check is retried before we give up on it until it's
+/// announced again.
+const MAX_EL_CHECK_RETRIES: u32 = 8;
+
+#[derive(Debug)]
+struct ElCheckRetry {
+    /// Retries scheduled so far.
+    retries: u32,
+
+    /// When the block is next due to be checked, if it's waiting.
+    due: Option<Instant>,
+}
+
+/// Blocks whose EL check timed out, or that were too far ahead of our clock, to
+/// be checked again after a backoff.
+#[derive(Debug, Default)]
+struct ElCheckRetries {
+    pending: HashMap<L2BlockId, ElCheckRetry>,
+}
+
+impl ElCheckRetries {
+    /// Schedules another check of the block, returning the delay before it or
+    /// `None` if it's been retried too many times already.
+    fn schedule(&mut self, blkid: L2BlockId, now: Instant) -> Option<Duration> {
+        let ent = self.pending.entry(blkid).or_insert(ElCheckRetry {
+            retries: 0,
+            due: None,
+        });
+        if ent.retries >= MAX_EL_CHECK_RETRIES {
+            self.pending.remove(&blkid);
+            return None;
+        }
+
+        let mult = 1u32.checked_shl(ent.retries).unwrap_or(u32::MAX);
+        let delay = EL_CHECK_RETRY_BASE_DELAY
+            .saturating_mul(mult)
+            .min(EL_CHECK_RETRY_MAX_DELAY);
+        ent.retries += 1;
+        ent.due = Some(now + delay);
+        Some(delay)
+    }
+
+    /// Forgets about the block, once the EL has given a verdict on it.
+    fn clear(&mut self, blkid: &L2BlockId) {
+        self.pending.remove(blkid);
+    }
+
+    /// Returns when the next block is due to be checked again.
+    fn next_due(&self) -> Option<Instant> {
+        self.pending.values().filter_map(|ent| ent.due).min()
+    }
+
+    /// Takes the blocks that are due to be checked again.  They're remembered
+    /// until cleared, so a further timeout backs off more.
+    fn take_due(&mut self, now: Instant) -> Vec<L2BlockId> {
+        self.pending
+            .iter_mut()
+            .filter(|(_, ent)| ent.due.is_some_and(|due| due <= now))
+            .map(|(blkid, ent)| {
+                ent.due = None;
+                *blkid
+            })
+            .collect()
+    }
+}
+
 /// Creates the forkchoice manager state from a database and rollup params.
 pub fn init_forkchoice_manager<D: Database>(
     database: &Arc<D>,
@@ -247,6 +329,7 @@ pub fn tracker_task<D: Database, E: ExecEngineCtl>(
 enum FcmEvent {
     NewFcmMsg(ForkChoiceMessage),
     NewStateUpdate(ClientState),
+    RetryElChecks,
     Abort,
 }
 
@@ -265,13 +348,17 @@ fn forkchoice_manager_task_inner<D: Database, E: ExecEngineCtl>(
             break;
         }
 
-        let fcm_ev = wait_for_fcm_event(&handle, &mut fcm_rx, &status_channel);
+        let next_retry = fcm_state.el_check_retries.next_due();
+        let fcm_ev = wait_for_fcm_event(&handle, &mut fcm_rx, &status_channel, next_retry);
 
         match fcm_ev {
             FcmEvent::NewFcmMsg(m) => {
                 process_fc_message(m, &mut fcm_state, engine, csm_ctl, &status_channel)
             }
             FcmEvent::NewStateUpdate(st) => handle_new_state(&mut fcm_state, st),
+            FcmEvent::RetryElChecks => {
+                retry_el_checks(&mut fcm_state, engine, csm_ctl, &status_channel)
+            }
             FcmEvent::Abort => break,
         }?;
     }
@@ -283,8 +370,16 @@ fn wait_for_fcm_event(
     handle: &Handle,
     fcm_rx: &mut mpsc::Receiver<ForkChoiceMessage>,
     status_channel: &StatusChannel,
+    next_retry: Option<Instant>,
 ) -> FcmEvent {
     handle.block_on(async {
+        let retry_wait = async {
+            match next_retry {
+                Some(due) => tokio::time::sleep_until(due.into()).await,
+                None => std::future::pending().await,
+            }
+        };
+
         tokio::select! {
             m = fcm_rx.recv() => {
                 m.map(FcmEvent::NewFcmMsg).unwrap_or_else(|| {
@@ -298,10 +393,33 @@ fn wait_for_fcm_event(
                     FcmEvent::Abort
                 })
             }
+            _ = retry_wait => FcmEvent::RetryElChecks,
         }
     })
 }
 
+/// Processes the blocks whose EL check is due to be retried as if they were
+/// just announced, skipping any that got attached in the meantime.
+fn retry_el_checks<D: Database, E: ExecEngineCtl>(
+    fcm_state: &mut ForkChoiceManager<D>,
+    engine: &E,
+    csm_ctl: &CsmController,
+    status_channel: &StatusChannel,
+) -> anyhow::Result<()> {
+    for blkid in fcm_state.el_check_retries.take_due(Instant::now()) {
+        if fcm_state.chain_tracker.is_seen_block(&blkid) {
+            fcm_state.el_check_retries.clear(&blkid);
+            continue;
+        }
+
+        debug!(?blkid, "retrying EL check");
+        let msg = ForkChoiceMessage::NewBlock(blkid);
+        process_fc_message(msg, fcm_state, engine, csm_ctl, status_channel)?;
+    }
+
+    Ok(())
+}
+
 fn process_fc_message<D: Database, E: ExecEngineCtl>(
     msg: ForkChoiceMessage,
     fcm_state: &mut ForkChoiceManager<D>,
@@ -318,27 +436,19 @@ fn process_fc_message<D: Database, E: ExecEngineCtl>(
             // First, decide if the block seems correctly signed and we haven't
             // already marked it as invalid.
             let cstate = fcm_state.cur_csm_state.clone();
-            let correctly_signed = check_new_block(&blkid, &block_bundle, &cstate, fcm_state)?;
-            if !correctly_signed {
-                // It's invalid, write that and return.
-                fcm_state.set_block_status(&blkid, BlockStatus::Invalid)?;
-                return Ok(());
+            match check_new_block(&blkid, &block_bundle, &cstate, fcm_state)? {
+                NewBlockCheck::Plausible => {}
+                NewBlockCheck::Invalid => {
+                    // It's invalid, write that and return.
+                    fcm_state.set_block_status(&blkid, BlockStatus::Invalid)?;
+                    return Ok(());
+                }
+                // It's checked again later, leave it unmarked until then.
+                NewBlockCheck::Deferred => return Ok(()),
             }
 
             // Try to execute the payload, seeing if *that's* valid.
-            // TODO take implicit input produced by the CL STF and include that in the payload data
-            let exec_hash = block_bundle.header().exec_payload_hash();
-            let eng_payload = ExecPayloadData::from_l2_block_bundle(&block_bundle);
-            debug!(?blkid, ?exec_hash, "submitting execution payload");
-            let res = engine.submit_payload(eng_payload)?;
-
-            // If the payload is invalid then we should write the full block as
-            // being invalid and return too.
-            // TODO verify this is reasonable behavior, especially with regard
-            // to pre-sync
-            if res == strata_eectl::engine::BlockStatus::Invalid {
-                // It's invalid, write that and return.
-                fcm_state.set_block_status(&blkid, BlockStatus::Invalid)?;
+            if !check_exec_payload(&blkid, &block_bundle, fcm_state, engine)? {
                 return Ok(());
             }
 
@@ -359,6 +469,17 @@ fn process_fc_message<D: Database, E: ExecEngineCtl>(
                 &fcm_state.l2_block_manager,
             )?;
 
+            // Don't let a fork rewrite too much of the chain we're on, no
+            // matter how good it looks.
+            let max_depth = fcm_state.params.run().max_reorg_depth;
+            if let Err(e) = fcm_state
+                .chain_tracker
+                .check_reorg_depth(&cur_tip, best_block, max_depth)
+            {
+                warn!(?blkid, err = %e, "refusing to switch to new tip");
+                return Ok(());
+            }
+
             // Figure out what our job is now.
             // TODO this shouldn't be called "reorg" here, make the types
             // context aware so that we know we're not doing anything abnormal
@@ -412,6 +533,11 @@ fn process_fc_message<D: Database, E: ExecEngineCtl>(
                 }
             }
         }
+
+        ForkChoiceMessage::DumpUnfinalizedTree(resp_tx) => {
+            // the caller may have given up waiting, that's fine
+            let _ = resp_tx.send(fcm_state.chain_tracker.dump_tree());
+        }
     }
 
     Ok(())
@@ -443,6 +569,19 @@ fn handle_new_state<D: Database>(
     Ok(())
 }
 
+/// Verdict of the checks done on a new block before its payload is executed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum NewBlockCheck {
+    /// The block is plausibly valid and can be attached.
+    Plausible,
+
+    /// The block can never be valid.
+    Invalid,
+
+    /// The block can't be judged yet and is queued to be checked again.
+    Deferred,
+}
+
 /// Considers if the block is plausibly valid and if we should attach it to the
 /// pending unfinalized blocks tree.  The block is assumed to already be
 /// structurally consistent.
@@ -451,7 +590,7 @@ fn check_new_block<D: Database>(
     block: &L2Block,
     _cstate: &ClientState,
     state: &mut ForkChoiceManager<D>,
-) -> anyhow::Result<bool, Error> {
+) -> anyhow::Result<NewBlockCheck, Error> {
     let params = state.params.as_ref();
 
     // Check that the block is correctly signed.
@@ -459,18 +598,130 @@ fn check_new_block<D: Database>(
         strata_state::block_validation::check_block_credential(block.header(), params.rollup());
     if !cred_ok {
         warn!(?blkid, "block has invalid credential");
-        return Ok(false);
+        return Ok(NewBlockCheck::Invalid);
     }
 
     // Check that we haven't already marked the block as invalid.
     if let Some(status) = state.get_block_status(blkid)? {
         if status == strata_db::traits::BlockStatus::Invalid {
             warn!(?blkid, "rejecting block that fails EL validation");
-            return Ok(false);
+            return Ok(NewBlockCheck::Invalid);
         }
     }
 
     if !validate_block_segments(block) {
+        return Ok(NewBlockCheck::Invalid);
+    }
+
+    // If we don't have the parent it can't be attached anyway.
+    if let Some(parent) = state.get_block_data(block.header().parent())? {
+        let ts = block.header().timestamp();
+        let parent_ts = parent.header().timestamp();
+        let max_drift = params.run().max_block_time_drift;
+        match check_block_timestamp(blkid, ts, parent_ts, now_millis(), max_drift) {
+            Ok(()) => {}
+            // Our clock may just be behind, so the block may well be fine once
+            // we've caught up to it.
+            Err(Error::BlockTimestampTooFarAhead(..)) => {
+                match state.el_check_retries.schedule(*blkid, Instant::now()) {
+                    Some(delay) => {
+                        debug!(?blkid, ?delay, "checking block from the future again later");
+                    }
+                    None => {
+                        warn!(
+                            ?blkid,
+                            "block still from the future after too many checks, leaving it unverified"
+                        );
+                    }
+                }
+                return Ok(NewBlockCheck::Deferred);
+            }
+            Err(_) => return Ok(NewBlockCheck::Invalid),
+        }
+    }
+
+    Ok(NewBlockCheck::Plausible)
+}
+
+/// Checks that a block's timestamp doesn't go back from its parent's and isn't
+/// more than `max_drift` ahead of `now`, all in milliseconds.
+fn check_block_timestamp(
+    blkid: &L2BlockId,
+    ts: u64,
+    parent_ts: u64,
+    now: u64,
+    max_drift: u64,
+) -> Result<(), Error> {
+    if ts < parent_ts {
+        warn!(?blkid, %ts, %parent_ts, "rejecting block from before its parent");
+        return Err(Error::BlockTimestampBeforeParent(*blkid, ts, parent_ts));
+    }
+
+    if ts > now.saturating_add(max_drift) {
+        warn!(?blkid, %ts, %now, "block is from the future");
+        return Err(Error::BlockTimestampTooFarAhead(*blkid, ts, now));
+    }
+
+    Ok(())
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("fcm: clock before unix epoch")
+        .as_millis() as u64
+}
+
+/// Submits the block's execution payload to the EL.  If the EL says the payload
+/// is invalid the full block is written as being invalid, and if it doesn't
+/// answer in time the block is queued to be checked again later.  Returns if
+/// the block is still worth considering.
+fn check_exec_payload<D: Database, E: ExecEngineCtl>(
+    blkid: &L2BlockId,
+    block_bundle: &L2BlockBundle,
+    fcm_state: &mut ForkChoiceManager<D>,
+    engine: &E,
+) -> anyhow::Result<bool> {
+    // TODO take implicit input produced by the CL STF and include that in the payload data
+    let exec_hash = block_bundle.header().exec_payload_hash();
+    let eng_payload = ExecPayloadData::from_l2_block_bundle(block_bundle);
+    debug!(?blkid, ?exec_hash, "submitting execution payload");
+    let timeout = Duration::from_millis(fcm_state.params.run().el_check_timeout);
+    let res = match engine.submit_payload_with_timeout(eng_payload, timeout) {
+        Ok(res) => res,
+        // A slow EL shouldn't hold up fork choice.  We leave the block
+        // unverified without marking it and check it again after a backoff.
+        Err(EngineError::Timeout(timeout)) => {
+            let retries = &mut fcm_state.el_check_retries;
+            match retries.schedule(*blkid, Instant::now()) {
+                Some(delay) => {
+                    warn!(
+                        ?blkid,
+                        ?timeout,
+                        ?delay,
+                        "EL check timed out, retrying later"
+                    );
+                }
+                None => {
+                    warn!(
+                        ?blkid,
+                        ?timeout,
+                        "EL check timed out too many times, leaving block unverified"
+                    );
+                }
+            }
+            return Ok(false);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    fcm_state.el_check_retries.clear(blkid);
+
+    // If the payload is invalid then we should write the full block as
+    // being invalid and return too.
+    // TODO verify this is reasonable behavior, especially with regard
+    // to pre-sync
+    if res == strata_eectl::engine::BlockStatus::Invalid {
+        fcm_state.set_block_status(blkid, BlockStatus::Invalid)?;
         return Ok(false);
     }
 
@@ -582,3 +833,246 @@ fn apply_tip_update<D: Database>(
 
     Ok(pre_state)
 }
+
+#[cfg(test)]
+mod tests {
+
+    use strata_db::traits::L2BlockDatabase;
+    use strata_eectl::stub::{StubController, StubFault};
+    use strata_primitives::buf::{Buf32, Buf64};
+    use strata_rocksdb::test_utils::get_common_db;
+    use strata_test_utils::l2::{gen_client_state, gen_l2_chain, gen_params};
+
+    use super::*;
+
+    fn setup_fcm() -> (ForkChoiceManager<impl Database>, Vec<L2BlockBundle>) {
+        let params = Arc::new(gen_params());
+        let database = get_common_db();
+        let chain = gen_l2_chain(None, 3);
+        for block in &chain {
+            database.l2_db().put_block_data(block.clone()).unwrap();
+        }
+
+        let pool = threadpool::ThreadPool::new(1);
+        let l2_block_manager = Arc::new(L2BlockManager::new(pool, database.clone()));
+        let genesis = chain[0].header().get_blockid();
+        let fcm = ForkChoiceManager::new(
+            params.clone(),
+            database,
+            l2_block_manager,
+            Arc::new(gen_client_state(Some(&params))),
+            UnfinalizedBlockTracker::new_empty(genesis),
+            genesis,
+            0,
+        );
+
+        (fcm, chain)
+    }
+
+    fn update_idx(block: &L2BlockBundle) -> u64 {
+        block.block().exec_segment().update().input().update_idx()
+    }
+
+    #[test]
+    fn test_el_rejects_block() {
+        let (mut fcm, chain) = setup_fcm();
+        let bad = &chain[2];
+        let engine =
+            StubController::new(Duration::ZERO).with_fault(update_idx(bad), StubFault::Invalid);
+
+        let bad_blkid = bad.header().get_blockid();
+        assert!(!check_exec_payload(&bad_blkid, bad, &mut fcm, &engine).unwrap());
+        assert_eq!(
+            fcm.get_block_status(&bad_blkid).unwrap(),
+            Some(BlockStatus::Invalid)
+        );
+
+        // the rest of the chain is left alone
+        for block in chain.iter().filter(|b| update_idx(b) != update_idx(bad)) {
+            let blkid = block.header().get_blockid();
+            assert!(check_exec_payload(&blkid, block, &mut fcm, &engine).unwrap());
+            assert_eq!(fcm.get_block_status(&blkid).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_el_wrong_state_root() {
+        let (mut fcm, chain) = setup_fcm();
+        let block = &chain[1];
+        let blkid = block.header().get_blockid();
+        let claimed = *block.block().exec_segment().update().output().new_state();
+
+        let honest = StubController::new(Duration::ZERO)
+            .with_fault(update_idx(block), StubFault::WrongStateRoot(claimed));
+        assert!(check_exec_payload(&blkid, block, &mut fcm, &honest).unwrap());
+
+        let wrong_root = Buf32::from([0xaa; 32]);
+        assert_ne!(wrong_root, claimed);
+        let engine = StubController::new(Duration::ZERO)
+            .with_fault(update_idx(block), StubFault::WrongStateRoot(wrong_root));
+        assert!(!check_exec_payload(&blkid, block, &mut fcm, &engine).unwrap());
+        assert_eq!(
+            fcm.get_block_status(&blkid).unwrap(),
+            Some(BlockStatus::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_el_failure_does_not_mark_block() {
+        let (mut fcm, chain) = setup_fcm();
+        let block = &chain[1];
+        let blkid = block.header().get_blockid();
+
+        // an unresponsive EL is an error, not a verdict on the block
+        for fault in [
+            StubFault::Error,
+            StubFault::Timeout(Duration::from_millis(10)),
+        ] {
+            let engine = StubController::new(Duration::ZERO).with_fault(update_idx(block), fault);
+            assert!(check_exec_payload(&blkid, block, &mut fcm, &engine).is_err());
+            assert_eq!(fcm.get_block_status(&blkid).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_el_check_timeout() {
+        let (mut fcm, chain) = setup_fcm();
+        let mut params = fcm.params.as_ref().clone();
+        params.run.el_check_timeout = 20;
+        fcm.params = Arc::new(params);
+
+        let block = &chain[1];
+        let blkid = block.header().get_blockid();
+        let engine = StubController::new(Duration::ZERO).with_fault(
+            update_idx(block),
+            StubFault::Timeout(Duration::from_secs(10)),
+        );
+
+        // a hanging EL check is given up on, leaving the block unverified
+        // rather than failing fork choice, and queued to be checked again
+        let start = Instant::now();
+        assert!(!check_exec_payload(&blkid, block, &mut fcm, &engine).unwrap());
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(fcm.get_block_status(&blkid).unwrap(), None);
+        assert!(fcm.el_check_retries.next_due().is_some());
+
+        // once the EL answers the block is no longer waiting
+        let engine = StubController::new(Duration::ZERO);
+        assert!(check_exec_payload(&blkid, block, &mut fcm, &engine).unwrap());
+        assert_eq!(fcm.el_check_retries.next_due(), None);
+
+        // other blocks are still checked as usual
+        let other = &chain[2];
+        let other_blkid = other.header().get_blockid();
+        assert!(check_exec_payload(&other_blkid, other, &mut fcm, &engine).unwrap());
+    }
+
+    #[test]
+    fn test_el_check_retry_backoff() {
+        let mut retries = ElCheckRetries::default();
+        let blkid = L2BlockId::from(Buf32::from([1; 32]));
+        let now = Instant::now();
+
+        assert_eq!(retries.next_due(), None);
+        assert_eq!(
+            retries.schedule(blkid, now),
+            Some(EL_CHECK_RETRY_BASE_DELAY)
+        );
+        assert_eq!(retries.next_due(), Some(now + EL_CHECK_RETRY_BASE_DELAY));
+
+        // not handed out before it's due, and only once after
+        assert!(retries.take_due(now).is_empty());
+        let later = now + EL_CHECK_RETRY_BASE_DELAY;
+        assert_eq!(retries.take_due(later), vec![blkid]);
+        assert!(retries.take_due(later).is_empty());
+        assert_eq!(retries.next_due(), None);
+
+        // further timeouts back off more, up to the limit
+        assert_eq!(
+            retries.schedule(blkid, now),
+            Some(EL_CHECK_RETRY_BASE_DELAY * 2)
+        );
+        for _ in 2..MAX_EL_CHECK_RETRIES {
+            let delay = retries.schedule(blkid, now).unwrap();
+            assert!(delay <= EL_CHECK_RETRY_MAX_DELAY);
+        }
+        assert_eq!(retries.schedule(blkid, now), None);
+        assert_eq!(retries.next_due(), None);
+
+        // a verdict starts it over
+        assert!(retries.schedule(blkid, now).is_some());
+        retries.clear(&blkid);
+        assert_eq!(retries.next_due(), None);
+    }
+
+    /// Makes a new child of the block with the given timestamp.
+    fn make_child_with_ts(parent: &L2BlockBundle, ts: u64) -> L2BlockBundle {
+        let child = gen_l2_chain(Some(parent.header().clone()), 1).remove(0);
+        let header = L2BlockHeader::new(
+            child.header().blockidx(),
+            ts,
+            parent.header().get_blockid(),
+            child.body(),
+            *child.header().state_root(),
+        );
+        let block = L2Block::new(
+            SignedL2BlockHeader::new(header, Buf64::zero()),
+            child.body().clone(),
+        );
+        L2BlockBundle::new(block, child.accessory().clone())
+    }
+
+    #[test]
+    fn test_block_timestamp() {
+        let (mut fcm, chain) = setup_fcm();
+        let max_drift = fcm.params.run().max_block_time_drift;
+        let cstate = fcm.cur_csm_state.clone();
+
+        // a2 - {backward, far_future, valid}
+        let parent_ts = chain[2].header().timestamp();
+        let backward = make_child_with_ts(&chain[2], parent_ts - 1);
+        let far_future = make_child_with_ts(&chain[2], now_millis() + max_drift + 60_000);
+        let valid = make_child_with_ts(&chain[2], parent_ts + fcm.params.rollup().block_time);
+        for block in [&backward, &far_future, &valid] {
+            fcm.database.l2_db().put_block_data(block.clone()).unwrap();
+        }
+
+        let backward_blkid = backward.header().get_blockid();
+        assert_eq!(
+            check_new_block(&backward_blkid, &backward, &cstate, &mut fcm).unwrap(),
+            NewBlockCheck::Invalid
+        );
+        assert_eq!(fcm.el_check_retries.next_due(), None);
+
+        // a block from the future isn't rejected, only checked again later
+        let far_future_blkid = far_future.header().get_blockid();
+        assert_eq!(
+            check_new_block(&far_future_blkid, &far_future, &cstate, &mut fcm).unwrap(),
+            NewBlockCheck::Deferred
+        );
+        assert!(fcm.el_check_retries.next_due().is_some());
+        let due = fcm
+            .el_check_retries
+            .take_due(Instant::now() + EL_CHECK_RETRY_MAX_DELAY);
+        assert_eq!(due, vec![far_future_blkid]);
+
+        let valid_blkid = valid.header().get_blockid();
+        assert_eq!(
+            check_new_block(&valid_blkid, &valid, &cstate, &mut fcm).unwrap(),
+            NewBlockCheck::Plausible
+        );
+
+        // the bounds themselves are fine
+        let now = 1_000_000;
+        check_block_timestamp(&valid_blkid, now, now, now, max_drift).unwrap();
+        check_block_timestamp(&valid_blkid, now + max_drift, now, now, max_drift).unwrap();
+        assert!(matches!(
+            check_block_timestamp(&valid_blkid, now - 1, now, now, max_drift),
+            Err(Error::BlockTimestampBeforeParent(..))
+        ));
+        assert!(matches!(
+            check_block_timestamp(&valid_blkid, now + max_drift + 1, now, now, max_drift),
+            Err(Error::BlockTimestampTooFarAhead(..))
+        ));
+    }
+}
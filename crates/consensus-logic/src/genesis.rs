@@ -51,6 +51,8 @@ pub fn init_genesis_chainstate(
 ) -> anyhow::Result<Chainstate> {
     debug!("preparing database genesis chainstate!");
 
+    params.validate()?;
+
     let horizon_blk_height = params.rollup.horizon_l1_height;
     let genesis_blk_height = params.rollup.genesis_l1_height;
 
@@ -163,17 +165,9 @@ pub fn make_genesis_chainstate(
 pub fn check_needs_client_init(database: &impl Database) -> anyhow::Result<bool> {
     let cs_db = database.client_state_db();
 
-    // Check if we've written any genesis state checkpoint.  These we perform a
-    // bit more carefully and check errors more granularly.
-    match cs_db.get_last_checkpoint_idx() {
-        Ok(_) => {}
-        Err(DbError::NotBootstrapped) => return Ok(true),
-
-        // TODO should we return an error here or skip?
-        Err(e) => return Err(e.into()),
-    }
-
-    Ok(false)
+    // Check if we've written any genesis state checkpoint.
+    // TODO should we return an error here or skip?
+    Ok(cs_db.get_last_checkpoint_idx_opt()?.is_none())
 }
 
 pub fn check_needs_genesis(database: &impl Database) -> anyhow::Result<bool> {
@@ -17,14 +17,17 @@ use strata_primitives::{
 use strata_risc0_adapter;
 use strata_sp1_adapter;
 use strata_state::{
-    batch::BatchCheckpoint, l1::L1Tx, sync_event::SyncEvent, tx::ProtocolOperation,
+    batch::BatchCheckpoint,
+    l1::L1Tx,
+    sync_event::SyncEvent,
+    tx::{CheckpointCommitment, ProtocolOperation},
 };
 use strata_tx_parser::messages::{BlockData, L1Event};
 use strata_zkvm::ZkVmResult;
 use tokio::sync::mpsc;
 use tracing::*;
 
-use crate::csm::ctl::CsmController;
+use crate::{csm::ctl::CsmController, errors::SubmitError};
 
 /// Consumes L1 events and reflects them in the database.
 pub fn bitcoin_data_handler_task<D: Database + Send + Sync + 'static>(
@@ -75,7 +78,7 @@ where
 
             // Write to sync event db.
             let ev = SyncEvent::L1Revert(revert_blk_num);
-            csm_ctl.submit_event(ev)?;
+            submit_event(csm_ctl, ev)?;
 
             Ok(())
         }
@@ -91,7 +94,6 @@ where
             }
 
             let l1blkid = blockdata.block().block_hash();
-
             let manifest = generate_block_manifest(blockdata.block(), epoch);
             let l1txs: Vec<_> = generate_l1txs(&blockdata);
             let num_txs = l1txs.len();
@@ -101,7 +103,17 @@ where
             // Write to sync event db if it's something we care about.
             let blkid: Buf32 = blockdata.block().block_hash().into();
             let ev = SyncEvent::L1Block(blockdata.block_num(), blkid.into());
-            csm_ctl.submit_event(ev)?;
+            match submit_event(csm_ctl, ev) {
+                Ok(()) => {}
+                // The reader re-scans blocks it already sent us if it restarted in the middle of
+                // a scan.  Writing the block data again is harmless, but the sync events were
+                // already submitted for it.
+                Err(SubmitError::OutOfOrderL1Event(_, last)) => {
+                    debug!(%height, %l1blkid, %last, "ignoring already submitted L1 block");
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
+            }
 
             // Check for da batch and send event accordingly
             debug!(?height, "Checking for da batch");
@@ -109,7 +121,14 @@ where
             debug!(?checkpoints, "Received checkpoints");
             if !checkpoints.is_empty() {
                 let ev = SyncEvent::L1DABatch(height, checkpoints);
-                csm_ctl.submit_event(ev)?;
+                submit_event(csm_ctl, ev)?;
+            }
+
+            // Bare commitments don't carry the checkpoint itself, so there's nothing the CSM could
+            // verify yet, but note them so they can be matched up with the DA batches.
+            for commitment in check_for_checkpoint_commitments(&blockdata) {
+                let CheckpointCommitment { idx, state_root } = commitment;
+                info!(%height, %idx, %state_root, "found checkpoint commitment");
             }
 
             // TODO: Check for deposits and forced inclusions and emit appropriate events
@@ -119,9 +138,22 @@ where
 
         L1Event::GenesisVerificationState(height, header_verification_state) => {
             let ev = SyncEvent::L1BlockGenesis(height, header_verification_state);
-            csm_ctl.submit_event(ev)?;
+            submit_event(csm_ctl, ev)?;
+            Ok(())
+        }
+    }
+}
+
+/// Submits the sync event to the CSM.  An unresponsive worker isn't an error
+/// here, the event is already stored and the worker picks it up later, so we
+/// go on with the events that come after it.
+fn submit_event(csm_ctl: &CsmController, ev: SyncEvent) -> Result<(), SubmitError> {
+    match csm_ctl.submit_event(ev) {
+        Err(SubmitError::WorkerUnresponsive(idx, timeout)) => {
+            warn!(%idx, ?timeout, "CSM worker unresponsive, continuing with stored event");
             Ok(())
         }
+        res => res,
     }
 }
 
@@ -159,6 +191,18 @@ fn check_for_da_batch(
     sig_verified_checkpoints.collect()
 }
 
+/// Returns the checkpoint commitments found in the transactions
+fn check_for_checkpoint_commitments(blockdata: &BlockData) -> Vec<CheckpointCommitment> {
+    blockdata
+        .protocol_ops_txs()
+        .iter()
+        .filter_map(|ops_txs| match ops_txs.proto_op() {
+            ProtocolOperation::CheckpointCommitment(commitment) => Some(commitment.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Verify that the provided checkpoint proof is valid for the verifier key.
 ///
 /// # Caution
@@ -319,3 +363,137 @@ fn get_cohashes_from_wtxids(wtxids: &[Wtxid], index: u32) -> (Vec<Buf32>, Buf32)
     }
     (proof, curr_level[0].into())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use strata_db::traits::SyncEventDatabase;
+    use strata_primitives::l1::L1Status;
+    use strata_rocksdb::test_utils::get_common_db;
+    use strata_state::batch::SignedBatchCheckpoint;
+    use strata_status::StatusChannel;
+    use strata_test_utils::{
+        bitcoin::get_btc_mainnet_block,
+        l2::{gen_client_state, gen_params},
+        ArbitraryGenerator,
+    };
+    use strata_tx_parser::messages::ProtocolOpTxRef;
+
+    use super::*;
+    use crate::csm::message::CsmMessage;
+
+    #[test]
+    fn test_rescanned_block_not_resubmitted() {
+        let database = get_common_db();
+        let pool = threadpool::ThreadPool::new(1);
+        let (csm_tx, _csm_rx) = mpsc::channel(16);
+        let status_channel = StatusChannel::new(gen_client_state(None), L1Status::default(), None);
+        let csm_ctl = CsmController::new(database.clone(), pool, csm_tx, status_channel).unwrap();
+        let params = Arc::new(gen_params());
+        let l1db = database.l1_db();
+
+        let height = params.rollup().horizon_l1_height;
+        let block = get_btc_mainnet_block();
+        let blkid: Buf32 = block.block_hash().into();
+
+        // the reader sending the same block again, like after a restart
+        for _ in 0..3 {
+            let ev = L1Event::BlockData(BlockData::new(height, block.clone(), vec![]), 0);
+            handle_bitcoin_event(ev, l1db.as_ref(), &csm_ctl, &params, None).unwrap();
+        }
+
+        let sync_ev_db = database.sync_event_db();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(1));
+        assert_eq!(
+            sync_ev_db.get_sync_event(1).unwrap(),
+            Some(SyncEvent::L1Block(height, blkid.into()))
+        );
+        assert_eq!(l1db.get_chain_tip().unwrap(), Some(height));
+    }
+
+    #[test]
+    fn test_stored_block_without_event_submitted() {
+        let database = get_common_db();
+        let (csm_tx, _csm_rx) = mpsc::channel(16);
+        let params = Arc::new(gen_params());
+        let l1db = database.l1_db();
+
+        let height = params.rollup().horizon_l1_height;
+        let block = get_btc_mainnet_block();
+        let blkid: Buf32 = block.block_hash().into();
+
+        // stopped after the block was written but before its event was submitted
+        let manifest = generate_block_manifest(&block, 0);
+        l1db.put_block_data(height, manifest, vec![]).unwrap();
+
+        let status_channel = StatusChannel::new(gen_client_state(None), L1Status::default(), None);
+        let csm_ctl = CsmController::new(
+            database.clone(),
+            threadpool::ThreadPool::new(1),
+            csm_tx.clone(),
+            status_channel,
+        )
+        .unwrap();
+        let ev = L1Event::BlockData(BlockData::new(height, block.clone(), vec![]), 0);
+        handle_bitcoin_event(ev, l1db.as_ref(), &csm_ctl, &params, None).unwrap();
+
+        let sync_ev_db = database.sync_event_db();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(1));
+        assert_eq!(
+            sync_ev_db.get_sync_event(1).unwrap(),
+            Some(SyncEvent::L1Block(height, blkid.into()))
+        );
+
+        // after a restart the block is still known to be submitted
+        let status_channel = StatusChannel::new(gen_client_state(None), L1Status::default(), None);
+        let csm_ctl = CsmController::new(
+            database.clone(),
+            threadpool::ThreadPool::new(1),
+            csm_tx,
+            status_channel,
+        )
+        .unwrap();
+        let ev = L1Event::BlockData(BlockData::new(height, block, vec![]), 0);
+        handle_bitcoin_event(ev, l1db.as_ref(), &csm_ctl, &params, None).unwrap();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_da_batch_submitted_with_unresponsive_worker() {
+        let database = get_common_db();
+        let params = Arc::new(gen_params());
+        let l1db = database.l1_db();
+
+        // a channel that's already full and never drained
+        let (csm_tx, _csm_rx) = mpsc::channel(1);
+        csm_tx.try_send(CsmMessage::EventInput(0)).unwrap();
+        let status_channel = StatusChannel::new(gen_client_state(None), L1Status::default(), None);
+        let csm_ctl = CsmController::new(
+            database.clone(),
+            threadpool::ThreadPool::new(1),
+            csm_tx,
+            status_channel,
+        )
+        .unwrap()
+        .with_submit_timeout(Duration::from_millis(10));
+
+        let height = params.rollup().horizon_l1_height;
+        let block = get_btc_mainnet_block();
+        let checkpoint: SignedBatchCheckpoint = ArbitraryGenerator::new().generate();
+        let ops = vec![ProtocolOpTxRef::new(
+            1,
+            ProtocolOperation::Checkpoint(checkpoint.clone()),
+        )];
+        let ev = L1Event::BlockData(BlockData::new(height, block, ops), 0);
+        handle_bitcoin_event(ev, l1db.as_ref(), &csm_ctl, &params, None).unwrap();
+
+        // both events are stored for the worker to pick up
+        let sync_ev_db = database.sync_event_db();
+        assert_eq!(sync_ev_db.get_last_idx().unwrap(), Some(2));
+        assert_eq!(
+            sync_ev_db.get_sync_event(2).unwrap(),
+            Some(SyncEvent::L1DABatch(height, vec![checkpoint.into()]))
+        );
+    }
+}
@@ -12,3 +12,6 @@ pub mod sync_manager;
 pub mod unfinalized_tracker;
 
 pub mod errors;
+
+#[cfg(test)]
+mod pipeline_tests;
@@ -0,0 +1,291 @@
+//! End to end tests of the L1 sync pipeline, running the reader against a mock
+//! bitcoin node and following its blocks through the L1 handler into the CSM
+//! worker.
+
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use bitcoin::BlockHash;
+use strata_btcio::{
+    reader::{
+        config::ReaderConfig, progress::ReaderProgressTracker, query::bitcoin_data_reader_task,
+    },
+    rpc::traits::Reader,
+    test_utils::MockL1Client,
+};
+use strata_db::traits::*;
+use strata_eectl::stub::StubController;
+use strata_primitives::{buf::Buf32, l1::L1Status, params::Params};
+use strata_rocksdb::test_utils::get_common_db;
+use strata_state::{
+    client_state::LocalL1State, header::L2Header, l1::L1BlockId, sync_event::SyncEvent,
+};
+use strata_status::StatusChannel;
+use strata_storage::{managers::checkpoint::CheckpointDbManager, L2BlockManager};
+use strata_tasks::TaskManager;
+use strata_test_utils::l2::{gen_client_state, gen_params};
+use strata_tx_parser::{filter::default_tx_filters, messages::L1Event};
+use tokio::{
+    runtime::Runtime,
+    sync::{broadcast, mpsc},
+};
+
+use crate::{
+    csm::{
+        ctl::CsmController,
+        message::{ClientUpdateNotif, CsmMessage},
+        metrics::EventTimings,
+        state_tracker::ClientStateCache,
+        worker,
+    },
+    genesis,
+    l1_handler::bitcoin_data_handler_task,
+};
+
+const HORIZON_HEIGHT: u64 = 2;
+const GENESIS_HEIGHT: u64 = 4;
+
+/// How long we give the pipeline to catch up with the node.
+const CATCH_UP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Reader, L1 handler and CSM worker tasks wired together like the client does
+/// it, reading from a mock node the test mines blocks on.
+struct L1PipelineHarness<D> {
+    client: Arc<MockL1Client>,
+    database: Arc<D>,
+    params: Arc<Params>,
+    status_channel: StatusChannel,
+
+    /// Dropping this stops the reader, after which the other tasks stop once
+    /// their channels close.
+    runtime: Runtime,
+}
+
+impl<D: Database + Send + Sync + 'static> L1PipelineHarness<D> {
+    fn start(database: Arc<D>) -> Self {
+        let mut params = gen_params();
+        params.rollup.horizon_l1_height = HORIZON_HEIGHT;
+        params.rollup.genesis_l1_height = GENESIS_HEIGHT;
+        let params = Arc::new(params);
+
+        genesis::init_client_state(&params, database.as_ref()).unwrap();
+        let status_channel =
+            StatusChannel::new(gen_client_state(Some(&params)), L1Status::default(), None);
+        let client = Arc::new(MockL1Client::new());
+
+        let runtime = Runtime::new().unwrap();
+        let task_manager = TaskManager::new(runtime.handle().clone());
+        let executor = task_manager.executor();
+        let pool = threadpool::ThreadPool::new(1);
+
+        // Same as `start_sync_tasks`, minus the fork choice manager.
+        let (csm_tx, csm_rx) = mpsc::channel::<CsmMessage>(64);
+        let csm_ctl = Arc::new(
+            CsmController::new(
+                database.clone(),
+                pool.clone(),
+                csm_tx,
+                status_channel.clone(),
+            )
+            .unwrap(),
+        );
+        let (cupdate_tx, _) = broadcast::channel::<Arc<ClientUpdateNotif>>(64);
+        let worker_state = worker::WorkerState::open(
+            params.clone(),
+            database.clone(),
+            Arc::new(L2BlockManager::new(pool.clone(), database.clone())),
+            cupdate_tx,
+            Arc::new(CheckpointDbManager::new(pool, database.clone())),
+            Arc::new(ClientStateCache::new()),
+            Arc::new(EventTimings::new(Duration::from_secs(1))),
+        )
+        .unwrap();
+        let engine = Arc::new(StubController::new(Duration::ZERO));
+        let st_ch = status_channel.clone();
+        executor.spawn_critical("client_worker_task", move |shutdown| {
+            worker::client_worker_task(shutdown, worker_state, engine, csm_rx, st_ch)
+                .map_err(Into::into)
+        });
+
+        // Same as `start_reader_tasks`.
+        let (ev_tx, ev_rx) = mpsc::channel::<L1Event>(100);
+        let reader_config = Arc::new(ReaderConfig::new(4, 10, params.clone()));
+        executor.spawn_critical_async(
+            "bitcoin_data_reader_task",
+            bitcoin_data_reader_task(
+                client.clone(),
+                ev_tx,
+                HORIZON_HEIGHT,
+                reader_config,
+                status_channel.clone(),
+                default_tx_filters(),
+                Arc::new(ReaderProgressTracker::new()),
+            ),
+        );
+
+        let l1db = database.l1_db().clone();
+        let handler_params = params.clone();
+        executor.spawn_critical("bitcoin_data_handler_task", move |_| {
+            bitcoin_data_handler_task::<D>(l1db, csm_ctl, ev_rx, handler_params)
+        });
+
+        Self {
+            client,
+            database,
+            params,
+            status_channel,
+            runtime,
+        }
+    }
+
+    /// Returns the hash of the node's block at the height.
+    fn node_block_hash(&self, height: u64) -> BlockHash {
+        self.runtime
+            .block_on(self.client.get_block_hash(height))
+            .unwrap()
+    }
+
+    /// Waits until the CSM's view of L1 satisfies the predicate.
+    ///
+    /// # Panics
+    ///
+    /// If it doesn't before the catch up timeout.
+    fn wait_for_l1_view(&self, what: &str, pred: impl Fn(&LocalL1State) -> bool) {
+        let deadline = Instant::now() + CATCH_UP_TIMEOUT;
+        while !pred(&self.status_channel.l1_view()) {
+            assert!(Instant::now() < deadline, "timed out waiting for {what}");
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// Waits until the CSM accepted the node's block at the height as its L1
+    /// tip.
+    fn wait_for_l1_tip(&self, height: u64) {
+        let blkid = L1BlockId::from(self.node_block_hash(height));
+        self.wait_for_l1_view(&format!("L1 tip {height}"), |l1v| {
+            l1v.tip_height() == height && l1v.tip_blkid() == Some(&blkid)
+        });
+    }
+
+    /// Checks the L1 blocks stored up to the height are the ones of the node.
+    fn assert_l1_db_matches_node(&self, tip_height: u64) {
+        let l1_db = self.database.l1_db();
+        assert_eq!(l1_db.get_chain_tip().unwrap(), Some(tip_height));
+        for height in HORIZON_HEIGHT..=tip_height {
+            let mf = l1_db.get_block_manifest(height).unwrap().unwrap();
+            assert_eq!(
+                mf.block_hash(),
+                Buf32::from(self.node_block_hash(height)),
+                "wrong block stored at {height}"
+            );
+        }
+    }
+
+    /// Returns all the sync events written so far, in order.
+    fn sync_events(&self) -> Vec<SyncEvent> {
+        let sync_ev_db = self.database.sync_event_db();
+        let last_idx = sync_ev_db.get_last_idx().unwrap().unwrap_or(0);
+        (1..=last_idx)
+            .map(|idx| sync_ev_db.get_sync_event(idx).unwrap().unwrap())
+            .collect()
+    }
+}
+
+#[test]
+fn test_l1_blocks_advance_consensus_state() {
+    let harness = L1PipelineHarness::start(get_common_db());
+
+    // enough blocks for the reader to trigger genesis
+    harness.client.mine_blocks(GENESIS_HEIGHT + 4);
+    let tip_height = GENESIS_HEIGHT + 4;
+    harness.wait_for_l1_tip(tip_height);
+    harness.assert_l1_db_matches_node(tip_height);
+
+    // the L1 blocks made the chain go through genesis
+    let gblkid = genesis::make_genesis_block(&harness.params)
+        .header()
+        .get_blockid();
+    let ss = harness
+        .status_channel
+        .sync_state()
+        .expect("genesis not done");
+    assert_eq!(ss.chain_tip_blkid(), &gblkid);
+    assert!(harness.status_channel.chain_state().is_some());
+
+    let l1v = harness.status_channel.l1_view();
+    assert_eq!(l1v.next_expected_block(), tip_height + 1);
+    let vs = l1v.tip_verification_state().unwrap();
+    assert!(vs.last_verified_block_num as u64 >= GENESIS_HEIGHT);
+
+    // new blocks keep getting picked up
+    harness.client.mine_blocks(2);
+    harness.wait_for_l1_tip(tip_height + 2);
+    harness.assert_l1_db_matches_node(tip_height + 2);
+
+    let events = harness.sync_events();
+    let l1_block_heights: Vec<_> = events
+        .iter()
+        .filter_map(|ev| match ev {
+            SyncEvent::L1Block(height, _) => Some(*height),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        l1_block_heights,
+        (HORIZON_HEIGHT..=tip_height + 2).collect::<Vec<_>>()
+    );
+    let num_genesis = events
+        .iter()
+        .filter(|ev| matches!(ev, SyncEvent::L1BlockGenesis(..)))
+        .count();
+    assert_eq!(num_genesis, 1);
+}
+
+#[test]
+fn test_l1_reorg() {
+    let harness = L1PipelineHarness::start(get_common_db());
+
+    let tip_height = GENESIS_HEIGHT + 4;
+    harness.client.mine_blocks(tip_height);
+    harness.wait_for_l1_tip(tip_height);
+    let old_tip = harness.node_block_hash(tip_height);
+
+    // replace the top 2 blocks with a longer fork
+    let pivot_height = tip_height - 2;
+    harness.client.disconnect_blocks(2);
+    harness.client.mine_blocks(3);
+    let new_tip_height = tip_height + 1;
+    assert_ne!(harness.node_block_hash(tip_height), old_tip);
+
+    harness.wait_for_l1_tip(new_tip_height);
+    harness.assert_l1_db_matches_node(new_tip_height);
+
+    // the CSM only kept the blocks below the fork
+    let l1v = harness.status_channel.l1_view();
+    assert_eq!(l1v.next_expected_block(), new_tip_height + 1);
+    assert_eq!(
+        l1v.local_unaccepted_blocks().len() as u64,
+        new_tip_height - HORIZON_HEIGHT + 1
+    );
+    let fork_blkids: Vec<_> = (pivot_height + 1..=new_tip_height)
+        .map(|height| L1BlockId::from(harness.node_block_hash(height)))
+        .collect();
+    let num_fork_blocks = fork_blkids.len();
+    assert_eq!(
+        &l1v.local_unaccepted_blocks()[l1v.local_unaccepted_blocks().len() - num_fork_blocks..],
+        fork_blkids.as_slice()
+    );
+
+    // the reader told the CSM where the fork started
+    let events = harness.sync_events();
+    let revert_pos = events
+        .iter()
+        .position(|ev| *ev == SyncEvent::L1Revert(pivot_height))
+        .expect("no revert event");
+    assert!(events[revert_pos + 1..]
+        .iter()
+        .any(|ev| *ev == SyncEvent::L1Block(pivot_height + 1, fork_blkids[0])));
+}
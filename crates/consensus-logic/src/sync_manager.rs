@@ -2,7 +2,7 @@
 //! status.  Exposes handles to interact with fork choice manager and CSM
 //! executor and other core sync pipeline tasks.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use strata_db::traits::Database;
 use strata_eectl::engine::ExecEngineCtl;
@@ -12,16 +12,19 @@ use strata_storage::{managers::checkpoint::CheckpointDbManager, L2BlockManager};
 use strata_tasks::TaskExecutor;
 use tokio::{
     runtime::Runtime,
-    sync::{broadcast, mpsc},
+    sync::{broadcast, mpsc, oneshot},
 };
 
 use crate::{
     csm::{
         ctl::CsmController,
         message::{ClientUpdateNotif, CsmMessage, ForkChoiceMessage},
+        metrics::EventTimings,
+        state_tracker::ClientStateCache,
         worker,
     },
     fork_choice_manager,
+    unfinalized_tracker::TreeDump,
 };
 
 /// Handle to the core pipeline tasks.
@@ -31,6 +34,8 @@ pub struct SyncManager {
     csm_controller: Arc<CsmController>,
     cupdate_rx: broadcast::Receiver<Arc<ClientUpdateNotif>>,
     status_channel: StatusChannel,
+    event_timings: Arc<EventTimings>,
+    client_state_cache: Arc<ClientStateCache>,
 }
 
 impl SyncManager {
@@ -63,6 +68,16 @@ impl SyncManager {
         &self.status_channel
     }
 
+    /// Gets a ref to the processing time stats of the CSM's sync events.
+    pub fn event_timings(&self) -> &EventTimings {
+        &self.event_timings
+    }
+
+    /// Gets a clone of the cache of the CSM's recently computed client states.
+    pub fn get_client_state_cache(&self) -> Arc<ClientStateCache> {
+        self.client_state_cache.clone()
+    }
+
     /// Submits a fork choice message if possible. (synchronously)
     pub fn submit_chain_tip_msg(&self, ctm: ForkChoiceMessage) -> bool {
         self.fc_manager_tx.blocking_send(ctm).is_ok()
@@ -72,6 +87,18 @@ impl SyncManager {
     pub async fn submit_chain_tip_msg_async(&self, ctm: ForkChoiceMessage) -> bool {
         self.fc_manager_tx.send(ctm).await.is_ok()
     }
+
+    /// Asks the fork choice manager for a snapshot of its unfinalized block
+    /// tree.  Returns `None` if it's not running.  It only starts answering
+    /// after genesis.
+    pub async fn get_unfinalized_tree(&self) -> Option<TreeDump> {
+        let (resp_tx, resp_rx) = oneshot::channel();
+        let msg = ForkChoiceMessage::DumpUnfinalizedTree(resp_tx);
+        if !self.submit_chain_tip_msg_async(msg).await {
+            return None;
+        }
+        resp_rx.await.ok()
+    }
 }
 
 /// Starts the sync tasks using provided settings.
@@ -89,11 +116,17 @@ pub fn start_sync_tasks<
     params: Arc<Params>,
     status_channel: StatusChannel,
     checkpoint_manager: Arc<CheckpointDbManager>,
+    slow_event_threshold: Duration,
 ) -> anyhow::Result<SyncManager> {
     // Create channels.
     let (fcm_tx, fcm_rx) = mpsc::channel::<ForkChoiceMessage>(64);
     let (csm_tx, csm_rx) = mpsc::channel::<CsmMessage>(64);
-    let csm_controller = Arc::new(CsmController::new(database.clone(), pool, csm_tx));
+    let csm_controller = Arc::new(CsmController::new(
+        database.clone(),
+        pool,
+        csm_tx,
+        status_channel.clone(),
+    )?);
 
     // TODO should this be in an `Arc`?  it's already fairly compact so we might
     // not be benefitting from the reduced cloning
@@ -124,12 +157,16 @@ pub fn start_sync_tasks<
     });
 
     // Prepare the client worker state and start the thread for that.
+    let event_timings = Arc::new(EventTimings::new(slow_event_threshold));
+    let client_state_cache = Arc::new(ClientStateCache::new());
     let client_worker_state = worker::WorkerState::open(
         params.clone(),
         database,
         l2_block_manager,
         cupdate_tx,
         checkpoint_manager,
+        client_state_cache.clone(),
+        event_timings.clone(),
     )?;
 
     let csm_engine = engine.clone();
@@ -146,5 +183,7 @@ pub fn start_sync_tasks<
         csm_controller,
         cupdate_rx,
         status_channel,
+        event_timings,
+        client_state_cache,
     })
 }
@@ -78,6 +78,58 @@ impl UnfinalizedBlockTracker {
         self.unfinalized_tips.iter()
     }
 
+    /// Checks that switching from `cur_tip` to `new_tip` reverts no more than
+    /// `max_depth` blocks of the current chain, which is how far down their
+    /// common ancestor is.
+    pub fn check_reorg_depth(
+        &self,
+        cur_tip: &L2BlockId,
+        new_tip: &L2BlockId,
+        max_depth: u64,
+    ) -> Result<(), ChainTipError> {
+        let new_chain: HashSet<_> =
+            std::iter::successors(Some(new_tip), |id| self.get_parent(id)).collect();
+
+        let mut depth = 0;
+        let mut cur = cur_tip;
+        while !new_chain.contains(cur) {
+            cur = self
+                .get_parent(cur)
+                .ok_or(ChainTipError::MissingBlock(*cur))?;
+            depth += 1;
+        }
+
+        if depth > max_depth {
+            return Err(ChainTipError::ReorgTooDeep {
+                depth,
+                max: max_depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns a snapshot of the tree, for debugging.
+    pub fn dump_tree(&self) -> TreeDump {
+        // only what hangs off the finalized tip, the table can still have
+        // entries below it
+        let mut pending: Vec<_> = self
+            .get_all_descendants(&self.finalized_tip)
+            .into_iter()
+            .filter_map(|blkid| self.get_parent(&blkid).map(|parent| (blkid, *parent)))
+            .collect();
+        pending.sort_unstable();
+
+        let mut tips: Vec<_> = self.unfinalized_tips.iter().copied().collect();
+        tips.sort_unstable();
+
+        TreeDump {
+            finalized_tip: self.finalized_tip,
+            pending,
+            tips,
+        }
+    }
+
     /// Checks if the block is traceable all the way back to the finalized tip.
     fn sanity_check_parent_seq(&self, blkid: &L2BlockId) -> bool {
         if *blkid == self.finalized_tip {
@@ -303,6 +355,20 @@ impl UnfinalizedBlockTracker {
     }
 }
 
+/// Snapshot of the unfinalized block tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreeDump {
+    /// Base of the tree.
+    pub finalized_tip: L2BlockId,
+
+    /// Blocks on top of the finalized tip along with their parents, sorted by
+    /// blkid.
+    pub pending: Vec<(L2BlockId, L2BlockId)>,
+
+    /// Unfinalized chain tips, sorted.
+    pub tips: Vec<L2BlockId>,
+}
+
 /// Report of blocks that we finalized when finalizing a new tip and blocks that
 /// we've permanently rejected.
 #[derive(Clone, Debug)]
@@ -356,7 +422,7 @@ mod tests {
     use strata_storage::L2BlockManager;
     use strata_test_utils::l2::gen_l2_chain;
 
-    use crate::unfinalized_tracker;
+    use crate::{errors::ChainTipError, unfinalized_tracker};
 
     fn setup_test_chain(l2_db: &impl L2BlockDatabase) -> [L2BlockId; 7] {
         // Chain A: g -> a1 -> a2 -> a3
@@ -567,4 +633,75 @@ mod tests {
             &blk_manager,
         );
     }
+
+    #[test]
+    fn test_check_reorg_depth() {
+        let db = get_common_db();
+        let l2_db = db.l2_db();
+
+        let [g, _a1, c1, a2, _b2, a3, b3] = setup_test_chain(l2_db.as_ref());
+
+        let mut chain_tracker = unfinalized_tracker::UnfinalizedBlockTracker::new_empty(g);
+        let pool = threadpool::ThreadPool::new(1);
+        let blkman = L2BlockManager::new(pool, db);
+        chain_tracker
+            .load_unfinalized_blocks(0, 3, &blkman)
+            .unwrap();
+
+        // extending the current chain doesn't revert anything
+        chain_tracker.check_reorg_depth(&a2, &a3, 1).unwrap();
+        chain_tracker.check_reorg_depth(&a3, &a3, 1).unwrap();
+
+        // a3 -> b3 reverts a3 and a2
+        chain_tracker.check_reorg_depth(&a3, &b3, 2).unwrap();
+        let res = chain_tracker.check_reorg_depth(&a3, &b3, 1);
+        assert!(matches!(
+            res,
+            Err(ChainTipError::ReorgTooDeep { depth: 2, max: 1 })
+        ));
+
+        // a3 -> c1 goes all the way down to the finalized tip
+        chain_tracker.check_reorg_depth(&a3, &c1, 3).unwrap();
+        let res = chain_tracker.check_reorg_depth(&a3, &c1, 2);
+        assert!(matches!(
+            res,
+            Err(ChainTipError::ReorgTooDeep { depth: 3, max: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_dump_tree() {
+        let db = get_common_db();
+        let l2_db = db.l2_db();
+
+        let [g, a1, c1, a2, b2, a3, b3] = setup_test_chain(l2_db.as_ref());
+
+        let mut chain_tracker = unfinalized_tracker::UnfinalizedBlockTracker::new_empty(g);
+        let dump = chain_tracker.dump_tree();
+        assert_eq!(dump.finalized_tip, g);
+        assert!(dump.pending.is_empty());
+        assert_eq!(dump.tips, vec![g]);
+
+        let pool = threadpool::ThreadPool::new(1);
+        let blkman = L2BlockManager::new(pool, db);
+        chain_tracker
+            .load_unfinalized_blocks(0, 3, &blkman)
+            .unwrap();
+
+        let dump = chain_tracker.dump_tree();
+        assert_eq!(dump.finalized_tip, g);
+        let mut pending = vec![(a1, g), (c1, g), (a2, a1), (b2, a1), (a3, a2), (b3, b2)];
+        pending.sort_unstable();
+        assert_eq!(dump.pending, pending);
+        let mut tips = vec![c1, a3, b3];
+        tips.sort_unstable();
+        assert_eq!(dump.tips, tips);
+
+        // finalizing prunes the competing forks from it
+        chain_tracker.update_finalized_tip(&a2).unwrap();
+        let dump = chain_tracker.dump_tree();
+        assert_eq!(dump.finalized_tip, a2);
+        assert_eq!(dump.pending, vec![(a3, a2)]);
+        assert_eq!(dump.tips, vec![a3]);
+    }
 }
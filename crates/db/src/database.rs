@@ -5,14 +5,22 @@ use super::traits::*;
 /// Shim database type that assumes that all the database impls are wrapped in
 /// `Arc`s and that the provider and stores are actually the same types.  We
 /// might actually use this in practice, it's just for testing.
-pub struct CommonDatabase<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB>
-where
+pub struct CommonDatabase<
+    L1DB,
+    L2DB,
+    SyncEventDB,
+    ClientStateDB,
+    ChainstateDB,
+    CheckpointDB,
+    BatchDB,
+> where
     L1DB: L1Database + Sync + Send + 'static,
     L2DB: L2BlockDatabase + Sync + Send + 'static,
     SyncEventDB: SyncEventDatabase + Sync + Send + 'static,
     ClientStateDB: ClientStateDatabase + Sync + Send + 'static,
     ChainstateDB: ChainstateDatabase + Sync + Send + 'static,
     CheckpointDB: CheckpointDatabase + Sync + Send + 'static,
+    BatchDB: AtomicBatchDatabase + Sync + Send + 'static,
 {
     l1_db: Arc<L1DB>,
     l2_db: Arc<L2DB>,
@@ -20,10 +28,11 @@ where
     client_state_db: Arc<ClientStateDB>,
     chain_state_db: Arc<ChainstateDB>,
     checkpoint_db: Arc<CheckpointDB>,
+    batch_db: Arc<BatchDB>,
 }
 
-impl<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB>
-    CommonDatabase<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB>
+impl<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB, BatchDB>
+    CommonDatabase<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB, BatchDB>
 where
     L1DB: L1Database + Sync + Send + 'static,
     L2DB: L2BlockDatabase + Sync + Send + 'static,
@@ -31,6 +40,7 @@ where
     ClientStateDB: ClientStateDatabase + Sync + Send + 'static,
     ChainstateDB: ChainstateDatabase + Sync + Send + 'static,
     CheckpointDB: CheckpointDatabase + Sync + Send + 'static,
+    BatchDB: AtomicBatchDatabase + Sync + Send + 'static,
 {
     pub fn new(
         l1_db: Arc<L1DB>,
@@ -39,6 +49,7 @@ where
         client_state_db: Arc<ClientStateDB>,
         chain_state_db: Arc<ChainstateDB>,
         checkpoint_db: Arc<CheckpointDB>,
+        batch_db: Arc<BatchDB>,
     ) -> Self {
         Self {
             l1_db,
@@ -47,12 +58,13 @@ where
             client_state_db,
             chain_state_db,
             checkpoint_db,
+            batch_db,
         }
     }
 }
 
-impl<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB> Database
-    for CommonDatabase<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB>
+impl<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB, BatchDB> Database
+    for CommonDatabase<L1DB, L2DB, SyncEventDB, ClientStateDB, ChainstateDB, CheckpointDB, BatchDB>
 where
     L1DB: L1Database + Sync + Send + 'static,
     L2DB: L2BlockDatabase + Sync + Send + 'static,
@@ -60,6 +72,7 @@ where
     ClientStateDB: ClientStateDatabase + Sync + Send + 'static,
     ChainstateDB: ChainstateDatabase + Sync + Send + 'static,
     CheckpointDB: CheckpointDatabase + Sync + Send + 'static,
+    BatchDB: AtomicBatchDatabase + Sync + Send + 'static,
 {
     type L1DB = L1DB;
     type L2DB = L2DB;
@@ -67,6 +80,7 @@ where
     type ClientStateDB = ClientStateDB;
     type ChainstateDB = ChainstateDB;
     type CheckpointDB = CheckpointDB;
+    type BatchDB = BatchDB;
 
     fn l1_db(&self) -> &Arc<Self::L1DB> {
         &self.l1_db
@@ -91,4 +105,8 @@ where
     fn checkpoint_db(&self) -> &Arc<Self::CheckpointDB> {
         &self.checkpoint_db
     }
+
+    fn batch_db(&self) -> &Arc<Self::BatchDB> {
+        &self.batch_db
+    }
 }
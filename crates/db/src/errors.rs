@@ -1,3 +1,4 @@
+use strata_state::{block_validation::BlockIntegrityError, id::L2BlockId};
 use thiserror::Error;
 
 use crate::entities::errors::EntityError;
@@ -17,9 +18,15 @@ pub enum DbError {
     #[error("missing {0} block {1} in range {2}..{3}")]
     MissingBlockInRange(&'static str, u64, u64, u64),
 
+    #[error("different L1 block already stored at idx {0}, must revert first")]
+    L1BlockConflict(u64),
+
     #[error("missing L1 block body (idx {0})")]
     MissingL1BlockBody(u64),
 
+    #[error("missing L2 block {0:?}")]
+    MissingL2Block(L2BlockId),
+
     #[error("missing L2 state (idx {0})")]
     MissingL2State(u64),
 
@@ -32,6 +39,11 @@ pub enum DbError {
     #[error("tried to overwrite consensus checkpoint at idx {0}")]
     OverwriteConsensusCheckpoint(u64),
 
+    /// The client state at the idx is behind finality and isn't kept, it can
+    /// still be recomputed by replaying from an earlier checkpoint.
+    #[error("client state at idx {0} was pruned, recompute it by replay")]
+    ClientStatePruned(u64),
+
     #[error("tried to overwrite state update at idx{0}. must purge in order to be replaced")]
     OverwriteStateUpdate(u64),
 
@@ -69,12 +81,29 @@ pub enum DbError {
     #[error("failed to load a cache entry")]
     CacheLoadFail,
 
+    #[error("block of {size} bytes exceeds the max block size of {max} bytes")]
+    BlockTooLarge { size: usize, max: usize },
+
+    #[error("block {0:?} doesn't match its header: {1}")]
+    InconsistentBlock(L2BlockId, BlockIntegrityError),
+
+    #[error("block {0:?} has parent {1:?} that's neither stored nor earlier in the batch")]
+    UnlinkedBlock(L2BlockId, L2BlockId),
+
+    #[error("block {0:?} at height {1} has parent at height {2}")]
+    ParentHeightMismatch(L2BlockId, u64, u64),
+
     #[error("codec error {0}")]
     CodecError(String),
 
     #[error("transaction error {0}")]
     TransactionError(String),
 
+    /// An optimistic transaction kept conflicting with concurrent writes
+    /// until it ran out of retries.
+    #[error("write to {0} kept conflicting with concurrent writes, out of retries")]
+    WriteConflict(&'static str),
+
     #[error("problem with entity: {0}")]
     EntityError(#[from] EntityError),
 
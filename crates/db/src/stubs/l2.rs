@@ -1,9 +1,10 @@
 use std::collections::*;
 
 use parking_lot::Mutex;
-use strata_state::{block::L2BlockBundle, prelude::*};
+use strata_state::{block::L2BlockBundle, block_validation::verify_block_integrity, prelude::*};
 
 use crate::{
+    errors::DbError,
     traits::{BlockStatus, *},
     DbResult,
 };
@@ -36,6 +37,7 @@ impl L2BlockDatabase for StubL2Db {
     fn put_block_data(&self, bundle: L2BlockBundle) -> DbResult<()> {
         let blkid = bundle.block().header().get_blockid();
         let idx = bundle.block().header().blockidx();
+        verify_block_integrity(bundle.block()).map_err(|e| DbError::InconsistentBlock(blkid, e))?;
 
         {
             let mut tbl = self.blocks.lock();
@@ -44,7 +46,38 @@ impl L2BlockDatabase for StubL2Db {
 
         {
             let mut tbl = self.heights.lock();
-            tbl.entry(idx).or_default().push(blkid);
+            let blkids = tbl.entry(idx).or_default();
+            if let Err(pos) = blkids.binary_search(&blkid) {
+                blkids.insert(pos, blkid);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn put_blocks_batch(&self, blocks: Vec<L2BlockBundle>) -> DbResult<()> {
+        {
+            let tbl = self.blocks.lock();
+            let mut batch_ids = HashSet::new();
+            for bundle in &blocks {
+                let header = bundle.block().header();
+                let blkid = header.get_blockid();
+                verify_block_integrity(bundle.block())
+                    .map_err(|e| DbError::InconsistentBlock(blkid, e))?;
+
+                let parent = *header.parent();
+                if header.blockidx() != 0
+                    && !batch_ids.contains(&parent)
+                    && !tbl.contains_key(&parent)
+                {
+                    return Err(DbError::UnlinkedBlock(blkid, parent));
+                }
+                batch_ids.insert(blkid);
+            }
+        }
+
+        for bundle in blocks {
+            self.put_block_data(bundle)?;
         }
 
         Ok(())
@@ -1,7 +1,7 @@
 //! Trait definitions for low level database interfaces.  This borrows some of
 //! its naming conventions from reth.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use strata_mmr::CompactMmr;
@@ -19,7 +19,8 @@ use strata_zkvm::ProofReceipt;
 
 use crate::{
     entities::bridge_tx_state::BridgeTxState,
-    types::{BlobEntry, CheckpointEntry, L1TxEntry},
+    errors::DbError,
+    types::{BlobEntry, BlobL1Status, BlobStatusTransition, CheckpointEntry, L1TxEntry},
     DbResult,
 };
 
@@ -33,6 +34,7 @@ pub trait Database {
     type ClientStateDB: ClientStateDatabase + Send + Sync;
     type ChainstateDB: ChainstateDatabase + Send + Sync;
     type CheckpointDB: CheckpointDatabase + Send + Sync;
+    type BatchDB: AtomicBatchDatabase + Send + Sync;
 
     fn l1_db(&self) -> &Arc<Self::L1DB>;
     fn l2_db(&self) -> &Arc<Self::L2DB>;
@@ -40,13 +42,54 @@ pub trait Database {
     fn client_state_db(&self) -> &Arc<Self::ClientStateDB>;
     fn chain_state_db(&self) -> &Arc<Self::ChainstateDB>;
     fn checkpoint_db(&self) -> &Arc<Self::CheckpointDB>;
+    fn batch_db(&self) -> &Arc<Self::BatchDB>;
+
+    /// Starts a new batch of writes across the different stores that get
+    /// committed atomically.
+    fn atomic_batch(&self) -> <Self::BatchDB as AtomicBatchDatabase>::Batch {
+        self.batch_db().new_batch()
+    }
+}
+
+/// Creates [`AtomicBatch`]es over the stores of a [`Database`].
+pub trait AtomicBatchDatabase {
+    type Batch: AtomicBatch;
+
+    /// Starts a new empty batch.
+    fn new_batch(&self) -> Self::Batch;
+}
+
+/// Accumulates writes to several stores so that they can be committed together.
+/// Either all of the writes in the batch get persisted or none of them do, so
+/// dropping a batch without committing it discards everything queued in it.
+///
+/// The writes are checked the same way as the equivalent calls on the
+/// individual stores when they're queued, against what's already in the
+/// database and earlier writes in the batch.
+pub trait AtomicBatch {
+    /// Queues writing a consensus output, like
+    /// [`ClientStateDatabase::write_client_update_output`].
+    fn put_client_update_output(&mut self, idx: u64, output: ClientUpdateOutput) -> DbResult<()>;
+
+    /// Queues writing a consensus checkpoint, like
+    /// [`ClientStateDatabase::write_client_state_checkpoint`].
+    fn put_client_state_checkpoint(&mut self, idx: u64, state: ClientState) -> DbResult<()>;
+
+    /// Queues setting the status of an L2 block, like
+    /// [`L2BlockDatabase::set_block_status`].
+    fn put_block_status(&mut self, id: L2BlockId, status: BlockStatus) -> DbResult<()>;
+
+    /// Commits all the queued writes in a single write.
+    fn commit(self) -> DbResult<()>;
 }
 
 /// Database interface to control our view of L1 data.
 pub trait L1Database {
     /// Atomically extends the chain with a new block, providing the manifest
     /// and a list of transactions we find relevant.  Returns error if
-    /// provided out-of-order.
+    /// provided out-of-order.  Re-putting the block we already have at `idx`
+    /// is a no-op, but a different block there returns an error and has to be
+    /// reverted first.
     fn put_block_data(&self, idx: u64, mf: L1BlockManifest, txs: Vec<L1Tx>) -> DbResult<()>;
 
     /// Stores an MMR checkpoint so we have to query less far back.  If the
@@ -66,6 +109,10 @@ pub trait L1Database {
     /// Gets the block manifest for a block index.
     fn get_block_manifest(&self, idx: u64) -> DbResult<Option<L1BlockManifest>>;
 
+    /// Gets the block manifest of the current chain tip, or `None` if we
+    /// don't have any blocks yet.
+    fn get_tip_manifest(&self) -> DbResult<Option<L1BlockManifest>>;
+
     /// Returns a half-open interval of block hashes, if we have all of them
     /// present.  Otherwise, returns error.
     fn get_blockid_range(&self, start_idx: u64, end_idx: u64) -> DbResult<Vec<Buf32>>;
@@ -73,12 +120,21 @@ pub trait L1Database {
     /// Gets the relevant txs we stored in a block.
     fn get_block_txs(&self, idx: u64) -> DbResult<Option<Vec<L1TxRef>>>;
 
+    /// Iterates over the relevant txs stored in the half-open interval of
+    /// blocks, in order, along with the height of their block.  Blocks are
+    /// loaded from the db as the iterator advances.
+    fn iter_block_txs_range(
+        &self,
+        start_idx: u64,
+        end_idx: u64,
+    ) -> DbResult<impl Iterator<Item = DbResult<(u64, L1TxRef)>> + '_>;
+
     /// Gets the tx with proof given a tx ref, if present.
     fn get_tx(&self, tx_ref: L1TxRef) -> DbResult<Option<L1Tx>>;
 
     /// Gets the last MMR checkpoint we stored before the given block height.
     /// Up to the caller to advance the MMR the rest of the way to the desired
-    /// state.
+    /// state, which can be done with [`strata_mmr::advance_mmr`].
     fn get_last_mmr_to(&self, idx: u64) -> DbResult<Option<CompactMmr>>;
 
     /// Get the [`L1Tx`]'s from a certain index (including the index) in a single flattened list
@@ -106,7 +162,8 @@ pub trait SyncEventDatabase {
     /// never need to look at them again.
     fn clear_sync_event(&self, start_idx: u64, end_idx: u64) -> DbResult<()>;
 
-    /// Returns the index of the most recently written sync event.
+    /// Returns the index of the most recently written sync event, or `None`
+    /// if none were written yet.  Indexes start from 1.
     fn get_last_idx(&self) -> DbResult<Option<u64>>;
 
     /// Gets the sync event with some index, if it exists.
@@ -142,6 +199,11 @@ pub trait ClientStateDatabase {
     /// Gets the last consensus checkpoint idx.
     fn get_last_checkpoint_idx(&self) -> DbResult<u64>;
 
+    /// Gets the last consensus checkpoint idx, or `None` if a bootstrap state
+    /// has not been written yet.  Unlike [`Self::get_last_checkpoint_idx`],
+    /// errors are only returned for actual failures.
+    fn get_last_checkpoint_idx_opt(&self) -> DbResult<Option<u64>>;
+
     /// Gets the idx of the last checkpoint up to the given input idx.  This is
     /// the idx we should resume at when playing out consensus writes since the
     /// saved checkpoint, which may be the same as the given idx (if we didn't
@@ -149,7 +211,23 @@ pub trait ClientStateDatabase {
     fn get_prev_checkpoint_at(&self, idx: u64) -> DbResult<u64>;
 
     /// Gets a state checkpoint at a previously written index, if it exists.
+    /// Returns [`DbError::ClientStatePruned`](crate::errors::DbError::ClientStatePruned)
+    /// instead of `None` for indexes behind the pruning horizon that aren't
+    /// kept, since the state there has to be recomputed.
     fn get_state_checkpoint(&self, idx: u64) -> DbResult<Option<ClientState>>;
+
+    /// Prunes the state checkpoints before `before_idx` that aren't a multiple
+    /// of `keep_interval`.  The consensus outputs are kept, so the pruned states
+    /// can still be recomputed from the ones that are left.
+    fn prune_client_states_before(&self, before_idx: u64, keep_interval: u64) -> DbResult<()>;
+
+    /// Records that the actions output for every input index up to `idx` have
+    /// been applied, so they don't have to be replayed after a restart.
+    fn put_last_acked_action_idx(&self, idx: u64) -> DbResult<()>;
+
+    /// Gets the index up to which the actions output have been applied, or
+    /// `None` if that was never recorded.
+    fn get_last_acked_action_idx(&self) -> DbResult<Option<u64>>;
 }
 
 /// L2 data store for CL blocks.  Does not store anything about what we think
@@ -159,6 +237,15 @@ pub trait L2BlockDatabase {
     /// block.  Also sets the block's status to "unchecked".
     fn put_block_data(&self, block: L2BlockBundle) -> DbResult<()>;
 
+    /// Stores a batch of L2 blocks at once, like
+    /// [`put_block_data`](Self::put_block_data) does each one.  Every block's
+    /// parent has to be either already stored or earlier in the batch, one
+    /// below the block's height, so blocks missing from the middle of the
+    /// batch or given out-of-order are rejected, in which case none of them
+    /// are stored.  The only block without a parent is the genesis block,
+    /// which builds on the zero block id and can't be replaced once stored.
+    fn put_blocks_batch(&self, blocks: Vec<L2BlockBundle>) -> DbResult<()>;
+
     /// Tries to delete an L2 block from the store, returning if it really
     /// existed or not.  This should only be used for blocks well before some
     /// buried L1 finalization horizon.
@@ -171,12 +258,112 @@ pub trait L2BlockDatabase {
     fn get_block_data(&self, id: L2BlockId) -> DbResult<Option<L2BlockBundle>>;
 
     /// Gets the L2 block IDs that we have at some height, in case there's more
-    /// than one on competing forks.
+    /// than one on competing forks.  The IDs are sorted and have no duplicates,
+    /// so that fork choice sees the same input on every node.
     // TODO do we even want to permit this as being a possible thing?
     fn get_blocks_at_height(&self, idx: u64) -> DbResult<Vec<L2BlockId>>;
 
     /// Gets the validity status of a block.
     fn get_block_status(&self, id: L2BlockId) -> DbResult<Option<BlockStatus>>;
+
+    /// Iterates over the blocks in the half-open interval of heights on the
+    /// chain ending at `tip`, in order.  Since we don't know which chain is the
+    /// canonical one here, the caller passes its tip and where there's
+    /// competing blocks at a height we pick the one in the tip's ancestry.
+    /// Errors if the tip is below the end of the range, and yields an error
+    /// and stops if a block is missing or doesn't build on the one before it.
+    ///
+    /// Blocks are looked up by height as the iterator advances, the tip's
+    /// ancestry is only walked if there's competing blocks in the range.
+    fn iter_blocks_range(
+        &self,
+        tip: L2BlockId,
+        start: u64,
+        end: u64,
+    ) -> DbResult<impl Iterator<Item = DbResult<L2BlockBundle>>> {
+        if start < end {
+            let tip_block = self
+                .get_block_data(tip)?
+                .ok_or(DbError::MissingL2Block(tip))?;
+            let tip_height = tip_block.header().blockidx();
+            if tip_height < end - 1 {
+                return Err(DbError::MissingBlockInRange(
+                    "L2",
+                    tip_height + 1,
+                    start,
+                    end,
+                ));
+            }
+        }
+
+        let mut prev: Option<L2BlockId> = None;
+        let mut tip_ancestors: Option<HashMap<u64, L2BlockId>> = None;
+        let mut next_block = move |height: u64| -> DbResult<L2BlockBundle> {
+            let missing = || DbError::MissingBlockInRange("L2", height, start, end);
+
+            let mut candidates = Vec::new();
+            for blkid in self.get_blocks_at_height(height)? {
+                let block = self
+                    .get_block_data(blkid)?
+                    .ok_or(DbError::MissingL2Block(blkid))?;
+                if prev.is_none_or(|prev| *block.header().parent() == prev) {
+                    candidates.push(block);
+                }
+            }
+
+            // once the tip's ancestry is known it decides, otherwise only
+            // competing blocks make us look it up
+            let block = if tip_ancestors.is_none() && candidates.len() <= 1 {
+                candidates.pop().ok_or_else(missing)?
+            } else {
+                let ancestors = match tip_ancestors.take() {
+                    Some(ancestors) => ancestors,
+                    None => get_ancestors_down_to(self, tip, height)?,
+                };
+                let ancestors = tip_ancestors.insert(ancestors);
+                let blkid = ancestors.get(&height).ok_or_else(missing)?;
+                candidates
+                    .into_iter()
+                    .find(|block| block.header().get_blockid() == *blkid)
+                    .ok_or_else(missing)?
+            };
+
+            prev = Some(block.header().get_blockid());
+            Ok(block)
+        };
+
+        let mut heights = start..end;
+        Ok(std::iter::from_fn(move || {
+            let height = heights.next()?;
+            let res = next_block(height);
+            if res.is_err() {
+                heights = end..end;
+            }
+            Some(res)
+        }))
+    }
+}
+
+/// Walks back from the block to the given height, returning the ids of the
+/// blocks on the way by height.
+fn get_ancestors_down_to(
+    db: &(impl L2BlockDatabase + ?Sized),
+    blkid: L2BlockId,
+    height: u64,
+) -> DbResult<HashMap<u64, L2BlockId>> {
+    let mut ancestors = HashMap::new();
+    let mut cur = blkid;
+    loop {
+        let block = db
+            .get_block_data(cur)?
+            .ok_or(DbError::MissingL2Block(cur))?;
+        let cur_height = block.header().blockidx();
+        ancestors.insert(cur_height, cur);
+        if cur_height <= height {
+            return Ok(ancestors);
+        }
+        cur = *block.header().parent();
+    }
 }
 
 /// Gets the status of a block.
@@ -258,14 +445,37 @@ pub trait BlobDatabase {
     /// Store the [`BlobEntry`].
     fn put_blob_entry(&self, blobid: Buf32, blobentry: BlobEntry) -> DbResult<()>;
 
+    /// Store the [`BlobEntry`]s atomically, new ones get indices in the order given.
+    fn put_blob_entries(&self, entries: Vec<(Buf32, BlobEntry)>) -> DbResult<()>;
+
     /// Get a [`BlobEntry`] by its hash
     fn get_blob_by_id(&self, id: Buf32) -> DbResult<Option<BlobEntry>>;
 
     /// Get the blob ID corresponding to the index
     fn get_blob_id(&self, blobidx: u64) -> DbResult<Option<Buf32>>;
 
+    /// Get the blob ID of the entry whose current commit or reveal txid is the
+    /// one given
+    fn get_blob_id_by_txid(&self, txid: Buf32) -> DbResult<Option<Buf32>>;
+
     /// Get the last blob index
     fn get_last_blob_idx(&self) -> DbResult<Option<u64>>;
+
+    /// Get the [`BlobEntry`]s in the given status created before the given
+    /// UNIX epoch millis, oldest first, along with their IDs.
+    fn get_blobs_created_before(
+        &self,
+        status: &BlobL1Status,
+        before_ms: u64,
+    ) -> DbResult<Vec<(Buf32, BlobEntry)>>;
+
+    /// Store the updated [`BlobEntry`]s atomically, logging a status transition with the reason
+    /// for each one whose status changed.  The log is never rewritten.
+    fn update_blob_entries(&self, entries: Vec<(Buf32, BlobEntry)>, reason: String)
+        -> DbResult<()>;
+
+    /// Get the status transitions of the [`BlobEntry`], oldest first.
+    fn get_blob_transitions(&self, id: Buf32) -> DbResult<Vec<BlobStatusTransition>>;
 }
 
 pub trait ProofDatabase {
@@ -300,6 +510,25 @@ pub trait ProofDatabase {
     /// Tries to delete dependencies of by its context, returning if it really
     /// existed or not.  
     fn del_proof_deps(&self, proof_context: ProofContext) -> DbResult<bool>;
+
+    /// Stores a serialized prover input, addressed by its hash.
+    ///
+    /// Identical inputs are only stored once.  Returns the hash to reference the input by.
+    fn put_input(&self, input: Vec<u8>) -> DbResult<Buf32>;
+
+    /// Retrieves a serialized prover input by its hash.
+    ///
+    /// Returns `Some(input)` if found, or `None` if not.
+    fn get_input(&self, input_hash: Buf32) -> DbResult<Option<Vec<u8>>>;
+
+    /// Sets the hash of the input stored with [`put_input`](Self::put_input) that the task for
+    /// the given [`ProofKey`] is proven with.
+    fn put_task_input(&self, proof_key: ProofKey, input_hash: Buf32) -> DbResult<()>;
+
+    /// Retrieves the hash of the input the task for the given [`ProofKey`] is proven with.
+    ///
+    /// Returns `Some(hash)` if set, or `None` if not.
+    fn get_task_input(&self, proof_key: ProofKey) -> DbResult<Option<Buf32>>;
 }
 
 pub trait BroadcastDatabase {
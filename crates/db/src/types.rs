@@ -1,5 +1,7 @@
 //! Module for database local types
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use arbitrary::Arbitrary;
 use bitcoin::{
     consensus::{self, deserialize, serialize},
@@ -12,6 +14,9 @@ use strata_state::batch::{BatchCheckpoint, BatchInfo, BootstrapState};
 use strata_zkvm::ProofReceipt;
 
 /// Represents data for a blob we're still planning to inscribe.
+///
+/// NOTE: Entries are stored with a versioned encoding, changing the fields needs a new record
+///   version in the rocksdb store so existing entries still decode.
 // TODO rename to `BlockInscriptionEntry` to emphasize this isn't just about *all* blobs
 #[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Arbitrary)]
 pub struct BlobEntry {
@@ -19,6 +24,19 @@ pub struct BlobEntry {
     pub commit_txid: Buf32,
     pub reveal_txid: Buf32,
     pub status: BlobL1Status,
+
+    /// Set if the entry is one of the chunks of a blob too large for a single inscription.
+    pub chunk: Option<BlobChunkInfo>,
+
+    /// Number of times the commit-reveal transactions had to be resigned.
+    pub resign_count: u32,
+
+    /// When the entry was created, in UNIX epoch millis.
+    pub created_at_ms: u64,
+
+    /// Ids of all the entries inscribed in the same commit-reveal transactions, in order, if the
+    /// entry was batched with others.
+    pub batch: Option<Vec<Buf32>>,
 }
 
 impl BlobEntry {
@@ -28,11 +46,19 @@ impl BlobEntry {
         reveal_txid: Buf32,
         status: BlobL1Status,
     ) -> Self {
+        let created_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
         Self {
             blob,
             commit_txid,
             reveal_txid,
             status,
+            chunk: None,
+            resign_count: 0,
+            created_at_ms,
+            batch: None,
         }
     }
 
@@ -46,10 +72,52 @@ impl BlobEntry {
         let rid = Buf32::zero();
         Self::new(blob, cid, rid, BlobL1Status::Unsigned)
     }
+
+    /// Create new unsigned blobentry for a chunk of a larger blob.
+    pub fn new_unsigned_chunk(blob: Vec<u8>, chunk: BlobChunkInfo) -> Self {
+        Self {
+            chunk: Some(chunk),
+            ..Self::new_unsigned(blob)
+        }
+    }
+
+    /// Returns if this is the last (or only) entry of its blob.
+    pub fn is_last_chunk(&self) -> bool {
+        self.chunk.as_ref().map_or(true, BlobChunkInfo::is_last)
+    }
 }
 
-/// Various status that transactions corresponding to a blob can be in L1
+/// Position of a [`BlobEntry`] among the chunks of the blob it was split from.
 #[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Arbitrary)]
+pub struct BlobChunkInfo {
+    /// Commitment of the whole blob, which is also the id of the first chunk.
+    pub blob_commitment: Buf32,
+
+    /// Index of the chunk in the blob.
+    pub index: u32,
+
+    /// Total number of chunks the blob was split into.
+    pub total: u32,
+}
+
+impl BlobChunkInfo {
+    pub fn new(blob_commitment: Buf32, index: u32, total: u32) -> Self {
+        Self {
+            blob_commitment,
+            index,
+            total,
+        }
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.index + 1 == self.total
+    }
+}
+
+/// Various status that transactions corresponding to a blob can be in L1
+#[derive(
+    Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Arbitrary, Serialize, Deserialize,
+)]
 pub enum BlobL1Status {
     /// The blob has not been signed yet, i.e commit-reveal transactions have not been created yet.
     Unsigned,
@@ -69,6 +137,48 @@ pub enum BlobL1Status {
     /// The transactions need to be resigned.
     /// This could be due to transactions input UTXOs already being spent.
     NeedsResign,
+
+    /// The blob needed to be resigned too many times and was given up on, this needs an operator
+    /// to look into it.
+    Failed,
+}
+
+impl BlobL1Status {
+    /// Returns if the blob is done being watched, successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Finalized | Self::Failed)
+    }
+}
+
+/// Record of a [`BlobEntry`]'s status changing, kept so we can tell how a blob ended up where it
+/// is.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Arbitrary)]
+pub struct BlobStatusTransition {
+    /// When the status changed, in UNIX epoch millis.
+    pub timestamp_ms: u64,
+
+    pub from: BlobL1Status,
+
+    pub to: BlobL1Status,
+
+    /// Why the status changed.
+    pub reason: String,
+}
+
+impl BlobStatusTransition {
+    /// Creates a transition that happened just now.
+    pub fn now(from: BlobL1Status, to: BlobL1Status, reason: impl Into<String>) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        Self {
+            timestamp_ms,
+            from,
+            to,
+            reason: reason.into(),
+        }
+    }
 }
 
 /// This is the entry that gets saved to the database corresponding to a bitcoin transaction that
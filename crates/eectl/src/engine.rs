@@ -8,6 +8,8 @@
 // errors as we'd be able to identify when our perspective on the state is
 // inconsistent with the remote state.
 
+use std::time::Duration;
+
 use strata_state::id::L2BlockId;
 
 use crate::{errors::*, messages::*};
@@ -22,6 +24,18 @@ pub trait ExecEngineCtl {
     /// Corresponds to `engine_newPayloadVX`.
     fn submit_payload(&self, payload: ExecPayloadData) -> EngineResult<BlockStatus>;
 
+    /// Like [`Self::submit_payload`], but gives up with
+    /// [`EngineError::Timeout`] if the engine doesn't respond in time.
+    ///
+    /// Engines that can't bound the request just submit the payload.
+    fn submit_payload_with_timeout(
+        &self,
+        payload: ExecPayloadData,
+        _timeout: Duration,
+    ) -> EngineResult<BlockStatus> {
+        self.submit_payload(payload)
+    }
+
     /// Tries to prepare a payload using the current state of the chain,
     /// returning an ID to query pending payload build jobs.  If this completes
     /// successfully and then `.update_head_block` is called on it, will
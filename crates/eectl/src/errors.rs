@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use strata_state::id::L2BlockId;
 use thiserror::Error;
 
@@ -20,6 +22,9 @@ pub enum EngineError {
     #[error("missing block in db {0}")]
     DbMissingBlock(L2BlockId),
 
+    #[error("engine request timed out after {0:?}")]
+    Timeout(Duration),
+
     #[error("{0}")]
     Other(String),
 }
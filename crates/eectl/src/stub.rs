@@ -4,8 +4,12 @@
 //! This just simulates producing a payload by waiting some amount before
 //! returning `Ready` with dummy state.  We might extend this slightly to make
 //! it more believable.
+//!
+//! Checking blocks can also be configured to misbehave for specific EL blocks
+//! so that we can see how consensus copes with a faulty EL.  Fork choice
+//! updates are recorded, and can be dropped for specific blocks.
 
-use std::{collections::*, sync::Mutex, time};
+use std::{collections::*, sync::Mutex, thread, time};
 
 use strata_primitives::buf::Buf32;
 use strata_state::{
@@ -18,6 +22,12 @@ use crate::{engine::*, errors::*, messages::*};
 struct State {
     next_idx: u64,
     payload_jobs: HashMap<u64, time::Instant>,
+
+    /// Fork choice updates received so far, including dropped ones.
+    fc_updates: Vec<StubFcUpdate>,
+
+    /// Number of further fork choice updates to drop, by block.
+    fc_drops: HashMap<L2BlockId, u32>,
 }
 
 impl State {
@@ -25,13 +35,52 @@ impl State {
         Self {
             next_idx: 1,
             payload_jobs: HashMap::new(),
+            fc_updates: Vec::new(),
+            fc_drops: HashMap::new(),
+        }
+    }
+}
+
+/// Fork choice update the stub was sent.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StubFcUpdate {
+    Head(L2BlockId),
+    Safe(L2BlockId),
+    Finalized(L2BlockId),
+}
+
+impl StubFcUpdate {
+    pub fn blkid(&self) -> &L2BlockId {
+        match self {
+            Self::Head(id) | Self::Safe(id) | Self::Finalized(id) => id,
         }
     }
 }
 
+/// Fault the stub injects when it's asked to check a particular EL block.
+#[derive(Clone, Debug)]
+pub enum StubFault {
+    /// Reports the block as invalid.
+    Invalid,
+
+    /// Fails the request with an engine error.
+    Error,
+
+    /// Waits for the duration before failing the request, like an EL that
+    /// stopped responding would.
+    Timeout(time::Duration),
+
+    /// Pretends executing the block produces this state root, so the block is
+    /// invalid unless it commits to the same root.
+    WrongStateRoot(Buf32),
+}
+
 pub struct StubController {
     payload_prep_dur: time::Duration,
     state: Mutex<State>,
+
+    /// Faults to inject when checking blocks, by EL block number.
+    faults: HashMap<u64, StubFault>,
 }
 
 impl StubController {
@@ -39,13 +88,78 @@ impl StubController {
         Self {
             payload_prep_dur,
             state: Mutex::new(State::new()),
+            faults: HashMap::new(),
+        }
+    }
+
+    /// Makes checking the EL block with the given number misbehave.
+    pub fn with_fault(mut self, update_idx: u64, fault: StubFault) -> Self {
+        self.faults.insert(update_idx, fault);
+        self
+    }
+
+    /// Makes the next `n` fork choice updates to the block fail, like a timed
+    /// out request would.
+    pub fn drop_fc_updates(&self, blkid: L2BlockId, n: u32) {
+        self.state.lock().unwrap().fc_drops.insert(blkid, n);
+    }
+
+    /// Takes the fork choice updates received so far, including dropped ones.
+    pub fn take_fc_updates(&self) -> Vec<StubFcUpdate> {
+        std::mem::take(&mut self.state.lock().unwrap().fc_updates)
+    }
+
+    fn update_fc(&self, update: StubFcUpdate) -> EngineResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.fc_updates.push(update);
+        match state.fc_drops.get_mut(update.blkid()) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                Err(EngineError::Other("engine request timed out".to_owned()))
+            }
+            _ => Ok(()),
         }
     }
 }
 
 impl ExecEngineCtl for StubController {
-    fn submit_payload(&self, _payload: ExecPayloadData) -> EngineResult<BlockStatus> {
-        Ok(BlockStatus::Valid)
+    fn submit_payload(&self, payload: ExecPayloadData) -> EngineResult<BlockStatus> {
+        let update = payload.exec_update();
+        let Some(fault) = self.faults.get(&update.input().update_idx()) else {
+            return Ok(BlockStatus::Valid);
+        };
+
+        match fault {
+            StubFault::Invalid => Ok(BlockStatus::Invalid),
+            StubFault::Error => Err(EngineError::Other("injected engine failure".to_owned())),
+            StubFault::Timeout(dur) => {
+                thread::sleep(*dur);
+                Err(EngineError::Other("engine request timed out".to_owned()))
+            }
+            StubFault::WrongStateRoot(root) => {
+                if update.output().new_state() == root {
+                    Ok(BlockStatus::Valid)
+                } else {
+                    Ok(BlockStatus::Invalid)
+                }
+            }
+        }
+    }
+
+    fn submit_payload_with_timeout(
+        &self,
+        payload: ExecPayloadData,
+        timeout: time::Duration,
+    ) -> EngineResult<BlockStatus> {
+        let update_idx = payload.exec_update().input().update_idx();
+        if let Some(StubFault::Timeout(dur)) = self.faults.get(&update_idx) {
+            if *dur > timeout {
+                thread::sleep(timeout);
+                return Err(EngineError::Timeout(timeout));
+            }
+        }
+
+        self.submit_payload(payload)
     }
 
     fn prepare_payload(&self, _env: PayloadEnv) -> EngineResult<u64> {
@@ -81,19 +195,99 @@ impl ExecEngineCtl for StubController {
         }
     }
 
-    fn update_head_block(&self, _id: L2BlockId) -> EngineResult<()> {
-        Ok(())
+    fn update_head_block(&self, id: L2BlockId) -> EngineResult<()> {
+        self.update_fc(StubFcUpdate::Head(id))
     }
 
-    fn update_safe_block(&self, _id: L2BlockId) -> EngineResult<()> {
-        Ok(())
+    fn update_safe_block(&self, id: L2BlockId) -> EngineResult<()> {
+        self.update_fc(StubFcUpdate::Safe(id))
     }
 
-    fn update_finalized_block(&self, _id: L2BlockId) -> EngineResult<()> {
-        Ok(())
+    fn update_finalized_block(&self, id: L2BlockId) -> EngineResult<()> {
+        self.update_fc(StubFcUpdate::Finalized(id))
     }
 
     fn check_block_exists(&self, _id: L2BlockId) -> EngineResult<bool> {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload_for(update_idx: u64, state_root: Buf32) -> ExecPayloadData {
+        ExecPayloadData::new(
+            ExecUpdate::new(
+                UpdateInput::new(update_idx, vec![], Buf32::zero(), Vec::new()),
+                UpdateOutput::new_from_state(state_root),
+            ),
+            Vec::new(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_faults_only_hit_configured_blocks() {
+        let root = Buf32::from([1; 32]);
+        let ctl = StubController::new(time::Duration::ZERO)
+            .with_fault(2, StubFault::Invalid)
+            .with_fault(3, StubFault::Error)
+            .with_fault(4, StubFault::Timeout(time::Duration::from_millis(10)))
+            .with_fault(5, StubFault::WrongStateRoot(root));
+
+        let status = |idx, root| ctl.submit_payload(payload_for(idx, root));
+
+        assert_eq!(status(1, Buf32::zero()).unwrap(), BlockStatus::Valid);
+        assert_eq!(status(2, Buf32::zero()).unwrap(), BlockStatus::Invalid);
+        assert!(status(3, Buf32::zero()).is_err());
+        assert!(status(4, Buf32::zero()).is_err());
+        assert_eq!(status(5, Buf32::zero()).unwrap(), BlockStatus::Invalid);
+        assert_eq!(status(5, root).unwrap(), BlockStatus::Valid);
+    }
+
+    #[test]
+    fn test_timeout_cuts_slow_checks_short() {
+        let ctl = StubController::new(time::Duration::ZERO)
+            .with_fault(2, StubFault::Timeout(time::Duration::from_secs(10)))
+            .with_fault(3, StubFault::Timeout(time::Duration::from_millis(10)));
+        let timeout = time::Duration::from_millis(50);
+        let status =
+            |idx| ctl.submit_payload_with_timeout(payload_for(idx, Buf32::zero()), timeout);
+
+        assert_eq!(status(1).unwrap(), BlockStatus::Valid);
+
+        let start = time::Instant::now();
+        assert!(matches!(status(2), Err(EngineError::Timeout(t)) if t == timeout));
+        assert!(start.elapsed() < time::Duration::from_secs(10));
+
+        // fails on its own before the timeout
+        assert!(matches!(status(3), Err(EngineError::Other(_))));
+    }
+
+    #[test]
+    fn test_fc_updates() {
+        let ctl = StubController::new(time::Duration::ZERO);
+        let [a, b] = [
+            L2BlockId::from(Buf32::from([1; 32])),
+            L2BlockId::from(Buf32::from([2; 32])),
+        ];
+        ctl.drop_fc_updates(b, 1);
+
+        ctl.update_safe_block(a).unwrap();
+        assert!(ctl.update_finalized_block(b).is_err());
+        ctl.update_finalized_block(b).unwrap();
+        ctl.update_head_block(a).unwrap();
+
+        assert_eq!(
+            ctl.take_fc_updates(),
+            vec![
+                StubFcUpdate::Safe(a),
+                StubFcUpdate::Finalized(b),
+                StubFcUpdate::Finalized(b),
+                StubFcUpdate::Head(a),
+            ]
+        );
+        assert!(ctl.take_fc_updates().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use alloy_rpc_types::{
     engine::{
@@ -305,6 +305,18 @@ impl<T: EngineRpc> ExecEngineCtl for RpcExecEngineCtl<T> {
             .block_on(self.inner.submit_new_payload(payload))
     }
 
+    fn submit_payload_with_timeout(
+        &self,
+        payload: ExecPayloadData,
+        timeout: Duration,
+    ) -> EngineResult<BlockStatus> {
+        self.tokio_handle.block_on(async {
+            tokio::time::timeout(timeout, self.inner.submit_new_payload(payload))
+                .await
+                .map_err(|_| EngineError::Timeout(timeout))?
+        })
+    }
+
     fn prepare_payload(&self, env: PayloadEnv) -> EngineResult<u64> {
         let prev_l2block = self
             .get_l2block(env.prev_l2_block_id())
@@ -13,9 +13,28 @@ pub enum ParseError {
     #[error("supplied address is invalid")]
     InvalidAddress(#[from] address::ParseError),
 
-    #[error("only taproot addresses are supported but found {0:?}")]
+    #[error("unsupported address type {0:?}")]
     UnsupportedAddress(Option<AddressType>),
 
     #[error("not a valid point on the curve: {0}")]
     InvalidPoint(Buf32),
 }
+
+/// Errors decoding an encoded [`L1BlockManifest`](crate::l1::L1BlockManifest).
+#[derive(Debug, Clone, Error)]
+pub enum ManifestDecodeError {
+    #[error("empty manifest")]
+    Empty,
+
+    #[error("unsupported manifest format version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("malformed manifest: {0}")]
+    Malformed(String),
+
+    #[error("invalid block header: {0}")]
+    InvalidHeader(String),
+
+    #[error("block header hashes to {0}, expected {1}")]
+    BlockHashMismatch(Buf32, Buf32),
+}
@@ -1,29 +1,38 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fmt::Display,
     io::{self, Read, Write},
     iter::Sum,
     ops::Add,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use arbitrary::{Arbitrary, Unstructured};
 use bitcoin::{
     absolute::LockTime,
     address::NetworkUnchecked,
-    consensus::serialize,
+    block::Header,
+    consensus::{deserialize, serialize},
     hashes::{sha256d, Hash},
     key::{rand, Keypair, Parity, TapTweak},
     secp256k1::{SecretKey, XOnlyPublicKey, SECP256K1},
     taproot::{ControlBlock, TaprootMerkleBranch},
     transaction::Version,
-    Address, AddressType, Amount, Block, BlockHash, Network, OutPoint, Psbt, ScriptBuf, Sequence,
-    TapNodeHash, Transaction, TxIn, TxOut, Txid, Witness,
+    Address, AddressType, Amount, Block, BlockHash, CompactTarget, Network, OutPoint, Psbt,
+    ScriptBuf, Sequence, TapNodeHash, Transaction, TxIn, TxMerkleNode, TxOut, Txid, Witness,
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use rand::rngs::OsRng;
 use reth_primitives::revm_primitives::FixedBytes;
 use serde::{de, Deserialize, Deserializer, Serialize};
 
-use crate::{buf::Buf32, constants::HASH_SIZE, errors::ParseError, impl_buf_wrapper};
+use crate::{
+    buf::Buf32,
+    constants::HASH_SIZE,
+    errors::{ManifestDecodeError, ParseError},
+    impl_buf_wrapper,
+    merkle::MerkleProof,
+};
 
 /// ID of an L1 block, usually the hash of its header.
 #[derive(
@@ -105,28 +114,8 @@ impl From<(u64, u32)> for L1TxRef {
     }
 }
 
-/// TODO: This is duplicate with state::l1::L1TxProof
-/// Merkle proof for a TXID within a block.
-// TODO rework this, make it possible to generate proofs, etc.
-#[derive(Clone, Debug, PartialEq, Eq, Arbitrary, BorshSerialize, BorshDeserialize)]
-pub struct L1TxProof {
-    position: u32,
-    cohashes: Vec<Buf32>,
-}
-
-impl L1TxProof {
-    pub fn new(position: u32, cohashes: Vec<Buf32>) -> Self {
-        Self { position, cohashes }
-    }
-
-    pub fn cohashes(&self) -> &[Buf32] {
-        &self.cohashes
-    }
-
-    pub fn position(&self) -> u32 {
-        self.position
-    }
-}
+/// Merkle proof for a WTXID within a block.
+pub type L1TxProof = MerkleProof;
 
 /// Includes [`L1BlockManifest`] along with scan rules that it is applied to
 #[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Arbitrary)]
@@ -158,14 +147,48 @@ impl L1BlockManifest {
         self.epoch
     }
 
+    /// Parses the block header, see [`L1BlockRecord::parse_header`].
+    pub fn parse_header(&self) -> Result<Header, ManifestDecodeError> {
+        self.record.parse_header()
+    }
+
     pub fn into_record(self) -> L1BlockRecord {
         self.record
     }
+
+    /// Encodes the manifest in the current version of the wire format, which
+    /// is the format version byte followed by the borsh-encoded manifest.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![L1_MANIFEST_FORMAT_VERSION];
+        self.serialize(&mut buf)
+            .expect("l1: serialize manifest into vec");
+        buf
+    }
+
+    /// Decodes a manifest produced by [`Self::encode`], checking that the
+    /// header it carries is a valid one for the block.
+    pub fn decode(buf: &[u8]) -> Result<Self, ManifestDecodeError> {
+        let (version, rest) = buf.split_first().ok_or(ManifestDecodeError::Empty)?;
+        if *version != L1_MANIFEST_FORMAT_VERSION {
+            return Err(ManifestDecodeError::UnsupportedVersion(*version));
+        }
+
+        let mf: Self =
+            borsh::from_slice(rest).map_err(|e| ManifestDecodeError::Malformed(e.to_string()))?;
+        mf.record.parse_header()?;
+        Ok(mf)
+    }
 }
 
+/// Version of the wire format [`L1BlockManifest::encode`] produces.
+pub const L1_MANIFEST_FORMAT_VERSION: u8 = 1;
+
+/// Length of a consensus-encoded Bitcoin block header.
+const BTC_HEADER_LEN: usize = 80;
+
 /// Describes an L1 block and associated data that we need to keep around.
 // TODO should we include the block index here?
-#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Arbitrary)]
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct L1BlockRecord {
     /// Block hash/ID, kept here so we don't have to be aware of the hash function
     /// here.  This is what we use in the MMR.
@@ -201,6 +224,47 @@ impl L1BlockRecord {
     pub fn txs_root(&self) -> Buf32 {
         self.txs_root
     }
+
+    /// Parses the stored header, checking it's a complete Bitcoin header that
+    /// hashes to the block id.
+    pub fn parse_header(&self) -> Result<Header, ManifestDecodeError> {
+        if self.header.len() != BTC_HEADER_LEN {
+            return Err(ManifestDecodeError::InvalidHeader(format!(
+                "expected {BTC_HEADER_LEN} bytes, got {}",
+                self.header.len()
+            )));
+        }
+
+        let header: Header = deserialize(&self.header)
+            .map_err(|e| ManifestDecodeError::InvalidHeader(e.to_string()))?;
+        let hash = Buf32(header.block_hash().to_raw_hash().to_byte_array());
+        if hash != self.blockid {
+            return Err(ManifestDecodeError::BlockHashMismatch(hash, self.blockid));
+        }
+
+        Ok(header)
+    }
+}
+
+impl<'a> Arbitrary<'a> for L1BlockRecord {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Build a real header so the record passes `parse_header` like a stored one would
+        let header = Header {
+            version: bitcoin::block::Version::from_consensus(i32::arbitrary(u)?),
+            prev_blockhash: BlockHash::from_byte_array(<[u8; 32]>::arbitrary(u)?),
+            merkle_root: TxMerkleNode::from_byte_array(<[u8; 32]>::arbitrary(u)?),
+            time: u32::arbitrary(u)?,
+            bits: CompactTarget::from_consensus(u32::arbitrary(u)?),
+            nonce: u32::arbitrary(u)?,
+        };
+        let blockid = Buf32(header.block_hash().to_raw_hash().to_byte_array());
+
+        Ok(Self {
+            blockid,
+            header: serialize(&header),
+            txs_root: Buf32::arbitrary(u)?,
+        })
+    }
 }
 
 impl From<Block> for L1BlockRecord {
@@ -309,6 +373,123 @@ pub struct L1Status {
 
     /// number of published transactions in current run (commit + reveal pair count as 1)
     pub published_inscription_count: u64,
+
+    /// UNIX millis time of when we last saw the L1 tip change.
+    pub last_seen_block_time_ms: u64,
+
+    /// Where the blobs the writer is inscribing are at in the current run.
+    pub inscriptions: InscriptionSummary,
+}
+
+/// How long we can go without seeing a new L1 block before considering the chain stalled.
+///
+/// Bitcoin targets a block every 10 minutes, so going an hour without one is already quite
+/// unlikely for a chain that's making progress.
+pub const DEFAULT_L1_STALL_THRESHOLD: Duration = Duration::from_secs(60 * 60);
+
+impl L1Status {
+    /// Returns if no new L1 block has appeared within `threshold`, which would mean that the
+    /// bitcoin node we're connected to isn't making progress.
+    pub fn is_chain_stalled(&self, threshold: Duration) -> bool {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_millis() as u64;
+        self.is_chain_stalled_at(now_ms, threshold)
+    }
+
+    /// Same as [`Self::is_chain_stalled`], but relative to the UNIX millis time `now_ms`.
+    ///
+    /// Never considers the chain stalled if we haven't seen a block yet.
+    pub fn is_chain_stalled_at(&self, now_ms: u64, threshold: Duration) -> bool {
+        if self.last_seen_block_time_ms == 0 {
+            return false;
+        }
+
+        now_ms.saturating_sub(self.last_seen_block_time_ms) > threshold.as_millis() as u64
+    }
+}
+
+/// How far along an inscribed blob is, as tracked in [`InscriptionSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Arbitrary)]
+pub enum InscriptionStage {
+    /// Signed or published, but not included in a block yet.
+    Pending,
+
+    /// Included in a block, but not buried deep enough to be final.
+    Confirmed,
+
+    /// Buried deep enough to never be reorged out.
+    Finalized,
+
+    /// Given up on after failing to get it included, needs an operator to look into it.
+    Failed,
+}
+
+/// Summary of the blobs the writer is inscribing in the current run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, Arbitrary)]
+pub struct InscriptionSummary {
+    /// Reveal txid and stage of each blob that's not finalized yet, by blob index.
+    unfinalized: BTreeMap<u64, (Buf32, InscriptionStage)>,
+
+    /// Number of blobs finalized in the current run.
+    finalized_count: u64,
+
+    /// Indexes of the blobs that were given up on.
+    failed: BTreeSet<u64>,
+}
+
+impl InscriptionSummary {
+    /// Records that the blob at `idx`, inscribed with `reveal_txid`, reached `stage`.
+    pub fn update(&mut self, idx: u64, reveal_txid: Buf32, stage: InscriptionStage) {
+        match stage {
+            InscriptionStage::Finalized => {
+                // The watcher moves past blobs once they're finalized, but don't double count
+                // in case it's reported again.
+                if self.unfinalized.remove(&idx).is_some() {
+                    self.finalized_count += 1;
+                }
+            }
+            InscriptionStage::Failed => {
+                self.unfinalized.remove(&idx);
+                self.failed.insert(idx);
+            }
+            _ => {
+                self.unfinalized.insert(idx, (reveal_txid, stage));
+            }
+        }
+    }
+
+    /// Reveal txids of the blobs that aren't finalized yet, by blob index.
+    pub fn unfinalized_txids(&self) -> impl Iterator<Item = (u64, Buf32)> + '_ {
+        self.unfinalized
+            .iter()
+            .map(|(idx, (txid, _))| (*idx, *txid))
+    }
+
+    pub fn pending_count(&self) -> u64 {
+        self.count_in(InscriptionStage::Pending)
+    }
+
+    pub fn confirmed_count(&self) -> u64 {
+        self.count_in(InscriptionStage::Confirmed)
+    }
+
+    pub fn finalized_count(&self) -> u64 {
+        self.finalized_count
+    }
+
+    /// Indexes of the blobs that were given up on, in ascending order.
+    pub fn failed_idxs(&self) -> impl Iterator<Item = u64> + '_ {
+        self.failed.iter().copied()
+    }
+
+    fn count_in(&self, stage: InscriptionStage) -> u64 {
+        self.unfinalized
+            .values()
+            .filter(|(_, s)| *s == stage)
+            .count() as u64
+    }
 }
 
 /// A wrapper around the [`bitcoin::Address<NetworkChecked>`] type created in order to implement
@@ -964,7 +1145,7 @@ impl<'a> Arbitrary<'a> for TaprootSpendPath {
 
 #[cfg(test)]
 mod tests {
-    use std::io::Cursor;
+    use std::{io::Cursor, time::Duration};
 
     use arbitrary::{Arbitrary, Unstructured};
     use bitcoin::{
@@ -980,10 +1161,13 @@ mod tests {
     use strata_test_utils::ArbitraryGenerator;
 
     use super::{
-        BitcoinAddress, BitcoinAmount, BitcoinTxid, BorshDeserialize, BorshSerialize, XOnlyPk,
+        BitcoinAddress, BitcoinAmount, BitcoinTxid, BorshDeserialize, BorshSerialize,
+        InscriptionStage, InscriptionSummary, L1BlockManifest, L1BlockRecord, L1Status, XOnlyPk,
+        L1_MANIFEST_FORMAT_VERSION,
     };
     use crate::{
-        errors::ParseError,
+        buf::Buf32,
+        errors::{ManifestDecodeError, ParseError},
         l1::{BitcoinPsbt, BitcoinTxOut, TaprootSpendPath},
     };
 
@@ -1412,4 +1596,129 @@ mod tests {
             "original and deserialized txid must be the same"
         );
     }
+
+    #[test]
+    fn test_l1_status_chain_stalled() {
+        let threshold = Duration::from_secs(60 * 60);
+        let last_seen = 1_700_000_000_000;
+        let status = L1Status {
+            last_seen_block_time_ms: last_seen,
+            ..Default::default()
+        };
+
+        // healthy, last block seen recently
+        assert!(!status.is_chain_stalled_at(last_seen, threshold));
+        assert!(!status.is_chain_stalled_at(last_seen + 10 * 60 * 1000, threshold));
+        assert!(!status.is_chain_stalled_at(last_seen + 60 * 60 * 1000, threshold));
+
+        // stalled, no block seen for longer than the threshold
+        assert!(status.is_chain_stalled_at(last_seen + 60 * 60 * 1000 + 1, threshold));
+        assert!(status.is_chain_stalled_at(last_seen + 5 * 60 * 60 * 1000, threshold));
+    }
+
+    #[test]
+    fn test_l1_status_no_block_seen_not_stalled() {
+        let status = L1Status::default();
+        assert!(!status.is_chain_stalled_at(1_700_000_000_000, Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_inscription_summary_counts() {
+        let mut summary = InscriptionSummary::default();
+        let txid = |i: u8| Buf32::from([i; 32]);
+
+        summary.update(0, txid(1), InscriptionStage::Pending);
+        summary.update(1, txid(2), InscriptionStage::Pending);
+        assert_eq!(summary.pending_count(), 2);
+        assert_eq!(summary.confirmed_count(), 0);
+        assert_eq!(summary.finalized_count(), 0);
+
+        summary.update(0, txid(1), InscriptionStage::Confirmed);
+        assert_eq!(summary.pending_count(), 1);
+        assert_eq!(summary.confirmed_count(), 1);
+
+        // resigned blob gets a new txid
+        summary.update(1, txid(3), InscriptionStage::Pending);
+        assert_eq!(
+            summary.unfinalized_txids().collect::<Vec<_>>(),
+            vec![(0, txid(1)), (1, txid(3))]
+        );
+
+        summary.update(0, txid(1), InscriptionStage::Finalized);
+        summary.update(0, txid(1), InscriptionStage::Finalized);
+        assert_eq!(summary.pending_count(), 1);
+        assert_eq!(summary.confirmed_count(), 0);
+        assert_eq!(summary.finalized_count(), 1);
+        assert_eq!(
+            summary.unfinalized_txids().collect::<Vec<_>>(),
+            vec![(1, txid(3))]
+        );
+    }
+
+    fn genesis_manifest() -> L1BlockManifest {
+        let block = bitcoin::blockdata::constants::genesis_block(Network::Regtest);
+        L1BlockManifest::new(L1BlockRecord::from(block), 3)
+    }
+
+    #[test]
+    fn test_manifest_encode_roundtrip() {
+        let mf = genesis_manifest();
+        let buf = mf.encode();
+        assert_eq!(buf[0], L1_MANIFEST_FORMAT_VERSION);
+
+        let decoded = L1BlockManifest::decode(&buf).unwrap();
+        assert_eq!(decoded, mf);
+        let header = decoded.into_record().parse_header().unwrap();
+        assert_eq!(header.time, 1296688602);
+    }
+
+    #[test]
+    fn test_manifest_decode_rejects_truncated_header() {
+        let mf = genesis_manifest();
+        let record = L1BlockRecord::new(mf.block_hash(), mf.header()[..79].to_vec(), mf.txs_root());
+        let buf = L1BlockManifest::new(record, mf.epoch()).encode();
+
+        let res = L1BlockManifest::decode(&buf);
+        assert!(matches!(res, Err(ManifestDecodeError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn test_arbitrary_manifest_decodes() {
+        let mut generator = ArbitraryGenerator::new();
+        for _ in 0..10 {
+            let mf: L1BlockManifest = generator.generate();
+            assert_eq!(L1BlockManifest::decode(&mf.encode()).unwrap(), mf);
+        }
+    }
+
+    #[test]
+    fn test_manifest_decode_rejects_bad_input() {
+        let mf = genesis_manifest();
+        let buf = mf.encode();
+
+        assert!(matches!(
+            L1BlockManifest::decode(&[]),
+            Err(ManifestDecodeError::Empty)
+        ));
+
+        let mut bad_version = buf.clone();
+        bad_version[0] = L1_MANIFEST_FORMAT_VERSION + 1;
+        assert!(matches!(
+            L1BlockManifest::decode(&bad_version),
+            Err(ManifestDecodeError::UnsupportedVersion(v)) if v == L1_MANIFEST_FORMAT_VERSION + 1
+        ));
+
+        assert!(matches!(
+            L1BlockManifest::decode(&buf[..buf.len() - 1]),
+            Err(ManifestDecodeError::Malformed(_))
+        ));
+
+        // a valid header that isn't the one of the block
+        let record = L1BlockRecord::new(Buf32::zero(), mf.header().to_vec(), mf.txs_root());
+        let buf = L1BlockManifest::new(record, 0).encode();
+        assert!(matches!(
+            L1BlockManifest::decode(&buf),
+            Err(ManifestDecodeError::BlockHashMismatch(_, expected)) if expected == Buf32::zero()
+        ));
+    }
 }
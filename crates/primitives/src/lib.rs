@@ -12,6 +12,7 @@ pub mod evm_exec;
 pub mod hash;
 pub mod l1;
 pub mod l2;
+pub mod merkle;
 #[macro_use]
 mod macros;
 pub mod keys;
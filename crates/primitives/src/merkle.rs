@@ -0,0 +1,139 @@
+//! Merkle inclusion proofs for Bitcoin-style merkle trees.
+
+use arbitrary::Arbitrary;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{buf::Buf32, hash::sha256d};
+
+/// Merkle inclusion proof for a leaf in a Bitcoin-style merkle tree, like the (witness)
+/// transaction tree committed to by a block.
+///
+/// Bitcoin pairs the last node of a level with itself when the level has an odd number of nodes,
+/// so for those the cohash is just a copy of the node being hashed up.
+#[derive(Clone, Debug, PartialEq, Eq, Arbitrary, BorshSerialize, BorshDeserialize)]
+pub struct MerkleProof {
+    position: u32,
+    cohashes: Vec<Buf32>,
+}
+
+impl MerkleProof {
+    pub fn new(position: u32, cohashes: Vec<Buf32>) -> Self {
+        Self { position, cohashes }
+    }
+
+    pub fn cohashes(&self) -> &[Buf32] {
+        &self.cohashes
+    }
+
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Computes the root of the tree the proof commits `leaf` to.
+    pub fn compute_root(&self, leaf: Buf32) -> Buf32 {
+        let mut cur_hash = leaf;
+        let mut pos = self.position;
+        for cohash in &self.cohashes {
+            let mut buf = [0u8; 64];
+            if pos & 1 == 0 {
+                buf[..32].copy_from_slice(cur_hash.as_ref());
+                buf[32..].copy_from_slice(cohash.as_ref());
+            } else {
+                buf[..32].copy_from_slice(cohash.as_ref());
+                buf[32..].copy_from_slice(cur_hash.as_ref());
+            }
+            cur_hash = sha256d(&buf);
+            pos >>= 1;
+        }
+        cur_hash
+    }
+
+    /// Checks that `leaf` is included in the tree with the given `root`.
+    pub fn verify(&self, leaf: Buf32, root: Buf32) -> bool {
+        // A position with bits above the tree height would just get shifted out, so it would be
+        // possible to "prove" the same leaf at several positions.
+        let height = self.cohashes.len();
+        if height < u32::BITS as usize && self.position >> height != 0 {
+            return false;
+        }
+
+        self.compute_root(leaf) == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, merkle_tree, TxMerkleNode};
+
+    use super::MerkleProof;
+    use crate::{buf::Buf32, utils::get_cohashes_from_wtxids};
+
+    fn gen_leaves(n: u8) -> Vec<Buf32> {
+        (1..=n).map(|i| Buf32::from([i; 32])).collect()
+    }
+
+    /// Computes the root with the `bitcoin` crate's implementation.
+    fn bitcoin_root(leaves: &[Buf32]) -> Buf32 {
+        let hashes = leaves
+            .iter()
+            .map(|l| TxMerkleNode::from_byte_array(*l.as_ref()));
+        let root = merkle_tree::calculate_root(hashes).expect("leaves must not be empty");
+        Buf32::from(root.to_byte_array())
+    }
+
+    fn gen_proof(leaves: &[Buf32], position: u32) -> MerkleProof {
+        let wtxids: Vec<_> = leaves
+            .iter()
+            .map(|l| bitcoin::Wtxid::from_byte_array(*l.as_ref()))
+            .collect();
+        let (cohashes, _) = get_cohashes_from_wtxids(&wtxids, position);
+        MerkleProof::new(position, cohashes)
+    }
+
+    #[test]
+    fn test_verify_all_positions() {
+        // odd and even numbers of leaves, odd ones hit the duplicate-last-node case
+        for n in 1..=9 {
+            let leaves = gen_leaves(n);
+            let root = bitcoin_root(&leaves);
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = gen_proof(&leaves, i as u32);
+                assert_eq!(proof.compute_root(*leaf), root, "n = {n}, i = {i}");
+                assert!(proof.verify(*leaf, root), "n = {n}, i = {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_duplicated_last_leaf() {
+        let leaves = gen_leaves(7);
+        let root = bitcoin_root(&leaves);
+
+        // the last leaf is its own sibling
+        let proof = gen_proof(&leaves, 6);
+        assert_eq!(proof.cohashes()[0], leaves[6]);
+        assert!(proof.verify(leaves[6], root));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_leaf() {
+        let leaves = gen_leaves(5);
+        let root = bitcoin_root(&leaves);
+
+        let proof = gen_proof(&leaves, 2);
+        assert!(!proof.verify(leaves[3], root));
+        assert!(!proof.verify(leaves[2], Buf32::zero()));
+    }
+
+    #[test]
+    fn test_verify_rejects_out_of_range_position() {
+        let leaves = gen_leaves(4);
+        let root = bitcoin_root(&leaves);
+
+        let proof = gen_proof(&leaves, 1);
+        let shifted = MerkleProof::new(1 + 4, proof.cohashes().to_vec());
+        assert_eq!(shifted.compute_root(leaves[1]), root);
+        assert!(!shifted.verify(leaves[1], root));
+    }
+}
@@ -1,14 +1,34 @@
 //! Global consensus parameters for the rollup.
 
+use anyhow::anyhow;
+use bitcoin::{
+    secp256k1::{PublicKey, SECP256K1},
+    taproot::TaprootBuilder,
+    Address, AddressType, ScriptBuf, WitnessProgram, WitnessVersion, XOnlyPublicKey,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
+use musig2::KeyAggContext;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    block_credential::CredRule, l1::BitcoinAddress, operator::OperatorPubkeys, prelude::Buf32,
+    block_credential::CredRule,
+    errors::ParseError,
+    l1::{BitcoinAddress, XOnlyPk},
+    operator::OperatorPubkeys,
+    prelude::Buf32,
     proof::RollupVerifyingKey,
 };
 
+/// Version of the encoding [`RollupParams::compute_hash`] commits to.  Has to
+/// be bumped whenever fields are added to or removed from [`RollupParams`],
+/// since that changes the commitment even for networks that don't use them.
+///
+/// Version 1 added `inscription_tag` and `deposit_magic_bytes`.
+/// Version 2 added `batched_inscriptions`.
+/// Version 3 added `federation_script`.
+pub const ROLLUP_PARAMS_COMMITMENT_VERSION: u8 = 3;
+
 /// Consensus parameters that don't change for the lifetime of the network
 /// (unless there's some weird hard fork).
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -16,6 +36,19 @@ pub struct RollupParams {
     /// Rollup name
     pub rollup_name: String,
 
+    /// Tag the rollup's inscriptions are marked with, the rollup name if unset.
+    #[serde(default)]
+    pub inscription_tag: Option<String>,
+
+    /// Magic bytes deposit requests are marked with, the rollup name if unset.
+    #[serde(default)]
+    pub deposit_magic_bytes: Option<Vec<u8>>,
+
+    /// Whether a reveal tx input can inscribe several blobs, each in its own
+    /// envelope.  Only the first envelope of each input is parsed otherwise.
+    #[serde(default)]
+    pub batched_inscriptions: bool,
+
     /// Block time in milliseconds.
     pub block_time: u64,
 
@@ -49,6 +82,11 @@ pub struct RollupParams {
     /// Exact "at-rest" deposit amount, in sats.
     pub deposit_amount: u64,
 
+    /// Script the federation custodies deposits with, the key path only
+    /// taproot output of the aggregated operator wallet keys if unset.
+    #[serde(default)]
+    pub federation_script: Option<FederationScript>,
+
     /// SP1 verifying key that is used to verify the Groth16 proof posted on Bitcoin
     // FIXME which proof?  should this be `checkpoint_vk`?
     pub rollup_vk: RollupVerifyingKey,
@@ -79,6 +117,14 @@ impl RollupParams {
             return Err(ParamsError::EmptyRollupName);
         }
 
+        if self.inscription_tag().is_empty() {
+            return Err(ParamsError::EmptyProperty("inscription_tag"));
+        }
+
+        if self.deposit_magic_bytes().is_empty() {
+            return Err(ParamsError::EmptyProperty("deposit_magic_bytes"));
+        }
+
         match &self.operator_config {
             OperatorConfig::Static(optbl) => {
                 if optbl.is_empty() {
@@ -119,14 +165,86 @@ impl RollupParams {
         Ok(())
     }
 
+    /// Computes the commitment to the params, over the encoding version and
+    /// the params themselves.
     pub fn compute_hash(&self) -> Buf32 {
-        let raw_bytes = bincode::serialize(&self).expect("rollup params serialization failed");
+        let raw_bytes = bincode::serialize(&(ROLLUP_PARAMS_COMMITMENT_VERSION, self))
+            .expect("rollup params serialization failed");
         crate::hash::raw(&raw_bytes)
     }
 
     pub fn rollup_vk(&self) -> RollupVerifyingKey {
         self.rollup_vk
     }
+
+    /// Returns the tag the rollup's inscriptions are marked with, which both
+    /// the inscription writer and the L1 parsers have to agree on.
+    pub fn inscription_tag(&self) -> &str {
+        self.inscription_tag.as_deref().unwrap_or(&self.rollup_name)
+    }
+
+    /// Returns the magic bytes deposit requests to the rollup start with.
+    pub fn deposit_magic_bytes(&self) -> &[u8] {
+        self.deposit_magic_bytes
+            .as_deref()
+            .unwrap_or(self.rollup_name.as_bytes())
+    }
+
+    /// Returns the wallet pubkeys of the operators in the genesis operator
+    /// table.
+    // FIXME: This is only for devnet as these pks have to be read from the chain state
+    pub fn operator_wallet_pks(&self) -> Vec<Buf32> {
+        let OperatorConfig::Static(operator_table) = &self.operator_config;
+
+        operator_table.iter().map(|op| *op.wallet_pk()).collect()
+    }
+
+    /// Returns the script the federation custodies deposits with, derived
+    /// from the operator table unless the params set one.
+    pub fn federation_script(&self) -> anyhow::Result<FederationScript> {
+        match &self.federation_script {
+            Some(script) => Ok(script.clone()),
+            None => Ok(FederationScript::from_address(
+                &self.operator_taproot_address()?,
+            )?),
+        }
+    }
+
+    /// Returns the address the federation custodies deposits at.
+    pub fn federation_address(&self) -> anyhow::Result<BitcoinAddress> {
+        let script_pubkey = self.federation_script()?.script_pubkey();
+        let addr = Address::from_script(&script_pubkey, self.network)?;
+
+        Ok(BitcoinAddress::parse(&addr.to_string(), self.network)?)
+    }
+
+    /// Derives the key path only taproot address of the aggregated operator
+    /// wallet keys.
+    fn operator_taproot_address(&self) -> anyhow::Result<BitcoinAddress> {
+        let keys = self.operator_wallet_pks().into_iter().map(|pk| {
+            PublicKey::from_x_only_public_key(
+                XOnlyPublicKey::from_slice(pk.as_ref()).expect("slice not an x-only public key"),
+                bitcoin::key::Parity::Even,
+            )
+        });
+
+        let x_only_pub_key = KeyAggContext::new(keys)?
+            .aggregated_pubkey::<PublicKey>()
+            .x_only_public_key()
+            .0;
+
+        let spend_info = TaprootBuilder::new()
+            .finalize(SECP256K1, x_only_pub_key)
+            .map_err(|_| anyhow!("taproot finalization"))?;
+        let addr = Address::p2tr(
+            SECP256K1,
+            x_only_pub_key,
+            spend_info.merkle_root(),
+            self.network,
+        );
+
+        Ok(BitcoinAddress::parse(&addr.to_string(), self.network)?)
+    }
 }
 
 /// Configuration common among deposit and deposit request transaction
@@ -143,19 +261,64 @@ pub struct DepositTxParams {
     // TODO: rename this to deposit_denominations and set the type to be a vec(possibly sorted)
     pub deposit_amount: u64,
 
-    /// federation address derived from operator entries
-    pub address: BitcoinAddress,
+    /// Federation script the deposit output must be locked to.
+    pub federation_script: FederationScript,
 }
 
 impl RollupParams {
-    pub fn get_deposit_params(&self, address: BitcoinAddress) -> DepositTxParams {
+    pub fn get_deposit_params(&self, federation_script: FederationScript) -> DepositTxParams {
         DepositTxParams {
-            magic_bytes: self.rollup_name.clone().into_bytes().to_vec(),
+            magic_bytes: self.deposit_magic_bytes().to_vec(),
             address_length: self.address_length,
             deposit_amount: self.deposit_amount,
-            address,
+            federation_script,
+        }
+    }
+}
+
+/// Output script the federation custodies deposits with.
+///
+/// Taproot is what we derive from the operator table by default, but some custody setups use a
+/// P2WSH multisig instead.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FederationScript {
+    /// P2TR output locked to the (tweaked) federation key.
+    Taproot(XOnlyPk),
+
+    /// P2WSH output locked to the hash of the federation's witness script.
+    Wsh(Buf32),
+}
+
+impl FederationScript {
+    /// Extracts the federation script from a P2TR or P2WSH address.
+    pub fn from_address(addr: &BitcoinAddress) -> Result<Self, ParseError> {
+        match addr.address().address_type() {
+            Some(AddressType::P2tr) => Ok(Self::Taproot(XOnlyPk::from_address(addr)?)),
+            Some(AddressType::P2wsh) => {
+                let script_pubkey = addr.address().script_pubkey();
+
+                // skip the version and length bytes
+                let mut hash = [0; 32];
+                hash.copy_from_slice(&script_pubkey.as_bytes()[2..34]);
+
+                Ok(Self::Wsh(Buf32(hash)))
+            }
+            other => Err(ParseError::UnsupportedAddress(other)),
         }
     }
+
+    /// Returns the `script_pubkey` a deposit output to the federation must have.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        let (version, program) = match self {
+            Self::Taproot(pk) => (WitnessVersion::V1, pk.inner()),
+            Self::Wsh(hash) => (WitnessVersion::V0, hash),
+        };
+
+        let program = WitnessProgram::new(version, program.as_slice())
+            .expect("32-byte witness programs are always valid");
+        ScriptBuf::new_witness_program(&program)
+    }
 }
 
 /// Describes how we decide to wait for proofs for checkpoints to generate.
@@ -188,6 +351,112 @@ pub struct SyncParams {
 
     /// Max number of recent l2 blocks that can be fetched from RPC
     pub l2_blocks_fetch_limit: u64,
+
+    /// If operators are allowed to force the finalization of L2 blocks, meant for recovering
+    /// from incidents.  Off unless explicitly enabled.
+    #[serde(default)]
+    pub allow_force_finalize: bool,
+
+    /// Max size of an encoded L2 block bundle we're willing to store, in bytes.
+    #[serde(default = "default_max_block_size")]
+    pub max_block_size: usize,
+
+    /// Max number of L2 blocks of the current chain fork choice is willing to revert when
+    /// switching to a different tip.
+    #[serde(default = "default_max_reorg_depth")]
+    pub max_reorg_depth: u64,
+
+    /// Interval of the client state checkpoints we keep around once they're
+    /// behind finality, the ones in between get pruned.  Has to be a multiple
+    /// of `client_checkpoint_interval`.
+    #[serde(default = "default_client_state_retention_interval")]
+    pub client_state_retention_interval: u64,
+
+    /// How far ahead of our clock an L2 block's timestamp can be before fork
+    /// choice refuses to accept it, in milliseconds.
+    #[serde(default = "default_max_block_time_drift")]
+    pub max_block_time_drift: u64,
+
+    /// How long we wait for the EL to check a block's payload before leaving
+    /// the block unverified, in milliseconds.
+    #[serde(default = "default_el_check_timeout")]
+    pub el_check_timeout: u64,
+}
+
+/// Default for [`SyncParams::max_block_size`].
+pub const DEFAULT_MAX_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+fn default_max_block_size() -> usize {
+    DEFAULT_MAX_BLOCK_SIZE
+}
+
+/// Default for [`SyncParams::max_reorg_depth`].
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 100;
+
+fn default_max_reorg_depth() -> u64 {
+    DEFAULT_MAX_REORG_DEPTH
+}
+
+/// Default for [`SyncParams::client_state_retention_interval`].
+pub const DEFAULT_CLIENT_STATE_RETENTION_INTERVAL: u64 = 1000;
+
+fn default_client_state_retention_interval() -> u64 {
+    DEFAULT_CLIENT_STATE_RETENTION_INTERVAL
+}
+
+/// Default for [`SyncParams::max_block_time_drift`].
+pub const DEFAULT_MAX_BLOCK_TIME_DRIFT: u64 = 15_000;
+
+fn default_max_block_time_drift() -> u64 {
+    DEFAULT_MAX_BLOCK_TIME_DRIFT
+}
+
+/// Default for [`SyncParams::el_check_timeout`].
+pub const DEFAULT_EL_CHECK_TIMEOUT: u64 = 10_000;
+
+fn default_el_check_timeout() -> u64 {
+    DEFAULT_EL_CHECK_TIMEOUT
+}
+
+impl SyncParams {
+    pub fn check_well_formed(&self) -> Result<(), ParamsError> {
+        if self.l1_follow_distance == 0 {
+            return Err(ParamsError::ZeroProperty("l1_follow_distance"));
+        }
+
+        if self.client_checkpoint_interval == 0 {
+            return Err(ParamsError::ZeroProperty("client_checkpoint_interval"));
+        }
+
+        if self.l2_blocks_fetch_limit == 0 {
+            return Err(ParamsError::ZeroProperty("l2_blocks_fetch_limit"));
+        }
+
+        if self.max_block_size == 0 {
+            return Err(ParamsError::ZeroProperty("max_block_size"));
+        }
+
+        if self.max_reorg_depth == 0 {
+            return Err(ParamsError::ZeroProperty("max_reorg_depth"));
+        }
+
+        if self.client_state_retention_interval == 0 {
+            return Err(ParamsError::ZeroProperty("client_state_retention_interval"));
+        }
+
+        if self.el_check_timeout == 0 {
+            return Err(ParamsError::ZeroProperty("el_check_timeout"));
+        }
+
+        if self.client_state_retention_interval % self.client_checkpoint_interval as u64 != 0 {
+            return Err(ParamsError::RetentionNotCheckpointMultiple(
+                self.client_state_retention_interval,
+                self.client_checkpoint_interval,
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 /// Combined set of parameters across all the consensus logic.
@@ -209,6 +478,12 @@ impl Params {
     pub fn network(&self) -> bitcoin::Network {
         self.rollup.network
     }
+
+    /// Checks that both the rollup and sync params are well formed.
+    pub fn validate(&self) -> Result<(), ParamsError> {
+        self.rollup.check_well_formed()?;
+        self.run.check_well_formed()
+    }
 }
 
 /// Describes how we determine the list of operators at genesis.
@@ -229,11 +504,17 @@ pub enum ParamsError {
     #[error("{0} must not be 0")]
     ZeroProperty(&'static str),
 
+    #[error("{0} must not be empty")]
+    EmptyProperty(&'static str),
+
     #[error("horizon block {0} after genesis trigger block {1}")]
     HorizonAfterGenesis(u64, u64),
 
     #[error("no operators set")]
     NoOperators,
+
+    #[error("client state retention interval {0} not a multiple of checkpoint interval {1}")]
+    RetentionNotCheckpointMultiple(u64, u32),
 }
 
 impl OperatorConfig {
@@ -244,3 +525,224 @@ impl OperatorConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_params() -> Params {
+        Params {
+            rollup: RollupParams {
+                rollup_name: "strata".to_string(),
+                inscription_tag: None,
+                deposit_magic_bytes: None,
+                batched_inscriptions: false,
+                block_time: 1000,
+                cred_rule: CredRule::Unchecked,
+                horizon_l1_height: 3,
+                genesis_l1_height: 5,
+                operator_config: OperatorConfig::Static(vec![OperatorPubkeys::new(
+                    Buf32::from([1; 32]),
+                    Buf32::from([2; 32]),
+                )]),
+                evm_genesis_block_hash: Buf32::zero(),
+                evm_genesis_block_state_root: Buf32::zero(),
+                l1_reorg_safe_depth: 3,
+                target_l2_batch_size: 64,
+                address_length: 20,
+                deposit_amount: 1_000_000_000,
+                federation_script: None,
+                rollup_vk: RollupVerifyingKey::SP1VerifyingKey(Buf32::zero()),
+                dispatch_assignment_dur: 64,
+                proof_publish_mode: ProofPublishMode::Strict,
+                max_deposits_in_block: 16,
+                network: bitcoin::Network::Regtest,
+            },
+            run: SyncParams {
+                l1_follow_distance: 3,
+                client_checkpoint_interval: 10,
+                l2_blocks_fetch_limit: 1000,
+                allow_force_finalize: false,
+                max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+                max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+                client_state_retention_interval: DEFAULT_CLIENT_STATE_RETENTION_INTERVAL,
+                max_block_time_drift: DEFAULT_MAX_BLOCK_TIME_DRIFT,
+                el_check_timeout: DEFAULT_EL_CHECK_TIMEOUT,
+            },
+        }
+    }
+
+    fn assert_zero_property(params: Params, expected: &str) {
+        match params.validate() {
+            Err(ParamsError::ZeroProperty(prop)) => assert_eq!(prop, expected),
+            res => panic!("expected {expected} to be rejected, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_valid_params() {
+        make_params().validate().expect("test: valid params");
+    }
+
+    #[test]
+    fn test_validate_zero_properties() {
+        let cases: &[(&str, fn(&mut Params))] = &[
+            ("block_time", |p| p.rollup.block_time = 0),
+            ("l1_reorg_safe_depth", |p| p.rollup.l1_reorg_safe_depth = 0),
+            ("target_l2_batch_size", |p| {
+                p.rollup.target_l2_batch_size = 0
+            }),
+            ("max_address_length", |p| p.rollup.address_length = 0),
+            ("deposit_amount", |p| p.rollup.deposit_amount = 0),
+            ("dispatch_assignment_dur", |p| {
+                p.rollup.dispatch_assignment_dur = 0
+            }),
+            ("max_deposits_in_block", |p| {
+                p.rollup.max_deposits_in_block = 0
+            }),
+            ("l1_follow_distance", |p| p.run.l1_follow_distance = 0),
+            ("client_checkpoint_interval", |p| {
+                p.run.client_checkpoint_interval = 0
+            }),
+            ("l2_blocks_fetch_limit", |p| p.run.l2_blocks_fetch_limit = 0),
+            ("max_block_size", |p| p.run.max_block_size = 0),
+            ("max_reorg_depth", |p| p.run.max_reorg_depth = 0),
+            ("client_state_retention_interval", |p| {
+                p.run.client_state_retention_interval = 0
+            }),
+            ("el_check_timeout", |p| p.run.el_check_timeout = 0),
+        ];
+
+        for &(prop, modify) in cases {
+            let mut params = make_params();
+            modify(&mut params);
+            assert_zero_property(params, prop);
+        }
+    }
+
+    #[test]
+    fn test_validate_horizon_after_genesis() {
+        let mut params = make_params();
+        params.rollup.horizon_l1_height = 6;
+
+        assert!(matches!(
+            params.validate(),
+            Err(ParamsError::HorizonAfterGenesis(6, 5))
+        ));
+
+        // horizon and genesis at the same height is fine
+        params.rollup.horizon_l1_height = 5;
+        params.validate().expect("test: horizon at genesis");
+    }
+
+    #[test]
+    fn test_validate_retention_interval() {
+        let mut params = make_params();
+        params.run.client_state_retention_interval = 25;
+
+        assert!(matches!(
+            params.validate(),
+            Err(ParamsError::RetentionNotCheckpointMultiple(25, 10))
+        ));
+
+        params.run.client_state_retention_interval = 30;
+        params
+            .validate()
+            .expect("test: retention interval multiple");
+    }
+
+    #[test]
+    fn test_validate_empty_rollup_name() {
+        let mut params = make_params();
+        params.rollup.rollup_name = String::new();
+
+        assert!(matches!(
+            params.validate(),
+            Err(ParamsError::EmptyRollupName)
+        ));
+    }
+
+    #[test]
+    fn test_l1_identity_defaults_to_rollup_name() {
+        let mut params = make_params();
+        assert_eq!(params.rollup.inscription_tag(), "strata");
+        assert_eq!(params.rollup.deposit_magic_bytes(), b"strata");
+
+        params.rollup.inscription_tag = Some("strata-insc".to_string());
+        params.rollup.deposit_magic_bytes = Some(b"stratadep".to_vec());
+        assert_eq!(params.rollup.inscription_tag(), "strata-insc");
+        let deposit_params = params
+            .rollup
+            .get_deposit_params(FederationScript::Wsh(Buf32::zero()));
+        assert_eq!(deposit_params.magic_bytes, b"stratadep");
+
+        // unset fields don't have to be in the params file
+        let json = serde_json::to_value(&make_params().rollup).unwrap();
+        let mut obj = json.as_object().unwrap().clone();
+        obj.remove("inscription_tag");
+        obj.remove("deposit_magic_bytes");
+        obj.remove("batched_inscriptions");
+        obj.remove("federation_script");
+        let parsed: RollupParams = serde_json::from_value(obj.into()).unwrap();
+        assert_eq!(parsed, make_params().rollup);
+    }
+
+    #[test]
+    fn test_compute_hash() {
+        let params = make_params().rollup;
+
+        // the version is committed to along with the params
+        let unversioned = bincode::serialize(&params).unwrap();
+        assert_ne!(params.compute_hash(), crate::hash::raw(&unversioned));
+        assert_eq!(params.compute_hash(), make_params().rollup.compute_hash());
+
+        // and so is the L1 identity, even though it defaults to the rollup name
+        let mut with_tag = params.clone();
+        with_tag.inscription_tag = Some(params.rollup_name.clone());
+        assert_eq!(with_tag.inscription_tag(), params.inscription_tag());
+        assert_ne!(with_tag.compute_hash(), params.compute_hash());
+
+        // as is the federation script
+        let mut with_script = params.clone();
+        with_script.federation_script = Some(FederationScript::Wsh(Buf32::from([7; 32])));
+        assert_ne!(with_script.compute_hash(), params.compute_hash());
+    }
+
+    #[test]
+    fn test_federation_script() {
+        let mut params = make_params().rollup;
+
+        // a configured script takes over from the operator keys
+        let wsh = FederationScript::Wsh(Buf32::from([7; 32]));
+        params.federation_script = Some(wsh.clone());
+        assert_eq!(params.federation_script().unwrap(), wsh);
+        let addr = params.federation_address().unwrap();
+        assert_eq!(addr.address().address_type(), Some(AddressType::P2wsh));
+        assert_eq!(FederationScript::from_address(&addr).unwrap(), wsh);
+    }
+
+    #[test]
+    fn test_validate_empty_l1_identity() {
+        let mut params = make_params();
+        params.rollup.inscription_tag = Some(String::new());
+        assert!(matches!(
+            params.validate(),
+            Err(ParamsError::EmptyProperty("inscription_tag"))
+        ));
+
+        let mut params = make_params();
+        params.rollup.deposit_magic_bytes = Some(Vec::new());
+        assert!(matches!(
+            params.validate(),
+            Err(ParamsError::EmptyProperty("deposit_magic_bytes"))
+        ));
+    }
+
+    #[test]
+    fn test_validate_no_operators() {
+        let mut params = make_params();
+        params.rollup.operator_config = OperatorConfig::Static(vec![]);
+
+        assert!(matches!(params.validate(), Err(ParamsError::NoOperators)));
+    }
+}
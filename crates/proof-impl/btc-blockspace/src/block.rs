@@ -55,25 +55,7 @@ pub fn compute_witness_commitment(
 /// Computes the block witness root from corresponding proof in [`L1Tx`]
 pub fn compute_witness_root(l1_tx: &L1Tx) -> Buf32 {
     let tx: Transaction = consensus::deserialize(l1_tx.tx_data()).unwrap();
-
-    // `cur_hash` represents the intermediate hash at each step. After all cohashes are processed
-    // `cur_hash` becomes the root hash
-    let mut cur_hash = *compute_wtxid(&tx).as_ref();
-
-    let mut pos = l1_tx.proof().position();
-    for cohash in l1_tx.proof().cohashes() {
-        let mut buf = [0u8; 64];
-        if pos & 1 == 0 {
-            buf[0..32].copy_from_slice(&cur_hash);
-            buf[32..64].copy_from_slice(cohash.as_ref());
-        } else {
-            buf[0..32].copy_from_slice(cohash.as_ref());
-            buf[32..64].copy_from_slice(&cur_hash);
-        }
-        cur_hash = *sha256d(&buf).as_ref();
-        pos >>= 1;
-    }
-    Buf32::from(cur_hash)
+    l1_tx.proof().compute_root(compute_wtxid(&tx))
 }
 
 /// Checks if witness commitment in coinbase matches the corresponding [`L1Tx`].
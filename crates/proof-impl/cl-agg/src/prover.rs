@@ -1,12 +1,20 @@
+use std::sync::Arc;
+
 use strata_proofimpl_cl_stf::L2BatchProofOutput;
 use strata_zkvm::{
-    AggregationInput, ProofReceipt, PublicValues, VerificationKey, ZkVmInputResult, ZkVmProver,
-    ZkVmResult,
+    AggregationInput, ProofReceipt, PublicValues, VkRegistry, ZkVmInputError, ZkVmInputResult,
+    ZkVmProver, ZkVmResult,
 };
 
+/// Name the CL STF program's verification key is registered under.
+pub const CL_STF_PROGRAM: &str = "cl_stf";
+
 pub struct ClAggInput {
     pub batch: Vec<ProofReceipt>,
-    pub cl_stf_vk: VerificationKey,
+
+    /// Keys the proofs in the batch are checked against, has to have the
+    /// [`CL_STF_PROGRAM`] one.
+    pub vk_registry: Arc<VkRegistry>,
 }
 
 pub struct ClAggProver;
@@ -23,15 +31,17 @@ impl ZkVmProver for ClAggProver {
     where
         B: strata_zkvm::ZkVmInputBuilder<'a>,
     {
+        let cl_stf_vk = input
+            .vk_registry
+            .get(CL_STF_PROGRAM)
+            .map_err(ZkVmInputError::VerificationKey)?;
+
         let len = input.batch.len() as u32;
         let mut input_builder = B::new();
         input_builder.write_serde(&len)?;
 
         for proof in &input.batch {
-            input_builder.write_proof(&AggregationInput::new(
-                proof.clone(),
-                input.cl_stf_vk.clone(),
-            ))?;
+            input_builder.write_proof(&AggregationInput::new(proof.clone(), cl_stf_vk.clone()))?;
         }
 
         input_builder.build()
@@ -0,0 +1,222 @@
+use std::{collections::HashSet, sync::Arc};
+
+use rockbound::{OptimisticTransactionDB, SchemaBatch, SchemaDBOperationsExt};
+use strata_db::{
+    errors::DbError,
+    traits::{AtomicBatch, AtomicBatchDatabase, BlockStatus},
+    DbResult,
+};
+use strata_state::{client_state::ClientState, id::L2BlockId, operation::ClientUpdateOutput};
+
+use crate::{
+    client_state::schemas::{ClientStateSchema, ClientUpdateOutputSchema},
+    l2::schemas::{L2BlockSchema, L2BlockStatusSchema},
+    utils::get_last_idx,
+};
+
+/// Creates [`RBAtomicBatch`]es over the stores sharing a rocksdb instance.
+pub struct AtomicBatchDb {
+    db: Arc<OptimisticTransactionDB>,
+}
+
+impl AtomicBatchDb {
+    /// Wraps an existing database handle.
+    ///
+    /// Assumes it was opened with column families as defined in `STORE_COLUMN_FAMILIES`.
+    pub fn new(db: Arc<OptimisticTransactionDB>) -> Self {
+        Self { db }
+    }
+}
+
+impl AtomicBatchDatabase for AtomicBatchDb {
+    type Batch = RBAtomicBatch;
+
+    fn new_batch(&self) -> Self::Batch {
+        RBAtomicBatch::new(self.db.clone())
+    }
+}
+
+/// Batch of writes across column families that gets committed as a single
+/// rocksdb write batch.
+pub struct RBAtomicBatch {
+    db: Arc<OptimisticTransactionDB>,
+    batch: SchemaBatch,
+
+    /// Idx of the last consensus output queued in the batch.
+    last_output_idx: Option<u64>,
+
+    /// Idxs of the consensus checkpoints queued in the batch.
+    checkpoint_idxs: HashSet<u64>,
+}
+
+impl RBAtomicBatch {
+    fn new(db: Arc<OptimisticTransactionDB>) -> Self {
+        Self {
+            db,
+            batch: SchemaBatch::new(),
+            last_output_idx: None,
+            checkpoint_idxs: HashSet::new(),
+        }
+    }
+}
+
+impl AtomicBatch for RBAtomicBatch {
+    fn put_client_update_output(&mut self, idx: u64, output: ClientUpdateOutput) -> DbResult<()> {
+        let last_idx = match self.last_output_idx {
+            Some(idx) => Some(idx),
+            None => get_last_idx::<ClientUpdateOutputSchema>(&self.db)?,
+        };
+        let expected_idx = last_idx.map_or(1, |idx| idx + 1);
+        if idx != expected_idx {
+            return Err(DbError::OooInsert("consensus_store", idx));
+        }
+
+        self.batch.put::<ClientUpdateOutputSchema>(&idx, &output)?;
+        self.last_output_idx = Some(idx);
+        Ok(())
+    }
+
+    fn put_client_state_checkpoint(&mut self, idx: u64, state: ClientState) -> DbResult<()> {
+        if self.checkpoint_idxs.contains(&idx) || self.db.get::<ClientStateSchema>(&idx)?.is_some()
+        {
+            return Err(DbError::OverwriteConsensusCheckpoint(idx));
+        }
+
+        self.batch.put::<ClientStateSchema>(&idx, &state)?;
+        self.checkpoint_idxs.insert(idx);
+        Ok(())
+    }
+
+    fn put_block_status(&mut self, id: L2BlockId, status: BlockStatus) -> DbResult<()> {
+        // same as `L2BlockDatabase::set_block_status`, unknown blocks are ignored
+        if self.db.get::<L2BlockSchema>(&id)?.is_none() {
+            return Ok(());
+        }
+
+        self.batch.put::<L2BlockStatusSchema>(&id, &status)?;
+        Ok(())
+    }
+
+    fn commit(self) -> DbResult<()> {
+        self.db.write_schemas(self.batch)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strata_db::traits::{ClientStateDatabase, L2BlockDatabase};
+    use strata_state::header::L2Header;
+    use strata_test_utils::{l2::gen_l2_chain, ArbitraryGenerator};
+
+    use super::*;
+    use crate::{l2::db::L2Db, test_utils::get_rocksdb_tmp_instance, ClientStateDb};
+
+    struct TestDbs {
+        batch_db: AtomicBatchDb,
+        cs_db: ClientStateDb,
+        l2_db: L2Db,
+        blkid: L2BlockId,
+    }
+
+    fn setup_dbs() -> TestDbs {
+        let (rbdb, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let batch_db = AtomicBatchDb::new(rbdb.clone());
+        let cs_db = ClientStateDb::new(rbdb.clone(), db_ops);
+        let l2_db = L2Db::new(rbdb, db_ops);
+
+        let block = gen_l2_chain(None, 0).pop().unwrap();
+        let blkid = block.header().get_blockid();
+        l2_db.put_block_data(block).unwrap();
+
+        TestDbs {
+            batch_db,
+            cs_db,
+            l2_db,
+            blkid,
+        }
+    }
+
+    /// Queues one write to each of the column families.
+    fn fill_batch(dbs: &TestDbs) -> RBAtomicBatch {
+        let mut arb = ArbitraryGenerator::new();
+        let output: ClientUpdateOutput = arb.generate();
+        let state: ClientState = arb.generate();
+
+        let mut batch = dbs.batch_db.new_batch();
+        batch.put_client_update_output(1, output).unwrap();
+        batch.put_client_state_checkpoint(1, state).unwrap();
+        batch
+            .put_block_status(dbs.blkid, BlockStatus::Invalid)
+            .unwrap();
+        batch
+    }
+
+    fn assert_nothing_written(dbs: &TestDbs) {
+        assert!(dbs.cs_db.get_client_update_actions(1).unwrap().is_none());
+        assert!(dbs.cs_db.get_state_checkpoint(1).unwrap().is_none());
+        assert_eq!(
+            dbs.l2_db.get_block_status(dbs.blkid).unwrap(),
+            Some(BlockStatus::Unchecked)
+        );
+    }
+
+    #[test]
+    fn test_commit() {
+        let dbs = setup_dbs();
+
+        let batch = fill_batch(&dbs);
+        assert_nothing_written(&dbs);
+
+        batch.commit().unwrap();
+        assert!(dbs.cs_db.get_client_update_actions(1).unwrap().is_some());
+        assert!(dbs.cs_db.get_state_checkpoint(1).unwrap().is_some());
+        assert_eq!(
+            dbs.l2_db.get_block_status(dbs.blkid).unwrap(),
+            Some(BlockStatus::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_crash_before_commit() {
+        let dbs = setup_dbs();
+
+        // simulate crashing after queuing everything but before committing
+        let batch = fill_batch(&dbs);
+        drop(batch);
+
+        assert_nothing_written(&dbs);
+    }
+
+    #[test]
+    fn test_failure_mid_batch() {
+        let dbs = setup_dbs();
+
+        // the caller bails out on the first error, dropping the batch
+        let mut batch = fill_batch(&dbs);
+        let output: ClientUpdateOutput = ArbitraryGenerator::new().generate();
+        let res = batch.put_client_update_output(3, output);
+        assert!(matches!(res, Err(DbError::OooInsert("consensus_store", 3))));
+        let res = batch.put_client_state_checkpoint(1, ArbitraryGenerator::new().generate());
+        assert!(matches!(res, Err(DbError::OverwriteConsensusCheckpoint(1))));
+        drop(batch);
+
+        assert_nothing_written(&dbs);
+    }
+
+    #[test]
+    fn test_batch_checks_earlier_writes() {
+        let dbs = setup_dbs();
+        fill_batch(&dbs).commit().unwrap();
+
+        let mut arb = ArbitraryGenerator::new();
+        let mut batch = dbs.batch_db.new_batch();
+        let res = batch.put_client_update_output(1, arb.generate());
+        assert!(matches!(res, Err(DbError::OooInsert("consensus_store", 1))));
+        batch.put_client_update_output(2, arb.generate()).unwrap();
+        batch.put_client_update_output(3, arb.generate()).unwrap();
+        batch.commit().unwrap();
+
+        assert_eq!(dbs.cs_db.get_last_write_idx().unwrap(), 3);
+    }
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
-use rockbound::{OptimisticTransactionDB, Schema, SchemaDBOperationsExt};
+use rockbound::{OptimisticTransactionDB, Schema, SchemaBatch, SchemaDBOperationsExt};
 use strata_db::{errors::*, traits::*, DbResult};
 use strata_state::operation::*;
 
-use super::schemas::{ClientStateSchema, ClientUpdateOutputSchema};
+use super::schemas::{
+    ClientActionsAckSchema, ClientStatePruneSchema, ClientStateSchema, ClientUpdateOutputSchema,
+};
 use crate::DbOpsConfig;
 
 pub struct ClientStateDb {
@@ -87,10 +89,12 @@ impl ClientStateDatabase for ClientStateDb {
     }
 
     fn get_last_checkpoint_idx(&self) -> DbResult<u64> {
-        match self.get_last_idx::<ClientStateSchema>()? {
-            Some(idx) => Ok(idx),
-            None => Err(DbError::NotBootstrapped),
-        }
+        self.get_last_checkpoint_idx_opt()?
+            .ok_or(DbError::NotBootstrapped)
+    }
+
+    fn get_last_checkpoint_idx_opt(&self) -> DbResult<Option<u64>> {
+        self.get_last_idx::<ClientStateSchema>()
     }
 
     fn get_prev_checkpoint_at(&self, idx: u64) -> DbResult<u64> {
@@ -117,7 +121,53 @@ impl ClientStateDatabase for ClientStateDb {
         &self,
         idx: u64,
     ) -> DbResult<Option<strata_state::client_state::ClientState>> {
-        Ok(self.db.get::<ClientStateSchema>(&idx)?)
+        if let Some(state) = self.db.get::<ClientStateSchema>(&idx)? {
+            return Ok(Some(state));
+        }
+
+        match self.db.get::<ClientStatePruneSchema>(&0)? {
+            Some((before_idx, keep_interval)) if idx < before_idx && idx % keep_interval != 0 => {
+                Err(DbError::ClientStatePruned(idx))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn prune_client_states_before(&self, before_idx: u64, keep_interval: u64) -> DbResult<()> {
+        if keep_interval == 0 {
+            return Err(DbError::InvalidArgument);
+        }
+
+        // never move the horizon back, states past it might be gone already
+        let before_idx = match self.db.get::<ClientStatePruneSchema>(&0)? {
+            Some((prev_idx, _)) => before_idx.max(prev_idx),
+            None => before_idx,
+        };
+
+        let mut batch = SchemaBatch::new();
+        for res in self.db.iter::<ClientStateSchema>()? {
+            let (idx, _) = res?.into_tuple();
+            if idx >= before_idx {
+                break;
+            }
+
+            if idx % keep_interval != 0 {
+                batch.delete::<ClientStateSchema>(&idx)?;
+            }
+        }
+        batch.put::<ClientStatePruneSchema>(&0, &(before_idx, keep_interval))?;
+        self.db.write_schemas(batch)?;
+
+        Ok(())
+    }
+
+    fn put_last_acked_action_idx(&self, idx: u64) -> DbResult<()> {
+        self.db.put::<ClientActionsAckSchema>(&0, &idx)?;
+        Ok(())
+    }
+
+    fn get_last_acked_action_idx(&self) -> DbResult<Option<u64>> {
+        Ok(self.db.get::<ClientActionsAckSchema>(&0)?)
     }
 }
 
@@ -217,6 +267,71 @@ mod tests {
         assert_eq!(idx, 3);
     }
 
+    #[test]
+    fn test_get_last_checkpoint_idx_opt() {
+        let db = setup_db();
+
+        // fresh store is just empty, not an error
+        assert_eq!(db.get_last_checkpoint_idx_opt().unwrap(), None);
+        let res = db.get_last_checkpoint_idx();
+        assert!(res.is_err_and(|x| matches!(x, DbError::NotBootstrapped)));
+
+        let state: ClientState = ArbitraryGenerator::new().generate();
+        db.write_client_state_checkpoint(0, state.clone()).unwrap();
+        assert_eq!(db.get_last_checkpoint_idx_opt().unwrap(), Some(0));
+
+        db.write_client_state_checkpoint(5, state).unwrap();
+        assert_eq!(db.get_last_checkpoint_idx_opt().unwrap(), Some(5));
+        assert_eq!(db.get_last_checkpoint_idx().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_prune_client_states() {
+        let state: ClientState = ArbitraryGenerator::new().generate();
+        let db = setup_db();
+
+        for idx in (0..=30).step_by(5) {
+            db.write_client_state_checkpoint(idx, state.clone())
+                .unwrap();
+        }
+
+        db.prune_client_states_before(22, 10).unwrap();
+
+        // only the ones at the interval survive below the horizon
+        for idx in [0, 10, 20, 25, 30] {
+            assert!(db.get_state_checkpoint(idx).unwrap().is_some(), "{idx}");
+        }
+        for idx in [5, 15] {
+            let res = db.get_state_checkpoint(idx);
+            assert!(res.is_err_and(|x| matches!(x, DbError::ClientStatePruned(i) if i == idx)));
+        }
+
+        // nothing was ever written past the last checkpoint
+        assert!(db.get_state_checkpoint(31).unwrap().is_none());
+
+        // checkpoints are still usable to resume from
+        assert_eq!(db.get_prev_checkpoint_at(19).unwrap(), 10);
+        assert_eq!(db.get_last_checkpoint_idx().unwrap(), 30);
+
+        // the horizon doesn't move back
+        db.prune_client_states_before(12, 10).unwrap();
+        let res = db.get_state_checkpoint(15);
+        assert!(res.is_err_and(|x| matches!(x, DbError::ClientStatePruned(15))));
+        assert!(db.get_state_checkpoint(25).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_last_acked_action_idx() {
+        let db = setup_db();
+        assert_eq!(db.get_last_acked_action_idx().unwrap(), None);
+
+        db.put_last_acked_action_idx(3).unwrap();
+        assert_eq!(db.get_last_acked_action_idx().unwrap(), Some(3));
+
+        db.put_last_acked_action_idx(4).unwrap();
+        assert_eq!(db.get_last_acked_action_idx().unwrap(), Some(4));
+    }
+
     #[test]
     fn test_get_previous_checkpoint_at() {
         let state: ClientState = ArbitraryGenerator::new().generate();
@@ -1,6 +1,9 @@
 use strata_state::{client_state::ClientState, operation::ClientUpdateOutput};
 
-use crate::{define_table_with_seek_key_codec, define_table_without_codec, impl_borsh_value_codec};
+use crate::{
+    define_table_with_default_codec, define_table_with_seek_key_codec, define_table_without_codec,
+    impl_borsh_value_codec,
+};
 
 // Consensus Output Schema and corresponding codecs implementation
 define_table_with_seek_key_codec!(
@@ -13,3 +16,15 @@ define_table_with_seek_key_codec!(
     /// Table to store client states.
     (ClientStateSchema) u64 => ClientState
 );
+
+define_table_with_default_codec!(
+    /// Table to store how client states have been pruned, as a single entry at
+    /// key 0 of (prune before idx, kept interval).
+    (ClientStatePruneSchema) u64 => (u64, u64)
+);
+
+define_table_with_default_codec!(
+    /// Table to store the idx up to which the client update actions have been
+    /// applied, as a single entry at key 0.
+    (ClientActionsAckSchema) u64 => u64
+);
@@ -43,6 +43,15 @@ impl L1Db {
 
 impl L1Database for L1Db {
     fn put_block_data(&self, idx: u64, mf: L1BlockManifest, txs: Vec<L1Tx>) -> DbResult<()> {
+        // Re-ingesting the block we already have at the height is fine, which happens if we
+        // restart in the middle of a scan, but a different one means the caller has to revert.
+        if let Some(existing_mf) = self.db.get::<L1BlockSchema>(&idx)? {
+            if existing_mf.block_hash() == mf.block_hash() {
+                return Ok(());
+            }
+            return Err(DbError::L1BlockConflict(idx));
+        }
+
         // If there is latest block then expect the idx to be 1 greater than the block number, else
         // allow arbitrary block number to be inserted
         match self.get_latest_block_number()? {
@@ -155,6 +164,47 @@ impl L1Database for L1Db {
         Ok(Some(txs_refs))
     }
 
+    fn iter_block_txs_range(
+        &self,
+        start_idx: u64,
+        end_idx: u64,
+    ) -> DbResult<impl Iterator<Item = DbResult<(u64, L1TxRef)>> + '_> {
+        let mut options = ReadOptions::default();
+        options.set_iterate_lower_bound(
+            KeyEncoder::<L1BlockSchema>::encode_key(&start_idx)
+                .map_err(|err| DbError::CodecError(err.to_string()))?,
+        );
+        options.set_iterate_upper_bound(
+            KeyEncoder::<L1BlockSchema>::encode_key(&end_idx)
+                .map_err(|err| DbError::CodecError(err.to_string()))?,
+        );
+
+        let blocks = self.db.iter_with_opts::<L1BlockSchema>(options)?;
+        let iter = blocks.flat_map(move |entry| {
+            let res = entry.map_err(DbError::from).and_then(|entry| {
+                let (idx, mf) = entry.into_tuple();
+                let Some(txs) = self.db.get::<TxnSchema>(&mf.block_hash())? else {
+                    warn!(%idx, "missing L1 block body");
+                    return Err(DbError::MissingL1BlockBody(idx));
+                };
+
+                Ok(txs
+                    .into_iter()
+                    .map(|tx| (idx, L1TxRef::from((idx, tx.proof().position()))))
+                    .collect::<Vec<_>>())
+            });
+
+            // flatten the block's txs, or yield the error in their place
+            let (refs, err) = match res {
+                Ok(refs) => (refs, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            refs.into_iter().map(Ok).chain(err.map(Err))
+        });
+
+        Ok(iter)
+    }
+
     fn get_last_mmr_to(&self, idx: u64) -> DbResult<Option<CompactMmr>> {
         Ok(self.db.get::<MmrSchema>(&idx)?)
     }
@@ -183,6 +233,18 @@ impl L1Database for L1Db {
         Ok(self.db.get::<L1BlockSchema>(&idx)?)
     }
 
+    fn get_tip_manifest(&self) -> DbResult<Option<L1BlockManifest>> {
+        let mut iterator = self.db.iter::<L1BlockSchema>()?;
+        iterator.seek_to_last();
+        match iterator.rev().next() {
+            Some(res) => {
+                let (_, mf) = res?.into_tuple();
+                Ok(Some(mf))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn get_txs_from(&self, start_idx: u64) -> DbResult<(Vec<L1Tx>, u64)> {
         let start_key = KeyEncoder::<L1BlockSchema>::encode_key(&start_idx)
             .map_err(|err| DbError::CodecError(err.to_string()))?;
@@ -303,6 +365,62 @@ mod tests {
         assert!(res.is_ok(), "Should successfully insert to db");
     }
 
+    #[test]
+    fn test_get_tip_manifest() {
+        let db = setup_db();
+        assert_eq!(db.get_tip_manifest().unwrap(), None);
+
+        // a block at height 0 isn't mistaken for an empty chain
+        let (mf0, _, _) = insert_block_data(0, &db, 1);
+        assert_eq!(db.get_tip_manifest().unwrap(), Some(mf0));
+
+        let _ = insert_block_data(1, &db, 1);
+        let (mf2, _, _) = insert_block_data(2, &db, 1);
+        assert_eq!(db.get_tip_manifest().unwrap(), Some(mf2));
+
+        db.revert_to_height(1).unwrap();
+        assert_eq!(
+            db.get_tip_manifest().unwrap(),
+            db.get_block_manifest(1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reinsert_same_block() {
+        let db = setup_db();
+        let (mf, txs, _) = insert_block_data(1, &db, 10);
+        let (mf2, txs2, _) = insert_block_data(2, &db, 10);
+
+        // re-putting identical blocks is a no-op
+        db.put_block_data(1, mf.clone(), txs.clone()).unwrap();
+        db.put_block_data(2, mf2.clone(), txs2.clone()).unwrap();
+
+        assert_eq!(db.get_chain_tip().unwrap(), Some(2));
+        assert_eq!(db.get_block_manifest(1).unwrap(), Some(mf));
+        assert_eq!(db.get_block_manifest(2).unwrap(), Some(mf2));
+
+        // and the chain can still be extended after that
+        let mf3: L1BlockManifest = ArbitraryGenerator::new().generate();
+        db.put_block_data(3, mf3, vec![]).unwrap();
+        assert_eq!(db.get_chain_tip().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_insert_conflicting_block() {
+        let db = setup_db();
+        let (mf, _, _) = insert_block_data(1, &db, 10);
+        let _ = insert_block_data(2, &db, 10);
+
+        let conflicting_mf: L1BlockManifest = ArbitraryGenerator::new().generate();
+        assert_ne!(conflicting_mf.block_hash(), mf.block_hash());
+
+        let res = db.put_block_data(1, conflicting_mf, vec![]);
+        assert!(res.is_err_and(|e| matches!(e, DbError::L1BlockConflict(1))));
+
+        // the stored block is untouched
+        assert_eq!(db.get_block_manifest(1).unwrap(), Some(mf));
+    }
+
     #[test]
     fn test_revert_to_invalid_height() {
         let db = setup_db();
@@ -471,6 +589,38 @@ mod tests {
         assert_eq!(block_txs, expected);
     }
 
+    #[test]
+    fn test_iter_block_txs_range() {
+        let db = setup_db();
+
+        // some blocks without any relevant txs in between
+        let num_txs = [3, 0, 2, 0, 0, 1];
+        for (i, n) in num_txs.iter().enumerate() {
+            insert_block_data(i as u64 + 1, &db, *n);
+        }
+
+        let txs = db
+            .iter_block_txs_range(2, 7)
+            .unwrap()
+            .collect::<DbResult<Vec<_>>>()
+            .unwrap();
+        let expected: Vec<(u64, L1TxRef)> =
+            vec![(3, (3, 0).into()), (3, (3, 1).into()), (6, (6, 0).into())];
+        assert_eq!(txs, expected);
+
+        // the end is exclusive
+        let txs = db
+            .iter_block_txs_range(1, 3)
+            .unwrap()
+            .collect::<DbResult<Vec<_>>>()
+            .unwrap();
+        let expected: Vec<(u64, L1TxRef)> = (0..3).map(|i| (1, (1, i).into())).collect();
+        assert_eq!(txs, expected);
+
+        // nothing past the tip
+        assert_eq!(db.iter_block_txs_range(7, 20).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_get_blockid_invalid_range() {
         let db = setup_db();
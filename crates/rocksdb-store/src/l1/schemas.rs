@@ -1,21 +1,47 @@
+use rockbound::{schema::ValueCodec, CodecError};
 use strata_mmr::CompactMmr;
-use strata_primitives::{buf::Buf32, l1::L1BlockManifest};
+use strata_primitives::{
+    buf::Buf32,
+    l1::{L1BlockManifest, L1_MANIFEST_FORMAT_VERSION},
+};
 use strata_state::l1::L1Tx;
 
 use crate::{
     define_table_with_default_codec, define_table_with_seek_key_codec, define_table_without_codec,
-    impl_borsh_value_codec,
+    impl_borsh_value_codec, impl_seek_key_codec,
 };
 
 // Readability for header hash
 type HeaderHash = Buf32;
 
 // L1 Block Schema and corresponding codecs implementation
-define_table_with_seek_key_codec!(
+define_table_without_codec!(
     /// A table to store L1 Block data. Maps block index to header
     (L1BlockSchema) u64 => L1BlockManifest
 );
 
+impl_seek_key_codec!(L1BlockSchema, u64);
+
+/// Manifests are written with [`L1BlockManifest::encode`], which checks their header when read
+/// back.  Untagged records written before that are plain borsh, they're told apart by not
+/// decoding as a tagged one and are read as they are.
+impl ValueCodec<L1BlockSchema> for L1BlockManifest {
+    fn encode_value(&self) -> Result<Vec<u8>, CodecError> {
+        Ok(self.encode())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self, CodecError> {
+        match (L1BlockManifest::decode(data), data) {
+            (Ok(mf), _) => Ok(mf),
+            (Err(e), [L1_MANIFEST_FORMAT_VERSION, ..]) => match borsh::from_slice(data) {
+                Ok(mf) => Ok(mf),
+                Err(_) => Err(anyhow::Error::from(e).into()),
+            },
+            (Err(_), _) => Ok(borsh::from_slice(data)?),
+        }
+    }
+}
+
 // L1 Txns Schema and corresponding codecs implementation
 define_table_with_default_codec!(
     /// A table to store L1 Txn data, maps block header hash to txns
@@ -27,3 +53,45 @@ define_table_with_seek_key_codec!(
     /// A table to store L1 Headers mmr
     (MmrSchema) u64 => CompactMmr
 );
+
+#[cfg(test)]
+mod tests {
+    use strata_primitives::l1::L1BlockRecord;
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    fn decode(data: &[u8]) -> Result<L1BlockManifest, CodecError> {
+        <L1BlockManifest as ValueCodec<L1BlockSchema>>::decode_value(data)
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mf: L1BlockManifest = ArbitraryGenerator::new().generate();
+        let data = mf.encode_value().unwrap();
+        assert_eq!(data, mf.encode());
+        assert_eq!(decode(&data).unwrap(), mf);
+    }
+
+    #[test]
+    fn test_decode_untagged_record() {
+        let mut arb = ArbitraryGenerator::new();
+        let mf: L1BlockManifest = arb.generate();
+        assert_eq!(decode(&borsh::to_vec(&mf).unwrap()).unwrap(), mf);
+
+        // even one that happens to start like a tagged one
+        let mut blockid = [0; 32];
+        blockid[0] = L1_MANIFEST_FORMAT_VERSION;
+        let record = L1BlockRecord::new(blockid.into(), mf.header().to_vec(), mf.txs_root());
+        let mf = L1BlockManifest::new(record, mf.epoch());
+        assert_eq!(decode(&borsh::to_vec(&mf).unwrap()).unwrap(), mf);
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_header() {
+        let mf: L1BlockManifest = ArbitraryGenerator::new().generate();
+        let record = L1BlockRecord::new(mf.block_hash(), mf.header()[..79].to_vec(), mf.txs_root());
+        let data = L1BlockManifest::new(record, mf.epoch()).encode();
+        assert!(decode(&data).is_err());
+    }
+}
@@ -1,12 +1,16 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use rockbound::{OptimisticTransactionDB, SchemaBatch, SchemaDBOperationsExt};
+use rockbound::{
+    OptimisticTransactionDB, SchemaBatch, SchemaDBOperationsExt, TransactionCtx,
+    TransactionDBMarker,
+};
 use strata_db::{
     errors::DbError,
     traits::{BlockStatus, L2BlockDatabase},
     DbResult,
 };
-use strata_state::{block::L2BlockBundle, prelude::*};
+use strata_primitives::{buf::Buf32, params::DEFAULT_MAX_BLOCK_SIZE};
+use strata_state::{block::L2BlockBundle, block_validation::verify_block_integrity, prelude::*};
 
 use super::schemas::{L2BlockSchema, L2BlockStatusSchema};
 use crate::{l2::schemas::L2BlockHeightSchema, DbOpsConfig};
@@ -14,40 +18,88 @@ use crate::{l2::schemas::L2BlockHeightSchema, DbOpsConfig};
 pub struct L2Db {
     db: Arc<OptimisticTransactionDB>,
     ops: DbOpsConfig,
+    max_block_size: usize,
 }
 
 impl L2Db {
     pub fn new(db: Arc<OptimisticTransactionDB>, ops: DbOpsConfig) -> Self {
-        Self { db, ops }
+        Self {
+            db,
+            ops,
+            max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+        }
+    }
+
+    /// Sets the max encoded size of block bundles accepted by
+    /// [`put_block_data`](L2BlockDatabase::put_block_data).
+    pub fn with_max_block_size(mut self, max_block_size: usize) -> Self {
+        self.max_block_size = max_block_size;
+        self
     }
 }
 
 impl L2BlockDatabase for L2Db {
     fn put_block_data(&self, bundle: L2BlockBundle) -> DbResult<()> {
-        let block_id = bundle.block().header().get_blockid();
+        // check this before anything gets written, the block could come from a peer
+        self.check_bundle(&bundle)?;
 
-        // append to previous block height data
-        let block_height = bundle.block().header().blockidx();
+        self.db
+            .with_optimistic_txn(
+                rockbound::TransactionRetry::Count(self.ops.retry_count),
+                |txn| {
+                    put_block_in_txn(txn, &bundle)?;
+                    Ok::<_, anyhow::Error>(())
+                },
+            )
+            .map_err(|e| DbError::TransactionError(e.to_string()))
+    }
+
+    fn put_blocks_batch(&self, blocks: Vec<L2BlockBundle>) -> DbResult<()> {
+        for bundle in &blocks {
+            self.check_bundle(bundle)?;
+        }
 
         self.db
             .with_optimistic_txn(
                 rockbound::TransactionRetry::Count(self.ops.retry_count),
                 |txn| {
-                    let mut block_height_data = txn
-                        .get_for_update::<L2BlockHeightSchema>(&block_height)?
-                        .unwrap_or(Vec::new());
-                    if !block_height_data.contains(&block_id) {
-                        block_height_data.push(block_id);
+                    let mut batch_heights = HashMap::new();
+                    for bundle in &blocks {
+                        let header = bundle.block().header();
+                        let block_id = header.get_blockid();
+                        let height = header.blockidx();
+                        let parent = *header.parent();
+
+                        if height == 0 {
+                            check_genesis_in_txn(txn, block_id, parent)?;
+                        } else {
+                            let parent_height = match batch_heights.get(&parent) {
+                                Some(parent_height) => *parent_height,
+                                None => txn
+                                    .get_for_update::<L2BlockSchema>(&parent)?
+                                    .map(|parent| parent.header().blockidx())
+                                    .ok_or(DbError::UnlinkedBlock(block_id, parent))?,
+                            };
+                            if parent_height + 1 != height {
+                                return Err(DbError::ParentHeightMismatch(
+                                    block_id,
+                                    height,
+                                    parent_height,
+                                ));
+                            }
+                        }
+
+                        put_block_in_txn(txn, bundle)?;
+                        batch_heights.insert(block_id, height);
                     }
 
-                    txn.put::<L2BlockSchema>(&block_id, &bundle)?;
-                    txn.put::<L2BlockStatusSchema>(&block_id, &BlockStatus::Unchecked)?;
-                    txn.put::<L2BlockHeightSchema>(&block_height, &block_height_data)?;
-
-                    Ok::<_, anyhow::Error>(())
+                    Ok::<_, DbError>(())
                 },
             )
-            .map_err(|e| DbError::TransactionError(e.to_string()))
+            .map_err(|err| match err {
+                rockbound::TransactionError::Rollback(e) => e,
+                e => DbError::TransactionError(e.to_string()),
+            })
     }
 
     fn del_block_data(&self, id: L2BlockId) -> DbResult<bool> {
@@ -97,10 +149,13 @@ impl L2BlockDatabase for L2Db {
     }
 
     fn get_blocks_at_height(&self, idx: u64) -> DbResult<Vec<L2BlockId>> {
-        Ok(self
+        let mut block_ids = self
             .db
             .get::<L2BlockHeightSchema>(&idx)?
-            .unwrap_or(Vec::new()))
+            .unwrap_or(Vec::new());
+        // entries written before we kept them sorted may not be
+        normalize_block_ids(&mut block_ids);
+        Ok(block_ids)
     }
 
     fn get_block_status(&self, id: L2BlockId) -> DbResult<Option<BlockStatus>> {
@@ -108,19 +163,88 @@ impl L2BlockDatabase for L2Db {
     }
 }
 
+impl L2Db {
+    /// Checks the block's size and that its body matches its header.
+    fn check_bundle(&self, bundle: &L2BlockBundle) -> DbResult<()> {
+        let size = borsh::object_length(bundle).map_err(|e| DbError::CodecError(e.to_string()))?;
+        if size > self.max_block_size {
+            return Err(DbError::BlockTooLarge {
+                size,
+                max: self.max_block_size,
+            });
+        }
+
+        let block_id = bundle.block().header().get_blockid();
+        verify_block_integrity(bundle.block()).map_err(|e| DbError::InconsistentBlock(block_id, e))
+    }
+}
+
+/// Writes the block with its status and adds it to the blocks at its height.
+fn put_block_in_txn<DB: TransactionDBMarker>(
+    txn: &TransactionCtx<DB>,
+    bundle: &L2BlockBundle,
+) -> anyhow::Result<()> {
+    let block_id = bundle.block().header().get_blockid();
+    let block_height = bundle.block().header().blockidx();
+
+    // append to previous block height data
+    let mut block_height_data = txn
+        .get_for_update::<L2BlockHeightSchema>(&block_height)?
+        .unwrap_or(Vec::new());
+    normalize_block_ids(&mut block_height_data);
+    if let Err(pos) = block_height_data.binary_search(&block_id) {
+        block_height_data.insert(pos, block_id);
+    }
+
+    txn.put::<L2BlockSchema>(&block_id, bundle)?;
+    txn.put::<L2BlockStatusSchema>(&block_id, &BlockStatus::Unchecked)?;
+    txn.put::<L2BlockHeightSchema>(&block_height, &block_height_data)?;
+
+    Ok(())
+}
+
+/// Checks that a block at height 0 is the genesis block, building on the zero
+/// block id, and that no other genesis block is stored already.
+fn check_genesis_in_txn<DB: TransactionDBMarker>(
+    txn: &TransactionCtx<DB>,
+    block_id: L2BlockId,
+    parent: L2BlockId,
+) -> DbResult<()> {
+    if parent != L2BlockId::from(Buf32::zero()) {
+        return Err(DbError::UnlinkedBlock(block_id, parent));
+    }
+
+    let stored = txn
+        .get_for_update::<L2BlockHeightSchema>(&0)?
+        .unwrap_or_default();
+    if stored.iter().any(|id| *id != block_id) {
+        return Err(DbError::UnlinkedBlock(block_id, parent));
+    }
+
+    Ok(())
+}
+
+/// Sorts the block ids and removes any duplicates.
+fn normalize_block_ids(block_ids: &mut Vec<L2BlockId>) {
+    block_ids.sort_unstable();
+    block_ids.dedup();
+}
+
 #[cfg(feature = "test_utils")]
 #[cfg(test)]
 mod tests {
-    use strata_test_utils::ArbitraryGenerator;
+    use strata_primitives::buf::Buf64;
+    use strata_state::{
+        block::{L2Block, L2BlockBody},
+        block_validation::BlockIntegrityError,
+    };
+    use strata_test_utils::{l2::gen_l2_chain, ArbitraryGenerator};
 
     use super::*;
     use crate::test_utils::get_rocksdb_tmp_instance;
 
     fn get_mock_data() -> L2BlockBundle {
-        let mut arb = ArbitraryGenerator::new();
-        let l2_block: L2BlockBundle = arb.generate();
-
-        l2_block
+        gen_l2_chain(None, 0).remove(0)
     }
 
     fn setup_db() -> L2Db {
@@ -242,4 +366,327 @@ mod tests {
             .unwrap();
         assert_eq!(block_status, BlockStatus::Unchecked);
     }
+
+    #[test]
+    fn put_block_data_size_limit() {
+        let bundle = get_mock_data();
+        let block_hash = bundle.block().header().get_blockid();
+        let size = borsh::object_length(&bundle).unwrap();
+
+        // just over the limit is rejected without writing anything
+        let (db, ops) = get_rocksdb_tmp_instance().unwrap();
+        let l2_db = L2Db::new(db, ops).with_max_block_size(size - 1);
+        let res = l2_db.put_block_data(bundle.clone());
+        assert!(matches!(
+            res,
+            Err(DbError::BlockTooLarge { size: s, max }) if s == size && max == size - 1
+        ));
+        assert!(l2_db.get_block_data(block_hash).unwrap().is_none());
+
+        // exactly at the limit is fine
+        let (db, ops) = get_rocksdb_tmp_instance().unwrap();
+        let l2_db = L2Db::new(db, ops).with_max_block_size(size);
+        l2_db
+            .put_block_data(bundle.clone())
+            .expect("failed to put block data");
+        assert_eq!(l2_db.get_block_data(block_hash).unwrap(), Some(bundle));
+    }
+
+    #[test]
+    fn put_block_data_inconsistent_body() {
+        let l2_db = setup_db();
+        let bundle = get_mock_data();
+
+        // same header and L1 segment, but the exec segment of another block
+        let other = get_mock_data();
+        let body = L2BlockBody::new(
+            bundle.body().l1_segment().clone(),
+            other.body().exec_segment().clone(),
+        );
+        let block = L2Block::new(bundle.header().clone(), body);
+        let tampered = L2BlockBundle::new(block, bundle.accessory().clone());
+        let blkid = tampered.block().header().get_blockid();
+
+        let res = l2_db.put_block_data(tampered);
+        assert!(matches!(
+            res,
+            Err(DbError::InconsistentBlock(id, BlockIntegrityError::ExecSegmentMismatch { .. }))
+                if id == blkid
+        ));
+        assert!(l2_db.get_block_data(blkid).unwrap().is_none());
+        assert!(l2_db.get_blocks_at_height(0).unwrap().is_empty());
+
+        // the block it was made from, with the same id, is fine
+        l2_db
+            .put_block_data(bundle.clone())
+            .expect("failed to put block data");
+        assert_eq!(l2_db.get_block_data(blkid).unwrap(), Some(bundle));
+    }
+
+    #[test]
+    fn put_blocks_batch_contiguous() {
+        let l2_db = setup_db();
+        let mut chain = gen_l2_chain(None, 5);
+
+        // the first blocks are stored already, the batch continues on top of them
+        let rest = chain.split_off(2);
+        l2_db.put_blocks_batch(chain.clone()).unwrap();
+        l2_db.put_blocks_batch(rest.clone()).unwrap();
+
+        for bundle in chain.iter().chain(&rest) {
+            let blkid = bundle.header().get_blockid();
+            assert_eq!(l2_db.get_block_data(blkid).unwrap().as_ref(), Some(bundle));
+            assert_eq!(
+                l2_db.get_block_status(blkid).unwrap(),
+                Some(BlockStatus::Unchecked)
+            );
+            assert_eq!(
+                l2_db
+                    .get_blocks_at_height(bundle.header().blockidx())
+                    .unwrap(),
+                vec![blkid]
+            );
+        }
+    }
+
+    #[test]
+    fn put_blocks_batch_gap() {
+        let l2_db = setup_db();
+        let mut chain = gen_l2_chain(None, 4);
+        let missing = chain.remove(2);
+
+        let res = l2_db.put_blocks_batch(chain.clone());
+        assert!(matches!(
+            res,
+            Err(DbError::UnlinkedBlock(id, parent))
+                if id == chain[2].header().get_blockid()
+                    && parent == missing.header().get_blockid()
+        ));
+
+        // none of them got stored, not even the ones before the gap
+        for bundle in &chain {
+            let blkid = bundle.header().get_blockid();
+            assert!(l2_db.get_block_data(blkid).unwrap().is_none());
+            assert!(l2_db
+                .get_blocks_at_height(bundle.header().blockidx())
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    /// Remakes the block with another height and parent.
+    fn relink(bundle: &L2BlockBundle, blockidx: u64, parent: L2BlockId) -> L2BlockBundle {
+        let header = bundle.header();
+        let header = L2BlockHeader::new(
+            blockidx,
+            header.timestamp(),
+            parent,
+            bundle.body(),
+            *header.state_root(),
+        );
+        let block = L2Block::new(
+            SignedL2BlockHeader::new(header, Buf64::zero()),
+            bundle.body().clone(),
+        );
+        L2BlockBundle::new(block, bundle.accessory().clone())
+    }
+
+    #[test]
+    fn put_blocks_batch_genesis() {
+        let l2_db = setup_db();
+        let chain = gen_l2_chain(None, 2);
+
+        // a height 0 block has to build on the zero id to be genesis
+        let fake_genesis = relink(&chain[0], 0, chain[1].header().get_blockid());
+        let res = l2_db.put_blocks_batch(vec![fake_genesis]);
+        assert!(matches!(res, Err(DbError::UnlinkedBlock(..))));
+
+        l2_db.put_blocks_batch(chain.clone()).unwrap();
+        l2_db.put_blocks_batch(chain[..1].to_vec()).unwrap();
+
+        // and there's only one of them
+        let other_genesis = gen_l2_chain(None, 0).remove(0);
+        let res = l2_db.put_blocks_batch(vec![other_genesis.clone()]);
+        assert!(matches!(
+            res,
+            Err(DbError::UnlinkedBlock(id, _)) if id == other_genesis.header().get_blockid()
+        ));
+        assert_eq!(
+            l2_db.get_blocks_at_height(0).unwrap(),
+            vec![chain[0].header().get_blockid()]
+        );
+    }
+
+    #[test]
+    fn put_blocks_batch_parent_height_mismatch() {
+        let l2_db = setup_db();
+        let chain = gen_l2_chain(None, 2);
+        l2_db.put_blocks_batch(chain[..2].to_vec()).unwrap();
+
+        // claims a height that doesn't follow its stored parent
+        let tip = chain[1].header().get_blockid();
+        let skipping = relink(&chain[2], 5, tip);
+        let res = l2_db.put_blocks_batch(vec![skipping.clone()]);
+        assert!(matches!(
+            res,
+            Err(DbError::ParentHeightMismatch(id, 5, 1)) if id == skipping.header().get_blockid()
+        ));
+
+        // or one earlier in the batch
+        let child = relink(&chain[2], 2, tip);
+        let grandchild = relink(&chain[2], 2, child.header().get_blockid());
+        let res = l2_db.put_blocks_batch(vec![child.clone(), grandchild]);
+        assert!(matches!(res, Err(DbError::ParentHeightMismatch(_, 2, 2))));
+        assert!(l2_db
+            .get_block_data(child.header().get_blockid())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn put_blocks_batch_out_of_order() {
+        let l2_db = setup_db();
+        let chain = gen_l2_chain(None, 3);
+        l2_db.put_block_data(chain[0].clone()).unwrap();
+
+        let mut batch = chain[1..].to_vec();
+        batch.swap(0, 1);
+        let res = l2_db.put_blocks_batch(batch);
+        assert!(matches!(
+            res,
+            Err(DbError::UnlinkedBlock(id, parent))
+                if id == chain[2].header().get_blockid()
+                    && parent == chain[1].header().get_blockid()
+        ));
+        for bundle in &chain[1..] {
+            let blkid = bundle.header().get_blockid();
+            assert!(l2_db.get_block_data(blkid).unwrap().is_none());
+        }
+
+        // in order it goes through
+        l2_db.put_blocks_batch(chain[1..].to_vec()).unwrap();
+        for bundle in &chain {
+            let blkid = bundle.header().get_blockid();
+            assert_eq!(l2_db.get_block_data(blkid).unwrap().as_ref(), Some(bundle));
+        }
+    }
+
+    /// Collects the blocks in the range, failing on the first error.
+    fn collect_range(
+        l2_db: &L2Db,
+        tip: L2BlockId,
+        start: u64,
+        end: u64,
+    ) -> DbResult<Vec<L2BlockBundle>> {
+        l2_db.iter_blocks_range(tip, start, end)?.collect()
+    }
+
+    #[test]
+    fn iter_blocks_range_linear() {
+        let l2_db = setup_db();
+        let chain = gen_l2_chain(None, 5);
+        l2_db.put_blocks_batch(chain.clone()).unwrap();
+        let tip = chain[5].header().get_blockid();
+
+        assert_eq!(collect_range(&l2_db, tip, 1, 4).unwrap(), chain[1..4]);
+        assert_eq!(collect_range(&l2_db, tip, 0, 6).unwrap(), chain);
+        assert!(collect_range(&l2_db, tip, 3, 3).unwrap().is_empty());
+
+        // past the tip
+        let res = l2_db.iter_blocks_range(tip, 2, 7);
+        assert!(matches!(
+            res,
+            Err(DbError::MissingBlockInRange("L2", 6, 2, 7))
+        ));
+    }
+
+    #[test]
+    fn iter_blocks_range_across_fork() {
+        let l2_db = setup_db();
+        let chain = gen_l2_chain(None, 3);
+        let fork = gen_l2_chain(Some(chain[1].header().clone()), 3);
+        l2_db.put_blocks_batch(chain.clone()).unwrap();
+        l2_db.put_blocks_batch(fork.clone()).unwrap();
+        assert_eq!(l2_db.get_blocks_at_height(2).unwrap().len(), 2);
+
+        // following the fork's tip picks its blocks above where it branched off
+        let fork_tip = fork[2].header().get_blockid();
+        let blocks = collect_range(&l2_db, fork_tip, 0, 5).unwrap();
+        let expected: Vec<_> = chain[..2].iter().chain(&fork).cloned().collect();
+        assert_eq!(blocks, expected);
+
+        let blocks = collect_range(&l2_db, fork_tip, 1, 3).unwrap();
+        assert_eq!(blocks, vec![chain[1].clone(), fork[0].clone()]);
+
+        // and the other tip its own
+        let tip = chain[3].header().get_blockid();
+        assert_eq!(collect_range(&l2_db, tip, 0, 4).unwrap(), chain);
+        assert_eq!(collect_range(&l2_db, tip, 2, 4).unwrap(), chain[2..4]);
+    }
+
+    #[test]
+    fn iter_blocks_range_missing_block() {
+        let l2_db = setup_db();
+        let chain = gen_l2_chain(None, 5);
+        l2_db.put_blocks_batch(chain.clone()).unwrap();
+        let tip = chain[5].header().get_blockid();
+        assert!(l2_db
+            .del_block_data(chain[3].header().get_blockid())
+            .unwrap());
+
+        // the blocks up to the gap come out, then the error and nothing after
+        let mut iter = l2_db.iter_blocks_range(tip, 1, 6).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), chain[1]);
+        assert_eq!(iter.next().unwrap().unwrap(), chain[2]);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(DbError::MissingBlockInRange("L2", 3, 1, 6)))
+        ));
+        assert!(iter.next().is_none());
+
+        // the range below the gap is still fine
+        assert_eq!(collect_range(&l2_db, tip, 0, 3).unwrap(), chain[..3]);
+    }
+
+    #[test]
+    fn get_blocks_at_height_sorted_and_deduped() {
+        let l2_db = setup_db();
+        let parent = gen_l2_chain(None, 0).remove(0);
+
+        // competing blocks on top of the same parent
+        let mut competing: Vec<_> = (0..4)
+            .map(|_| gen_l2_chain(Some(parent.header().clone()), 1).remove(0))
+            .collect();
+        let height = competing[0].header().blockidx();
+        for bundle in &competing {
+            l2_db
+                .put_block_data(bundle.clone())
+                .expect("failed to put block data");
+        }
+
+        // storing the same block again doesn't list it twice
+        l2_db
+            .put_block_data(competing[2].clone())
+            .expect("failed to put block data");
+
+        competing.sort_by_key(|b| b.header().get_blockid());
+        let expected: Vec<_> = competing.iter().map(|b| b.header().get_blockid()).collect();
+        assert_eq!(l2_db.get_blocks_at_height(height).unwrap(), expected);
+    }
+
+    #[test]
+    fn get_blocks_at_height_normalizes_stored_ids() {
+        let l2_db = setup_db();
+        let mut arb = ArbitraryGenerator::new();
+        let a: L2BlockId = arb.generate();
+        let b: L2BlockId = arb.generate();
+        let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+
+        // unsorted with a duplicate, like it could have been written before
+        l2_db
+            .db
+            .put::<L2BlockHeightSchema>(&7, &vec![hi, lo, hi])
+            .unwrap();
+        assert_eq!(l2_db.get_blocks_at_height(7).unwrap(), vec![lo, hi]);
+    }
 }
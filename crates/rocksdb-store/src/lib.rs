@@ -1,3 +1,4 @@
+pub mod batch;
 pub mod bridge;
 pub mod bridge_relay;
 pub mod broadcaster;
@@ -6,8 +7,10 @@ pub mod checkpoint;
 pub mod client_state;
 pub mod l1;
 pub mod l2;
+pub mod maintenance;
 pub mod prover;
 pub mod sequencer;
+pub mod stats;
 pub mod sync_event;
 
 pub mod macros;
@@ -24,6 +27,8 @@ pub const STORE_COLUMN_FAMILIES: &[ColumnFamilyName] = &[
     ChainstateSchema::COLUMN_FAMILY_NAME,
     ClientUpdateOutputSchema::COLUMN_FAMILY_NAME,
     ClientStateSchema::COLUMN_FAMILY_NAME,
+    ClientStatePruneSchema::COLUMN_FAMILY_NAME,
+    ClientActionsAckSchema::COLUMN_FAMILY_NAME,
     L1BlockSchema::COLUMN_FAMILY_NAME,
     MmrSchema::COLUMN_FAMILY_NAME,
     SyncEventSchema::COLUMN_FAMILY_NAME,
@@ -35,6 +40,9 @@ pub const STORE_COLUMN_FAMILIES: &[ColumnFamilyName] = &[
     // Seqdb schemas
     SeqBlobIdSchema::COLUMN_FAMILY_NAME,
     SeqBlobSchema::COLUMN_FAMILY_NAME,
+    SeqBlobTxidSchema::COLUMN_FAMILY_NAME,
+    SeqBlobStatusSchema::COLUMN_FAMILY_NAME,
+    SeqBlobTransitionSchema::COLUMN_FAMILY_NAME,
     // Bcast schemas
     BcastL1TxIdSchema::COLUMN_FAMILY_NAME,
     BcastL1TxSchema::COLUMN_FAMILY_NAME,
@@ -62,9 +70,12 @@ pub const PROVER_COLUMN_FAMILIES: &[ColumnFamilyName] = &[
     SequenceSchema::COLUMN_FAMILY_NAME,
     prover::schemas::ProofSchema::COLUMN_FAMILY_NAME,
     prover::schemas::ProofDepsSchema::COLUMN_FAMILY_NAME,
+    prover::schemas::ProverInputSchema::COLUMN_FAMILY_NAME,
+    prover::schemas::ProofInputRefSchema::COLUMN_FAMILY_NAME,
 ];
 
 // Re-exports
+pub use batch::AtomicBatchDb;
 pub use bridge_relay::db::BridgeMsgDb;
 use bridge_relay::schemas::*;
 pub use broadcaster::db::L1BroadcastDb;
@@ -77,12 +88,16 @@ pub use l1::db::L1Db;
 use l2::schemas::{L2BlockHeightSchema, L2BlockSchema, L2BlockStatusSchema};
 use rockbound::{schema::ColumnFamilyName, Schema};
 pub use sequencer::db::RBSeqBlobDb;
-use sequencer::schemas::{SeqBlobIdSchema, SeqBlobSchema};
+use sequencer::schemas::{
+    SeqBlobIdSchema, SeqBlobSchema, SeqBlobStatusSchema, SeqBlobTransitionSchema, SeqBlobTxidSchema,
+};
 pub use sync_event::db::SyncEventDb;
 
 use crate::{
     chain_state::schemas::{ChainstateSchema, WriteBatchSchema},
-    client_state::schemas::{ClientStateSchema, ClientUpdateOutputSchema},
+    client_state::schemas::{
+        ClientActionsAckSchema, ClientStatePruneSchema, ClientStateSchema, ClientUpdateOutputSchema,
+    },
     l1::schemas::{L1BlockSchema, MmrSchema, TxnSchema},
     sequence::SequenceSchema,
     sync_event::schemas::SyncEventSchema,
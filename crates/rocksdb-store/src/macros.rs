@@ -51,8 +51,21 @@ macro_rules! define_table_with_default_codec {
     ($(#[$docs:meta])+ ($table_name:ident) $key:ty => $value:ty) => {
         define_table_without_codec!($(#[$docs])+ ( $table_name ) $key => $value);
 
+        $crate::impl_borsh_key_codec!($table_name, $key);
+
+        impl_borsh_value_codec!($table_name, $value);
+    };
+}
+
+/// Implements the borsh key codec used by [`define_table_with_default_codec`],
+/// for tables that need a custom value codec.
+#[macro_export]
+macro_rules! impl_borsh_key_codec {
+    ($table_name:ident, $key:ty) => {
         impl ::rockbound::schema::KeyEncoder<$table_name> for $key {
-            fn encode_key(&self) -> ::std::result::Result<::std::vec::Vec<u8>, ::rockbound::CodecError> {
+            fn encode_key(
+                &self,
+            ) -> ::std::result::Result<::std::vec::Vec<u8>, ::rockbound::CodecError> {
                 ::borsh::to_vec(self).map_err(Into::into)
             }
         }
@@ -62,8 +75,6 @@ macro_rules! define_table_with_default_codec {
                 ::borsh::BorshDeserialize::deserialize_reader(&mut &data[..]).map_err(Into::into)
             }
         }
-
-        impl_borsh_value_codec!($table_name, $value);
     };
 }
 
@@ -77,8 +88,22 @@ macro_rules! define_table_with_seek_key_codec {
     ($(#[$docs:meta])+ ($table_name:ident) $key:ty => $value:ty) => {
         define_table_without_codec!($(#[$docs])+ ( $table_name ) $key => $value);
 
+        $crate::impl_seek_key_codec!($table_name, $key);
+
+        impl_borsh_value_codec!($table_name, $value);
+    };
+}
+
+/// Implements the big-endian [`bincode`] key codec used by
+/// [`define_table_with_seek_key_codec`], for tables that need a custom value
+/// codec.
+#[macro_export]
+macro_rules! impl_seek_key_codec {
+    ($table_name:ident, $key:ty) => {
         impl ::rockbound::schema::KeyEncoder<$table_name> for $key {
-            fn encode_key(&self) -> ::std::result::Result<::std::vec::Vec<u8>, ::rockbound::CodecError> {
+            fn encode_key(
+                &self,
+            ) -> ::std::result::Result<::std::vec::Vec<u8>, ::rockbound::CodecError> {
                 use ::anyhow::Context as _;
                 use ::bincode::Options as _;
 
@@ -86,7 +111,10 @@ macro_rules! define_table_with_seek_key_codec {
                     .with_fixint_encoding()
                     .with_big_endian();
 
-                bincode_options.serialize(self).context("Failed to serialize key").map_err(Into::into)
+                bincode_options
+                    .serialize(self)
+                    .context("Failed to serialize key")
+                    .map_err(Into::into)
             }
         }
 
@@ -99,10 +127,11 @@ macro_rules! define_table_with_seek_key_codec {
                     .with_fixint_encoding()
                     .with_big_endian();
 
-                bincode_options.deserialize_from(&mut &data[..]).context("Failed to deserialize key").map_err(Into::into)
+                bincode_options
+                    .deserialize_from(&mut &data[..])
+                    .context("Failed to deserialize key")
+                    .map_err(Into::into)
             }
         }
-
-        impl_borsh_value_codec!($table_name, $value);
     };
 }
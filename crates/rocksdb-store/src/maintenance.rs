@@ -0,0 +1,77 @@
+//! Manual maintenance operations on the rocksdb store, meant to be triggered by
+//! operators on long-running nodes.
+
+use rockbound::{schema::ColumnFamilyName, OptimisticTransactionDB};
+use strata_db::{errors::DbError, DbResult};
+
+/// Runs a manual compaction over the whole key range of each of the column
+/// families, which drops the tombstones left behind by deletions.
+pub fn compact_column_families(
+    db: &OptimisticTransactionDB,
+    cfs: &[ColumnFamilyName],
+) -> DbResult<()> {
+    let inner = db.db();
+    for name in cfs {
+        let cf = inner
+            .cf_handle(name)
+            .ok_or_else(|| DbError::Other(format!("unknown column family {name}")))?;
+        inner.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rockbound::Schema;
+    use strata_db::traits::SyncEventDatabase;
+    use strata_state::sync_event::SyncEvent;
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+    use crate::{
+        stats::DbStats, sync_event::schemas::SyncEventSchema, test_utils::get_rocksdb_tmp_instance,
+        SyncEventDb, STORE_COLUMN_FAMILIES,
+    };
+
+    #[test]
+    fn test_compaction_and_stats() {
+        let (rbdb, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let sync_ev_db = SyncEventDb::new(rbdb.clone(), db_ops);
+
+        // leave some tombstones behind
+        let mut arb = ArbitraryGenerator::new();
+        for _ in 0..10 {
+            let ev: SyncEvent = arb.generate();
+            sync_ev_db.write_sync_event(ev).unwrap();
+        }
+        sync_ev_db.clear_sync_event(1, 8).unwrap();
+
+        compact_column_families(&rbdb, STORE_COLUMN_FAMILIES).unwrap();
+
+        let stats = DbStats::new(rbdb.clone(), STORE_COLUMN_FAMILIES)
+            .get_all_cf_stats()
+            .unwrap();
+        assert_eq!(stats.len(), STORE_COLUMN_FAMILIES.len());
+        for (stat, name) in stats.iter().zip(STORE_COLUMN_FAMILIES) {
+            assert_eq!(stat.name, *name);
+        }
+
+        // the compaction flushed the remaining events to disk
+        let sync_ev_stats = stats
+            .iter()
+            .find(|s| s.name == SyncEventSchema::COLUMN_FAMILY_NAME)
+            .unwrap();
+        assert!(sync_ev_stats.sst_files_size > 0);
+        assert!(sync_ev_stats.num_keys > 0);
+
+        // the data is still there
+        assert!(sync_ev_db.get_sync_event(9).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_unknown_column_family() {
+        let (rbdb, _) = get_rocksdb_tmp_instance().unwrap();
+        assert!(compact_column_families(&rbdb, &["nonexistent"]).is_err());
+    }
+}
@@ -2,10 +2,14 @@ use std::sync::Arc;
 
 use rockbound::{OptimisticTransactionDB, SchemaDBOperationsExt, TransactionRetry};
 use strata_db::{errors::DbError, traits::ProofDatabase, DbResult};
-use strata_primitives::proof::{ProofContext, ProofKey};
+use strata_primitives::{
+    buf::Buf32,
+    hash,
+    proof::{ProofContext, ProofKey},
+};
 use strata_zkvm::ProofReceipt;
 
-use super::schemas::{ProofDepsSchema, ProofSchema};
+use super::schemas::{ProofDepsSchema, ProofInputRefSchema, ProofSchema, ProverInputSchema};
 use crate::DbOpsConfig;
 
 #[derive(Debug, Clone)]
@@ -82,6 +86,42 @@ impl ProofDatabase for ProofDb {
             })
             .map_err(|e| DbError::TransactionError(e.to_string()))
     }
+
+    fn put_input(&self, input: Vec<u8>) -> DbResult<Buf32> {
+        let input_hash = hash::raw(&input);
+        self.db
+            .with_optimistic_txn(TransactionRetry::Count(self.ops.retry_count), |tx| {
+                // same hash means same input, nothing to do
+                if tx.get::<ProverInputSchema>(&input_hash)?.is_none() {
+                    tx.put::<ProverInputSchema>(&input_hash, &input)?;
+                }
+
+                Ok::<_, anyhow::Error>(input_hash)
+            })
+            .map_err(|e| DbError::TransactionError(e.to_string()))
+    }
+
+    fn get_input(&self, input_hash: Buf32) -> DbResult<Option<Vec<u8>>> {
+        Ok(self.db.get::<ProverInputSchema>(&input_hash)?)
+    }
+
+    fn put_task_input(&self, proof_key: ProofKey, input_hash: Buf32) -> DbResult<()> {
+        self.db
+            .with_optimistic_txn(TransactionRetry::Count(self.ops.retry_count), |tx| {
+                if tx.get::<ProofInputRefSchema>(&proof_key)?.is_some() {
+                    return Err(DbError::EntryAlreadyExists);
+                }
+
+                tx.put::<ProofInputRefSchema>(&proof_key, &input_hash)?;
+
+                Ok(())
+            })
+            .map_err(|e| DbError::TransactionError(e.to_string()))
+    }
+
+    fn get_task_input(&self, proof_key: ProofKey) -> DbResult<Option<Buf32>> {
+        Ok(self.db.get::<ProofInputRefSchema>(&proof_key)?)
+    }
 }
 
 #[cfg(test)]
@@ -217,4 +257,32 @@ mod tests {
             "Nonexistent proof deps should return None"
         );
     }
+
+    #[test]
+    fn test_identical_inputs_stored_once() {
+        let db = setup_db();
+
+        let input = vec![7u8; 1024];
+        let hash_a = db.put_input(input.clone()).unwrap();
+        let hash_b = db.put_input(input.clone()).unwrap();
+        assert_eq!(hash_a, hash_b);
+
+        let other_hash = db.put_input(vec![8u8; 1024]).unwrap();
+        assert_ne!(hash_a, other_hash);
+
+        let stored_inputs = db.db.iter::<ProverInputSchema>().unwrap().count();
+        assert_eq!(stored_inputs, 2, "identical inputs should share one blob");
+        assert_eq!(db.get_input(hash_a).unwrap(), Some(input));
+        assert_eq!(db.get_input(Buf32::zero()).unwrap(), None);
+
+        // tasks with the same input reference the same blob
+        let (proof_key, _) = generate_proof();
+        let other_key = ProofKey::new(*proof_key.context(), ProofZkVm::SP1);
+        db.put_task_input(proof_key, hash_a).unwrap();
+        db.put_task_input(other_key, hash_b).unwrap();
+        assert_eq!(db.get_task_input(proof_key).unwrap(), Some(hash_a));
+        assert_eq!(db.get_task_input(other_key).unwrap(), Some(hash_a));
+
+        assert!(db.put_task_input(proof_key, other_hash).is_err());
+    }
 }
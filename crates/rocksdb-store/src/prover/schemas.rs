@@ -1,4 +1,7 @@
-use strata_primitives::proof::{ProofContext, ProofKey};
+use strata_primitives::{
+    buf::Buf32,
+    proof::{ProofContext, ProofKey},
+};
 use strata_zkvm::ProofReceipt;
 
 use crate::{define_table_with_default_codec, define_table_without_codec, impl_borsh_value_codec};
@@ -12,3 +15,13 @@ define_table_with_default_codec!(
     /// A table to store dependencies of a proof context
     (ProofDepsSchema) ProofContext => Vec<ProofContext>
 );
+
+define_table_with_default_codec!(
+    /// A table to store serialized prover inputs by their hash
+    (ProverInputSchema) Buf32 => Vec<u8>
+);
+
+define_table_with_default_codec!(
+    /// A table to store the hash of the input a proof is generated from
+    (ProofInputRefSchema) ProofKey => Buf32
+);
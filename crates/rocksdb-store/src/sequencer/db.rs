@@ -1,15 +1,21 @@
 use std::sync::Arc;
 
-use rockbound::{OptimisticTransactionDB, SchemaDBOperationsExt};
+use rockbound::{
+    rocksdb::ReadOptions, schema::KeyEncoder, OptimisticTransactionDB, SchemaDBOperationsExt,
+    TransactionCtx,
+};
 use strata_db::{
     errors::DbError,
     traits::{BlobDatabase, SequencerDatabase},
-    types::BlobEntry,
+    types::{BlobEntry, BlobL1Status, BlobStatusTransition},
     DbResult,
 };
 use strata_primitives::buf::Buf32;
 
-use super::schemas::{SeqBlobIdSchema, SeqBlobSchema};
+use super::schemas::{
+    blob_status_key, blob_status_tag, SeqBlobIdSchema, SeqBlobSchema, SeqBlobStatusSchema,
+    SeqBlobTransitionSchema, SeqBlobTxidSchema,
+};
 use crate::{sequence::get_next_id, DbOpsConfig};
 
 pub struct RBSeqBlobDb {
@@ -29,19 +35,18 @@ impl RBSeqBlobDb {
 
 impl BlobDatabase for RBSeqBlobDb {
     fn put_blob_entry(&self, blob_hash: Buf32, blob: BlobEntry) -> DbResult<()> {
+        self.put_blob_entries(vec![(blob_hash, blob)])
+    }
+
+    fn put_blob_entries(&self, entries: Vec<(Buf32, BlobEntry)>) -> DbResult<()> {
         self.db
             .with_optimistic_txn(
                 rockbound::TransactionRetry::Count(self.ops.retry_count),
                 |tx| -> Result<(), DbError> {
-                    // If new, increment idx
-                    if tx.get::<SeqBlobSchema>(&blob_hash)?.is_none() {
-                        let idx = get_next_id::<SeqBlobIdSchema, OptimisticTransactionDB>(tx)?;
-
-                        tx.put::<SeqBlobIdSchema>(&idx, &blob_hash)?;
+                    for (blob_hash, blob) in &entries {
+                        put_blob_entry_in_txn(tx, *blob_hash, blob)?;
                     }
 
-                    tx.put::<SeqBlobSchema>(&blob_hash, &blob)?;
-
                     Ok(())
                 },
             )
@@ -59,6 +64,130 @@ impl BlobDatabase for RBSeqBlobDb {
     fn get_blob_id(&self, blobidx: u64) -> DbResult<Option<Buf32>> {
         Ok(self.db.get::<SeqBlobIdSchema>(&blobidx)?)
     }
+
+    fn get_blob_id_by_txid(&self, txid: Buf32) -> DbResult<Option<Buf32>> {
+        Ok(self.db.get::<SeqBlobTxidSchema>(&txid)?)
+    }
+
+    fn get_blobs_created_before(
+        &self,
+        status: &BlobL1Status,
+        before_ms: u64,
+    ) -> DbResult<Vec<(Buf32, BlobEntry)>> {
+        let tag = blob_status_tag(status);
+        let mut options = ReadOptions::default();
+        options.set_iterate_lower_bound(
+            KeyEncoder::<SeqBlobStatusSchema>::encode_key(&(tag, 0, Buf32::zero()))
+                .map_err(|err| DbError::CodecError(err.to_string()))?,
+        );
+        options.set_iterate_upper_bound(
+            KeyEncoder::<SeqBlobStatusSchema>::encode_key(&(tag, before_ms, Buf32::zero()))
+                .map_err(|err| DbError::CodecError(err.to_string()))?,
+        );
+
+        let mut blobs = Vec::new();
+        for res in self.db.iter_with_opts::<SeqBlobStatusSchema>(options)? {
+            let ((_, _, id), _) = res?.into_tuple();
+            // both are written in the same txn
+            let Some(entry) = self.db.get::<SeqBlobSchema>(&id)? else {
+                continue;
+            };
+            blobs.push((id, entry));
+        }
+
+        Ok(blobs)
+    }
+
+    fn update_blob_entries(
+        &self,
+        entries: Vec<(Buf32, BlobEntry)>,
+        reason: String,
+    ) -> DbResult<()> {
+        self.db
+            .with_optimistic_txn(
+                rockbound::TransactionRetry::Count(self.ops.retry_count),
+                |tx| -> Result<(), DbError> {
+                    for (blob_hash, blob) in &entries {
+                        let prev = tx.get_for_update::<SeqBlobSchema>(blob_hash)?;
+                        if let Some(prev) = prev.filter(|prev| prev.status != blob.status) {
+                            let seq = get_next_id::<SeqBlobTransitionSchema, _>(tx)?;
+                            let transition = BlobStatusTransition::now(
+                                prev.status,
+                                blob.status.clone(),
+                                reason.clone(),
+                            );
+                            tx.put::<SeqBlobTransitionSchema>(&(*blob_hash, seq), &transition)?;
+                        }
+
+                        put_blob_entry_in_txn(tx, *blob_hash, blob)?;
+                    }
+
+                    Ok(())
+                },
+            )
+            .map_err(|e| DbError::TransactionError(e.to_string()))
+    }
+
+    fn get_blob_transitions(&self, id: Buf32) -> DbResult<Vec<BlobStatusTransition>> {
+        let mut options = ReadOptions::default();
+        options.set_iterate_lower_bound(
+            KeyEncoder::<SeqBlobTransitionSchema>::encode_key(&(id, 0))
+                .map_err(|err| DbError::CodecError(err.to_string()))?,
+        );
+        options.set_iterate_upper_bound(
+            KeyEncoder::<SeqBlobTransitionSchema>::encode_key(&(id, u64::MAX))
+                .map_err(|err| DbError::CodecError(err.to_string()))?,
+        );
+
+        let transitions = self
+            .db
+            .iter_with_opts::<SeqBlobTransitionSchema>(options)?
+            .map(|res| res.map(|item| item.into_tuple().1))
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        Ok(transitions)
+    }
+}
+
+fn put_blob_entry_in_txn(
+    tx: &TransactionCtx<OptimisticTransactionDB>,
+    blob_hash: Buf32,
+    blob: &BlobEntry,
+) -> Result<(), DbError> {
+    // If new, increment idx
+    match tx.get::<SeqBlobSchema>(&blob_hash)? {
+        None => {
+            let idx = get_next_id::<SeqBlobIdSchema, OptimisticTransactionDB>(tx)?;
+
+            tx.put::<SeqBlobIdSchema>(&idx, &blob_hash)?;
+        }
+        Some(prev) => {
+            // Drop the txids it had before being resigned
+            for txid in [prev.commit_txid, prev.reveal_txid] {
+                if txid != blob.commit_txid && txid != blob.reveal_txid {
+                    tx.delete::<SeqBlobTxidSchema>(&txid)?;
+                }
+            }
+
+            let prev_status_key = blob_status_key(blob_hash, &prev);
+            if prev_status_key != blob_status_key(blob_hash, blob) {
+                tx.delete::<SeqBlobStatusSchema>(&prev_status_key)?;
+            }
+        }
+    }
+
+    tx.put::<SeqBlobStatusSchema>(&blob_status_key(blob_hash, blob), &())?;
+
+    // Unsigned entries don't have txids yet
+    for txid in [blob.commit_txid, blob.reveal_txid] {
+        if txid != Buf32::zero() {
+            tx.put::<SeqBlobTxidSchema>(&txid, &blob_hash)?;
+        }
+    }
+
+    tx.put::<SeqBlobSchema>(&blob_hash, blob)?;
+
+    Ok(())
 }
 
 pub struct SequencerDB<D> {
@@ -82,7 +211,7 @@ impl<B: BlobDatabase> SequencerDatabase for SequencerDB<B> {
 #[cfg(feature = "test_utils")]
 #[cfg(test)]
 mod tests {
-    use strata_db::traits::BlobDatabase;
+    use strata_db::{traits::BlobDatabase, types::BlobL1Status};
     use strata_primitives::buf::Buf32;
     use strata_test_utils::ArbitraryGenerator;
     use test;
@@ -183,4 +312,189 @@ mod tests {
         let last_blob_idx = seq_db.get_last_blob_idx().unwrap();
         assert_eq!(last_blob_idx, Some(1));
     }
+
+    #[test]
+    fn test_put_blob_entries() {
+        let (db, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let seq_db = RBSeqBlobDb::new(db, db_ops);
+
+        let entries: Vec<(Buf32, BlobEntry)> = (0..3u8)
+            .map(|i| ([i; 32].into(), BlobEntry::new_unsigned(vec![i; 10])))
+            .collect();
+        seq_db.put_blob_entries(entries.clone()).unwrap();
+
+        assert_eq!(seq_db.get_last_blob_idx().unwrap(), Some(2));
+        for (idx, (id, entry)) in entries.into_iter().enumerate() {
+            assert_eq!(seq_db.get_blob_id(idx as u64).unwrap(), Some(id));
+            assert_eq!(seq_db.get_blob_by_id(id).unwrap(), Some(entry));
+        }
+    }
+
+    #[test]
+    fn test_get_blob_id_by_txid() {
+        let (db, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let seq_db = RBSeqBlobDb::new(db, db_ops);
+        let blob_hash: Buf32 = [0; 32].into();
+        let other_hash: Buf32 = [1; 32].into();
+
+        // unsigned entries aren't indexed
+        let blob = BlobEntry::new_unsigned(vec![1; 10]);
+        seq_db.put_blob_entry(blob_hash, blob.clone()).unwrap();
+        assert_eq!(seq_db.get_blob_id_by_txid(Buf32::zero()).unwrap(), None);
+
+        let mut signed = blob;
+        signed.commit_txid = [2; 32].into();
+        signed.reveal_txid = [3; 32].into();
+        signed.status = BlobL1Status::Unpublished;
+        seq_db.put_blob_entry(blob_hash, signed.clone()).unwrap();
+
+        let mut other: BlobEntry = ArbitraryGenerator::new().generate();
+        other.commit_txid = [4; 32].into();
+        other.reveal_txid = [5; 32].into();
+        seq_db.put_blob_entry(other_hash, other).unwrap();
+
+        let id = seq_db.get_blob_id_by_txid([3; 32].into()).unwrap();
+        assert_eq!(id, Some(blob_hash));
+        let entry = seq_db.get_blob_by_id(id.unwrap()).unwrap();
+        assert_eq!(entry, Some(signed.clone()));
+        assert_eq!(
+            seq_db.get_blob_id_by_txid([2; 32].into()).unwrap(),
+            Some(blob_hash)
+        );
+        assert_eq!(
+            seq_db.get_blob_id_by_txid([5; 32].into()).unwrap(),
+            Some(other_hash)
+        );
+
+        // resigning moves the entry to the new txids
+        let mut resigned = signed;
+        resigned.commit_txid = [6; 32].into();
+        resigned.reveal_txid = [7; 32].into();
+        seq_db.put_blob_entry(blob_hash, resigned).unwrap();
+        assert_eq!(seq_db.get_blob_id_by_txid([2; 32].into()).unwrap(), None);
+        assert_eq!(seq_db.get_blob_id_by_txid([3; 32].into()).unwrap(), None);
+        assert_eq!(
+            seq_db.get_blob_id_by_txid([7; 32].into()).unwrap(),
+            Some(blob_hash)
+        );
+    }
+
+    #[test]
+    fn test_get_blobs_created_before() {
+        let (db, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let seq_db = RBSeqBlobDb::new(db, db_ops);
+
+        let cases = [
+            (BlobL1Status::Published, 1_000),
+            (BlobL1Status::Published, 5_000),
+            (BlobL1Status::Confirmed, 1_000),
+            (BlobL1Status::Published, 2_000),
+        ];
+        for (i, (status, created_at_ms)) in cases.into_iter().enumerate() {
+            let mut blob: BlobEntry = ArbitraryGenerator::new().generate();
+            blob.status = status;
+            blob.created_at_ms = created_at_ms;
+            seq_db.put_blob_entry([i as u8; 32].into(), blob).unwrap();
+        }
+
+        let created_before = |status, before_ms| {
+            seq_db
+                .get_blobs_created_before(&status, before_ms)
+                .unwrap()
+                .into_iter()
+                .map(|(id, b)| (id, b.created_at_ms))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            created_before(BlobL1Status::Published, 3_000),
+            vec![([0; 32].into(), 1_000), ([3; 32].into(), 2_000)]
+        );
+        assert!(
+            created_before(BlobL1Status::Confirmed, 1_000).is_empty(),
+            "cutoff is exclusive"
+        );
+        assert!(created_before(BlobL1Status::Finalized, u64::MAX).is_empty());
+
+        // entries move along the index as their status changes
+        let mut blob = seq_db.get_blob_by_id([0; 32].into()).unwrap().unwrap();
+        blob.status = BlobL1Status::Confirmed;
+        seq_db
+            .update_blob_entries(vec![([0; 32].into(), blob)], "confirmed".to_string())
+            .unwrap();
+        assert_eq!(
+            created_before(BlobL1Status::Published, 3_000),
+            vec![([3; 32].into(), 2_000)]
+        );
+        assert_eq!(
+            created_before(BlobL1Status::Confirmed, 3_000),
+            vec![([0; 32].into(), 1_000), ([2; 32].into(), 1_000)]
+        );
+    }
+
+    #[test]
+    fn test_blob_transitions() {
+        let (db, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let seq_db = RBSeqBlobDb::new(db, db_ops);
+        let blob_hash: Buf32 = [0; 32].into();
+        let other_hash: Buf32 = [1; 32].into();
+
+        let mut blob = BlobEntry::new_unsigned(vec![1; 10]);
+        let mut other = BlobEntry::new_unsigned(vec![2; 10]);
+        seq_db
+            .put_blob_entries(vec![(blob_hash, blob.clone()), (other_hash, other.clone())])
+            .unwrap();
+        assert!(seq_db.get_blob_transitions(blob_hash).unwrap().is_empty());
+
+        blob.status = BlobL1Status::Unpublished;
+        other.status = BlobL1Status::Unpublished;
+        seq_db
+            .update_blob_entries(
+                vec![(blob_hash, blob.clone()), (other_hash, other.clone())],
+                "signed".to_string(),
+            )
+            .unwrap();
+
+        // unchanged status isn't logged
+        seq_db
+            .update_blob_entries(vec![(blob_hash, blob.clone())], "noop".to_string())
+            .unwrap();
+
+        blob.status = BlobL1Status::Published;
+        seq_db
+            .update_blob_entries(vec![(blob_hash, blob.clone())], "published".to_string())
+            .unwrap();
+
+        let statuses = |id| {
+            seq_db
+                .get_blob_transitions(id)
+                .unwrap()
+                .into_iter()
+                .map(|t| (t.from, t.to, t.reason))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            statuses(blob_hash),
+            vec![
+                (
+                    BlobL1Status::Unsigned,
+                    BlobL1Status::Unpublished,
+                    "signed".to_string()
+                ),
+                (
+                    BlobL1Status::Unpublished,
+                    BlobL1Status::Published,
+                    "published".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            statuses(other_hash),
+            vec![(
+                BlobL1Status::Unsigned,
+                BlobL1Status::Unpublished,
+                "signed".to_string()
+            )]
+        );
+        assert_eq!(seq_db.get_blob_by_id(blob_hash).unwrap(), Some(blob));
+    }
 }
@@ -1,17 +1,198 @@
-use strata_db::types::BlobEntry;
+use borsh::{BorshDeserialize, BorshSerialize};
+use rockbound::{schema::ValueCodec, CodecError};
+use strata_db::types::{BlobEntry, BlobL1Status, BlobStatusTransition};
 use strata_primitives::buf::Buf32;
 
 use crate::{
     define_table_with_default_codec, define_table_with_seek_key_codec, define_table_without_codec,
-    impl_borsh_value_codec,
+    impl_borsh_key_codec, impl_borsh_value_codec,
 };
 
+/// Marks a blob entry record as tagged with a format version.
+///
+/// Untagged v1 records start with the length of the blob instead, which can
+/// start the same way.  Such a record doesn't decode as a whole as a tagged
+/// one though, so it's told apart by falling back to the v1 layout.
+const RECORD_VERSION_MARKER: u8 = 0xff;
+
+/// Format version blob entry records are written with.
+///
+/// Bump this whenever a change to [`BlobEntry`] alters its borsh encoding,
+/// freezing a copy of the previous layout to upgrade the old records from on
+/// read, as done for [`BlobEntryV1`].
+const BLOB_ENTRY_RECORD_VERSION: u8 = 2;
+
+/// Untagged record written before blob entry records were versioned, when
+/// entries only had their blob, txids and status.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+struct BlobEntryV1 {
+    blob: Vec<u8>,
+    commit_txid: Buf32,
+    reveal_txid: Buf32,
+    status: BlobL1Status,
+}
+
+impl From<BlobEntryV1> for BlobEntry {
+    fn from(value: BlobEntryV1) -> Self {
+        // When the entry was created wasn't recorded, so it's left at the epoch
+        BlobEntry {
+            created_at_ms: 0,
+            ..BlobEntry::new(
+                value.blob,
+                value.commit_txid,
+                value.reveal_txid,
+                value.status,
+            )
+        }
+    }
+}
+
 define_table_with_seek_key_codec!(
     /// A table to store idx-> blobid mapping
     (SeqBlobIdSchema) u64 => Buf32
 );
 
-define_table_with_default_codec!(
+define_table_without_codec!(
     /// A table to store blobid -> blob mapping
     (SeqBlobSchema) Buf32 => BlobEntry
 );
+
+impl_borsh_key_codec!(SeqBlobSchema, Buf32);
+
+/// Records are written as the version marker and [`BLOB_ENTRY_RECORD_VERSION`]
+/// followed by the borsh encoded entry.  Records of older versions are
+/// upgraded to the current [`BlobEntry`] when read, they're never rewritten.
+impl ValueCodec<SeqBlobSchema> for BlobEntry {
+    fn encode_value(&self) -> Result<Vec<u8>, CodecError> {
+        let mut buf = vec![RECORD_VERSION_MARKER, BLOB_ENTRY_RECORD_VERSION];
+        borsh::to_writer(&mut buf, self)?;
+        Ok(buf)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self, CodecError> {
+        if let [RECORD_VERSION_MARKER, BLOB_ENTRY_RECORD_VERSION, rest @ ..] = data {
+            if let Ok(entry) = borsh::from_slice(rest) {
+                return Ok(entry);
+            }
+        }
+
+        match (borsh::from_slice::<BlobEntryV1>(data), data) {
+            (Ok(record), _) => Ok(record.into()),
+            (Err(_), [RECORD_VERSION_MARKER, version, ..])
+                if *version > BLOB_ENTRY_RECORD_VERSION =>
+            {
+                Err(anyhow::anyhow!("unsupported blob entry record version {version}").into())
+            }
+            (Err(e), _) => Err(e.into()),
+        }
+    }
+}
+
+define_table_with_default_codec!(
+    /// A table to store commit/reveal txid -> blobid mapping
+    (SeqBlobTxidSchema) Buf32 => Buf32
+);
+
+define_table_with_seek_key_codec!(
+    /// A table to index blobs by their status and creation time, as
+    /// (status tag, created at millis, blobid) -> () mapping
+    (SeqBlobStatusSchema) (u8, u64, Buf32) => ()
+);
+
+/// Returns the key the entry is indexed under in [`SeqBlobStatusSchema`].
+pub(crate) fn blob_status_key(id: Buf32, entry: &BlobEntry) -> (u8, u64, Buf32) {
+    (blob_status_tag(&entry.status), entry.created_at_ms, id)
+}
+
+/// Returns the tag the status is indexed by in [`SeqBlobStatusSchema`].
+pub(crate) fn blob_status_tag(status: &BlobL1Status) -> u8 {
+    match status {
+        BlobL1Status::Unsigned => 0,
+        BlobL1Status::Unpublished => 1,
+        BlobL1Status::Published => 2,
+        BlobL1Status::Confirmed => 3,
+        BlobL1Status::Finalized => 4,
+        BlobL1Status::NeedsResign => 5,
+        BlobL1Status::Failed => 6,
+    }
+}
+
+define_table_with_seek_key_codec!(
+    /// A table to store (blobid, seq) -> status transition mapping, seq increasing with each
+    /// transition logged
+    (SeqBlobTransitionSchema) (Buf32, u64) => BlobStatusTransition
+);
+
+#[cfg(test)]
+mod tests {
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    fn decode(data: &[u8]) -> Result<BlobEntry, CodecError> {
+        <BlobEntry as ValueCodec<SeqBlobSchema>>::decode_value(data)
+    }
+
+    #[test]
+    fn test_decode_v1_record() {
+        let record = BlobEntryV1 {
+            blob: vec![1; 10],
+            commit_txid: [2; 32].into(),
+            reveal_txid: [3; 32].into(),
+            status: BlobL1Status::Published,
+        };
+        let data = borsh::to_vec(&record).unwrap();
+
+        let entry = decode(&data).unwrap();
+        assert_eq!(entry.blob, record.blob);
+        assert_eq!(entry.commit_txid, record.commit_txid);
+        assert_eq!(entry.reveal_txid, record.reveal_txid);
+        assert_eq!(entry.status, record.status);
+        assert_eq!(entry.chunk, None);
+        assert_eq!(entry.resign_count, 0);
+        assert_eq!(entry.created_at_ms, 0);
+        assert_eq!(entry.batch, None);
+    }
+
+    #[test]
+    fn test_decode_v1_record_starting_like_tagged_one() {
+        // The blob length is encoded as [0xff, 0x02, 0, 0]
+        let record = BlobEntryV1 {
+            blob: vec![1; 0x02ff],
+            commit_txid: [2; 32].into(),
+            reveal_txid: [3; 32].into(),
+            status: BlobL1Status::Finalized,
+        };
+        let data = borsh::to_vec(&record).unwrap();
+        assert_eq!(
+            &data[..2],
+            &[RECORD_VERSION_MARKER, BLOB_ENTRY_RECORD_VERSION]
+        );
+
+        let entry = decode(&data).unwrap();
+        assert_eq!(entry.blob, record.blob);
+        assert_eq!(entry.status, record.status);
+    }
+
+    #[test]
+    fn test_roundtrip_current_version() {
+        let mut arb = ArbitraryGenerator::new();
+        for _ in 0..10 {
+            let entry: BlobEntry = arb.generate();
+            let data = entry.encode_value().unwrap();
+            assert_eq!(
+                &data[..2],
+                &[RECORD_VERSION_MARKER, BLOB_ENTRY_RECORD_VERSION]
+            );
+            assert_eq!(decode(&data).unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let entry = BlobEntry::new_unsigned(vec![1; 10]);
+        let mut data = entry.encode_value().unwrap();
+        data[1] = BLOB_ENTRY_RECORD_VERSION + 1;
+        assert!(decode(&data).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Storage usage stats of the column families of the rocksdb store.
+
+use std::sync::Arc;
+
+use rockbound::{rocksdb::properties, schema::ColumnFamilyName, OptimisticTransactionDB};
+use strata_db::{errors::DbError, DbResult};
+
+use crate::utils::translate_rocksdb_error;
+
+/// Number of LSM levels rocksdb keeps SST files in, we don't change the
+/// default.
+const NUM_LEVELS: usize = 7;
+
+/// Approximate storage usage of a column family.
+#[derive(Clone, Debug)]
+pub struct CfStats {
+    /// Name of the column family.
+    pub name: ColumnFamilyName,
+
+    /// Estimated size of the live data of the column family, in bytes.
+    pub live_data_size: u64,
+
+    /// Total size of the SST files of the column family, in bytes.
+    pub sst_files_size: u64,
+
+    /// Number of SST files of the column family, across all levels.
+    pub sst_file_count: u64,
+
+    /// Estimated number of keys in the column family.
+    pub num_keys: u64,
+}
+
+/// Provides storage usage stats for a set of column families of the store.
+///
+/// All the stats come from rocksdb property queries, so they're estimates that
+/// only account for data already flushed to SST files, except for the key
+/// count which also includes the memtables.
+#[derive(Clone)]
+pub struct DbStats {
+    db: Arc<OptimisticTransactionDB>,
+    cfs: &'static [ColumnFamilyName],
+}
+
+impl DbStats {
+    pub fn new(db: Arc<OptimisticTransactionDB>, cfs: &'static [ColumnFamilyName]) -> Self {
+        Self { db, cfs }
+    }
+
+    /// Returns the stats of a single column family.
+    pub fn get_cf_stats(&self, name: ColumnFamilyName) -> DbResult<CfStats> {
+        let inner = self.db.db();
+        let cf = inner
+            .cf_handle(name)
+            .ok_or_else(|| DbError::Other(format!("unknown column family {name}")))?;
+        let get_prop = |prop: &properties::PropName| {
+            inner
+                .property_int_value_cf(&cf, prop)
+                .map(Option::unwrap_or_default)
+                .map_err(translate_rocksdb_error)
+        };
+
+        let mut sst_file_count = 0;
+        for level in 0..NUM_LEVELS {
+            sst_file_count += get_prop(&properties::num_files_at_level(level))?;
+        }
+
+        Ok(CfStats {
+            name,
+            live_data_size: get_prop(properties::ESTIMATE_LIVE_DATA_SIZE)?,
+            sst_files_size: get_prop(properties::TOTAL_SST_FILES_SIZE)?,
+            sst_file_count,
+            num_keys: get_prop(properties::ESTIMATE_NUM_KEYS)?,
+        })
+    }
+
+    /// Returns the stats of all the column families, in order.
+    pub fn get_all_cf_stats(&self) -> DbResult<Vec<CfStats>> {
+        self.cfs
+            .iter()
+            .map(|name| self.get_cf_stats(name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rockbound::Schema;
+    use strata_db::{
+        traits::{L1BroadcastDatabase, L2BlockDatabase, SyncEventDatabase},
+        types::L1TxEntry,
+    };
+    use strata_primitives::buf::Buf32;
+    use strata_state::sync_event::SyncEvent;
+    use strata_test_utils::{bitcoin::get_test_bitcoin_txs, l2::gen_l2_chain, ArbitraryGenerator};
+
+    use super::*;
+    use crate::{
+        broadcaster::schemas::BcastL1TxSchema,
+        checkpoint::schemas::BatchCheckpointSchema,
+        l2::{db::L2Db, schemas::L2BlockSchema},
+        maintenance::compact_column_families,
+        sync_event::schemas::SyncEventSchema,
+        test_utils::get_rocksdb_tmp_instance,
+        L1BroadcastDb, SyncEventDb, STORE_COLUMN_FAMILIES,
+    };
+
+    #[test]
+    fn test_cf_stats() {
+        let (rbdb, db_ops) = get_rocksdb_tmp_instance().unwrap();
+        let stats = DbStats::new(rbdb.clone(), STORE_COLUMN_FAMILIES);
+
+        let mut arb = ArbitraryGenerator::new();
+        let sync_ev_db = SyncEventDb::new(rbdb.clone(), db_ops);
+        for _ in 0..3 {
+            let ev: SyncEvent = arb.generate();
+            sync_ev_db.write_sync_event(ev).unwrap();
+        }
+
+        let l2_db = L2Db::new(rbdb.clone(), db_ops);
+        for block in gen_l2_chain(None, 4) {
+            l2_db.put_block_data(block).unwrap();
+        }
+
+        let bcast_db = L1BroadcastDb::new(rbdb.clone(), db_ops);
+        let txentry = L1TxEntry::from_tx(&get_test_bitcoin_txs()[0]);
+        for i in 0..7 {
+            bcast_db
+                .put_tx_entry(Buf32::from([i; 32]), txentry.clone())
+                .unwrap();
+        }
+
+        // get everything into SST files
+        compact_column_families(&rbdb, STORE_COLUMN_FAMILIES).unwrap();
+
+        let all_stats = stats.get_all_cf_stats().unwrap();
+        assert_eq!(all_stats.len(), STORE_COLUMN_FAMILIES.len());
+
+        let cases = [
+            (SyncEventSchema::COLUMN_FAMILY_NAME, 3),
+            (L2BlockSchema::COLUMN_FAMILY_NAME, 5),
+            (BcastL1TxSchema::COLUMN_FAMILY_NAME, 7),
+        ];
+        let mut sizes = Vec::new();
+        for (name, num_keys) in cases {
+            let cf_stats = all_stats.iter().find(|s| s.name == name).unwrap();
+            assert_eq!(cf_stats.num_keys, num_keys, "{name}");
+            assert!(cf_stats.live_data_size > 0, "{name}");
+            assert!(cf_stats.sst_files_size > 0, "{name}");
+            assert!(cf_stats.sst_file_count > 0, "{name}");
+            sizes.push(cf_stats.live_data_size);
+        }
+
+        sizes.sort();
+        sizes.dedup();
+        assert_eq!(sizes.len(), cases.len(), "stats are not per CF");
+
+        // nothing was written to the checkpoint CF
+        let empty = stats
+            .get_cf_stats(BatchCheckpointSchema::COLUMN_FAMILY_NAME)
+            .unwrap();
+        assert_eq!(empty.num_keys, 0);
+        assert_eq!(empty.sst_file_count, 0);
+    }
+
+    #[test]
+    fn test_unknown_column_family() {
+        let (rbdb, _) = get_rocksdb_tmp_instance().unwrap();
+        let stats = DbStats::new(rbdb, &["nonexistent"]);
+        assert!(stats.get_cf_stats("nonexistent").is_err());
+        assert!(stats.get_all_cf_stats().is_err());
+    }
+}
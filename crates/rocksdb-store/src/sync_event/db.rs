@@ -44,12 +44,23 @@ impl SyncEventDatabase for SyncEventDb {
                         |v| v + 1,
                         1,
                     )?;
+
+                    // The sequence should never hand out an id that's taken, but if it
+                    // ever does we'd silently overwrite an event.
+                    if txn.get_for_update::<SyncEventSchema>(&id)?.is_some() {
+                        return Err(DbError::EntryAlreadyExists);
+                    }
+
                     let event = SyncEventWithTimestamp::new(ev.clone());
                     txn.put::<SyncEventSchema>(&id, &event)?;
-                    Ok::<_, anyhow::Error>(id)
+                    Ok::<_, DbError>(id)
                 },
             )
-            .map_err(|err| DbError::TransactionError(err.to_string()))
+            .map_err(|err| match err {
+                rockbound::TransactionError::Rollback(e) => e,
+                // concurrent writers kept taking the id from under us
+                _ => DbError::WriteConflict("sync_event_store"),
+            })
     }
 
     fn clear_sync_event(&self, start_idx: u64, end_idx: u64) -> DbResult<()> {
@@ -152,15 +163,26 @@ mod tests {
     fn test_get_last_idx_1() {
         let db = setup_db();
 
-        let idx = db.get_last_idx().unwrap().unwrap_or(0);
-        assert_eq!(idx, 0);
+        // empty isn't the same as having written the event at idx 0
+        assert_eq!(db.get_last_idx().unwrap(), None);
 
         let n = 5;
         for i in 1..=n {
             let _ = insert_event(&db);
-            let idx = db.get_last_idx().unwrap().unwrap_or(0);
-            assert_eq!(idx, i);
+            assert_eq!(db.get_last_idx().unwrap(), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_get_last_idx_after_clear() {
+        let db = setup_db();
+        for _ in 0..3 {
+            let _ = insert_event(&db);
         }
+
+        // clearing the early events doesn't change the last one
+        db.clear_sync_event(1, 3).unwrap();
+        assert_eq!(db.get_last_idx().unwrap(), Some(3));
     }
 
     #[test]
@@ -214,6 +236,101 @@ mod tests {
         assert!(res.is_err_and(|x| matches!(x, DbError::Other(ref msg) if msg == "end_idx must be less than or equal to last_key")));
     }
 
+    #[test]
+    fn test_concurrent_writes() {
+        let (rbdb, _) = get_rocksdb_tmp_instance().unwrap();
+        let db = Arc::new(SyncEventDb::new(rbdb, DbOpsConfig::new(1000)));
+
+        let num_threads = 8;
+        let writes_per_thread = 25;
+        let handles: Vec<_> = (0..num_threads)
+            .map(|_| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    let mut arb = ArbitraryGenerator::new();
+                    (0..writes_per_thread)
+                        .map(|_| {
+                            let ev: SyncEvent = arb.generate();
+                            (db.write_sync_event(ev.clone()).unwrap(), ev)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut written: Vec<_> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        written.sort_by_key(|(idx, _)| *idx);
+
+        let total = (num_threads * writes_per_thread) as u64;
+        let idxs: Vec<_> = written.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(idxs, (1..=total).collect::<Vec<_>>());
+        assert_eq!(db.get_last_idx().unwrap(), Some(total));
+
+        // nothing got overwritten
+        for (idx, ev) in written {
+            assert_eq!(db.get_sync_event(idx).unwrap(), Some(ev));
+        }
+    }
+
+    #[test]
+    fn test_contended_writes() {
+        // few retries so that some of the writes give up
+        let (rbdb, _) = get_rocksdb_tmp_instance().unwrap();
+        let db = Arc::new(SyncEventDb::new(rbdb, DbOpsConfig::new(1)));
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    let mut arb = ArbitraryGenerator::new();
+                    let mut idxs = Vec::new();
+                    for _ in 0..50 {
+                        match db.write_sync_event(arb.generate()) {
+                            Ok(idx) => idxs.push(idx),
+                            Err(DbError::WriteConflict(_)) => {}
+                            Err(e) => panic!("unexpected error {e}"),
+                        }
+                    }
+                    idxs
+                })
+            })
+            .collect();
+
+        let mut idxs: Vec<_> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        idxs.sort();
+
+        // no gaps or duplicates
+        let num_written = idxs.len() as u64;
+        assert!(num_written > 0);
+        assert_eq!(idxs, (1..=num_written).collect::<Vec<_>>());
+        assert_eq!(db.get_last_idx().unwrap(), Some(num_written));
+        for idx in idxs {
+            assert!(db.get_sync_event(idx).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn test_write_refuses_taken_id() {
+        let db = setup_db();
+        let ev = insert_event(&db);
+
+        // an event stored without going through the sequence
+        let stray: SyncEvent = ArbitraryGenerator::new().generate();
+        db.db
+            .put::<SyncEventSchema>(&2, &SyncEventWithTimestamp::new(stray.clone()))
+            .unwrap();
+
+        let res = db.write_sync_event(ev);
+        assert!(matches!(res, Err(DbError::EntryAlreadyExists)));
+        assert_eq!(db.get_sync_event(2).unwrap(), Some(stray));
+    }
+
     #[test]
     fn test_get_last_idx_2() {
         let db = setup_db();
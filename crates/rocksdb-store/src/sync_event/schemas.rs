@@ -1,9 +1,27 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use strata_state::sync_event::SyncEvent;
+use rockbound::{schema::ValueCodec, CodecError};
+use strata_state::{
+    batch::BatchCheckpoint,
+    id::L2BlockId,
+    l1::{HeaderVerificationState, L1BlockId},
+    sync_event::SyncEvent,
+};
 
-use crate::{define_table_with_seek_key_codec, define_table_without_codec, impl_borsh_value_codec};
+use crate::{define_table_without_codec, impl_seek_key_codec};
+
+/// Marks a sync event record as tagged with a format version.  Untagged v1
+/// records start with the borsh enum tag of the event instead, which can't
+/// reach this value.
+const RECORD_VERSION_MARKER: u8 = 0xff;
+
+/// Format version sync event records are written with.
+///
+/// Bump this whenever a change to [`SyncEvent`] alters the borsh encoding of
+/// existing variants, freezing a copy of the previous layout to upgrade the
+/// old records from on read, as done for [`SyncEventV1`].
+const SYNC_EVENT_RECORD_VERSION: u8 = 2;
 
 #[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct SyncEventWithTimestamp {
@@ -29,8 +47,158 @@ impl SyncEventWithTimestamp {
     }
 }
 
+/// Layout of [`SyncEvent`] in the untagged v1 records, kept frozen so they
+/// still decode after variants are added to or moved around in [`SyncEvent`].
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+enum SyncEventV1 {
+    L1Block(u64, L1BlockId),
+    L1Revert(u64),
+    L1DABatch(u64, Vec<BatchCheckpoint>),
+    L1BlockGenesis(u64, HeaderVerificationState),
+    NewTipBlock(L2BlockId),
+    ForceFinalize(L2BlockId),
+}
+
+impl From<SyncEventV1> for SyncEvent {
+    fn from(value: SyncEventV1) -> Self {
+        match value {
+            SyncEventV1::L1Block(h, id) => Self::L1Block(h, id),
+            SyncEventV1::L1Revert(h) => Self::L1Revert(h),
+            SyncEventV1::L1DABatch(h, ckpts) => Self::L1DABatch(h, ckpts),
+            SyncEventV1::L1BlockGenesis(h, st) => Self::L1BlockGenesis(h, st),
+            SyncEventV1::NewTipBlock(id) => Self::NewTipBlock(id),
+            SyncEventV1::ForceFinalize(id) => Self::ForceFinalize(id),
+        }
+    }
+}
+
+/// Untagged record written before sync event records were versioned.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+struct SyncEventWithTimestampV1 {
+    event: SyncEventV1,
+    timestamp: u64,
+}
+
+impl From<SyncEventWithTimestampV1> for SyncEventWithTimestamp {
+    fn from(value: SyncEventWithTimestampV1) -> Self {
+        SyncEventWithTimestamp {
+            event: value.event.into(),
+            timestamp: value.timestamp,
+        }
+    }
+}
+
 // Sync Event Schema and corresponding codecs implementation
-define_table_with_seek_key_codec!(
+define_table_without_codec!(
     /// A table to store Sync Events. Maps event index to event
     (SyncEventSchema) u64 => SyncEventWithTimestamp
 );
+
+impl_seek_key_codec!(SyncEventSchema, u64);
+
+/// Records are written as the version marker and [`SYNC_EVENT_RECORD_VERSION`]
+/// followed by the borsh encoded event.  Records of older versions are
+/// upgraded to the current [`SyncEvent`] when read, they're never rewritten.
+impl ValueCodec<SyncEventSchema> for SyncEventWithTimestamp {
+    fn encode_value(&self) -> Result<Vec<u8>, CodecError> {
+        let mut buf = vec![RECORD_VERSION_MARKER, SYNC_EVENT_RECORD_VERSION];
+        borsh::to_writer(&mut buf, self)?;
+        Ok(buf)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self, CodecError> {
+        match data {
+            [RECORD_VERSION_MARKER, SYNC_EVENT_RECORD_VERSION, rest @ ..] => {
+                Ok(borsh::from_slice(rest)?)
+            }
+            [RECORD_VERSION_MARKER, version, ..] => {
+                Err(anyhow::anyhow!("unsupported sync event record version {version}").into())
+            }
+            _ => {
+                let record: SyncEventWithTimestampV1 = borsh::from_slice(data)?;
+                Ok(record.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    fn v1_events() -> Vec<SyncEventV1> {
+        let mut arb = ArbitraryGenerator::new();
+        vec![
+            SyncEventV1::L1Block(1, arb.generate()),
+            SyncEventV1::L1Revert(2),
+            SyncEventV1::L1DABatch(3, vec![arb.generate()]),
+            SyncEventV1::L1BlockGenesis(4, arb.generate()),
+            SyncEventV1::NewTipBlock(arb.generate()),
+            SyncEventV1::ForceFinalize(arb.generate()),
+        ]
+    }
+
+    #[test]
+    fn test_decode_v1_record() {
+        for event in v1_events() {
+            let record = SyncEventWithTimestampV1 {
+                event: event.clone(),
+                timestamp: 42,
+            };
+            let data = borsh::to_vec(&record).unwrap();
+
+            let decoded =
+                <SyncEventWithTimestamp as ValueCodec<SyncEventSchema>>::decode_value(&data)
+                    .unwrap();
+            assert_eq!(decoded.clone().timestamp(), 42);
+            assert_eq!(decoded.event(), SyncEvent::from(event));
+        }
+    }
+
+    #[test]
+    fn test_v1_record_reencoded_as_current_version() {
+        let record = SyncEventWithTimestampV1 {
+            event: SyncEventV1::L1Revert(7),
+            timestamp: 42,
+        };
+        let data = borsh::to_vec(&record).unwrap();
+
+        let decoded =
+            <SyncEventWithTimestamp as ValueCodec<SyncEventSchema>>::decode_value(&data).unwrap();
+        let reencoded = decoded.encode_value().unwrap();
+        assert_eq!(
+            &reencoded[..2],
+            &[RECORD_VERSION_MARKER, SYNC_EVENT_RECORD_VERSION]
+        );
+
+        let redecoded =
+            <SyncEventWithTimestamp as ValueCodec<SyncEventSchema>>::decode_value(&reencoded)
+                .unwrap();
+        assert_eq!(redecoded, decoded);
+    }
+
+    #[test]
+    fn test_roundtrip_current_version() {
+        let mut arb = ArbitraryGenerator::new();
+        for _ in 0..10 {
+            let record = SyncEventWithTimestamp::new(arb.generate());
+            let data = record.encode_value().unwrap();
+            let decoded =
+                <SyncEventWithTimestamp as ValueCodec<SyncEventSchema>>::decode_value(&data)
+                    .unwrap();
+            assert_eq!(decoded, record);
+        }
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let record = SyncEventWithTimestamp::new(SyncEvent::L1Revert(1));
+        let mut data = record.encode_value().unwrap();
+        data[1] = SYNC_EVENT_RECORD_VERSION + 1;
+        assert!(
+            <SyncEventWithTimestamp as ValueCodec<SyncEventSchema>>::decode_value(&data).is_err()
+        );
+    }
+}
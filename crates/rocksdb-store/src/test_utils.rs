@@ -5,7 +5,8 @@ use strata_db::database::CommonDatabase;
 use tempfile::TempDir;
 
 use crate::{
-    l2::db::L2Db, ChainstateDb, ClientStateDb, DbOpsConfig, L1Db, RBCheckpointDB, SyncEventDb,
+    l2::db::L2Db, AtomicBatchDb, ChainstateDb, ClientStateDb, DbOpsConfig, L1Db, RBCheckpointDB,
+    SyncEventDb,
 };
 
 pub fn get_rocksdb_tmp_instance() -> anyhow::Result<(Arc<OptimisticTransactionDB>, DbOpsConfig)> {
@@ -42,8 +43,17 @@ fn get_rocksdb_tmp_instance_core(
     Ok((Arc::new(rbdb), db_ops))
 }
 
-pub fn get_common_db(
-) -> Arc<CommonDatabase<L1Db, L2Db, SyncEventDb, ClientStateDb, ChainstateDb, RBCheckpointDB>> {
+pub fn get_common_db() -> Arc<
+    CommonDatabase<
+        L1Db,
+        L2Db,
+        SyncEventDb,
+        ClientStateDb,
+        ChainstateDb,
+        RBCheckpointDB,
+        AtomicBatchDb,
+    >,
+> {
     let (rbdb, db_ops) = get_rocksdb_tmp_instance().unwrap();
     let l1_db = Arc::new(L1Db::new(rbdb.clone(), db_ops));
     let l2_db = Arc::new(L2Db::new(rbdb.clone(), db_ops));
@@ -51,7 +61,8 @@ pub fn get_common_db(
     let cs_db = Arc::new(ClientStateDb::new(rbdb.clone(), db_ops));
     let chst_db = Arc::new(ChainstateDb::new(rbdb.clone(), db_ops));
     let chpt_db = Arc::new(RBCheckpointDB::new(rbdb.clone(), db_ops));
+    let batch_db = Arc::new(AtomicBatchDb::new(rbdb.clone()));
     Arc::new(CommonDatabase::new(
-        l1_db, l2_db, sync_ev_db, cs_db, chst_db, chpt_db,
+        l1_db, l2_db, sync_ev_db, cs_db, chst_db, chpt_db, batch_db,
     ))
 }
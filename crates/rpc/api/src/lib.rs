@@ -1,12 +1,13 @@
 //! Macro trait def for the `strata_` RPC namespace using jsonrpsee.
 use bitcoin::Txid;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use strata_db::types::L1TxStatus;
+use strata_db::types::{BlobL1Status, L1TxStatus};
 use strata_primitives::bridge::{OperatorIdx, PublickeyTable};
 use strata_rpc_types::{
     types::{RpcBlockHeader, RpcClientStatus, RpcL1Status},
-    HexBytes, HexBytes32, L2BlockStatus, RpcBridgeDuties, RpcCheckpointInfo, RpcDepositEntry,
-    RpcExecUpdate, RpcSyncStatus,
+    ConsensusStateSummary, HexBytes, HexBytes32, L2BlockStatus, RpcBridgeDuties, RpcCheckpointInfo,
+    RpcDbCfStats, RpcDepositEntry, RpcExecUpdate, RpcInscriptionCost, RpcL1BlockManifest,
+    RpcStaleBlob, RpcSyncEventStats, RpcSyncStatus, RpcUnfinalizedTree,
 };
 use strata_state::{id::L2BlockId, operation::ClientUpdateOutput, sync_event::SyncEvent};
 use strata_zkvm::ProofReceipt;
@@ -29,6 +30,11 @@ pub trait StrataApi {
     #[method(name = "getL1blockHash")]
     async fn get_l1_block_hash(&self, height: u64) -> RpcResult<Option<String>>;
 
+    /// Returns the manifest the node stored for the L1 block at the height, if
+    /// it has one.
+    #[method(name = "getL1blockManifest")]
+    async fn get_l1_block_manifest(&self, height: u64) -> RpcResult<Option<RpcL1BlockManifest>>;
+
     #[method(name = "clientStatus")]
     async fn get_client_status(&self) -> RpcResult<RpcClientStatus>;
 
@@ -113,13 +119,40 @@ pub trait StrataApi {
     #[method(name = "getSyncEvent")]
     async fn get_sync_event(&self, idx: u64) -> RpcResult<Option<SyncEvent>>;
 
-    /// Gets the index of the last written sync event.
+    /// Gets the index of the last written sync event, if any were written.
     #[method(name = "getLastSyncEventIdx")]
-    async fn get_last_sync_event_idx(&self) -> RpcResult<u64>;
+    async fn get_last_sync_event_idx(&self) -> RpcResult<Option<u64>>;
 
     /// Gets the client update output produced as a result of the sync event idx given.
     #[method(name = "getClientUpdateOutput")]
     async fn get_client_update_output(&self, idx: u64) -> RpcResult<Option<ClientUpdateOutput>>;
+
+    /// Gets a summary of the consensus state after the sync event idx given,
+    /// including a hash of the full state to compare against other nodes.
+    #[method(name = "getConsensusState")]
+    async fn get_consensus_state(&self, idx: u64) -> RpcResult<ConsensusStateSummary>;
+
+    /// Gets the full borsh-serialized consensus state after the sync event idx
+    /// given.  Only available if debug RPCs are enabled.
+    #[method(name = "getConsensusStateFull")]
+    async fn get_consensus_state_full(&self, idx: u64) -> RpcResult<HexBytes>;
+
+    /// Gets the processing time stats of each kind of sync event since the
+    /// node started.  Only available if debug RPCs are enabled.
+    #[method(name = "getSyncEventStats")]
+    async fn get_sync_event_stats(&self) -> RpcResult<Vec<RpcSyncEventStats>>;
+
+    /// Re-processes the sync event idx given against the consensus state
+    /// right before it and returns the output, without persisting anything.
+    /// Only available if debug RPCs are enabled.
+    #[method(name = "replaySyncEvent")]
+    async fn replay_sync_event(&self, idx: u64) -> RpcResult<ClientUpdateOutput>;
+
+    /// Gets the fork choice manager's tree of unfinalized blocks: the
+    /// finalized tip, the blocks on top of it with their parents and the chain
+    /// tips.  Only available if debug RPCs are enabled.
+    #[method(name = "getUnfinalizedTree")]
+    async fn get_unfinalized_tree(&self) -> RpcResult<RpcUnfinalizedTree>;
 }
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "strataadmin"))]
@@ -128,6 +161,21 @@ pub trait StrataAdminApi {
     /// Stop the node.
     #[method(name = "stop")]
     async fn stop(&self) -> RpcResult<()>;
+
+    /// Runs a manual compaction of the database, dropping tombstones left
+    /// behind by pruning.  Returns once the compaction is done.
+    #[method(name = "triggerCompaction")]
+    async fn trigger_compaction(&self) -> RpcResult<()>;
+
+    /// Gets the approximate size and number of keys of each database column
+    /// family.
+    #[method(name = "getDbStats")]
+    async fn get_db_stats(&self) -> RpcResult<Vec<RpcDbCfStats>>;
+
+    /// Finalizes an unfinalized ancestor of the chain tip without waiting for
+    /// a checkpoint.  Only available if forced finalization is enabled.
+    #[method(name = "forceFinalize")]
+    async fn force_finalize(&self, blkid: L2BlockId) -> RpcResult<()>;
 }
 
 /// rpc endpoints that are only available on sequencer
@@ -138,6 +186,24 @@ pub trait StrataSequencerApi {
     #[method(name = "strataadmin_submitDABlob")]
     async fn submit_da_blob(&self, blobdata: HexBytes) -> RpcResult<()>;
 
+    /// Estimates the fees of inscribing a DA blob with a payload of the given
+    /// size at the fee rate, in sat/vB, before submitting it.
+    #[method(name = "strataadmin_estimateInscriptionCost")]
+    async fn estimate_inscription_cost(
+        &self,
+        payload_len: usize,
+        fee_rate: u64,
+    ) -> RpcResult<RpcInscriptionCost>;
+
+    /// Lists the DA blob entries in the given status that were created more than `min_age_secs`
+    /// ago, oldest first, so the ones stuck on their way to L1 can be alerted on.
+    #[method(name = "strataadmin_getStaleBlobs")]
+    async fn get_stale_blobs(
+        &self,
+        status: BlobL1Status,
+        min_age_secs: u64,
+    ) -> RpcResult<Vec<RpcStaleBlob>>;
+
     /// Verifies and adds the submitted proof to the checkpoint database
     #[method(name = "strataadmin_submitCheckpointProof")]
     async fn submit_checkpoint_proof(&self, idx: u64, proof: ProofReceipt) -> RpcResult<()>;
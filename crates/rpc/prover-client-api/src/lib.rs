@@ -1,7 +1,7 @@
 //! Provides prover-client related APIs for the RPC server.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use strata_rpc_types::ProofKey;
+use strata_rpc_types::{ProofKey, RpcProvingTaskStatus, RpcTaskSummary};
 
 /// RPCs related to information about the client itself.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "dev_strata"))]
@@ -40,7 +40,14 @@ pub trait StrataProverClientApi {
     #[method(name = "proveLatestCheckPoint")]
     async fn prove_latest_checkpoint(&self) -> RpcResult<Vec<ProofKey>>;
 
-    /// Start proving the given el block
+    /// Get the status of the task for the given proof key, if there is one
     #[method(name = "getTaskStatus")]
-    async fn get_task_status(&self, task_id: ProofKey) -> RpcResult<Option<String>>;
+    async fn get_task_status(&self, task_id: ProofKey) -> RpcResult<Option<RpcProvingTaskStatus>>;
+
+    /// List the tasks, optionally only the ones with the given status
+    #[method(name = "listTasks")]
+    async fn list_tasks(
+        &self,
+        filter: Option<RpcProvingTaskStatus>,
+    ) -> RpcResult<Vec<RpcTaskSummary>>;
 }
@@ -55,6 +55,15 @@ pub enum RpcServerError {
     #[error("Invalid proof for checkpoint {0}: {1}")]
     InvalidProof(u64, String),
 
+    #[error("missing client state for index {0}")]
+    MissingClientState(u64),
+
+    #[error("debug RPCs are not enabled")]
+    DebugRpcDisabled,
+
+    #[error("forced finalization is not enabled")]
+    ForceFinalizeDisabled,
+
     /// Generic internal error message.  If this is used often it should be made
     /// into its own error type.
     #[error("{0}")]
@@ -83,6 +92,9 @@ impl RpcServerError {
             Self::MissingCheckpointInDb(_) => -32610,
             Self::ProofAlreadyCreated(_) => -32611,
             Self::InvalidProof(_, _) => -32612,
+            Self::MissingClientState(_) => -32613,
+            Self::DebugRpcDisabled => -32614,
+            Self::ForceFinalizeDisabled => -32615,
             Self::BlockingAbort(_) => -32001,
             Self::Other(_) => -32000,
             Self::OtherEx(_, _) => -32000,
@@ -4,18 +4,24 @@
 //!  - implementation of RPC client
 //!  - crate for just data structures that represents the JSON responses from Bitcoin core RPC
 
-use bitcoin::{Network, Txid};
+use std::collections::BTreeMap;
+
+use bitcoin::{block::Header, hashes::Hash, Network, Txid};
 use serde::{Deserialize, Serialize};
 use strata_primitives::{
     bridge::OperatorIdx,
-    l1::{BitcoinAmount, L1TxRef, OutputRef},
+    errors::ManifestDecodeError,
+    hash::compute_borsh_hash,
+    l1::{BitcoinAmount, L1BlockManifest, L1TxRef, OutputRef, DEFAULT_L1_STALL_THRESHOLD},
     prelude::L1Status,
+    proof::ProofKey,
 };
 use strata_state::{
     batch::BatchInfo,
     bridge_duties::BridgeDuty,
     bridge_ops::WithdrawalIntent,
     bridge_state::{DepositEntry, DepositState},
+    client_state::ClientState,
     id::L2BlockId,
 };
 
@@ -80,6 +86,28 @@ pub struct RpcL1Status {
     /// UNIX millis time of the last time we got a new update from the L1 connector.
     pub last_update: u64,
 
+    /// UNIX millis time of when we last saw the L1 tip change.
+    pub last_seen_block_time_ms: u64,
+
+    /// If no new L1 block has appeared for a while, which means the bitcoin node isn't making
+    /// progress rather than our reader being broken.
+    pub chain_stalled: bool,
+
+    /// Reveal txids of the inscribed blobs that aren't finalized yet, by blob index.
+    pub unfinalized_inscription_txids: BTreeMap<u64, Txid>,
+
+    /// Number of inscriptions not included in a block yet.
+    pub pending_inscription_count: u64,
+
+    /// Number of inscriptions included in a block but not finalized yet.
+    pub confirmed_inscription_count: u64,
+
+    /// Number of inscriptions finalized in the current run.
+    pub finalized_inscription_count: u64,
+
+    /// Blob indexes of the inscriptions that were given up on and need an operator to look into.
+    pub failed_inscription_idxs: Vec<u64>,
+
     /// Underlying network.
     pub network: Network,
 }
@@ -94,6 +122,17 @@ impl RpcL1Status {
             last_published_txid: l1s.last_published_txid.map(Into::into),
             published_inscription_count: l1s.published_inscription_count,
             last_update: l1s.last_update,
+            last_seen_block_time_ms: l1s.last_seen_block_time_ms,
+            chain_stalled: l1s.is_chain_stalled(DEFAULT_L1_STALL_THRESHOLD),
+            unfinalized_inscription_txids: l1s
+                .inscriptions
+                .unfinalized_txids()
+                .map(|(idx, txid)| (idx, txid.into()))
+                .collect(),
+            pending_inscription_count: l1s.inscriptions.pending_count(),
+            confirmed_inscription_count: l1s.inscriptions.confirmed_count(),
+            finalized_inscription_count: l1s.inscriptions.finalized_count(),
+            failed_inscription_idxs: l1s.inscriptions.failed_idxs().collect(),
             network,
         }
     }
@@ -109,6 +148,13 @@ impl Default for RpcL1Status {
             last_published_txid: Default::default(),
             published_inscription_count: Default::default(),
             last_update: Default::default(),
+            last_seen_block_time_ms: Default::default(),
+            chain_stalled: Default::default(),
+            unfinalized_inscription_txids: Default::default(),
+            pending_inscription_count: Default::default(),
+            confirmed_inscription_count: Default::default(),
+            finalized_inscription_count: Default::default(),
+            failed_inscription_idxs: Default::default(),
             network: Network::Regtest,
         }
     }
@@ -164,6 +210,78 @@ pub struct RpcBlockHeader {
     pub state_root: [u8; 32],
 }
 
+/// Fields of a Bitcoin block header.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RpcL1HeaderSummary {
+    /// Block version.
+    pub version: i32,
+
+    /// Hash of the previous block.
+    #[serde(with = "hex::serde")]
+    pub prev_blockhash: [u8; 32],
+
+    /// Root of the merkle tree of the block's transactions.
+    #[serde(with = "hex::serde")]
+    pub merkle_root: [u8; 32],
+
+    /// Block timestamp, in UNIX epoch seconds.
+    pub time: u32,
+
+    /// Compact encoding of the block's target.
+    pub bits: u32,
+
+    /// Nonce the block was mined with.
+    pub nonce: u32,
+}
+
+impl From<&Header> for RpcL1HeaderSummary {
+    fn from(header: &Header) -> Self {
+        Self {
+            version: header.version.to_consensus(),
+            prev_blockhash: header.prev_blockhash.to_byte_array(),
+            merkle_root: header.merkle_root.to_byte_array(),
+            time: header.time,
+            bits: header.bits.to_consensus(),
+            nonce: header.nonce,
+        }
+    }
+}
+
+/// L1 block manifest the node stored for a height.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RpcL1BlockManifest {
+    /// Height of the block.
+    pub height: u64,
+
+    /// Block hash.
+    #[serde(with = "hex::serde")]
+    pub blockid: [u8; 32],
+
+    /// Witness transactions root.
+    #[serde(with = "hex::serde")]
+    pub txs_root: [u8; 32],
+
+    /// Epoch the manifest was generated with.
+    pub epoch: u64,
+
+    /// Decoded block header.
+    pub header: RpcL1HeaderSummary,
+}
+
+impl RpcL1BlockManifest {
+    /// Summarizes a stored manifest, failing if its header doesn't decode.
+    pub fn from_manifest(height: u64, mf: &L1BlockManifest) -> Result<Self, ManifestDecodeError> {
+        let header = mf.parse_header()?;
+        Ok(Self {
+            height,
+            blockid: mf.block_hash().0,
+            txs_root: mf.txs_root().0,
+            epoch: mf.epoch(),
+            header: RpcL1HeaderSummary::from(&header),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct DaBlob {
     /// The destination or identifier for the blob.
@@ -212,6 +330,50 @@ pub struct RpcSyncStatus {
 
     /// L2 block that's been finalized and proven on L1.
     pub finalized_block_id: L2BlockId,
+
+    /// Height of the last L1 block the reader scanned.
+    #[serde(default)]
+    pub l1_scanned_height: u64,
+
+    /// Number of L1 blocks the reader has left to scan to catch up with the
+    /// tip.
+    #[serde(default)]
+    pub l1_behind_by: u64,
+}
+
+/// Summary of the consensus state at some sync event index, meant for cheaply
+/// comparing the states of two nodes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConsensusStateSummary {
+    /// Sync event index the state is at.
+    pub idx: u64,
+
+    /// Hash of the borsh-serialized client state, nodes that agree on the
+    /// state agree on this.
+    #[serde(with = "hex::serde")]
+    pub state_hash: [u8; 32],
+
+    /// L2 chain tip, if the chain is active.
+    pub chain_tip: Option<L2BlockId>,
+
+    /// Finalized L2 block, if the chain is active.
+    pub finalized_blkid: Option<L2BlockId>,
+
+    /// Height of the tip of the local L1 view.
+    pub l1_tip_height: u64,
+}
+
+impl ConsensusStateSummary {
+    pub fn from_client_state(idx: u64, state: &ClientState) -> Self {
+        let sync = state.sync();
+        Self {
+            idx,
+            state_hash: *compute_borsh_hash(state).as_ref(),
+            chain_tip: sync.map(|ss| *ss.chain_tip_blkid()),
+            finalized_blkid: sync.map(|ss| *ss.finalized_blkid()),
+            l1_tip_height: state.l1_view().tip_height(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -302,6 +464,111 @@ impl RpcDepositEntry {
     }
 }
 
+/// Status of a prover client proving task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RpcProvingTaskStatus {
+    /// Waiting for the proofs it depends on.
+    WaitingForDependencies,
+    /// Ready to be started.
+    Pending,
+    /// Currently being proven.
+    ProvingInProgress,
+    /// Proven successfully.
+    Completed,
+    /// Proving failed.
+    Failed,
+}
+
+/// Summary of a prover client proving task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcTaskSummary {
+    /// Key of the proof the task produces.
+    pub key: ProofKey,
+
+    /// Current status of the task.
+    pub status: RpcProvingTaskStatus,
+
+    /// When the task was created, in UNIX epoch millis.
+    pub created_at: u64,
+
+    /// When the status of the task last changed, in UNIX epoch millis.
+    pub updated_at: u64,
+
+    /// Number of times proving the task was started.
+    pub attempts: u32,
+}
+
+/// Projected cost of inscribing a DA blob on L1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcInscriptionCost {
+    /// Number of commit/reveal tx pairs the blob is inscribed with.
+    pub num_inscriptions: u32,
+
+    /// Total vsize of the commit txs.
+    pub commit_vsize: u64,
+
+    /// Total vsize of the reveal txs.
+    pub reveal_vsize: u64,
+
+    /// Total fee of all the txs, in sats.
+    pub total_fee: u64,
+}
+
+/// A DA blob entry that has been in the same status for too long.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcStaleBlob {
+    /// Id of the blob entry.
+    pub id: HexBytes32,
+
+    /// When the entry was created, in UNIX epoch millis.
+    pub created_at_ms: u64,
+
+    /// Txid of the commit tx, if the entry was signed.
+    pub commit_txid: Option<Txid>,
+
+    /// Txid of the reveal tx, if the entry was signed.
+    pub reveal_txid: Option<Txid>,
+}
+
+/// Approximate storage usage of a database column family.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcDbCfStats {
+    /// Name of the column family.
+    pub name: String,
+
+    /// Estimated size of the live data of the column family, in bytes.
+    pub live_data_size: u64,
+
+    /// Total size of the SST files of the column family, in bytes.
+    pub sst_files_size: u64,
+
+    /// Number of SST files of the column family.
+    pub sst_file_count: u64,
+
+    /// Estimated number of keys in the column family.
+    pub num_keys: u64,
+}
+
+/// Processing time stats of one kind of sync event in the CSM.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcSyncEventStats {
+    /// Name of the event kind.
+    pub kind: String,
+
+    /// Number of events of this kind processed since startup.
+    pub count: u64,
+
+    /// Number of those that were logged as slow.
+    pub slow_count: u64,
+
+    /// Median processing duration over the recent events, in microseconds.
+    pub p50_us: u64,
+
+    /// 99th percentile processing duration over the recent events, in
+    /// microseconds.
+    pub p99_us: u64,
+}
+
 /// status of L2 Block
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum L2BlockStatus {
@@ -314,3 +581,50 @@ pub enum L2BlockStatus {
     /// Block is now finalized, certain depth has been reached in L1
     Finalized(u64),
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_state::client_state::SyncState;
+
+    use super::*;
+
+    #[test]
+    fn test_consensus_state_summary_hash() {
+        let state = ClientState::from_genesis_params(10, 20);
+        let summary = ConsensusStateSummary::from_client_state(5, &state);
+        assert!(summary.chain_tip.is_none());
+        assert!(summary.finalized_blkid.is_none());
+
+        // stable for the same state
+        let same = ConsensusStateSummary::from_client_state(5, &state.clone());
+        assert_eq!(summary.state_hash, same.state_hash);
+
+        // changes with the state
+        let mut changed = state.clone();
+        changed.set_sync_state(SyncState::from_genesis_blkid(L2BlockId::default()));
+        let changed = ConsensusStateSummary::from_client_state(5, &changed);
+        assert_ne!(summary.state_hash, changed.state_hash);
+        assert_eq!(changed.chain_tip, Some(L2BlockId::default()));
+        assert_eq!(changed.finalized_blkid, Some(L2BlockId::default()));
+    }
+}
+
+/// Block in the unfinalized block tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcPendingBlock {
+    pub block_id: L2BlockId,
+    pub parent: L2BlockId,
+}
+
+/// Snapshot of the fork choice manager's unfinalized block tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcUnfinalizedTree {
+    /// Base of the tree.
+    pub finalized_tip: L2BlockId,
+
+    /// Blocks on top of the finalized tip, sorted by block id.
+    pub pending_blocks: Vec<RpcPendingBlock>,
+
+    /// Unfinalized chain tips, sorted.
+    pub tips: Vec<L2BlockId>,
+}
@@ -1,36 +1,57 @@
-use strata_crypto::verify_schnorr_sig;
-use strata_primitives::{block_credential::CredRule, hash, params::RollupParams};
+use strata_primitives::{buf::Buf32, hash, params::RollupParams};
+use thiserror::Error;
 use tracing::warn;
 
 use crate::{
     block::L2Block,
+    cred_rule,
     header::{L2Header, SignedL2BlockHeader},
 };
 
-pub fn validate_block_segments(block: &L2Block) -> bool {
-    // Check if the l1_segment_hash matches between L2Block and L2BlockHeader
+/// A block whose header doesn't commit to its body.
+#[derive(Debug, Clone, Error)]
+pub enum BlockIntegrityError {
+    #[error("header commits to L1 segment {header}, body has {computed}")]
+    L1SegmentMismatch { header: Buf32, computed: Buf32 },
+
+    #[error("header commits to exec segment {header}, body has {computed}")]
+    ExecSegmentMismatch { header: Buf32, computed: Buf32 },
+}
+
+/// Recomputes the hashes of the block's segments and checks they're the ones
+/// its header commits to.
+pub fn verify_block_integrity(block: &L2Block) -> Result<(), BlockIntegrityError> {
     let l1seg_buf = borsh::to_vec(block.l1_segment()).expect("blockasm: enc l1 segment");
     let l1_segment_hash = hash::raw(&l1seg_buf);
     if l1_segment_hash != *block.header().l1_payload_hash() {
-        warn!("computed l1_segment_hash doesn't match between L2Block and L2BlockHeader");
-        return false;
+        return Err(BlockIntegrityError::L1SegmentMismatch {
+            header: *block.header().l1_payload_hash(),
+            computed: l1_segment_hash,
+        });
     }
 
-    // Check if the exec_segment_hash matches between L2Block and L2BlockHeader
     let eseg_buf = borsh::to_vec(block.exec_segment()).expect("blockasm: enc exec segment");
     let exec_segment_hash = hash::raw(&eseg_buf);
     if exec_segment_hash != *block.header().exec_payload_hash() {
-        warn!("computed exec_segment_hash doesn't match between L2Block and L2BlockHeader");
-        return false;
+        return Err(BlockIntegrityError::ExecSegmentMismatch {
+            header: *block.header().exec_payload_hash(),
+            computed: exec_segment_hash,
+        });
     }
 
-    true
+    Ok(())
 }
 
-pub fn check_block_credential(header: &SignedL2BlockHeader, rollup_params: &RollupParams) -> bool {
-    let sigcom = header.header().get_sighash();
-    match &rollup_params.cred_rule {
-        CredRule::Unchecked => true,
-        CredRule::SchnorrKey(pubkey) => verify_schnorr_sig(header.sig(), &sigcom, pubkey),
+pub fn validate_block_segments(block: &L2Block) -> bool {
+    match verify_block_integrity(block) {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(%e, "block segments don't match the header");
+            false
+        }
     }
 }
+
+pub fn check_block_credential(header: &SignedL2BlockHeader, rollup_params: &RollupParams) -> bool {
+    cred_rule::verify(&rollup_params.cred_rule, header.header(), header.sig())
+}
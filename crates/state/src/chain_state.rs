@@ -1,10 +1,11 @@
 use arbitrary::Arbitrary;
 use borsh::{BorshDeserialize, BorshSerialize};
-use strata_primitives::{buf::Buf32, hash::compute_borsh_hash};
+use strata_primitives::{bridge::OperatorIdx, buf::Buf32, hash::compute_borsh_hash};
+use thiserror::Error;
 
 use crate::{
     bridge_ops,
-    bridge_state::{self, DepositsTable, OperatorTable},
+    bridge_state::{self, DepositState, DepositsTable, OperatorTable},
     exec_env::{self, ExecEnvState},
     genesis::GenesisStateData,
     l1::{self, L1ViewState},
@@ -47,6 +48,25 @@ pub struct Chainstate {
     pub(crate) deposits_table: bridge_state::DepositsTable,
 }
 
+/// Relationship between parts of the [`Chainstate`] that doesn't hold.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+pub enum StateInvariantError {
+    #[error("L1 view safe height {1} below horizon {0}")]
+    L1ViewBelowHorizon(u64, u64),
+
+    #[error("deposit {1} listed after deposit {0}")]
+    DepositsUnsorted(u32, u32),
+
+    #[error("deposit {0} not below the next deposit idx {1}")]
+    DepositAfterNextIdx(u32, u32),
+
+    #[error("deposit {0} notarized by unknown operator {1}")]
+    UnknownNotaryOperator(u32, OperatorIdx),
+
+    #[error("deposit {0} dispatched to unknown operator {1}")]
+    UnknownAssignee(u32, OperatorIdx),
+}
+
 /// Hashed Chain State. This is used to compute the state root of the [`Chainstate`]
 // TODO: FIXME: Note that this is used as a temporary solution for the state root calculation
 // It should be replaced once we swap out Chainstate's type definitions with SSZ type definitions
@@ -129,6 +149,52 @@ impl Chainstate {
     pub fn exec_env_state(&self) -> &ExecEnvState {
         &self.exec_env_state
     }
+
+    /// Checks that the parts of the chainstate agree with each other.  A
+    /// transition should never produce a state where this fails.
+    pub fn check_invariants(&self) -> Result<(), StateInvariantError> {
+        let l1v = &self.l1_state;
+        if l1v.safe_height() < l1v.horizon_height {
+            return Err(StateInvariantError::L1ViewBelowHorizon(
+                l1v.horizon_height,
+                l1v.safe_height(),
+            ));
+        }
+
+        let next_deposit_idx = self.deposits_table.next_idx();
+        let mut prev_idx = None;
+        for deposit in self.deposits_table.deposits() {
+            let idx = deposit.idx();
+            if let Some(prev_idx) = prev_idx.filter(|prev| *prev >= idx) {
+                return Err(StateInvariantError::DepositsUnsorted(prev_idx, idx));
+            }
+            prev_idx = Some(idx);
+
+            if idx >= next_deposit_idx {
+                return Err(StateInvariantError::DepositAfterNextIdx(
+                    idx,
+                    next_deposit_idx,
+                ));
+            }
+
+            if let Some(op) = deposit
+                .notary_operators()
+                .iter()
+                .find(|op| self.operator_table.get_operator(**op).is_none())
+            {
+                return Err(StateInvariantError::UnknownNotaryOperator(idx, *op));
+            }
+
+            if let DepositState::Dispatched(dispatched) = deposit.deposit_state() {
+                let assignee = dispatched.assignee();
+                if self.operator_table.get_operator(assignee).is_none() {
+                    return Err(StateInvariantError::UnknownAssignee(idx, assignee));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 // NOTE: This is a helper setter that is supposed to be used only in tests.
@@ -153,7 +219,75 @@ impl<'a> Arbitrary<'a> for Chainstate {
 mod tests {
     //use arbitrary::Unstructured;
 
-    //use super::*;
+    use strata_primitives::l1::{BitcoinAmount, OutputRef};
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+    use crate::bridge_state::{DispatchCommand, DispatchedState};
+
+    /// Generates a chainstate with one deposit notarized by all the operators.
+    fn gen_state_with_deposit() -> Chainstate {
+        let mut arb = ArbitraryGenerator::new();
+        let mut state: Chainstate = arb.generate();
+        state.operator_table.insert(arb.generate(), arb.generate());
+        let operators: Vec<_> = state.operator_table.indices().collect();
+        let outref: OutputRef = arb.generate();
+        state
+            .deposits_table
+            .add_deposits(&outref, &operators, BitcoinAmount::from_sat(1_000));
+        state
+    }
+
+    #[test]
+    fn test_consistent_state() {
+        let state: Chainstate = ArbitraryGenerator::new().generate();
+        state.check_invariants().unwrap();
+        gen_state_with_deposit().check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_l1_view_below_horizon() {
+        let mut state = gen_state_with_deposit();
+        let safe_height = state.l1_state.safe_height();
+        state.l1_state.horizon_height = safe_height + 1;
+        assert_eq!(
+            state.check_invariants(),
+            Err(StateInvariantError::L1ViewBelowHorizon(
+                safe_height + 1,
+                safe_height
+            ))
+        );
+    }
+
+    #[test]
+    fn test_unknown_operators() {
+        let mut arb = ArbitraryGenerator::new();
+        let mut state = gen_state_with_deposit();
+        let unknown_op = state.operator_table.len() + 10;
+
+        let mut bad_notaries = state.clone();
+        let outref: OutputRef = arb.generate();
+        bad_notaries.deposits_table.add_deposits(
+            &outref,
+            &[unknown_op],
+            BitcoinAmount::from_sat(1_000),
+        );
+        assert_eq!(
+            bad_notaries.check_invariants(),
+            Err(StateInvariantError::UnknownNotaryOperator(1, unknown_op))
+        );
+
+        let dispatched = DispatchedState::new(DispatchCommand::new(Vec::new()), unknown_op, 100);
+        state
+            .deposits_table
+            .get_deposit_mut(0)
+            .unwrap()
+            .set_state(DepositState::Dispatched(dispatched));
+        assert_eq!(
+            state.check_invariants(),
+            Err(StateInvariantError::UnknownAssignee(0, unknown_op))
+        );
+    }
 
     // TODO re-enable this test, it's going to be changing a lot so these kinds
     // of test vectors aren't that useful right now
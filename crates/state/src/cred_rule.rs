@@ -0,0 +1,69 @@
+//! Checking block signatures against the rollup's credential rule.  Both the
+//! sequencer signing blocks and consensus checking them go through here, so
+//! they can't disagree on what's correctly signed.
+
+use strata_crypto::verify_schnorr_sig;
+use strata_primitives::{block_credential::CredRule, buf::Buf64};
+
+use crate::header::L2BlockHeader;
+
+/// Checks if the signature over the header satisfies the credential rule.
+pub fn verify(rule: &CredRule, header: &L2BlockHeader, sig: &Buf64) -> bool {
+    match rule {
+        CredRule::Unchecked => true,
+        CredRule::SchnorrKey(pubkey) => verify_schnorr_sig(sig, &header.get_sighash(), pubkey),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::{Keypair, Message, SecretKey, SECP256K1};
+    use strata_primitives::buf::Buf32;
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    fn keypair(seed: u8) -> Keypair {
+        let sk = SecretKey::from_slice(&[seed; 32]).unwrap();
+        Keypair::from_secret_key(SECP256K1, &sk)
+    }
+
+    fn pubkey(kp: &Keypair) -> Buf32 {
+        kp.x_only_public_key().0.serialize().into()
+    }
+
+    fn sign(header: &L2BlockHeader, kp: &Keypair) -> Buf64 {
+        let msg = Message::from_digest(header.get_sighash().0);
+        SECP256K1.sign_schnorr_no_aux_rand(&msg, kp).into()
+    }
+
+    #[test]
+    fn test_unchecked() {
+        let mut arb = ArbitraryGenerator::new();
+        let header: L2BlockHeader = arb.generate();
+
+        assert!(verify(&CredRule::Unchecked, &header, &Buf64::zero()));
+        assert!(verify(&CredRule::Unchecked, &header, &arb.generate()));
+    }
+
+    #[test]
+    fn test_schnorr_key() {
+        let mut arb = ArbitraryGenerator::new();
+        let header: L2BlockHeader = arb.generate();
+        let kp = keypair(1);
+        let rule = CredRule::SchnorrKey(pubkey(&kp));
+
+        let sig = sign(&header, &kp);
+        assert!(verify(&rule, &header, &sig));
+
+        // signed by another key
+        let other_sig = sign(&header, &keypair(2));
+        assert!(!verify(&rule, &header, &other_sig));
+
+        // signature over another header
+        let other_header: L2BlockHeader = arb.generate();
+        assert!(!verify(&rule, &other_header, &sig));
+
+        assert!(!verify(&rule, &header, &Buf64::zero()));
+    }
+}
@@ -5,7 +5,14 @@
 use arbitrary::Arbitrary;
 use borsh::{BorshDeserialize, BorshSerialize};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use strata_primitives::buf::Buf32;
+use strata_primitives::{buf::Buf32, hash};
+use thiserror::Error;
+
+/// Max size of a blob payload.
+///
+/// This is well over what fits in a single inscription, the L1 writer splits
+/// larger payloads across as many as it takes.
+pub const MAX_BLOB_PAYLOAD_SIZE: usize = 4_000_000;
 
 /// DA destination identifier.   This will eventually be used to enable
 /// storing blobs on alternative availability schemes.
@@ -89,6 +96,11 @@ impl BlobIntent {
         }
     }
 
+    /// Returns a builder that validates the intent before constructing it.
+    pub fn builder() -> BlobIntentBuilder {
+        BlobIntentBuilder::default()
+    }
+
     /// The target we expect the DA blob to be stored on.
     pub fn dest(&self) -> BlobDest {
         self.dest
@@ -115,3 +127,142 @@ impl BlobIntent {
         }
     }
 }
+
+/// Error that can arise when building a [`BlobIntent`].
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum BlobIntentError {
+    #[error("blob destination not set")]
+    MissingDest,
+
+    #[error("blob payload is empty")]
+    EmptyPayload,
+
+    #[error("blob payload too large (max {0} bytes, got {1})")]
+    PayloadTooLarge(usize, usize),
+}
+
+/// Builder for [`BlobIntent`]s.
+///
+/// If no commitment is provided, the commitment is computed as the hash of the
+/// payload.
+#[derive(Clone, Debug)]
+pub struct BlobIntentBuilder {
+    dest: Option<BlobDest>,
+    commitment: Option<Buf32>,
+    payload: Vec<u8>,
+    max_payload_size: usize,
+}
+
+impl Default for BlobIntentBuilder {
+    fn default() -> Self {
+        Self {
+            dest: None,
+            commitment: None,
+            payload: Vec::new(),
+            max_payload_size: MAX_BLOB_PAYLOAD_SIZE,
+        }
+    }
+}
+
+impl BlobIntentBuilder {
+    pub fn dest(mut self, dest: BlobDest) -> Self {
+        self.dest = Some(dest);
+        self
+    }
+
+    /// Sets a context-specific commitment instead of the payload hash.
+    pub fn commitment(mut self, commitment: Buf32) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    pub fn payload(mut self, payload: Vec<u8>) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Overrides the max payload size, [`MAX_BLOB_PAYLOAD_SIZE`] by default.
+    pub fn max_payload_size(mut self, max_payload_size: usize) -> Self {
+        self.max_payload_size = max_payload_size;
+        self
+    }
+
+    pub fn build(self) -> Result<BlobIntent, BlobIntentError> {
+        let dest = self.dest.ok_or(BlobIntentError::MissingDest)?;
+
+        if self.payload.is_empty() {
+            return Err(BlobIntentError::EmptyPayload);
+        }
+
+        if self.payload.len() > self.max_payload_size {
+            return Err(BlobIntentError::PayloadTooLarge(
+                self.max_payload_size,
+                self.payload.len(),
+            ));
+        }
+
+        let commitment = self.commitment.unwrap_or_else(|| hash::raw(&self.payload));
+
+        Ok(BlobIntent::new(dest, commitment, self.payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_valid_intent() {
+        let payload = vec![1; 100];
+        let intent = BlobIntent::builder()
+            .dest(BlobDest::L1)
+            .payload(payload.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(intent.dest(), BlobDest::L1);
+        assert_eq!(intent.payload(), payload);
+        assert_eq!(*intent.commitment(), hash::raw(&payload));
+    }
+
+    #[test]
+    fn test_build_with_explicit_commitment() {
+        let commitment = Buf32::from([7; 32]);
+        let intent = BlobIntent::builder()
+            .dest(BlobDest::L1)
+            .commitment(commitment)
+            .payload(vec![1; 100])
+            .build()
+            .unwrap();
+
+        assert_eq!(*intent.commitment(), commitment);
+    }
+
+    #[test]
+    fn test_build_rejects_oversized_payload() {
+        let res = BlobIntent::builder()
+            .dest(BlobDest::L1)
+            .payload(vec![1; MAX_BLOB_PAYLOAD_SIZE + 1])
+            .build();
+
+        assert_eq!(
+            res,
+            Err(BlobIntentError::PayloadTooLarge(
+                MAX_BLOB_PAYLOAD_SIZE,
+                MAX_BLOB_PAYLOAD_SIZE + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_missing_dest() {
+        let res = BlobIntent::builder().payload(vec![1; 100]).build();
+        assert_eq!(res, Err(BlobIntentError::MissingDest));
+    }
+
+    #[test]
+    fn test_build_rejects_empty_payload() {
+        let res = BlobIntent::builder().dest(BlobDest::L1).build();
+        assert_eq!(res, Err(BlobIntentError::EmptyPayload));
+    }
+}
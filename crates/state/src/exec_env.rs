@@ -3,8 +3,27 @@
 use arbitrary::Arbitrary;
 use borsh::{BorshDeserialize, BorshSerialize};
 use strata_primitives::buf::Buf32;
+use thiserror::Error;
 
-use crate::{bridge_ops, da_blob, exec_update, forced_inclusion, state_queue::StateQueue};
+use crate::{
+    bridge_ops, da_blob,
+    exec_update::{self, ExecUpdate, Op},
+    forced_inclusion,
+    state_queue::StateQueue,
+};
+
+/// An exec update that can't be applied on top of an exec env state.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+pub enum ExecUpdateError {
+    #[error("update idx {1} doesn't follow {0}")]
+    UpdateIdxMismatch(u64, u64),
+
+    #[error("update applies deposit intent {0} that isn't pending")]
+    UnknownDepositIntent(u64),
+
+    #[error("update applies deposit intent {0} with a different amount or destination")]
+    DepositIntentMismatch(u64),
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct ExecEnvState {
@@ -62,6 +81,64 @@ impl ExecEnvState {
     pub fn pending_deposits_mut(&mut self) -> &mut StateQueue<bridge_ops::DepositIntent> {
         &mut self.pending_deposits
     }
+
+    pub fn waiting_da_blobs(&self) -> &[da_blob::BlobSpec] {
+        &self.waiting_da_blobs
+    }
+
+    /// Computes the state after the update, checking it follows from this one.
+    ///
+    /// The update has to be the next one, and the deposits it applies have to
+    /// be pending here exactly as they're applied.  Pending deposits up to the
+    /// last applied one are consumed.
+    pub fn apply_update(&self, update: &ExecUpdate) -> Result<ExecEnvState, ExecUpdateError> {
+        let input = update.input();
+        let output = update.output();
+
+        if Some(input.update_idx()) != self.update_idx().checked_add(1) {
+            return Err(ExecUpdateError::UpdateIdxMismatch(
+                self.update_idx(),
+                input.update_idx(),
+            ));
+        }
+
+        let mut last_applied_deposit = None;
+        for op in input.applied_ops() {
+            let Op::Deposit(deposit) = op;
+            let idx = deposit.intent_idx();
+            let intent = self
+                .pending_deposits
+                .get_absolute(idx)
+                .ok_or(ExecUpdateError::UnknownDepositIntent(idx))?;
+            if intent.amt() != deposit.amt() || intent.dest_ident() != deposit.dest_addr() {
+                return Err(ExecUpdateError::DepositIntentMismatch(idx));
+            }
+
+            last_applied_deposit = last_applied_deposit.max(Some(idx));
+        }
+
+        let mut post_state = self.clone();
+        post_state.last_update_input = input.clone();
+        post_state.cur_state = *output.new_state();
+
+        if let Some(idx) = last_applied_deposit {
+            let n_consumed = idx + 1 - post_state.pending_deposits.base_idx();
+            post_state
+                .pending_deposits
+                .pop_front_n_vec(n_consumed as usize)
+                .expect("exec_env: consume applied deposits");
+        }
+
+        post_state
+            .waiting_da_blobs
+            .extend_from_slice(output.da_blobs());
+        post_state
+            .waiting_da_blobs
+            .sort_unstable_by_key(|b| (b.dest(), *b.commitment()));
+        post_state.waiting_da_blobs.dedup();
+
+        Ok(post_state)
+    }
 }
 
 impl<'a> Arbitrary<'a> for ExecEnvState {
@@ -71,3 +148,96 @@ impl<'a> Arbitrary<'a> for ExecEnvState {
         Ok(Self::from_base_input(inp, state))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_primitives::l1::BitcoinAmount;
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+    use crate::{
+        bridge_ops::DepositIntent,
+        exec_update::{ELDepositData, UpdateInput, UpdateOutput},
+    };
+
+    const DEST: [u8; 20] = [7; 20];
+
+    fn deposit_op(intent_idx: u64, amt: u64) -> Op {
+        Op::Deposit(ELDepositData::new(intent_idx, amt, DEST.to_vec()))
+    }
+
+    /// Env state at update 5 with three pending deposits of 1, 2 and 3 BTC.
+    fn make_env_state() -> ExecEnvState {
+        let input = UpdateInput::new(5, Vec::new(), Buf32::from([1; 32]), Vec::new());
+        let mut state = ExecEnvState::from_base_input(input, Buf32::from([2; 32]));
+        for btc in 1..=3 {
+            let amt = BitcoinAmount::from_int_btc(btc);
+            state
+                .pending_deposits_mut()
+                .push_back(DepositIntent::new(amt, &DEST));
+        }
+        state
+    }
+
+    #[test]
+    fn test_apply_update() {
+        let mut arb = ArbitraryGenerator::new();
+        let state = make_env_state();
+        let blob: da_blob::BlobSpec = arb.generate();
+
+        // applies the first two deposits
+        let input = UpdateInput::new(
+            6,
+            vec![deposit_op(0, 100_000_000), deposit_op(1, 200_000_000)],
+            Buf32::from([3; 32]),
+            Vec::new(),
+        );
+        let output = UpdateOutput::new_from_state(Buf32::from([4; 32])).with_da_blobs(vec![blob]);
+        let update = ExecUpdate::new(input.clone(), output);
+
+        let mut expected = ExecEnvState::from_base_input(input, Buf32::from([4; 32]));
+        expected.pending_deposits = StateQueue::new_at_index(2);
+        expected
+            .pending_deposits
+            .push_back(DepositIntent::new(BitcoinAmount::from_int_btc(3), &DEST));
+        expected.waiting_da_blobs = vec![blob];
+
+        let post_state = state.apply_update(&update).unwrap();
+        assert_eq!(post_state, expected);
+        assert_eq!(post_state.update_idx(), 6);
+
+        // an update without deposits only moves the state along
+        let input = UpdateInput::new(7, Vec::new(), Buf32::from([5; 32]), Vec::new());
+        let update = ExecUpdate::new(input, UpdateOutput::new_from_state(Buf32::from([6; 32])));
+        let next_state = post_state.apply_update(&update).unwrap();
+        assert_eq!(next_state.cur_state_root(), &Buf32::from([6; 32]));
+        assert_eq!(next_state.pending_deposits(), post_state.pending_deposits());
+        assert_eq!(next_state.waiting_da_blobs(), &[blob]);
+    }
+
+    #[test]
+    fn test_apply_bad_update() {
+        let state = make_env_state();
+        let make_update = |idx, ops| {
+            let input = UpdateInput::new(idx, ops, Buf32::zero(), Vec::new());
+            ExecUpdate::new(input, UpdateOutput::new_from_state(Buf32::zero()))
+        };
+
+        assert_eq!(
+            state.apply_update(&make_update(7, Vec::new())),
+            Err(ExecUpdateError::UpdateIdxMismatch(5, 7))
+        );
+        assert_eq!(
+            state.apply_update(&make_update(5, Vec::new())),
+            Err(ExecUpdateError::UpdateIdxMismatch(5, 5))
+        );
+        assert_eq!(
+            state.apply_update(&make_update(6, vec![deposit_op(3, 100_000_000)])),
+            Err(ExecUpdateError::UnknownDepositIntent(3))
+        );
+        assert_eq!(
+            state.apply_update(&make_update(6, vec![deposit_op(1, 100_000_000)])),
+            Err(ExecUpdateError::DepositIntentMismatch(1))
+        );
+    }
+}
@@ -138,6 +138,11 @@ impl UpdateOutput {
         self
     }
 
+    pub fn with_da_blobs(mut self, da_blobs: Vec<da_blob::BlobSpec>) -> Self {
+        self.da_blobs = da_blobs;
+        self
+    }
+
     pub fn new_state(&self) -> &Buf32 {
         &self.new_state
     }
@@ -1,4 +1,4 @@
-use bitcoin::{block::Header, consensus::Encodable, hashes::Hash, Block, Wtxid};
+use bitcoin::{block::Header, consensus::Encodable, hashes::Hash, Block, Transaction, Wtxid};
 use strata_primitives::{
     buf::Buf32, hash::sha256d, l1::L1TxProof, utils::get_cohashes_from_wtxids,
 };
@@ -61,10 +61,29 @@ pub fn generate_l1_tx(block: &Block, idx: u32, proto_op_data: ProtocolOperation)
     L1Tx::new(proof, tx, proto_op_data)
 }
 
+/// Checks that the [`L1Tx`] is included in the block with the given witness transactions root.
+///
+/// Returns `false` if the proof doesn't match or the tx data can't be decoded.
+pub fn verify_tx_inclusion(l1_tx: &L1Tx, wtxs_root: Buf32) -> bool {
+    let proof = l1_tx.proof();
+
+    // Coinbase's wtxid is all zeros
+    let wtxid = if proof.position() == 0 {
+        Wtxid::all_zeros()
+    } else {
+        match bitcoin::consensus::deserialize::<Transaction>(l1_tx.tx_data()) {
+            Ok(tx) => tx.compute_wtxid(),
+            Err(_) => return false,
+        }
+    };
+
+    proof.verify(Buf32::from(wtxid.to_byte_array()), wtxs_root)
+}
+
 #[cfg(test)]
 mod tests {
     use bitcoin::hashes::Hash;
-    use strata_test_utils::bitcoin::get_btc_mainnet_block;
+    use strata_test_utils::{bitcoin::get_btc_mainnet_block, ArbitraryGenerator};
 
     use super::*;
 
@@ -75,4 +94,34 @@ mod tests {
         let actual = compute_block_hash(&btc_block.header);
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_verify_tx_inclusion() {
+        let btc_block = get_btc_mainnet_block();
+        let wtxs_root = Buf32::from(btc_block.witness_root().unwrap().to_byte_array());
+
+        // the last tx exercises the duplicated-last-node case when a level is odd
+        let last = btc_block.txdata.len() as u32 - 1;
+        for idx in [0, 1, last / 2, last] {
+            let l1_tx = generate_l1_tx(
+                &btc_block,
+                idx,
+                ProtocolOperation::Deposit(ArbitraryGenerator::new().generate()),
+            );
+            assert!(verify_tx_inclusion(&l1_tx, wtxs_root), "idx = {idx}");
+        }
+    }
+
+    #[test]
+    fn test_verify_tx_inclusion_wrong_root() {
+        let btc_block = get_btc_mainnet_block();
+        let l1_tx = generate_l1_tx(
+            &btc_block,
+            1,
+            ProtocolOperation::Deposit(ArbitraryGenerator::new().generate()),
+        );
+
+        let merkle_root = Buf32::from(btc_block.header.merkle_root.to_byte_array());
+        assert!(!verify_tx_inclusion(&l1_tx, merkle_root));
+    }
 }
@@ -1,9 +1,19 @@
 use arbitrary::Arbitrary;
 use borsh::{BorshDeserialize, BorshSerialize};
+use thiserror::Error;
 
 use super::{L1HeaderRecord, L1MaturationEntry};
 use crate::prelude::StateQueue;
 
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+pub enum L1ViewError {
+    #[error("can't roll back to L1 height {0}, tip is below it at {1}")]
+    RollbackAboveTip(u64, u64),
+
+    #[error("can't roll back to L1 height {0}, blocks up to {1} already matured")]
+    RollbackMatured(u64, u64),
+}
+
 /// Describes state relating to the CL's view of L1.  Updated by entries in the
 /// L1 segment of CL blocks.
 #[derive(Clone, Debug, Eq, PartialEq, BorshSerialize, BorshDeserialize)]
@@ -56,6 +66,29 @@ impl L1ViewState {
     pub fn maturation_queue(&self) -> &StateQueue<L1MaturationEntry> {
         &self.maturation_queue
     }
+
+    /// Drops the blocks above the height from the maturation queue, so the
+    /// block at the height is the last one in it.  Blocks that already matured
+    /// can't be rolled back.
+    pub fn rollback_to(&mut self, height: u64) -> Result<(), L1ViewError> {
+        let next_idx = self.maturation_queue.next_idx();
+        if height >= next_idx {
+            return Err(L1ViewError::RollbackAboveTip(height, next_idx - 1));
+        }
+
+        let base_idx = self.maturation_queue.base_idx();
+        if height + 1 < base_idx {
+            return Err(L1ViewError::RollbackMatured(height, base_idx - 1));
+        }
+
+        for _ in height + 1..next_idx {
+            self.maturation_queue
+                .pop_back()
+                .expect("l1view: pop unmatured block");
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> Arbitrary<'a> for L1ViewState {
@@ -64,3 +97,72 @@ impl<'a> Arbitrary<'a> for L1ViewState {
         Ok(Self::new_at_horizon(u64::arbitrary(u)?, blk))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_test_utils::ArbitraryGenerator;
+
+    use super::*;
+
+    const HORIZON: u64 = 100;
+
+    /// Builds a view with the blocks accepted into it, in order.
+    fn build_view(safe_block: &L1HeaderRecord, blocks: &[L1MaturationEntry]) -> L1ViewState {
+        let mut view = L1ViewState::new_at_horizon(HORIZON, safe_block.clone());
+        for block in blocks {
+            view.maturation_queue.push_back(block.clone());
+        }
+        view
+    }
+
+    #[test]
+    fn test_rollback_to() {
+        let mut arb = ArbitraryGenerator::new();
+        let safe_block: L1HeaderRecord = arb.generate();
+        let blocks: Vec<L1MaturationEntry> = (0..6).map(|_| arb.generate()).collect();
+
+        let mut view = build_view(&safe_block, &blocks);
+        assert_eq!(view.tip_height(), HORIZON + 6);
+
+        // rolling back to the last block does nothing
+        view.rollback_to(HORIZON + 5).unwrap();
+        assert_eq!(view, build_view(&safe_block, &blocks));
+
+        view.rollback_to(HORIZON + 2).unwrap();
+        assert_eq!(view, build_view(&safe_block, &blocks[..3]));
+        assert_eq!(view.tip_height(), HORIZON + 3);
+
+        // the view can be built on again after
+        view.maturation_queue.push_back(blocks[3].clone());
+        assert_eq!(view, build_view(&safe_block, &blocks[..4]));
+
+        // all the way down to below the first unmatured block
+        view.rollback_to(HORIZON - 1).unwrap();
+        assert_eq!(view, build_view(&safe_block, &[]));
+    }
+
+    #[test]
+    fn test_rollback_to_out_of_range() {
+        let mut arb = ArbitraryGenerator::new();
+        let safe_block: L1HeaderRecord = arb.generate();
+        let blocks: Vec<L1MaturationEntry> = (0..4).map(|_| arb.generate()).collect();
+        let mut view = build_view(&safe_block, &blocks);
+
+        assert_eq!(
+            view.rollback_to(HORIZON + 4),
+            Err(L1ViewError::RollbackAboveTip(HORIZON + 4, HORIZON + 3))
+        );
+
+        // once the first two matured they're out of reach
+        view.maturation_queue.pop_front_n_vec(2).unwrap();
+        assert_eq!(
+            view.rollback_to(HORIZON),
+            Err(L1ViewError::RollbackMatured(HORIZON, HORIZON + 1))
+        );
+        view.rollback_to(HORIZON + 1).unwrap();
+        assert!(view.maturation_queue().is_empty());
+
+        // and the matured ones stay matured
+        assert_eq!(view.safe_height(), HORIZON + 2);
+    }
+}
@@ -14,6 +14,7 @@ pub mod bridge_ops;
 pub mod bridge_state;
 pub mod chain_state;
 pub mod client_state;
+pub mod cred_rule;
 pub mod csm_status;
 pub mod da_blob;
 pub mod exec_env;
@@ -78,6 +78,13 @@ pub enum ClientStateWrite {
 
     /// Updates the L1 header verification state
     UpdateVerificationState(HeaderVerificationState),
+
+    /// Sets the finalized L2 block without a checkpoint backing it.
+    UpdateFinalized(L2BlockId),
+
+    /// Marks the last L2 block of a checkpoint as DA-confirmed, since the
+    /// checkpoint was posted in the L1 block at the given height.
+    MarkDaConfirmed(u64, L2BlockId),
 }
 
 /// Actions the client state machine directs the node to take to update its own
@@ -110,6 +117,15 @@ pub enum SyncAction {
     /// Indicates the worker to write the checkpoints to checkpoint db that appear in given L1
     /// height
     FinalizeCheckpoints(u64, Vec<BatchCheckpoint>),
+
+    /// Reverts our tip to an ancestor of it, because we're switching to a fork
+    /// that branches off there.  This is always followed by an `UpdateTip` for
+    /// the tip of the fork.
+    RevertTip(L2BlockId),
+
+    /// Requests an L2 block we don't have yet, like the missing parent of a
+    /// block we were told about.
+    FetchBlock(L2BlockId),
 }
 
 /// Applies client state writes to a target state.
@@ -151,20 +167,29 @@ pub fn apply_writes_to_state(
                     panic!("operation: emitted invalid write");
                 }
 
-                let new_unacc_len = (height - buried_height) as usize;
+                // The block at the height we roll back to is kept.
+                let new_unacc_len =
+                    ((height - buried_height + 1) as usize).min(l1v.local_unaccepted_blocks.len());
                 let l1_vs = l1v.tip_verification_state();
                 if let Some(l1_vs) = l1_vs {
                     // TODO: handle other things
                     let mut rollbacked_l1_vs = l1_vs.clone();
                     rollbacked_l1_vs.last_verified_block_num = height as u32;
-                    rollbacked_l1_vs.last_verified_block_hash =
-                        l1v.local_unaccepted_blocks[new_unacc_len];
+                    if let Some(i) = new_unacc_len.checked_sub(1) {
+                        rollbacked_l1_vs.last_verified_block_hash = l1v.local_unaccepted_blocks[i];
+                    }
                 }
                 l1v.local_unaccepted_blocks.truncate(new_unacc_len);
+                l1v.next_expected_block = buried_height + new_unacc_len as u64;
 
                 // Keep pending checkpoints whose l1 height is less than or equal to rollback height
                 l1v.verified_checkpoints
                     .retain(|ckpt| ckpt.height <= height);
+
+                // The DA confirmations from reverted blocks are gone too
+                if let Some(ss) = state.sync_state.as_mut() {
+                    ss.confirmed_checkpoint_blocks.retain(|(h, _)| *h <= height);
+                }
             }
 
             AcceptL1Block(l1blkid) => {
@@ -249,7 +274,93 @@ pub fn apply_writes_to_state(
                     // Update finalized blockid in StateSync
                     state.expect_sync_mut().finalized_blkid = fin_blockid;
                 }
+
+                // Finalized blocks don't have to be tracked as confirmed anymore
+                if let Some(ss) = state.sync_state.as_mut() {
+                    ss.confirmed_checkpoint_blocks.retain(|(h, _)| *h > height);
+                }
+            }
+
+            MarkDaConfirmed(height, blkid) => {
+                debug!(%height, %blkid, "received MarkDaConfirmed");
+                let ss = state.expect_sync_mut();
+                let confirmed = &mut ss.confirmed_checkpoint_blocks;
+                if !confirmed.contains(&(height, blkid)) {
+                    // Keep them ordered by height
+                    let pos = confirmed.partition_point(|(h, _)| *h <= height);
+                    confirmed.insert(pos, (height, blkid));
+                }
+            }
+
+            UpdateFinalized(blkid) => {
+                warn!(%blkid, "forcing finalized block");
+                state.expect_sync_mut().finalized_blkid = blkid;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_primitives::buf::Buf32;
+
+    use super::*;
+
+    fn l1_blkid(height: u64) -> L1BlockId {
+        L1BlockId::from(Buf32::from([height as u8; 32]))
+    }
+
+    /// Makes a client state with the L1 blocks from the horizon up to the tip.
+    fn state_with_l1_blocks(horizon: u64, tip: u64) -> ClientState {
+        let mut state = ClientState::from_genesis_params(horizon, horizon + 2);
+        let writes = (horizon..=tip).map(|h| ClientStateWrite::AcceptL1Block(l1_blkid(h)));
+        apply_writes_to_state(&mut state, writes);
+        state
+    }
+
+    #[test]
+    fn test_rollback_l1_blocks_keeps_rollback_block() {
+        let mut state = state_with_l1_blocks(10, 14);
+        assert_eq!(state.l1_view().buried_l1_height(), 10);
+        assert_eq!(state.next_exp_l1_block(), 15);
+
+        // the block we roll back to is still there and the next one is
+        // expected again
+        let writes = [ClientStateWrite::RollbackL1BlocksTo(12)];
+        apply_writes_to_state(&mut state, writes.into_iter());
+        let l1v = state.l1_view();
+        assert_eq!(
+            l1v.local_unaccepted_blocks(),
+            &[l1_blkid(10), l1_blkid(11), l1_blkid(12)]
+        );
+        assert_eq!(l1v.tip_height(), 12);
+        assert_eq!(l1v.next_expected_block(), 13);
+        assert_eq!(l1v.buried_l1_height(), 10);
+
+        // the replacement block then lines up with the expected height
+        let writes = [ClientStateWrite::AcceptL1Block(l1_blkid(100))];
+        apply_writes_to_state(&mut state, writes.into_iter());
+        let l1v = state.l1_view();
+        assert_eq!(l1v.tip_height(), 13);
+        assert_eq!(l1v.unacc_blocks_iter().last(), Some((13, &l1_blkid(100))));
+    }
+
+    #[test]
+    fn test_rollback_l1_blocks_bounds() {
+        // rolling back to the tip or past it changes nothing
+        for height in [14, 20] {
+            let mut state = state_with_l1_blocks(10, 14);
+            let before = state.l1_view().clone();
+            let writes = [ClientStateWrite::RollbackL1BlocksTo(height)];
+            apply_writes_to_state(&mut state, writes.into_iter());
+            assert_eq!(state.l1_view(), &before);
+        }
+
+        // rolling back to the buried height keeps only that block
+        let mut state = state_with_l1_blocks(10, 14);
+        let writes = [ClientStateWrite::RollbackL1BlocksTo(10)];
+        apply_writes_to_state(&mut state, writes.into_iter());
+        assert_eq!(state.l1_view().local_unaccepted_blocks(), &[l1_blkid(10)]);
+        assert_eq!(state.next_exp_l1_block(), 11);
+    }
+}
@@ -99,23 +99,8 @@ fn apply_op_to_chainstate(op: &StateOp, state: &mut Chainstate) {
 
         StateOp::RevertL1Height(to_height) => {
             debug!(%to_height, "Obtained RevertL1Height Operation");
-            let mqueue = &mut state.l1_state.maturation_queue;
-            let back_idx = mqueue.back_idx().expect("stateop: maturation queue empty");
-
-            // Do some bookkeeping to make sure it's safe to do this.
-            if *to_height > back_idx {
-                panic!("stateop: revert to above tip block");
-            }
-
-            let n_drop = back_idx - to_height;
-            if n_drop > mqueue.len() as u64 {
-                panic!("stateop: revert matured block");
-            }
-
-            // Now that it's safe to do the revert, we can just do it.
-            for _ in 0..n_drop {
-                // This expect should never trigger.
-                mqueue.pop_back().expect("stateop: unable to revert more");
+            if let Err(e) = state.l1_state.rollback_to(*to_height) {
+                panic!("stateop: {e}");
             }
         }
 
@@ -32,6 +32,16 @@ pub enum SyncEvent {
     /// we've already asked the EL to check if it's valid and know we *could*
     /// accept it.  This is also how we indicate the genesis block.
     NewTipBlock(L2BlockId),
+
+    /// Operator asked to finalize up to this block, which has to be an
+    /// ancestor of the current tip.  Only submitted through the admin RPC if
+    /// enabled in the sync params.
+    ForceFinalize(L2BlockId),
+
+    /// A checkpoint committing to an L2 tip was recognized on L1.  It isn't
+    /// verified, so it only finalizes the tip if it matches a checkpoint we've
+    /// verified already.
+    L1Checkpoint { idx: u64, l2_tip: L2BlockId },
 }
 
 impl fmt::Display for SyncEvent {
@@ -43,6 +53,10 @@ impl fmt::Display for SyncEvent {
             Self::L1DABatch(h, _ckpts) => f.write_fmt(format_args!("l1da:<$data>@{h}")),
             Self::L1BlockGenesis(h, _st) => f.write_fmt(format_args!("l1genesis:{h}")),
             Self::NewTipBlock(id) => f.write_fmt(format_args!("newtip:{id}")),
+            Self::ForceFinalize(id) => f.write_fmt(format_args!("forcefinalize:{id}")),
+            Self::L1Checkpoint { idx, l2_tip } => {
+                f.write_fmt(format_args!("l1checkpoint:{idx}->{l2_tip}"))
+            }
         }
     }
 }
@@ -1,6 +1,9 @@
 use arbitrary::Arbitrary;
 use borsh::{BorshDeserialize, BorshSerialize};
-use strata_primitives::l1::{BitcoinAmount, OutputRef};
+use strata_primitives::{
+    buf::Buf32,
+    l1::{BitcoinAmount, OutputRef},
+};
 
 use crate::batch::SignedBatchCheckpoint;
 
@@ -14,6 +17,8 @@ pub enum ProtocolOperation {
     DepositRequest(DepositRequestInfo),
     /// Checkpoint data
     Checkpoint(SignedBatchCheckpoint),
+    /// Commitment to a checkpoint posted in an OP_RETURN output
+    CheckpointCommitment(CheckpointCommitment),
     // TODO: add other kinds like Proofs and statediffs
 }
 
@@ -41,18 +46,92 @@ pub struct DepositRequestInfo {
     pub address: Vec<u8>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Arbitrary)]
+pub struct CheckpointCommitment {
+    /// index of the committed checkpoint
+    pub idx: u64,
+
+    /// L2 state root at the end of the checkpoint
+    pub state_root: Buf32,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Arbitrary)]
 pub struct InscriptionData {
     /// payload present in inscription transaction (either batchTx or checkpointTx)
     batch_data: Vec<u8>,
+
+    /// Set if the payload is one of the chunks of a blob too large for a single inscription.
+    chunk: Option<InscriptionChunk>,
 }
 
 impl InscriptionData {
     pub fn new(batch_data: Vec<u8>) -> Self {
-        Self { batch_data }
+        Self {
+            batch_data,
+            chunk: None,
+        }
+    }
+
+    pub fn new_chunk(batch_data: Vec<u8>, chunk: InscriptionChunk) -> Self {
+        Self {
+            batch_data,
+            chunk: Some(chunk),
+        }
     }
 
     pub fn batch_data(&self) -> &[u8] {
         &self.batch_data
     }
+
+    pub fn chunk(&self) -> Option<&InscriptionChunk> {
+        self.chunk.as_ref()
+    }
+}
+
+/// Position of an inscribed payload among the chunks of the blob it was split from.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Arbitrary)]
+pub struct InscriptionChunk {
+    /// Commitment of the whole blob.
+    pub blob_commitment: Buf32,
+
+    /// Index of the chunk in the blob.
+    pub index: u32,
+
+    /// Total number of chunks the blob was split into.
+    pub total: u32,
+}
+
+impl InscriptionChunk {
+    /// Size of the chunk info when encoded in an inscription.
+    pub const ENCODED_SIZE: usize = 32 + 4 + 4;
+
+    pub fn new(blob_commitment: Buf32, index: u32, total: u32) -> Self {
+        Self {
+            blob_commitment,
+            index,
+            total,
+        }
+    }
+
+    /// Encodes the chunk info as the commitment followed by the big endian index and total.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_SIZE] {
+        let mut buf = [0; Self::ENCODED_SIZE];
+        buf[..32].copy_from_slice(self.blob_commitment.as_ref());
+        buf[32..36].copy_from_slice(&self.index.to_be_bytes());
+        buf[36..].copy_from_slice(&self.total.to_be_bytes());
+        buf
+    }
+
+    /// Decodes chunk info encoded with [`Self::to_bytes`], returns `None` if the bytes are
+    /// malformed or the index isn't less than the total.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::ENCODED_SIZE {
+            return None;
+        }
+
+        let blob_commitment = Buf32::from(<[u8; 32]>::try_from(&bytes[..32]).ok()?);
+        let index = u32::from_be_bytes(bytes[32..36].try_into().ok()?);
+        let total = u32::from_be_bytes(bytes[36..].try_into().ok()?);
+        (index < total).then_some(Self::new(blob_commitment, index, total))
+    }
 }
@@ -20,6 +20,24 @@ pub enum StatusError {
     Other(String),
 }
 
+/// How far the CSM worker got with the sync events written for it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncEventProgress {
+    /// Idx of the last sync event written to the database.
+    pub last_written_idx: u64,
+
+    /// Idx of the last sync event the CSM worker processed.
+    pub last_processed_idx: u64,
+}
+
+impl SyncEventProgress {
+    /// Number of written sync events the worker has yet to process.
+    pub fn pending(&self) -> u64 {
+        self.last_written_idx
+            .saturating_sub(self.last_processed_idx)
+    }
+}
+
 /// A wrapper around the status sender and receiver.
 ///
 /// This struct provides a convenient way to manage and access
@@ -48,16 +66,19 @@ impl StatusChannel {
         let (cl_tx, cl_rx) = watch::channel(cl_state);
         let (l1_tx, l1_rx) = watch::channel(l1_status);
         let (chs_tx, chs_rx) = watch::channel(ch_state);
+        let (sev_tx, sev_rx) = watch::channel(SyncEventProgress::default());
 
         let sender = Arc::new(StatusSender {
             cl: cl_tx,
             l1: l1_tx,
             chs: chs_tx,
+            sev: sev_tx,
         });
         let receiver = Arc::new(StatusReceiver {
             cl: cl_rx,
             l1: l1_rx,
             chs: chs_rx,
+            sev: sev_rx,
         });
 
         Self { sender, receiver }
@@ -125,6 +146,27 @@ impl StatusChannel {
         Ok(state)
     }
 
+    /// Gets the latest [`SyncEventProgress`].
+    pub fn sync_event_progress(&self) -> SyncEventProgress {
+        *self.receiver.sev.borrow()
+    }
+
+    /// Waits until the CSM worker has at most `max_pending` sync events left to
+    /// process and returns the progress.
+    pub async fn wait_for_pending_sync_events(
+        &self,
+        max_pending: u64,
+    ) -> Result<SyncEventProgress, RecvError> {
+        let mut rx = self.receiver.sev.clone();
+        loop {
+            let progress = *rx.borrow_and_update();
+            if progress.pending() <= max_pending {
+                return Ok(progress);
+            }
+            rx.changed().await?;
+        }
+    }
+
     /// Waits until genesis and returns the client state.
     pub async fn wait_until_genesis(&self) -> Result<ClientState, RecvError> {
         let mut rx = self.receiver.cl.clone();
@@ -161,6 +203,19 @@ impl StatusChannel {
             warn!("l1 status receiver dropped");
         }
     }
+
+    /// Records that a sync event was written for the CSM worker to process.
+    pub fn update_sync_event_written(&self, idx: u64) {
+        // Events can be written concurrently, so they can get here out of order.
+        self.sender.sev.send_modify(|p| {
+            p.last_written_idx = p.last_written_idx.max(idx);
+        });
+    }
+
+    /// Records that the CSM worker processed the sync event.
+    pub fn update_sync_event_processed(&self, idx: u64) {
+        self.sender.sev.send_modify(|p| p.last_processed_idx = idx);
+    }
 }
 
 /// Wrapper for watch status receivers
@@ -169,6 +224,7 @@ struct StatusReceiver {
     cl: watch::Receiver<ClientState>,
     l1: watch::Receiver<L1Status>,
     chs: watch::Receiver<Option<Chainstate>>,
+    sev: watch::Receiver<SyncEventProgress>,
 }
 
 /// Wrapper for watch status senders
@@ -177,4 +233,5 @@ struct StatusSender {
     cl: watch::Sender<ClientState>,
     l1: watch::Sender<L1Status>,
     chs: watch::Sender<Option<Chainstate>>,
+    sev: watch::Sender<SyncEventProgress>,
 }
@@ -7,7 +7,7 @@ use strata_db::{DbError, DbResult};
 use tokio::sync::{broadcast, RwLock};
 use tracing::*;
 
-use crate::exec::DbRecv;
+use crate::{exec::DbRecv, pool::check_blocking_outside_db_pool};
 
 /// Entry for something we can put into the cache without actually knowing what it is, and so we can
 /// keep the reservation to it.
@@ -57,6 +57,7 @@ impl<T: Clone> SlotState<T> {
                 // correctly.
                 // TODO figure out how to test this
                 trace!("waiting for database fetch to complete");
+                check_blocking_outside_db_pool();
                 match ch.resubscribe().blocking_recv() {
                     Ok(v) => Ok(v),
                     Err(_e) => Err(DbError::WorkerFailedStrangely),
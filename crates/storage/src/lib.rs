@@ -2,6 +2,7 @@ mod cache;
 mod exec;
 pub mod managers;
 pub mod ops;
+pub mod pool;
 
 pub use managers::l2::L2BlockManager;
 pub use ops::l1tx_broadcast::BroadcastDbOps;
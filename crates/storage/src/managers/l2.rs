@@ -38,6 +38,28 @@ impl L2BlockManager {
         Ok(())
     }
 
+    /// Puts a batch of blocks in the database all at once, purging their cache
+    /// entries.
+    pub async fn put_blocks_batch_async(&self, blocks: Vec<L2BlockBundle>) -> DbResult<()> {
+        let ids: Vec<_> = blocks.iter().map(|b| b.header().get_blockid()).collect();
+        self.ops.put_blocks_batch_async(blocks).await?;
+        for id in &ids {
+            self.block_cache.purge(id);
+        }
+        Ok(())
+    }
+
+    /// Puts a batch of blocks in the database all at once, purging their cache
+    /// entries.
+    pub fn put_blocks_batch_blocking(&self, blocks: Vec<L2BlockBundle>) -> DbResult<()> {
+        let ids: Vec<_> = blocks.iter().map(|b| b.header().get_blockid()).collect();
+        self.ops.put_blocks_batch_blocking(blocks)?;
+        for id in &ids {
+            self.block_cache.purge(id);
+        }
+        Ok(())
+    }
+
     /// Gets a block either in the cache or from the underlying database.
     pub async fn get_block_async(&self, id: &L2BlockId) -> DbResult<Option<L2BlockBundle>> {
         self.block_cache
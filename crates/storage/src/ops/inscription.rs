@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use strata_db::{
     traits::{BlobDatabase, SequencerDatabase},
-    types::BlobEntry,
+    types::{BlobEntry, BlobL1Status, BlobStatusTransition},
     DbResult,
 };
 use strata_primitives::buf::Buf32;
@@ -34,8 +34,13 @@ inst_ops! {
         get_blob_entry(id: Buf32) => Option<BlobEntry>;
         get_blob_entry_by_idx(idx: u64) => Option<BlobEntry>;
         get_blob_entry_id(idx: u64) => Option<Buf32>;
+        get_blob_entry_id_by_txid(txid: Buf32) => Option<Buf32>;
         get_next_blob_idx() => u64;
+        get_blobs_created_before(status: BlobL1Status, before_ms: u64) => Vec<(Buf32, BlobEntry)>;
         put_blob_entry(id: Buf32, entry: BlobEntry) => ();
+        put_blob_entries(entries: Vec<(Buf32, BlobEntry)>) => ();
+        update_blob_entries(entries: Vec<(Buf32, BlobEntry)>, reason: String) => ();
+        get_blob_transitions(id: Buf32) => Vec<BlobStatusTransition>;
     }
 }
 
@@ -52,6 +57,25 @@ fn get_blob_entry_id<D: SequencerDatabase>(ctx: &Context<D>, idx: u64) -> DbResu
     blob_db.get_blob_id(idx)
 }
 
+/// Gets the id of the entry one of our commit or reveal txs belongs to.
+fn get_blob_entry_id_by_txid<D: SequencerDatabase>(
+    ctx: &Context<D>,
+    txid: Buf32,
+) -> DbResult<Option<Buf32>> {
+    let blob_db = ctx.db.blob_db();
+    blob_db.get_blob_id_by_txid(txid)
+}
+
+/// Gets the entries in the status created before the UNIX epoch millis, oldest first.
+fn get_blobs_created_before<D: SequencerDatabase>(
+    ctx: &Context<D>,
+    status: BlobL1Status,
+    before_ms: u64,
+) -> DbResult<Vec<(Buf32, BlobEntry)>> {
+    let blob_db = ctx.db.blob_db();
+    blob_db.get_blobs_created_before(&status, before_ms)
+}
+
 fn get_blob_entry_by_idx<D: SequencerDatabase>(
     ctx: &Context<D>,
     idx: u64,
@@ -79,3 +103,28 @@ fn put_blob_entry<D: SequencerDatabase>(
     let blob_db = ctx.db.blob_db();
     blob_db.put_blob_entry(id, entry)
 }
+
+fn put_blob_entries<D: SequencerDatabase>(
+    ctx: &Context<D>,
+    entries: Vec<(Buf32, BlobEntry)>,
+) -> DbResult<()> {
+    let blob_db = ctx.db.blob_db();
+    blob_db.put_blob_entries(entries)
+}
+
+fn update_blob_entries<D: SequencerDatabase>(
+    ctx: &Context<D>,
+    entries: Vec<(Buf32, BlobEntry)>,
+    reason: String,
+) -> DbResult<()> {
+    let blob_db = ctx.db.blob_db();
+    blob_db.update_blob_entries(entries, reason)
+}
+
+fn get_blob_transitions<D: SequencerDatabase>(
+    ctx: &Context<D>,
+    id: Buf32,
+) -> DbResult<Vec<BlobStatusTransition>> {
+    let blob_db = ctx.db.blob_db();
+    blob_db.get_blob_transitions(id)
+}
@@ -28,6 +28,7 @@ inst_ops! {
         get_blocks_at_height(h: u64) => Vec<L2BlockId>;
         get_block_status(id: L2BlockId) => Option<BlockStatus>;
         put_block(block: L2BlockBundle) => ();
+        put_blocks_batch(blocks: Vec<L2BlockBundle>) => ();
         put_block_status(id: L2BlockId, status: BlockStatus) => ();
     }
 }
@@ -55,6 +56,11 @@ fn put_block<D: Database>(context: &Context<D>, block: L2BlockBundle) -> DbResul
     l2_db.put_block_data(block)
 }
 
+fn put_blocks_batch<D: Database>(context: &Context<D>, blocks: Vec<L2BlockBundle>) -> DbResult<()> {
+    let l2_db = context.db.l2_db();
+    l2_db.put_blocks_batch(blocks)
+}
+
 fn put_block_status<D: Database>(
     context: &Context<D>,
     id: L2BlockId,
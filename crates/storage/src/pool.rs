@@ -0,0 +1,132 @@
+//! Threadpool shared by the database operation interfaces.
+
+use std::{panic::Location, thread};
+
+use tracing::*;
+
+/// Name given to the threads of the database threadpool.
+const DB_POOL_NAME: &str = "strata-pool";
+
+/// Default number of database threads, one per available CPU.
+pub fn default_db_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Creates the threadpool the `_async` and `_chan` database ops are executed
+/// on, meant to be shared by all the ops interfaces.
+///
+/// Each op occupies a thread for as long as the underlying blocking db call
+/// takes, so the size bounds how many ops run at once.  A pool that's too small
+/// serializes ops from unrelated tasks behind each other, while a too large one
+/// oversubscribes the CPUs, which rocksdb's own background threads also compete
+/// for, without any throughput to show for it.
+///
+/// # Panics
+///
+/// If `num_threads` is 0.
+pub fn create_db_pool(num_threads: usize) -> threadpool::ThreadPool {
+    threadpool::ThreadPool::with_name(DB_POOL_NAME.to_owned(), num_threads)
+}
+
+/// Returns if the current thread is a worker of a pool made by
+/// [`create_db_pool`].
+pub fn is_db_pool_thread() -> bool {
+    thread::current().name() == Some(DB_POOL_NAME)
+}
+
+/// Guards blocking on the result of a job queued on the db pool.
+///
+/// Doing that from one of the pool's own workers holds up a worker until
+/// another one picks up the job, so it deadlocks once every worker ends up
+/// doing it at once.  Panics in debug builds so it gets caught during
+/// development, just warns otherwise.
+#[track_caller]
+pub fn check_blocking_outside_db_pool() {
+    if !is_db_pool_thread() {
+        return;
+    }
+
+    let caller = Location::caller();
+    if cfg!(debug_assertions) {
+        panic!("blocking on a db op from a db pool worker can deadlock (at {caller})");
+    } else {
+        warn!(%caller, "blocking on a db op from a db pool worker can deadlock");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Barrier,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use tokio::task::JoinSet;
+
+    use super::*;
+    use crate::exec::OpShim;
+
+    #[test]
+    fn test_pool_size() {
+        let pool = create_db_pool(5);
+        assert_eq!(pool.max_count(), 5);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ops_bounded_by_pool_size() {
+        const NUM_THREADS: usize = 3;
+        let pool = create_db_pool(NUM_THREADS);
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        // ops only get past the barrier once a full pool's worth runs at once
+        let barrier = Arc::new(Barrier::new(NUM_THREADS));
+        let op = {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            Arc::new(OpShim::wrap(move |_: ()| {
+                let cur = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(cur, Ordering::SeqCst);
+                barrier.wait();
+                thread::sleep(Duration::from_millis(10));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            }))
+        };
+
+        let mut tasks = JoinSet::new();
+        for _ in 0..NUM_THREADS * 4 {
+            let op = op.clone();
+            let pool = pool.clone();
+            tasks.spawn(async move { op.exec_async(&pool, ()).await });
+        }
+        while let Some(res) = tasks.join_next().await {
+            res.unwrap().unwrap();
+        }
+
+        assert_eq!(max_running.load(Ordering::SeqCst), NUM_THREADS);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_blocking_guard() {
+        // fine outside of the pool
+        check_blocking_outside_db_pool();
+
+        let pool = create_db_pool(1);
+        let (tx, rx) = std::sync::mpsc::channel();
+        pool.execute(move || {
+            let res = std::panic::catch_unwind(check_blocking_outside_db_pool);
+            tx.send(res.is_err()).unwrap();
+        });
+        assert!(rx.recv().unwrap(), "guard didn't fire in a pool worker");
+    }
+}
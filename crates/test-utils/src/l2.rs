@@ -7,7 +7,11 @@ use strata_primitives::{
     block_credential,
     buf::Buf64,
     operator::OperatorPubkeys,
-    params::{OperatorConfig, Params, ProofPublishMode, RollupParams, SyncParams},
+    params::{
+        OperatorConfig, Params, ProofPublishMode, RollupParams, SyncParams,
+        DEFAULT_CLIENT_STATE_RETENTION_INTERVAL, DEFAULT_EL_CHECK_TIMEOUT, DEFAULT_MAX_BLOCK_SIZE,
+        DEFAULT_MAX_BLOCK_TIME_DRIFT, DEFAULT_MAX_REORG_DEPTH,
+    },
     proof::RollupVerifyingKey,
 };
 use strata_state::{
@@ -74,6 +78,9 @@ pub fn gen_params_with_seed(seed: u64) -> Params {
     Params {
         rollup: RollupParams {
             rollup_name: "strata".to_string(),
+            inscription_tag: None,
+            deposit_magic_bytes: None,
+            batched_inscriptions: false,
             block_time: 1000,
             cred_rule: block_credential::CredRule::Unchecked,
             horizon_l1_height: 40318,
@@ -91,6 +98,7 @@ pub fn gen_params_with_seed(seed: u64) -> Params {
             target_l2_batch_size: 64,
             address_length: 20,
             deposit_amount: 1_000_000_000,
+            federation_script: None,
             rollup_vk: RollupVerifyingKey::SP1VerifyingKey(
                 "0x00b01ae596b4e51843484ff71ccbd0dd1a030af70b255e6b9aad50b81d81266f"
                     .parse()
@@ -105,6 +113,12 @@ pub fn gen_params_with_seed(seed: u64) -> Params {
             l2_blocks_fetch_limit: 1000,
             l1_follow_distance: 3,
             client_checkpoint_interval: 10,
+            allow_force_finalize: false,
+            max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+            client_state_retention_interval: DEFAULT_CLIENT_STATE_RETENTION_INTERVAL,
+            max_block_time_drift: DEFAULT_MAX_BLOCK_TIME_DRIFT,
+            el_check_timeout: DEFAULT_EL_CHECK_TIMEOUT,
         },
     }
 }
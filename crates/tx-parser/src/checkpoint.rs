@@ -0,0 +1,142 @@
+//! Parser for checkpoint commitment outputs, which commit to a checkpoint in an
+//! OP_RETURN as the magic bytes followed by the checkpoint index and the L2
+//! state root at the end of it.
+
+use bitcoin::{opcodes::all::OP_RETURN, ScriptBuf, Transaction};
+use strata_primitives::buf::Buf32;
+use strata_state::tx::CheckpointCommitment;
+use thiserror::Error;
+
+use crate::utils::{next_bytes, next_op};
+
+/// Length of the data following the magic bytes, the big endian index and the state root.
+const COMMITMENT_DATA_LEN: usize = 8 + 32;
+
+#[derive(Debug, Clone, Error)]
+pub enum CheckpointParseError {
+    #[error("no OP_RETURN")]
+    NoOpReturn,
+
+    #[error("no data")]
+    NoData,
+
+    #[error("magic bytes mismatch")]
+    MagicBytesMismatch,
+
+    #[error("invalid commitment length (exp {COMMITMENT_DATA_LEN}, found {0})")]
+    InvalidLength(usize),
+}
+
+/// Extracts the [`CheckpointCommitment`] from the first output of the transaction that is one.
+pub fn extract_checkpoint_commitment(
+    tx: &Transaction,
+    magic_bytes: &[u8],
+) -> Option<CheckpointCommitment> {
+    tx.output
+        .iter()
+        .find_map(|out| parse_checkpoint_commitment(&out.script_pubkey, magic_bytes).ok())
+}
+
+/// Parses the checkpoint index and state root out of the script, given that it is an OP_RETURN
+/// carrying the magic bytes.
+pub fn parse_checkpoint_commitment(
+    script: &ScriptBuf,
+    magic_bytes: &[u8],
+) -> Result<CheckpointCommitment, CheckpointParseError> {
+    let mut instructions = script.instructions();
+
+    if next_op(&mut instructions) != Some(OP_RETURN) {
+        return Err(CheckpointParseError::NoOpReturn);
+    }
+
+    let Some(data) = next_bytes(&mut instructions) else {
+        return Err(CheckpointParseError::NoData);
+    };
+
+    let Some(data) = data.strip_prefix(magic_bytes) else {
+        return Err(CheckpointParseError::MagicBytesMismatch);
+    };
+
+    if data.len() != COMMITMENT_DATA_LEN {
+        return Err(CheckpointParseError::InvalidLength(data.len()));
+    }
+
+    let (idx, state_root) = data.split_at(8);
+    Ok(CheckpointCommitment {
+        idx: u64::from_be_bytes(idx.try_into().expect("checked length")),
+        state_root: Buf32::from(<[u8; 32]>::try_from(state_root).expect("checked length")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{absolute::LockTime, script::PushBytesBuf, transaction::Version, Amount, TxOut};
+
+    use super::*;
+
+    const MAGIC: &[u8] = b"stratasss";
+
+    fn commitment_script(magic: &[u8], data: &[u8]) -> ScriptBuf {
+        let mut bytes = magic.to_vec();
+        bytes.extend_from_slice(data);
+        ScriptBuf::new_op_return(PushBytesBuf::try_from(bytes).unwrap())
+    }
+
+    fn commitment_data(idx: u64, state_root: [u8; 32]) -> Vec<u8> {
+        let mut data = idx.to_be_bytes().to_vec();
+        data.extend_from_slice(&state_root);
+        data
+    }
+
+    fn create_tx(scripts: Vec<ScriptBuf>) -> Transaction {
+        Transaction {
+            version: Version(2),
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: scripts
+                .into_iter()
+                .map(|script_pubkey| TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_checkpoint_commitment() {
+        let script = commitment_script(MAGIC, &commitment_data(7, [3; 32]));
+        let tx = create_tx(vec![ScriptBuf::new(), script]);
+
+        let commitment = extract_checkpoint_commitment(&tx, MAGIC).unwrap();
+        assert_eq!(commitment.idx, 7);
+        assert_eq!(commitment.state_root, Buf32::from([3; 32]));
+    }
+
+    #[test]
+    fn test_reject_malformed_commitments() {
+        let data = commitment_data(7, [3; 32]);
+
+        let res = parse_checkpoint_commitment(&ScriptBuf::new(), MAGIC);
+        assert!(matches!(res, Err(CheckpointParseError::NoOpReturn)));
+
+        let res = parse_checkpoint_commitment(&ScriptBuf::new_op_return([]), MAGIC);
+        assert!(matches!(res, Err(CheckpointParseError::MagicBytesMismatch)));
+
+        let script = commitment_script(b"otherssss", &data);
+        let res = parse_checkpoint_commitment(&script, MAGIC);
+        assert!(matches!(res, Err(CheckpointParseError::MagicBytesMismatch)));
+
+        let script = commitment_script(MAGIC, &data[..data.len() - 1]);
+        let res = parse_checkpoint_commitment(&script, MAGIC);
+        assert!(matches!(res, Err(CheckpointParseError::InvalidLength(39))));
+
+        // a deposit request with the same magic isn't a commitment
+        let script = commitment_script(MAGIC, &[1; 52]);
+        let res = parse_checkpoint_commitment(&script, MAGIC);
+        assert!(matches!(res, Err(CheckpointParseError::InvalidLength(52))));
+
+        let tx = create_tx(vec![script, commitment_script(b"otherssss", &data)]);
+        assert!(extract_checkpoint_commitment(&tx, MAGIC).is_none());
+    }
+}
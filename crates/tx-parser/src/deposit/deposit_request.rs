@@ -2,29 +2,50 @@
 
 use std::convert::TryInto;
 
-use bitcoin::{opcodes::all::OP_RETURN, ScriptBuf, Transaction};
+use bitcoin::{
+    opcodes::all::{OP_ENDIF, OP_RETURN},
+    script::Instruction,
+    Script, ScriptBuf, Transaction,
+};
 use strata_bridge_tx_builder::prelude::BRIDGE_DENOMINATION;
 use strata_primitives::params::DepositTxParams;
 use strata_state::tx::DepositRequestInfo;
 use tracing::debug;
 
 use super::{common::DepositRequestScriptInfo, error::DepositParseError};
-use crate::utils::{next_bytes, next_op};
+use crate::{
+    inscription::enter_envelope,
+    utils::{next_bytes, next_op},
+};
 
 /// Extracts the DepositInfo from the Deposit Transaction
+///
+/// The deposit request metadata is looked for in an OP_RETURN second output first, and then in an
+/// envelope in the tapscript of one of the inputs, which isn't limited to 80 bytes.
 pub fn extract_deposit_request_info(
     tx: &Transaction,
     config: &DepositTxParams,
 ) -> Option<DepositRequestInfo> {
-    // Ensure that the transaction has at least 2 outputs
+    // Ensure that the transaction has at least 1 output
     let addr_txn = tx.output.first()?;
-    let op_return_txn = tx.output.get(1)?;
 
-    // Parse the deposit request script from the second output's script_pubkey
+    // Parse the deposit request script from the second output's script_pubkey, or from the
+    // witness if there's no OP_RETURN
     let DepositRequestScriptInfo {
         tap_ctrl_blk_hash,
         ee_bytes,
-    } = parse_deposit_request_script(&op_return_txn.script_pubkey, config).ok()?;
+    } = tx
+        .output
+        .get(1)
+        .and_then(|op_return_txn| {
+            parse_deposit_request_script(&op_return_txn.script_pubkey, config).ok()
+        })
+        .or_else(|| {
+            tx.input.iter().find_map(|inp| {
+                let script = inp.witness.tapscript()?;
+                parse_deposit_request_envelope(script, config).ok()
+            })
+        })?;
 
     // if sent value is less than equal to what we expect for bridge denomination. The extra amount
     // is used for fees to create deposit transaction
@@ -68,11 +89,45 @@ pub fn parse_deposit_request_script(
     #[cfg(debug_assertions)]
     assert!(data.len() < 80);
 
+    parse_deposit_request_data(data, config)
+}
+
+/// extracts the tapscript block and EE address given that the script contains an `OP_FALSE OP_IF
+/// .. OP_ENDIF` envelope, whose pushes concatenated are the same data as in the OP_RETURN variant
+pub fn parse_deposit_request_envelope(
+    script: &Script,
+    config: &DepositTxParams,
+) -> Result<DepositRequestScriptInfo, DepositParseError> {
+    let mut instructions = script.instructions();
+    enter_envelope(&mut instructions).map_err(|_| DepositParseError::NoEnvelope)?;
+
+    // data can be split across several pushes as they're limited to 520 bytes each
+    let mut data = Vec::new();
+    loop {
+        match instructions.next() {
+            Some(Ok(Instruction::PushBytes(bytes))) => data.extend_from_slice(bytes.as_bytes()),
+            Some(Ok(Instruction::Op(op))) if op == OP_ENDIF => break,
+            _ => return Err(DepositParseError::NoEnvelope),
+        }
+    }
+
+    if data.is_empty() {
+        return Err(DepositParseError::NoData);
+    }
+
+    parse_deposit_request_data(&data, config)
+}
+
+/// extracts the tapscript block and EE address from the deposit request data, which is the Magic
+/// Bytes followed by the tapscript block hash and the EE address
+fn parse_deposit_request_data(
+    data: &[u8],
+    config: &DepositTxParams,
+) -> Result<DepositRequestScriptInfo, DepositParseError> {
     // data has expected magic bytes
     let magic_bytes = &config.magic_bytes;
     let magic_len = magic_bytes.len();
-    let actual_magic_bytes = &data[..magic_len];
-    if data.len() < magic_len || actual_magic_bytes != magic_bytes {
+    if data.len() < magic_len || &data[..magic_len] != magic_bytes {
         //debug!(expected_magic_bytes = ?magic_bytes, ?actual_magic_bytes, "mismatched magic
         // bytes");
         return Err(DepositParseError::MagicBytesMismatch);
@@ -91,9 +146,10 @@ pub fn parse_deposit_request_script(
     // configured bytes for address
     let address = &data[32..];
     if address.len() != config.address_length as usize {
-        // casting is safe as address.len() < data.len() < 80
+        // saturating since envelope data isn't limited to 80 bytes like OP_RETURN data is
+        let address_len = u8::try_from(address.len()).unwrap_or(u8::MAX);
         debug!(?data, expected = config.address_length, got = %address.len(), "incorrect number of bytes in address");
-        return Err(DepositParseError::InvalidDestAddress(address.len() as u8));
+        return Err(DepositParseError::InvalidDestAddress(address_len));
     }
 
     Ok(DepositRequestScriptInfo {
@@ -108,10 +164,11 @@ mod tests {
 
     use super::extract_deposit_request_info;
     use crate::deposit::{
-        deposit_request::parse_deposit_request_script,
+        deposit_request::{parse_deposit_request_envelope, parse_deposit_request_script},
         error::DepositParseError,
         test_utils::{
-            build_no_op_deposit_request_script, build_test_deposit_request_script,
+            build_no_op_deposit_request_script, build_test_deposit_request_envelope,
+            build_test_deposit_request_script, create_test_deposit_request_envelope_tx,
             create_test_deposit_tx, get_deposit_tx_config, test_taproot_addr,
         },
     };
@@ -239,4 +296,86 @@ mod tests {
         // Should return an error as the transaction has no outputs
         assert!(out.is_none());
     }
+
+    #[test]
+    fn check_deposit_parser_op_return_and_envelope() {
+        let mut config = get_deposit_tx_config();
+        config.deposit_amount += 100000;
+        let amt = Amount::from_sat(config.deposit_amount);
+        let evm_addr = [1; 20];
+        let dummy_control_block = [0xFF; 32];
+        let addr_script = test_taproot_addr().address().script_pubkey();
+
+        let op_return_tx = create_test_deposit_tx(
+            amt,
+            &addr_script,
+            &build_test_deposit_request_script(
+                config.magic_bytes.clone(),
+                dummy_control_block.to_vec(),
+                evm_addr.to_vec(),
+            ),
+        );
+        let envelope_tx = create_test_deposit_request_envelope_tx(
+            amt,
+            &addr_script,
+            &build_test_deposit_request_envelope(
+                config.magic_bytes.clone(),
+                dummy_control_block.to_vec(),
+                evm_addr.to_vec(),
+            ),
+        );
+
+        // both encodings are the same logical deposit request
+        let op_return_out = extract_deposit_request_info(&op_return_tx, &config).unwrap();
+        let envelope_out = extract_deposit_request_info(&envelope_tx, &config).unwrap();
+        assert_eq!(op_return_out, envelope_out);
+        assert_eq!(envelope_out.amt, amt.to_sat());
+        assert_eq!(envelope_out.address, evm_addr);
+        assert_eq!(envelope_out.take_back_leaf_hash, dummy_control_block);
+    }
+
+    #[test]
+    fn check_deposit_parser_envelope_long_address() {
+        // too long to fit in an OP_RETURN along with the magic and the leaf hash
+        let mut config = get_deposit_tx_config();
+        config.address_length = 200;
+        let dest_addr = vec![7; 200];
+        let dummy_control_block = [0xFF; 32];
+
+        let envelope = build_test_deposit_request_envelope(
+            config.magic_bytes.clone(),
+            dummy_control_block.to_vec(),
+            dest_addr.clone(),
+        );
+        let out = parse_deposit_request_envelope(&envelope, &config).unwrap();
+        assert_eq!(out.ee_bytes, dest_addr);
+        assert_eq!(out.tap_ctrl_blk_hash, dummy_control_block);
+
+        let tx = create_test_deposit_request_envelope_tx(
+            Amount::from_sat(config.deposit_amount + 100000),
+            &test_taproot_addr().address().script_pubkey(),
+            &envelope,
+        );
+        let out = extract_deposit_request_info(&tx, &config).unwrap();
+        assert_eq!(out.address, dest_addr);
+    }
+
+    #[test]
+    fn test_envelope_invalid_magic_bytes() {
+        let config = get_deposit_tx_config();
+        let envelope =
+            build_test_deposit_request_envelope(vec![0x00; 4], vec![0xFF; 32], vec![1; 20]);
+
+        let out = parse_deposit_request_envelope(&envelope, &config);
+        assert!(matches!(out, Err(DepositParseError::MagicBytesMismatch)));
+
+        // the OP_RETURN script isn't an envelope
+        let script = build_test_deposit_request_script(
+            config.magic_bytes.clone(),
+            vec![0xFF; 32],
+            vec![1; 20],
+        );
+        let out = parse_deposit_request_envelope(&script, &config);
+        assert!(matches!(out, Err(DepositParseError::NoEnvelope)));
+    }
 }
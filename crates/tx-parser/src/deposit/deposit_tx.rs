@@ -26,8 +26,8 @@ pub fn extract_deposit_info(tx: &Transaction, config: &DepositTxParams) -> Optio
         return None;
     }
 
-    // check if p2tr address matches
-    if send_addr_out.script_pubkey != config.address.address().script_pubkey() {
+    // check if the output is locked to the federation
+    if send_addr_out.script_pubkey != config.federation_script.script_pubkey() {
         return None;
     }
 
@@ -85,12 +85,13 @@ fn parse_deposit_script<'a>(
 mod tests {
 
     use bitcoin::Amount;
+    use strata_primitives::params::FederationScript;
 
     use crate::deposit::{
         deposit_tx::extract_deposit_info,
         test_utils::{
             build_test_deposit_script, create_test_deposit_tx, get_deposit_tx_config,
-            test_taproot_addr,
+            test_taproot_addr, test_wsh_addr,
         },
     };
 
@@ -118,4 +119,44 @@ mod tests {
         assert_eq!(out.amt, amt.into());
         assert_eq!(out.address, ee_addr);
     }
+
+    #[test]
+    fn check_deposit_parser_wsh_federation() {
+        let mut config = get_deposit_tx_config();
+        let wsh_addr = test_wsh_addr();
+        config.federation_script = FederationScript::from_address(&wsh_addr).unwrap();
+        assert!(matches!(config.federation_script, FederationScript::Wsh(_)));
+
+        let ee_addr = [1; 20];
+        let deposit_script =
+            build_test_deposit_script(config.magic_bytes.clone(), ee_addr.to_vec());
+
+        let test_transaction = create_test_deposit_tx(
+            Amount::from_sat(config.deposit_amount),
+            &wsh_addr.address().script_pubkey(),
+            &deposit_script,
+        );
+
+        let out = extract_deposit_info(&test_transaction, &config).expect("should parse deposit");
+
+        assert_eq!(out.amt, Amount::from_sat(config.deposit_amount).into());
+        assert_eq!(out.address, ee_addr);
+    }
+
+    #[test]
+    fn check_deposit_parser_rejects_other_federation_script() {
+        // deposit sent to the taproot address while the P2WSH one is configured
+        let mut config = get_deposit_tx_config();
+        config.federation_script = FederationScript::from_address(&test_wsh_addr()).unwrap();
+
+        let deposit_script = build_test_deposit_script(config.magic_bytes.clone(), vec![1; 20]);
+
+        let test_transaction = create_test_deposit_tx(
+            Amount::from_sat(config.deposit_amount),
+            &test_taproot_addr().address().script_pubkey(),
+            &deposit_script,
+        );
+
+        assert!(extract_deposit_info(&test_transaction, &config).is_none());
+    }
 }
@@ -8,6 +8,9 @@ pub enum DepositParseError {
     #[error("No data")]
     NoData,
 
+    #[error("no OP_FALSE OP_IF .. OP_ENDIF envelope")]
+    NoEnvelope,
+
     #[error("no magic bytes")]
     NoMagicBytes,
 
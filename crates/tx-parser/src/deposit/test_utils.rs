@@ -2,13 +2,16 @@ use std::str::FromStr;
 
 use bitcoin::{
     absolute::LockTime,
-    opcodes::all::OP_RETURN,
+    opcodes::{
+        all::{OP_CHECKSIG, OP_ENDIF, OP_IF, OP_RETURN},
+        OP_FALSE,
+    },
     script::{self, PushBytesBuf},
     Address, Amount, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
 };
 use strata_primitives::{
     l1::{BitcoinAddress, OutputRef},
-    params::DepositTxParams,
+    params::{DepositTxParams, FederationScript},
 };
 use strata_test_utils::ArbitraryGenerator;
 
@@ -22,12 +25,22 @@ pub fn test_taproot_addr() -> BitcoinAddress {
     BitcoinAddress::parse(&addr.to_string(), bitcoin::Network::Regtest).unwrap()
 }
 
+pub fn test_wsh_addr() -> BitcoinAddress {
+    let witness_script = script::Builder::new()
+        .push_slice([0x02; 33])
+        .push_opcode(OP_CHECKSIG)
+        .into_script();
+    let addr = Address::p2wsh(&witness_script, bitcoin::Network::Regtest);
+
+    BitcoinAddress::parse(&addr.to_string(), bitcoin::Network::Regtest).unwrap()
+}
+
 pub fn get_deposit_tx_config() -> DepositTxParams {
     DepositTxParams {
         magic_bytes: "stratasss".to_string().as_bytes().to_vec(),
         address_length: 20,
         deposit_amount: 1_000_000_000,
-        address: test_taproot_addr(),
+        federation_script: FederationScript::from_address(&test_taproot_addr()).unwrap(),
     }
 }
 
@@ -66,6 +79,25 @@ pub fn create_test_deposit_tx(
     }
 }
 
+/// Creates a deposit request tx with the metadata in the tapscript of its input's witness rather
+/// than in an OP_RETURN output.
+pub fn create_test_deposit_request_envelope_tx(
+    amt: Amount,
+    addr_script: &ScriptBuf,
+    envelope_script: &ScriptBuf,
+) -> Transaction {
+    let mut tx = create_test_deposit_tx(amt, addr_script, &ScriptBuf::new());
+    tx.output.truncate(1);
+
+    // only the tapscript matters for parsing, the rest is dummy
+    let witness = &mut tx.input[0].witness;
+    witness.push([1; 64]);
+    witness.push(envelope_script);
+    witness.push([0xc0; 33]);
+
+    tx
+}
+
 pub fn build_no_op_deposit_request_script(
     magic: Vec<u8>,
     dummy_block: Vec<u8>,
@@ -104,3 +136,24 @@ pub fn build_test_deposit_script(magic: Vec<u8>, dest_addr: Vec<u8>) -> ScriptBu
 
     builder.into_script()
 }
+
+pub fn build_test_deposit_request_envelope(
+    magic: Vec<u8>,
+    dummy_block: Vec<u8>,
+    dest_addr: Vec<u8>,
+) -> ScriptBuf {
+    let mut data = magic;
+    data.extend(dummy_block);
+    data.extend(dest_addr);
+
+    let mut builder = script::Builder::new()
+        .push_slice([0x02; 32])
+        .push_opcode(OP_CHECKSIG)
+        .push_opcode(OP_FALSE)
+        .push_opcode(OP_IF);
+    for chunk in data.chunks(100) {
+        builder = builder.push_slice(PushBytesBuf::try_from(chunk.to_vec()).unwrap());
+    }
+
+    builder.push_opcode(OP_ENDIF).into_script()
+}
@@ -1,45 +1,117 @@
 use bitcoin::{Block, Transaction};
 use strata_state::{
     batch::SignedBatchCheckpoint,
-    tx::{DepositInfo, DepositRequestInfo, ProtocolOperation},
+    tx::{CheckpointCommitment, DepositInfo, DepositRequestInfo, ProtocolOperation},
 };
 
 use super::messages::ProtocolOpTxRef;
 pub use crate::filter_types::TxFilterConfig;
 use crate::{
+    checkpoint::extract_checkpoint_commitment,
     deposit::{deposit_request::extract_deposit_request_info, deposit_tx::extract_deposit_info},
-    inscription::parse_inscription_data,
+    inscription::parse_revealed_inscription_data,
 };
 
 /// Filter protocol operations as refs from relevant [`Transaction`]s in a block based on given
-/// [`TxFilterConfig`]s
+/// [`TxFilterConfig`]s, using the [`default_tx_filters`].
 pub fn filter_protocol_op_tx_refs(
     block: &Block,
     filter_config: &TxFilterConfig,
+) -> Vec<ProtocolOpTxRef> {
+    filter_protocol_op_tx_refs_with(block, filter_config, &default_tx_filters())
+}
+
+/// Recognizes one category of relevant transactions, so that new categories can be registered
+/// with the L1 reader without touching the block scanning.
+pub trait TxFilter: Send + Sync + 'static {
+    /// Returns the protocol operations in the [`Transaction`] this filter is interested in, if any.
+    fn matches(&self, tx: &Transaction, filter_conf: &TxFilterConfig) -> Vec<ProtocolOperation>;
+}
+
+/// Matches checkpoint inscriptions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CheckpointTxFilter;
+
+impl TxFilter for CheckpointTxFilter {
+    fn matches(&self, tx: &Transaction, filter_conf: &TxFilterConfig) -> Vec<ProtocolOperation> {
+        parse_inscription_checkpoints(tx, filter_conf)
+            .map(ProtocolOperation::Checkpoint)
+            .collect()
+    }
+}
+
+/// Matches checkpoint commitment outputs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CheckpointCommitmentTxFilter;
+
+impl TxFilter for CheckpointCommitmentTxFilter {
+    fn matches(&self, tx: &Transaction, filter_conf: &TxFilterConfig) -> Vec<ProtocolOperation> {
+        parse_checkpoint_commitments(tx, filter_conf)
+            .map(ProtocolOperation::CheckpointCommitment)
+            .collect()
+    }
+}
+
+/// Matches deposits to the bridge.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DepositTxFilter;
+
+impl TxFilter for DepositTxFilter {
+    fn matches(&self, tx: &Transaction, filter_conf: &TxFilterConfig) -> Vec<ProtocolOperation> {
+        parse_deposits(tx, filter_conf)
+            .map(ProtocolOperation::Deposit)
+            .collect()
+    }
+}
+
+/// Matches deposit requests to the bridge.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DepositRequestTxFilter;
+
+impl TxFilter for DepositRequestTxFilter {
+    fn matches(&self, tx: &Transaction, filter_conf: &TxFilterConfig) -> Vec<ProtocolOperation> {
+        parse_deposit_requests(tx, filter_conf)
+            .map(ProtocolOperation::DepositRequest)
+            .collect()
+    }
+}
+
+/// Returns the filters for all the categories of transactions currently recognized.
+pub fn default_tx_filters() -> Vec<Box<dyn TxFilter>> {
+    vec![
+        Box::new(CheckpointTxFilter),
+        Box::new(CheckpointCommitmentTxFilter),
+        Box::new(DepositTxFilter),
+        Box::new(DepositRequestTxFilter),
+    ]
+}
+
+/// Filter protocol operations as refs from the [`Transaction`]s in a block matched by any of the
+/// given [`TxFilter`]s, in the order of the filters for each transaction.
+pub fn filter_protocol_op_tx_refs_with(
+    block: &Block,
+    filter_config: &TxFilterConfig,
+    filters: &[Box<dyn TxFilter>],
 ) -> Vec<ProtocolOpTxRef> {
     block
         .txdata
         .iter()
         .enumerate()
         .flat_map(|(i, tx)| {
-            extract_protocol_ops(tx, filter_config)
-                .into_iter()
+            filters
+                .iter()
+                .flat_map(move |filter| filter.matches(tx, filter_config))
                 .map(move |relevant_tx| ProtocolOpTxRef::new(i as u32, relevant_tx))
         })
         .collect()
 }
 
-/// If a [`Transaction`] is relevant based on given [`RelevantTxType`]s then we extract relevant
-/// info.
-//  TODO: make this function return multiple ops as a single tx can have multiple outpoints that's
-//  relevant
-fn extract_protocol_ops(tx: &Transaction, filter_conf: &TxFilterConfig) -> Vec<ProtocolOperation> {
-    // Currently all we have are inscription txs, deposits and deposit requests
-    parse_inscription_checkpoints(tx, filter_conf)
-        .map(ProtocolOperation::Checkpoint)
-        .chain(parse_deposits(tx, filter_conf).map(ProtocolOperation::Deposit))
-        .chain(parse_deposit_requests(tx, filter_conf).map(ProtocolOperation::DepositRequest))
-        .collect()
+fn parse_checkpoint_commitments(
+    tx: &Transaction,
+    filter_conf: &TxFilterConfig,
+) -> impl Iterator<Item = CheckpointCommitment> {
+    // commitments are made under the same magic bytes as deposit requests
+    extract_checkpoint_commitment(tx, &filter_conf.deposit_config.magic_bytes).into_iter()
 }
 
 fn parse_deposit_requests(
@@ -66,14 +138,17 @@ fn parse_inscription_checkpoints<'a>(
     tx: &'a Transaction,
     filter_conf: &'a TxFilterConfig,
 ) -> impl Iterator<Item = SignedBatchCheckpoint> + 'a {
-    tx.input.iter().filter_map(|inp| {
-        inp.witness
-            .tapscript()
-            .and_then(|scr| {
-                parse_inscription_data(&scr.into(), &filter_conf.rollup_name.clone()).ok()
-            })
-            .and_then(|data| borsh::from_slice::<SignedBatchCheckpoint>(data.batch_data()).ok())
-    })
+    tx.input
+        .iter()
+        .filter_map(|inp| inp.witness.tapscript())
+        .flat_map(|scr| {
+            parse_revealed_inscription_data(
+                &scr.into(),
+                &filter_conf.inscription_tag,
+                filter_conf.batched_inscriptions,
+            )
+        })
+        .filter_map(|data| borsh::from_slice::<SignedBatchCheckpoint>(data.batch_data()).ok())
 }
 
 #[cfg(test)]
@@ -85,6 +160,7 @@ mod test {
         block::{Header, Version as BVersion},
         hashes::Hash,
         key::{Parity, UntweakedKeypair},
+        script::PushBytesBuf,
         secp256k1::{XOnlyPublicKey, SECP256K1},
         taproot::{ControlBlock, LeafVersion, TaprootMerkleBranch},
         transaction::Version,
@@ -108,7 +184,10 @@ mod test {
             build_test_deposit_request_script, build_test_deposit_script, create_test_deposit_tx,
             test_taproot_addr,
         },
-        filter::filter_protocol_op_tx_refs,
+        filter::{
+            filter_protocol_op_tx_refs, filter_protocol_op_tx_refs_with, CheckpointTxFilter,
+            DepositTxFilter, TxFilter,
+        },
     };
 
     const OTHER_ADDR: &str = "bcrt1q6u6qyya3sryhh42lahtnz2m7zuufe7dlt8j0j5";
@@ -196,7 +275,7 @@ mod test {
         // Test with valid name
         let filter_config = create_tx_filter_config();
 
-        let rollup_name = filter_config.rollup_name.clone();
+        let rollup_name = filter_config.inscription_tag.clone();
         let tx = create_inscription_tx(rollup_name.clone());
         let block = create_test_block(vec![tx]);
 
@@ -232,7 +311,7 @@ mod test {
     #[test]
     fn test_filter_relevant_txs_multiple_matches() {
         let filter_config = create_tx_filter_config();
-        let rollup_name = filter_config.rollup_name.clone();
+        let rollup_name = filter_config.inscription_tag.clone();
         let tx1 = create_inscription_tx(rollup_name.clone());
         let tx2 = create_test_tx(vec![create_test_txout(100, &parse_addr(OTHER_ADDR))]);
         let tx3 = create_inscription_tx(rollup_name);
@@ -257,7 +336,7 @@ mod test {
 
         let tx = create_test_deposit_tx(
             Amount::from_sat(deposit_config.deposit_amount),
-            &deposit_config.address.address().script_pubkey(),
+            &deposit_config.federation_script.script_pubkey(),
             &deposit_script,
         );
 
@@ -300,7 +379,7 @@ mod test {
 
         let tx = create_test_deposit_tx(
             Amount::from_sat(deposit_config.deposit_amount), // Any amount
-            &deposit_config.address.address().script_pubkey(),
+            &deposit_config.federation_script.script_pubkey(),
             &deposit_request_script,
         );
 
@@ -363,12 +442,12 @@ mod test {
 
         let tx1 = create_test_deposit_tx(
             Amount::from_sat(deposit_config.deposit_amount),
-            &deposit_config.address.address().script_pubkey(),
+            &deposit_config.federation_script.script_pubkey(),
             &deposit_script1,
         );
         let tx2 = create_test_deposit_tx(
             Amount::from_sat(deposit_config.deposit_amount),
-            &deposit_config.address.address().script_pubkey(),
+            &deposit_config.federation_script.script_pubkey(),
             &deposit_script2,
         );
 
@@ -411,4 +490,70 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_filter_relevant_txs_checkpoint_commitment() {
+        let filter_config = create_tx_filter_config();
+        let mut data = filter_config.deposit_config.magic_bytes.clone();
+        data.extend_from_slice(&5u64.to_be_bytes());
+        data.extend_from_slice(&[2; 32]);
+        let script = ScriptBuf::new_op_return(PushBytesBuf::try_from(data).unwrap());
+
+        let commitment_tx = create_test_tx(vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: script,
+        }]);
+        let other_tx = create_test_tx(vec![create_test_txout(100, &parse_addr(OTHER_ADDR))]);
+        let block = create_test_block(vec![other_tx, commitment_tx]);
+
+        let result = filter_protocol_op_tx_refs(&block, &filter_config);
+        assert_eq!(result.len(), 1, "Should find the commitment tx");
+        assert_eq!(result[0].index(), 1);
+        match result[0].proto_op() {
+            ProtocolOperation::CheckpointCommitment(commitment) => {
+                assert_eq!(commitment.idx, 5);
+                assert_eq!(commitment.state_root, [2; 32].into());
+            }
+            op => panic!("expected a checkpoint commitment, got {op:?}"),
+        }
+    }
+
+    #[test]
+    fn test_filter_registered_tx_filters() {
+        let filter_config = create_tx_filter_config();
+        let deposit_config = filter_config.deposit_config.clone();
+        let deposit_script =
+            build_test_deposit_script(deposit_config.magic_bytes.clone(), vec![1u8; 20]);
+
+        let inscription_tx = create_inscription_tx(filter_config.inscription_tag.clone());
+        let other_tx = create_test_tx(vec![create_test_txout(100, &parse_addr(OTHER_ADDR))]);
+        let deposit_tx = create_test_deposit_tx(
+            Amount::from_sat(deposit_config.deposit_amount),
+            &deposit_config.federation_script.script_pubkey(),
+            &deposit_script,
+        );
+        let block = create_test_block(vec![inscription_tx, other_tx, deposit_tx]);
+
+        let filters: Vec<Box<dyn TxFilter>> =
+            vec![Box::new(CheckpointTxFilter), Box::new(DepositTxFilter)];
+        let result = filter_protocol_op_tx_refs_with(&block, &filter_config, &filters);
+
+        assert_eq!(result.len(), 2, "Should find both categories of txs");
+        assert_eq!(result[0].index(), 0);
+        assert!(matches!(
+            result[0].proto_op(),
+            ProtocolOperation::Checkpoint(_)
+        ));
+        assert_eq!(result[1].index(), 2);
+        assert!(matches!(
+            result[1].proto_op(),
+            ProtocolOperation::Deposit(_)
+        ));
+
+        // only the registered categories are captured
+        let filters: Vec<Box<dyn TxFilter>> = vec![Box::new(DepositTxFilter)];
+        let result = filter_protocol_op_tx_refs_with(&block, &filter_config, &filters);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].index(), 2);
+    }
 }
@@ -6,13 +6,14 @@ use strata_primitives::{
     sorted_vec::SortedVec,
 };
 
-use crate::utils::{generate_taproot_address, get_operator_wallet_pks};
-
 /// A configuration that determines how relevant transactions in a bitcoin block are filtered.
 #[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct TxFilterConfig {
-    /// For checkpoint update inscriptions.
-    pub rollup_name: String,
+    /// Tag of the checkpoint update inscriptions.
+    pub inscription_tag: String,
+
+    /// Whether an input can reveal several inscriptions, only the first one is parsed otherwise.
+    pub batched_inscriptions: bool,
 
     /// For addresses that are expected to be spent to.
     pub expected_addrs: SortedVec<BitcoinAddress>,
@@ -31,22 +32,18 @@ impl TxFilterConfig {
     /// Derive a `TxFilterConfig` from `RollupParams`.
     // TODO: this will need chainstate too in the future
     pub fn derive_from(rollup_params: &RollupParams) -> anyhow::Result<Self> {
-        let operator_wallet_pks = get_operator_wallet_pks(rollup_params);
-        let address = generate_taproot_address(&operator_wallet_pks, rollup_params.network)?;
+        let federation_script = rollup_params.federation_script()?;
+        let address = rollup_params.federation_address()?;
 
-        let rollup_name = rollup_params.rollup_name.clone();
+        let inscription_tag = rollup_params.inscription_tag().to_string();
         let expected_blobs = SortedVec::new(); // TODO: this should come from chainstate
         let expected_addrs = SortedVec::from(vec![address.clone()]);
         let expected_outpoints = SortedVec::new();
 
-        let deposit_config = DepositTxParams {
-            magic_bytes: rollup_name.clone().into_bytes(),
-            address_length: rollup_params.address_length,
-            deposit_amount: rollup_params.deposit_amount,
-            address,
-        };
+        let deposit_config = rollup_params.get_deposit_params(federation_script);
         Ok(Self {
-            rollup_name,
+            inscription_tag,
+            batched_inscriptions: rollup_params.batched_inscriptions,
             expected_blobs,
             expected_addrs,
             expected_outpoints,
@@ -54,3 +51,31 @@ impl TxFilterConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::AddressType;
+    use strata_primitives::{buf::Buf32, params::FederationScript};
+    use strata_test_utils::l2::gen_params;
+
+    use super::*;
+
+    #[test]
+    fn test_derive_from_federation_script() {
+        let mut params = gen_params().rollup;
+        let config = TxFilterConfig::derive_from(&params).unwrap();
+        assert!(matches!(
+            config.deposit_config.federation_script,
+            FederationScript::Taproot(_)
+        ));
+
+        // a P2WSH federation is watched for instead of the operator taproot address
+        let wsh = FederationScript::Wsh(Buf32::from([7; 32]));
+        params.federation_script = Some(wsh.clone());
+        let config = TxFilterConfig::derive_from(&params).unwrap();
+        assert_eq!(config.deposit_config.federation_script, wsh);
+        let addrs: Vec<_> = config.expected_addrs.iter().collect();
+        assert_eq!(addrs.len(), 1);
+        assert_eq!(addrs[0].address().address_type(), Some(AddressType::P2wsh));
+    }
+}
@@ -1,9 +1,10 @@
 use bitcoin::{
     opcodes::all::OP_IF,
     script::{Instruction, Instructions},
-    ScriptBuf,
+    ScriptBuf, Transaction,
 };
-use strata_state::tx::InscriptionData;
+use strata_primitives::params::RollupParams;
+use strata_state::tx::{InscriptionChunk, InscriptionData};
 use thiserror::Error;
 use tracing::debug;
 
@@ -12,6 +13,7 @@ use super::utils::{next_bytes, next_int, next_op};
 pub const ROLLUP_NAME_TAG: &[u8] = &[1];
 pub const VERSION_TAG: &[u8] = &[2];
 pub const BATCH_DATA_TAG: &[u8] = &[3];
+pub const CHUNK_TAG: &[u8] = &[4];
 
 #[derive(Debug, Error)]
 pub enum InscriptionParseError {
@@ -36,6 +38,9 @@ pub enum InscriptionParseError {
     /// Does not have a valid blob
     #[error("Invalid/Missing blob tag")]
     InvalidBlob,
+    /// Does not have a valid chunk info
+    #[error("Invalid chunk info")]
+    InvalidChunk,
     /// Does not have a valid format
     #[error("Invalid Format")]
     InvalidFormat,
@@ -50,11 +55,47 @@ pub fn parse_inscription_data(
     script: &ScriptBuf,
     rollup_name: &str,
 ) -> Result<InscriptionData, InscriptionParseError> {
+    parse_envelope(&mut script.instructions(), rollup_name)
+}
+
+/// Parse the [`InscriptionData`] of each of the envelopes in the script, in order, stopping at the
+/// first one that isn't valid.
+///
+/// The writer puts several envelopes in a single reveal script when it inscribes blobs in a
+/// batch.
+pub fn parse_all_inscription_data(script: &ScriptBuf, rollup_name: &str) -> Vec<InscriptionData> {
     let mut instructions = script.instructions();
+    let mut datas = Vec::new();
+    while let Ok(data) = parse_envelope(&mut instructions, rollup_name) {
+        datas.push(data);
+    }
+    datas
+}
+
+/// Parse the [`InscriptionData`] revealed by the script, all the envelopes of a batch if `batched`
+/// is set and only the first one otherwise.
+pub fn parse_revealed_inscription_data(
+    script: &ScriptBuf,
+    rollup_name: &str,
+    batched: bool,
+) -> Vec<InscriptionData> {
+    if batched {
+        parse_all_inscription_data(script, rollup_name)
+    } else {
+        parse_inscription_data(script, rollup_name)
+            .into_iter()
+            .collect()
+    }
+}
 
-    enter_envelope(&mut instructions)?;
+/// Parses the next envelope from the instructions, consuming them up to the end of it.
+fn parse_envelope(
+    instructions: &mut Instructions,
+    rollup_name: &str,
+) -> Result<InscriptionData, InscriptionParseError> {
+    enter_envelope(instructions)?;
     // Parse name
-    let (tag, name) = parse_bytes_pair(&mut instructions)?;
+    let (tag, name) = parse_bytes_pair(instructions)?;
 
     let extracted_rollup_name = match (tag, name) {
         (ROLLUP_NAME_TAG, namebytes) => String::from_utf8(namebytes.to_vec())
@@ -67,28 +108,104 @@ pub fn parse_inscription_data(
     }
 
     // Parse version
-    let (tag, ver) = parse_bytes_pair(&mut instructions)?;
+    let (tag, ver) = parse_bytes_pair(instructions)?;
     let _version = match (tag, ver) {
         (VERSION_TAG, [v]) => Ok(v),
         (VERSION_TAG, _) => Err(InscriptionParseError::InvalidVersion),
         _ => Err(InscriptionParseError::InvalidVersionTag),
     }?;
 
+    // Parse chunk info, which is only present if the blob was split across inscriptions
+    let mut tag = next_bytes(instructions).ok_or(InscriptionParseError::InvalidBlobTag)?;
+    let mut chunk = None;
+    if tag == CHUNK_TAG {
+        let info = next_bytes(instructions).ok_or(InscriptionParseError::InvalidChunk)?;
+        chunk =
+            Some(InscriptionChunk::from_bytes(info).ok_or(InscriptionParseError::InvalidChunk)?);
+        tag = next_bytes(instructions).ok_or(InscriptionParseError::InvalidBlobTag)?;
+    }
+
     // Parse bytes
-    let tag = next_bytes(&mut instructions).ok_or(InscriptionParseError::InvalidBlobTag)?;
-    let size = next_int(&mut instructions);
+    let size = next_int(instructions);
     match (tag, size) {
         (BATCH_DATA_TAG, Some(size)) => {
-            let batch_data = extract_n_bytes(size, &mut instructions)?;
-            Ok(InscriptionData::new(batch_data))
+            let batch_data = extract_n_bytes(size, instructions)?;
+            Ok(match chunk {
+                Some(chunk) => InscriptionData::new_chunk(batch_data, chunk),
+                None => InscriptionData::new(batch_data),
+            })
         }
         (BATCH_DATA_TAG, None) => Err(InscriptionParseError::InvalidBlob),
         _ => Err(InscriptionParseError::InvalidBlobTag),
     }
 }
 
+/// Config needed to recognize the rollup's inscriptions.
+#[derive(Clone, Debug)]
+pub struct InscriptionConfig {
+    /// Tag the rollup's inscriptions are marked with.
+    pub inscription_tag: String,
+
+    /// Whether an input can reveal several inscriptions, only the first one is parsed otherwise.
+    pub batched_inscriptions: bool,
+}
+
+impl InscriptionConfig {
+    /// Derives the config from the rollup params, the same way the inscription writer does.
+    pub fn derive_from(rollup_params: &RollupParams) -> Self {
+        Self {
+            inscription_tag: rollup_params.inscription_tag().to_string(),
+            batched_inscriptions: rollup_params.batched_inscriptions,
+        }
+    }
+}
+
+/// Blob payload recovered from an inscription, along with its position if it's a chunk of a
+/// larger blob.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InscribedBlob {
+    pub payload: Vec<u8>,
+    pub chunk: Option<InscriptionChunk>,
+}
+
+impl From<InscriptionData> for InscribedBlob {
+    fn from(data: InscriptionData) -> Self {
+        Self {
+            chunk: data.chunk().cloned(),
+            payload: data.batch_data().to_vec(),
+        }
+    }
+}
+
+/// Parses the first inscription of the rollup revealed by any of the inputs of the transaction.
+pub fn parse_inscription(tx: &Transaction, config: &InscriptionConfig) -> Option<InscribedBlob> {
+    tx.input.iter().find_map(|inp| {
+        let script = inp.witness.tapscript()?;
+        parse_inscription_data(&script.into(), &config.inscription_tag)
+            .ok()
+            .map(InscribedBlob::from)
+    })
+}
+
+/// Parses all the inscriptions of the rollup revealed by the inputs of the transaction, in order,
+/// including all the ones of a batch revealed by the same input if batches are allowed.
+pub fn parse_inscriptions(tx: &Transaction, config: &InscriptionConfig) -> Vec<InscribedBlob> {
+    tx.input
+        .iter()
+        .filter_map(|inp| inp.witness.tapscript())
+        .flat_map(|script| {
+            parse_revealed_inscription_data(
+                &script.into(),
+                &config.inscription_tag,
+                config.batched_inscriptions,
+            )
+        })
+        .map(InscribedBlob::from)
+        .collect()
+}
+
 /// Check for consecutive `OP_FALSE` and `OP_IF` that marks the beginning of an inscription
-fn enter_envelope(instructions: &mut Instructions) -> Result<(), InscriptionParseError> {
+pub(crate) fn enter_envelope(instructions: &mut Instructions) -> Result<(), InscriptionParseError> {
     // loop until OP_FALSE is found
     loop {
         let next = instructions.next();
@@ -146,11 +263,48 @@ fn extract_n_bytes(
 
 #[cfg(test)]
 mod tests {
-
+    use bitcoin::{
+        absolute::LockTime, transaction::Version, Amount, OutPoint, Sequence, TxIn, TxOut, Witness,
+    };
     use strata_btcio::test_utils::generate_inscription_script_test;
+    use strata_primitives::buf::Buf32;
 
     use super::*;
 
+    const ROLLUP_NAME: &str = "TestRollup";
+
+    /// Builds a tx revealing the inscription, only the tapscript matters for parsing.
+    fn create_reveal_tx(inscription_data: InscriptionData) -> Transaction {
+        let script = generate_inscription_script_test(inscription_data, ROLLUP_NAME, 1).unwrap();
+
+        let mut witness = Witness::new();
+        witness.push([1; 64]);
+        witness.push(script);
+        witness.push([0xc0; 33]);
+
+        Transaction {
+            version: Version(2),
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness,
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    fn config() -> InscriptionConfig {
+        InscriptionConfig {
+            inscription_tag: ROLLUP_NAME.to_string(),
+            batched_inscriptions: true,
+        }
+    }
+
     #[test]
     fn test_parse_inscription_data() {
         let bytes = vec![0, 1, 2, 3];
@@ -176,4 +330,95 @@ mod tests {
         // Assert the rollup name was parsed correctly
         assert_eq!(result, inscription_data);
     }
+
+    #[test]
+    fn test_parse_inscription_roundtrip() {
+        let payload = vec![5; 1500];
+        let tx = create_reveal_tx(InscriptionData::new(payload.clone()));
+
+        let blob = parse_inscription(&tx, &config()).unwrap();
+
+        assert_eq!(blob.payload, payload);
+        assert_eq!(blob.chunk, None);
+    }
+
+    #[test]
+    fn test_parse_inscription_chunks_roundtrip() {
+        let commitment = Buf32::from([9; 32]);
+        let payload: Vec<u8> = (0..2500u32).map(|i| i as u8).collect();
+        let chunks: Vec<_> = payload.chunks(1000).collect();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let info = InscriptionChunk::new(commitment, i as u32, chunks.len() as u32);
+            let tx = create_reveal_tx(InscriptionData::new_chunk(chunk.to_vec(), info.clone()));
+            let blob = parse_inscription(&tx, &config()).unwrap();
+            assert_eq!(blob.payload, *chunk);
+            assert_eq!(blob.chunk, Some(info));
+        }
+    }
+
+    #[test]
+    fn test_parse_batched_inscriptions() {
+        let datas = vec![
+            InscriptionData::new(vec![1; 10]),
+            InscriptionData::new(vec![2; 1500]),
+            InscriptionData::new_chunk(vec![3; 20], InscriptionChunk::new(Buf32::zero(), 0, 2)),
+        ];
+        let mut script_bytes = Vec::new();
+        for data in &datas {
+            let script = generate_inscription_script_test(data.clone(), ROLLUP_NAME, 1).unwrap();
+            script_bytes.extend(script.into_bytes());
+        }
+        let script = ScriptBuf::from(script_bytes);
+
+        assert_eq!(parse_all_inscription_data(&script, ROLLUP_NAME), datas);
+        // only the first one of the batch is picked up by the single parser
+        assert_eq!(
+            parse_inscription_data(&script, ROLLUP_NAME).unwrap(),
+            datas[0]
+        );
+        assert!(parse_all_inscription_data(&script, "OtherRollup").is_empty());
+
+        let mut tx = create_reveal_tx(datas[0].clone());
+        let mut witness = Witness::new();
+        witness.push([1; 64]);
+        witness.push(script);
+        witness.push([0xc0; 33]);
+        tx.input[0].witness = witness;
+
+        let blobs = parse_inscriptions(&tx, &config());
+        let expected: Vec<_> = datas.into_iter().map(InscribedBlob::from).collect();
+        assert_eq!(blobs, expected);
+
+        // unless the params allow batches only the first one counts
+        let unbatched = InscriptionConfig {
+            batched_inscriptions: false,
+            ..config()
+        };
+        assert_eq!(parse_inscriptions(&tx, &unbatched), expected[..1]);
+    }
+
+    #[test]
+    fn test_parse_inscription_other_rollup() {
+        let tx = create_reveal_tx(InscriptionData::new(vec![1; 10]));
+        let config = InscriptionConfig {
+            inscription_tag: "OtherRollup".to_string(),
+            batched_inscriptions: false,
+        };
+
+        assert_eq!(parse_inscription(&tx, &config), None);
+    }
+
+    #[test]
+    fn test_parse_inscription_invalid_chunk_info() {
+        // index not less than total
+        let info = InscriptionChunk::new(Buf32::zero(), 3, 3);
+        let data = InscriptionData::new_chunk(vec![1; 10], info);
+        let script = generate_inscription_script_test(data, ROLLUP_NAME, 1).unwrap();
+
+        assert!(matches!(
+            parse_inscription_data(&script, ROLLUP_NAME),
+            Err(InscriptionParseError::InvalidChunk)
+        ));
+    }
 }
@@ -1,3 +1,4 @@
+pub mod checkpoint;
 pub mod deposit;
 pub mod filter;
 pub mod filter_types;
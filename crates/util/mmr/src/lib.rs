@@ -25,6 +25,29 @@ pub struct CompactMmr {
     roots: Vec<Hash>,
 }
 
+impl CompactMmr {
+    /// Returns the number of leaves accumulated into the MMR.
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+}
+
+/// Advances a compact MMR checkpoint by appending `leaves` in order, like they'd be appended with
+/// [`MerkleMr::add_leaf`] on the full MMR.
+///
+/// This is meant for bringing a checkpoint (like the ones returned from the L1 database) up to
+/// date with the blocks stored after it.
+pub fn advance_mmr<H: MerkleHasher + Clone>(
+    mmr: &mut CompactMmr,
+    leaves: impl IntoIterator<Item = Hash>,
+) {
+    let mut full = MerkleMr::<H>::from_compact(mmr);
+    for leaf in leaves {
+        full.add_leaf(leaf);
+    }
+    *mmr = full.to_compact();
+}
+
 #[derive(Clone)]
 pub struct MerkleMr<H: MerkleHasher + Clone> {
     // number of elements inserted into mmr
@@ -312,7 +335,7 @@ mod test {
 
     use sha2::{Digest, Sha256};
 
-    use super::{hasher::Hash, MerkleMr, MerkleProof};
+    use super::{advance_mmr, hasher::Hash, MerkleMr, MerkleProof};
     use crate::error::MerkleError;
 
     fn generate_for_n_integers(n: usize) -> (MerkleMr<Sha256>, Vec<MerkleProof<Sha256>>) {
@@ -535,4 +558,50 @@ mod test {
             assert!(proof_list[i].verify_against_mmr(&mmr, num_hash[i]));
         }
     }
+
+    #[test]
+    fn check_advance_compact_mmr() {
+        let hashes = generate_hashes_for_n_integers(37);
+
+        let mut full_mmr: MerkleMr<Sha256> = MerkleMr::new(14);
+        for h in &hashes {
+            full_mmr.add_leaf(*h);
+        }
+
+        // advance from a few checkpoints, including an empty one
+        for checkpoint_at in [0, 1, 5, 16, 36, 37] {
+            let mut mmr: MerkleMr<Sha256> = MerkleMr::new(14);
+            for h in &hashes[..checkpoint_at] {
+                mmr.add_leaf(*h);
+            }
+
+            let mut compact = mmr.to_compact();
+            advance_mmr::<Sha256>(&mut compact, hashes[checkpoint_at..].iter().copied());
+
+            assert_eq!(compact.entries(), hashes.len() as u64);
+            assert_eq!(
+                compact,
+                full_mmr.to_compact(),
+                "checkpoint at {checkpoint_at}"
+            );
+        }
+    }
+
+    #[test]
+    fn check_advance_compact_mmr_to_single_root() {
+        let hashes = generate_hashes_for_n_integers(32);
+
+        let mut mmr: MerkleMr<Sha256> = MerkleMr::new(14);
+        for h in &hashes[..10] {
+            mmr.add_leaf(*h);
+        }
+        let mut compact = mmr.to_compact();
+        advance_mmr::<Sha256>(&mut compact, hashes[10..].iter().copied());
+
+        let (full_mmr, _) = generate_for_n_integers(32);
+        assert_eq!(
+            MerkleMr::<Sha256>::from_compact(&compact).get_single_root(),
+            full_mmr.get_single_root()
+        );
+    }
 }
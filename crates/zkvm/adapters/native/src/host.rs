@@ -51,3 +51,53 @@ impl fmt::Display for NativeHost {
         write!(f, "native")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use strata_zkvm::{ZkVmEnv, ZkVmInputBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_proof_size_bytes() {
+        let host = NativeHost {
+            process_proof: Arc::new(Box::new(|_: &NativeMachine| Ok(()))),
+        };
+        let input = NativeMachineInputBuilder::new().build().unwrap();
+        let receipt = host.prove(input, ProofType::Core).unwrap();
+        assert_eq!(
+            NativeHost::proof_size_bytes(receipt.proof()),
+            receipt.proof().as_bytes().len()
+        );
+
+        let proof = Proof::new(vec![1; 260]);
+        assert_eq!(NativeHost::proof_size_bytes(&proof), 260);
+    }
+
+    #[test]
+    fn test_stream_large_public_output() {
+        let output: Vec<u64> = (0..200_000).collect();
+        let committed = output.clone();
+        let host = NativeHost {
+            process_proof: Arc::new(Box::new(move |env: &NativeMachine| {
+                env.commit_borsh(&committed);
+                Ok(())
+            })),
+        };
+        let input = NativeMachineInputBuilder::new().build().unwrap();
+        let receipt = host.prove(input, ProofType::Core).unwrap();
+
+        let mut streamed = Vec::new();
+        NativeHost::extract_public_output_into(receipt.public_values(), &mut streamed).unwrap();
+        assert_eq!(streamed, borsh::to_vec(&output).unwrap());
+
+        let mut reader = streamed.as_slice();
+        let read: Vec<u64> = NativeHost::read_borsh_public_output(&mut reader).unwrap();
+        assert_eq!(read, output);
+        assert!(reader.is_empty());
+
+        // a truncated output doesn't decode
+        let mut truncated = &streamed[..streamed.len() - 1];
+        assert!(NativeHost::read_borsh_public_output::<Vec<u64>, _>(&mut truncated).is_err());
+    }
+}
@@ -30,6 +30,11 @@ impl Risc0Host {
     pub fn get_elf(&self) -> &[u8] {
         &self.elf
     }
+
+    /// Returns the id of the program, the image id computed from its ELF.
+    pub fn get_program_id(&self) -> [u32; 8] {
+        self.id.into()
+    }
 }
 
 impl ZkVmHost for Risc0Host {
@@ -0,0 +1,120 @@
+//! Caching of generated proofs, so proving the same input to the same program
+//! twice doesn't redo the work.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use sha2::{Digest, Sha256};
+use sp1_sdk::{HashableKey, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey};
+use strata_primitives::buf::Buf32;
+use strata_zkvm::ProofType;
+use tracing::*;
+
+/// Storage for proofs that were already generated, keyed by
+/// [`proof_cache_key`].
+pub trait ProofCache: Send + Sync + 'static {
+    /// Returns the proof cached under the key, if there is one.
+    fn get(&self, key: &Buf32) -> Option<SP1ProofWithPublicValues>;
+
+    /// Caches the proof under the key, replacing the one already there.
+    fn put(&self, key: Buf32, proof: &SP1ProofWithPublicValues);
+}
+
+/// Computes the key the proof of the input to the program with the verifying
+/// key is cached under.
+pub fn proof_cache_key(
+    verifying_key: &SP1VerifyingKey,
+    proof_type: ProofType,
+    input: &SP1Stdin,
+) -> Buf32 {
+    let proof_type_tag: u8 = match proof_type {
+        ProofType::Groth16 => 0,
+        ProofType::Core => 1,
+        ProofType::Compressed => 2,
+    };
+    let input_bytes = bincode::serialize(input).expect("sp1: serialize stdin");
+
+    let mut hasher = Sha256::new();
+    hasher.update(verifying_key.hash_bytes());
+    hasher.update([proof_type_tag]);
+    hasher.update(input_bytes);
+    Buf32(hasher.finalize().into())
+}
+
+/// Keeps the proofs in memory, for the lifetime of the process.
+#[derive(Debug, Default)]
+pub struct InMemoryProofCache {
+    proofs: Mutex<HashMap<Buf32, SP1ProofWithPublicValues>>,
+}
+
+impl InMemoryProofCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ProofCache for InMemoryProofCache {
+    fn get(&self, key: &Buf32) -> Option<SP1ProofWithPublicValues> {
+        self.proofs
+            .lock()
+            .expect("sp1: proof cache lock")
+            .get(key)
+            .cloned()
+    }
+
+    fn put(&self, key: Buf32, proof: &SP1ProofWithPublicValues) {
+        self.proofs
+            .lock()
+            .expect("sp1: proof cache lock")
+            .insert(key, proof.clone());
+    }
+}
+
+/// Keeps each proof in its own file in a directory, so they survive restarts.
+///
+/// Failing to read or write a file is logged and treated as a cache miss.
+#[derive(Debug, Clone)]
+pub struct DiskProofCache {
+    dir: PathBuf,
+}
+
+impl DiskProofCache {
+    /// Uses the directory, creating it if it doesn't exist.
+    pub fn new(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn proof_path(&self, key: &Buf32) -> PathBuf {
+        self.dir.join(format!("{key}.proof"))
+    }
+}
+
+impl ProofCache for DiskProofCache {
+    fn get(&self, key: &Buf32) -> Option<SP1ProofWithPublicValues> {
+        let path = self.proof_path(key);
+        if !path.exists() {
+            return None;
+        }
+
+        match SP1ProofWithPublicValues::load(&path) {
+            Ok(proof) => Some(proof),
+            Err(err) => {
+                warn!(path = %path.display(), %err, "failed to load cached proof");
+                None
+            }
+        }
+    }
+
+    fn put(&self, key: Buf32, proof: &SP1ProofWithPublicValues) {
+        let path = self.proof_path(&key);
+        if let Err(err) = proof.save(&path) {
+            warn!(path = %path.display(), %err, "failed to cache proof");
+        }
+    }
+}
@@ -1,12 +1,76 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use serde::{de::DeserializeOwned, Serialize};
 use sp1_sdk::{HashableKey, ProverClient, SP1ProvingKey, SP1VerifyingKey};
 use strata_zkvm::{
     ProofType, PublicValues, VerificationKey, ZkVmError, ZkVmHost, ZkVmInputBuilder, ZkVmResult,
 };
+use tracing::*;
 
-use crate::{input::SP1ProofInputBuilder, proof::SP1ProofReceipt};
+use crate::{
+    cache::{proof_cache_key, ProofCache},
+    input::SP1ProofInputBuilder,
+    proof::SP1ProofReceipt,
+};
+
+/// Which prover an [`SP1Host`] generates and verifies proofs with.
+#[derive(Clone, PartialEq, Eq)]
+pub enum ProverMode {
+    /// Prove on this machine.
+    Local,
+
+    /// Send proving requests to the SP1 prover network.
+    Network {
+        /// RPC endpoint of the prover network.
+        endpoint: String,
+
+        /// Private key the proving requests are signed with.
+        key: String,
+    },
+
+    /// Only execute the program and produce proofs that aren't checked.
+    Mock,
+}
+
+impl ProverMode {
+    /// Creates a client using the prover, regardless of the `SP1_PROVER` env
+    /// var.
+    fn client(&self) -> ProverClient {
+        match self {
+            Self::Local => ProverClient::local(),
+            Self::Network { endpoint, key } => ProverClient::builder()
+                .mode(sp1_sdk::ProverMode::Network)
+                .private_key(key.clone())
+                .rpc_url(endpoint.clone())
+                .build(),
+            Self::Mock => ProverClient::mock(),
+        }
+    }
+}
+
+impl Default for ProverMode {
+    fn default() -> Self {
+        if cfg!(feature = "mock") {
+            Self::Mock
+        } else {
+            Self::Local
+        }
+    }
+}
+
+impl fmt::Debug for ProverMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "Local"),
+            // don't leak the key into logs
+            Self::Network { endpoint, .. } => f
+                .debug_struct("Network")
+                .field("endpoint", endpoint)
+                .finish_non_exhaustive(),
+            Self::Mock => write!(f, "Mock"),
+        }
+    }
+}
 
 /// A host for the `SP1` zkVM that stores the guest program in ELF format.
 /// The `SP1Host` is responsible for program execution and proving
@@ -16,6 +80,8 @@ pub struct SP1Host {
     elf: Vec<u8>,
     proving_key: SP1ProvingKey,
     verifying_key: SP1VerifyingKey,
+    prover_mode: ProverMode,
+    proof_cache: Option<Arc<dyn ProofCache>>,
 }
 
 impl SP1Host {
@@ -24,6 +90,8 @@ impl SP1Host {
             elf: elf.to_vec(),
             proving_key,
             verifying_key,
+            prover_mode: ProverMode::default(),
+            proof_cache: None,
         }
     }
 
@@ -40,15 +108,38 @@ impl SP1Host {
     }
 
     pub fn init(guest_code: &[u8]) -> Self {
-        let client = ProverClient::new();
-        let (proving_key, verifying_key) = client.setup(guest_code);
+        let prover_mode = ProverMode::default();
+        let (proving_key, verifying_key) = prover_mode.client().setup(guest_code);
         Self {
             elf: guest_code.to_vec(),
             proving_key,
             verifying_key,
+            prover_mode,
+            proof_cache: None,
         }
     }
 
+    /// Sets the prover proofs are generated and verified with.
+    pub fn with_prover_mode(mut self, prover_mode: ProverMode) -> Self {
+        self.prover_mode = prover_mode;
+        self
+    }
+
+    pub fn prover_mode(&self) -> &ProverMode {
+        &self.prover_mode
+    }
+
+    /// Returns the id of the program, the hash of its verifying key.
+    pub fn get_program_id(&self) -> [u32; 8] {
+        self.verifying_key.hash_u32()
+    }
+
+    /// Sets a cache to reuse the proofs of inputs that were already proven.
+    pub fn with_proof_cache(mut self, proof_cache: Arc<dyn ProofCache>) -> Self {
+        self.proof_cache = Some(proof_cache);
+        self
+    }
+
     // TODO: consider moving to ZkVkHost trait.
     pub fn get_elf(&self) -> &[u8] {
         &self.elf
@@ -63,13 +154,25 @@ impl ZkVmHost for SP1Host {
         prover_input: <Self::Input<'a> as ZkVmInputBuilder<'a>>::Input,
         proof_type: ProofType,
     ) -> ZkVmResult<SP1ProofReceipt> {
-        #[cfg(feature = "mock")]
-        {
-            std::env::set_var("SP1_PROVER", "mock");
+        let client = self.prover_mode.client();
+
+        let cache = self.proof_cache.as_ref().map(|cache| {
+            let key = proof_cache_key(&self.verifying_key, proof_type, &prover_input);
+            (cache, key)
+        });
+        if let Some((cache, key)) = &cache {
+            if let Some(proof) = cache.get(key) {
+                // don't trust the cache blindly, it might be on disk
+                match client.verify(&proof, &self.verifying_key) {
+                    Ok(()) => {
+                        debug!(host = %self, %key, "reusing cached proof");
+                        return Ok(proof.into());
+                    }
+                    Err(err) => warn!(host = %self, %key, %err, "cached proof invalid, reproving"),
+                }
+            }
         }
 
-        let client = ProverClient::new();
-
         // Start proving
         let mut prover = client.prove(&self.proving_key, prover_input);
         prover = match proof_type {
@@ -82,6 +185,10 @@ impl ZkVmHost for SP1Host {
             .run()
             .map_err(|e| ZkVmError::ProofGenerationError(e.to_string()))?;
 
+        if let Some((cache, key)) = cache {
+            cache.put(key, &proof_info);
+        }
+
         Ok(proof_info.into())
     }
 
@@ -99,7 +206,7 @@ impl ZkVmHost for SP1Host {
     }
 
     fn verify_inner(&self, proof: &SP1ProofReceipt) -> ZkVmResult<()> {
-        let client = ProverClient::new();
+        let client = self.prover_mode.client();
         client
             .verify(proof.as_ref(), &self.verifying_key)
             .map_err(|e| ZkVmError::ProofVerificationError(e.to_string()))?;
@@ -119,12 +226,21 @@ impl fmt::Display for SP1Host {
 #[cfg(not(debug_assertions))]
 mod tests {
 
-    use std::{fs::File, io::Write};
-
-    use sp1_sdk::HashableKey;
+    use std::{
+        fs::File,
+        io::Write,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use sp1_sdk::{
+        provers::{Prover, ProverType},
+        HashableKey, SP1ProofWithPublicValues, SP1Stdin,
+    };
+    use strata_primitives::buf::Buf32;
     use strata_zkvm::{ProofType, ZkVmHost};
 
     use super::*;
+    use crate::cache::InMemoryProofCache;
 
     // Adding compiled guest code `TEST_ELF` to save the build time
     // #![no_main]
@@ -160,6 +276,73 @@ mod tests {
         assert_eq!(input, out)
     }
 
+    #[test]
+    fn test_mock_mode() {
+        let prover_input = SP1ProofInputBuilder::new()
+            .write_serde(&7u32)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let zkvm = SP1Host::init(TEST_ELF).with_prover_mode(ProverMode::Mock);
+        assert_eq!(zkvm.prover_mode().client().prover.id(), ProverType::Mock);
+
+        let proof = zkvm
+            .prove(prover_input, ProofType::Core)
+            .expect("mock proving failed");
+        zkvm.verify(&proof).expect("mock proof verification failed");
+        let out: u32 = SP1Host::extract_serde_public_output(&proof.public_values).unwrap();
+        assert_eq!(out, 7);
+    }
+
+    /// Cache counting how many proofs got stored in it.
+    #[derive(Default)]
+    struct CountingCache {
+        inner: InMemoryProofCache,
+        puts: AtomicUsize,
+    }
+
+    impl ProofCache for CountingCache {
+        fn get(&self, key: &Buf32) -> Option<SP1ProofWithPublicValues> {
+            self.inner.get(key)
+        }
+
+        fn put(&self, key: Buf32, proof: &SP1ProofWithPublicValues) {
+            self.puts.fetch_add(1, Ordering::SeqCst);
+            self.inner.put(key, proof);
+        }
+    }
+
+    fn build_input(n: u32) -> SP1Stdin {
+        SP1ProofInputBuilder::new()
+            .write_serde(&n)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cached_proof_reused() {
+        let cache = Arc::new(CountingCache::default());
+        let zkvm = SP1Host::init(TEST_ELF)
+            .with_prover_mode(ProverMode::Mock)
+            .with_proof_cache(cache.clone());
+
+        let proof = zkvm.prove(build_input(1), ProofType::Core).unwrap();
+        assert_eq!(cache.puts.load(Ordering::SeqCst), 1);
+
+        // only proving again got the proof into the cache, so not storing it
+        // again means the prover didn't run
+        let cached = zkvm.prove(build_input(1), ProofType::Core).unwrap();
+        assert_eq!(cache.puts.load(Ordering::SeqCst), 1);
+        assert_eq!(cached, proof);
+
+        // different input or proof type is another proof
+        zkvm.prove(build_input(2), ProofType::Core).unwrap();
+        zkvm.prove(build_input(1), ProofType::Compressed).unwrap();
+        assert_eq!(cache.puts.load(Ordering::SeqCst), 3);
+    }
+
     #[test]
     fn test_groth16_proof_generation() {
         sp1_sdk::utils::setup_logger();
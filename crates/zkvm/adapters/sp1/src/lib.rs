@@ -1,7 +1,12 @@
+#[cfg(feature = "prover")]
+mod cache;
+#[cfg(feature = "prover")]
+pub use cache::{proof_cache_key, DiskProofCache, InMemoryProofCache, ProofCache};
+
 #[cfg(feature = "prover")]
 mod host;
 #[cfg(feature = "prover")]
-pub use host::SP1Host;
+pub use host::{ProverMode, SP1Host};
 
 #[cfg(feature = "prover")]
 mod input;
@@ -1,6 +1,9 @@
 #[macro_use]
 extern crate cfg_if;
 
+mod program_id;
+pub use program_id::*;
+
 cfg_if! {
     if #[cfg(feature = "native")] {
         pub mod native;
@@ -8,7 +8,7 @@ use strata_proofimpl_cl_stf::process_cl_stf;
 use strata_proofimpl_evm_ee_stf::process_block_transaction_outer;
 use strata_proofimpl_l1_batch::process_l1_batch_proof;
 
-use crate::ProofVm;
+use crate::{GuestProgram, ProofVm};
 
 /// A mock verification key used in native mode when proof verification is not performed.
 ///
@@ -75,3 +75,14 @@ pub fn get_host(vm: ProofVm) -> &'static NativeHost {
         ProofVm::Checkpoint => &CHECKPOINT_HOST,
     }
 }
+
+/// Native hosts run the program logic directly, there's no guest program.
+impl GuestProgram for NativeHost {
+    fn committed_program_id(_vm: ProofVm) -> Option<[u32; 8]> {
+        None
+    }
+
+    fn loaded_program_id(&self) -> Option<[u32; 8]> {
+        None
+    }
+}
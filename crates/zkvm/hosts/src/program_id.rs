@@ -0,0 +1,89 @@
+use thiserror::Error;
+
+use crate::ProofVm;
+
+/// The program a host loaded doesn't have the id the guest builder committed to,
+/// which means the guest artifacts it was built from are stale.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{vm:?} host loaded program {loaded:?}, but the guest builder committed to {committed:?}")]
+pub struct ProgramIdMismatch {
+    pub vm: ProofVm,
+    pub committed: [u32; 8],
+    pub loaded: [u32; 8],
+}
+
+/// A host of a guest program, whose id is committed to by the guest builder.
+pub trait GuestProgram {
+    /// Returns the id the guest builder committed to for the program, if the
+    /// host's programs have one.
+    fn committed_program_id(vm: ProofVm) -> Option<[u32; 8]>;
+
+    /// Returns the id recomputed from the ELF or verification key the host
+    /// loaded, if it has one.
+    fn loaded_program_id(&self) -> Option<[u32; 8]>;
+}
+
+/// Checks the program loaded by the host for the [`ProofVm`] is the one the
+/// guest builder committed to.
+pub fn verify_program_id<H: GuestProgram>(vm: ProofVm, host: &H) -> Result<(), ProgramIdMismatch> {
+    check_program_id(vm, H::committed_program_id(vm), host.loaded_program_id())
+}
+
+fn check_program_id(
+    vm: ProofVm,
+    committed: Option<[u32; 8]>,
+    loaded: Option<[u32; 8]>,
+) -> Result<(), ProgramIdMismatch> {
+    match (committed, loaded) {
+        (Some(committed), Some(loaded)) if committed != loaded => Err(ProgramIdMismatch {
+            vm,
+            committed,
+            loaded,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Host that loaded the program with the id, committed to by a builder
+    /// that always commits to `[1; 8]`.
+    struct TestHost(Option<[u32; 8]>);
+
+    impl GuestProgram for TestHost {
+        fn committed_program_id(_vm: ProofVm) -> Option<[u32; 8]> {
+            Some([1; 8])
+        }
+
+        fn loaded_program_id(&self) -> Option<[u32; 8]> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_matching_program_id() {
+        verify_program_id(ProofVm::CLAggregation, &TestHost(Some([1; 8]))).unwrap();
+
+        // nothing to compare against
+        verify_program_id(ProofVm::CLAggregation, &TestHost(None)).unwrap();
+        check_program_id(ProofVm::CLAggregation, None, Some([2; 8])).unwrap();
+    }
+
+    #[test]
+    fn test_mismatched_program_id() {
+        let mut stale_id = [1; 8];
+        stale_id[7] = 2;
+
+        let res = verify_program_id(ProofVm::CLAggregation, &TestHost(Some(stale_id)));
+        assert_eq!(
+            res,
+            Err(ProgramIdMismatch {
+                vm: ProofVm::CLAggregation,
+                committed: [1; 8],
+                loaded: stale_id,
+            })
+        );
+    }
+}
@@ -2,11 +2,13 @@ use std::sync::LazyLock;
 
 use strata_risc0_adapter::Risc0Host;
 use strata_risc0_guest_builder::{
-    GUEST_RISC0_BTC_BLOCKSPACE_ELF, GUEST_RISC0_CHECKPOINT_ELF, GUEST_RISC0_CL_AGG_ELF,
-    GUEST_RISC0_CL_STF_ELF, GUEST_RISC0_EVM_EE_STF_ELF, GUEST_RISC0_L1_BATCH_ELF,
+    GUEST_RISC0_BTC_BLOCKSPACE_ELF, GUEST_RISC0_BTC_BLOCKSPACE_ID, GUEST_RISC0_CHECKPOINT_ELF,
+    GUEST_RISC0_CHECKPOINT_ID, GUEST_RISC0_CL_AGG_ELF, GUEST_RISC0_CL_AGG_ID,
+    GUEST_RISC0_CL_STF_ELF, GUEST_RISC0_CL_STF_ID, GUEST_RISC0_EVM_EE_STF_ELF,
+    GUEST_RISC0_EVM_EE_STF_ID, GUEST_RISC0_L1_BATCH_ELF, GUEST_RISC0_L1_BATCH_ID,
 };
 
-use crate::ProofVm;
+use crate::{GuestProgram, ProofVm};
 
 static BTC_BLOCKSPACE_HOST: LazyLock<Risc0Host> =
     std::sync::LazyLock::new(|| Risc0Host::init(GUEST_RISC0_BTC_BLOCKSPACE_ELF));
@@ -36,3 +38,21 @@ pub fn get_host(vm: ProofVm) -> &'static Risc0Host {
         ProofVm::Checkpoint => &CHECKPOINT_HOST,
     }
 }
+
+impl GuestProgram for Risc0Host {
+    fn committed_program_id(vm: ProofVm) -> Option<[u32; 8]> {
+        let id = match vm {
+            ProofVm::BtcProving => GUEST_RISC0_BTC_BLOCKSPACE_ID,
+            ProofVm::L1Batch => GUEST_RISC0_L1_BATCH_ID,
+            ProofVm::ELProving => GUEST_RISC0_EVM_EE_STF_ID,
+            ProofVm::CLProving => GUEST_RISC0_CL_STF_ID,
+            ProofVm::CLAggregation => GUEST_RISC0_CL_AGG_ID,
+            ProofVm::Checkpoint => GUEST_RISC0_CHECKPOINT_ID,
+        };
+        Some(id)
+    }
+
+    fn loaded_program_id(&self) -> Option<[u32; 8]> {
+        Some(self.get_program_id())
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::LazyLock;
 use strata_sp1_adapter::SP1Host;
 use strata_sp1_guest_builder::*;
 
-use crate::ProofVm;
+use crate::{GuestProgram, ProofVm};
 
 pub static BTC_BLOCKSPACE_HOST: LazyLock<SP1Host> = std::sync::LazyLock::new(|| {
     {
@@ -63,3 +63,21 @@ pub fn get_host(vm: ProofVm) -> &'static SP1Host {
         ProofVm::Checkpoint => &CHECKPOINT_HOST,
     }
 }
+
+impl GuestProgram for SP1Host {
+    fn committed_program_id(vm: ProofVm) -> Option<[u32; 8]> {
+        let vk_hash = match vm {
+            ProofVm::BtcProving => GUEST_BTC_BLOCKSPACE_VK_HASH_U32,
+            ProofVm::L1Batch => GUEST_L1_BATCH_VK_HASH_U32,
+            ProofVm::ELProving => GUEST_EVM_EE_STF_VK_HASH_U32,
+            ProofVm::CLProving => GUEST_CL_STF_VK_HASH_U32,
+            ProofVm::CLAggregation => GUEST_CL_AGG_VK_HASH_U32,
+            ProofVm::Checkpoint => GUEST_CHECKPOINT_VK_HASH_U32,
+        };
+        Some(vk_hash.try_into().expect("sp1: vk hash is 8 words"))
+    }
+
+    fn loaded_program_id(&self) -> Option<[u32; 8]> {
+        Some(self.get_program_id())
+    }
+}
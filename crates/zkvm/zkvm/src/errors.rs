@@ -78,6 +78,12 @@ pub enum ZkVmVerificationKeyError {
 
     #[error("Verification Key size error")]
     InvalidVerificationKeySize,
+
+    #[error("No verification key registered for program {0}")]
+    UnknownProgram(String),
+
+    #[error("Conflicting verification key for program {0}")]
+    ConflictingProgramKey(String),
 }
 
 #[derive(Debug, Error)]
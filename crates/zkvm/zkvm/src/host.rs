@@ -1,11 +1,14 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    io::{Read, Write},
+};
 
 use borsh::BorshDeserialize;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    input::ZkVmInputBuilder, ProofReceipt, ProofType, PublicValues, VerificationKey, ZkVmError,
-    ZkVmProofError, ZkVmResult,
+    input::ZkVmInputBuilder, Proof, ProofReceipt, ProofType, PublicValues, VerificationKey,
+    ZkVmError, ZkVmProofError, ZkVmResult,
 };
 
 /// A trait implemented by the prover ("host") of a zkVM program.
@@ -36,6 +39,11 @@ pub trait ZkVmHost: Send + Sync + Clone + Display + 'static {
     /// Returns the Verification key for the loaded ELF program
     fn get_verification_key(&self) -> VerificationKey;
 
+    /// Returns the size of the proof in bytes, which is what it costs to post it on L1.
+    fn proof_size_bytes(proof: &Proof) -> usize {
+        proof.as_bytes().len()
+    }
+
     /// Extracts the public output from the public values using ZkVm's `serde`
     /// serialization/deserialization.
     fn extract_serde_public_output<T: Serialize + DeserializeOwned>(
@@ -51,6 +59,27 @@ pub trait ZkVmHost: Send + Sync + Clone + Display + 'static {
             .map_err(|e| ZkVmError::OutputExtractionError { source: e.into() })
     }
 
+    /// Writes the raw public values into the writer, so that large outputs can
+    /// be passed on, e.g. to a file, without decoding them into memory.
+    fn extract_public_output_into<W: Write>(
+        public_values: &PublicValues,
+        writer: &mut W,
+    ) -> ZkVmResult<()> {
+        writer
+            .write_all(public_values.as_bytes())
+            .map_err(|e| ZkVmError::OutputExtractionError { source: e.into() })
+    }
+
+    /// Decodes a Borsh serialized public output incrementally from the reader,
+    /// like one written out by [`Self::extract_public_output_into`].
+    ///
+    /// Unlike [`Self::extract_borsh_public_output`], any bytes following the
+    /// output are left in the reader.
+    fn read_borsh_public_output<T: BorshDeserialize, R: Read>(reader: &mut R) -> ZkVmResult<T> {
+        T::deserialize_reader(reader)
+            .map_err(|e| ZkVmError::OutputExtractionError { source: e.into() })
+    }
+
     /// Verifies the proof generated by the ZkVm
     fn verify_inner(&self, proof: &Self::ZkVmProofReceipt) -> ZkVmResult<()>;
 
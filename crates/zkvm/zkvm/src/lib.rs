@@ -7,6 +7,7 @@ mod host;
 mod input;
 mod proof;
 mod prover;
+mod registry;
 
 pub use env::*;
 pub use errors::*;
@@ -14,6 +15,7 @@ pub use host::*;
 pub use input::*;
 pub use proof::*;
 pub use prover::*;
+pub use registry::*;
 
 /// Represents the ZkVm host used for proof generation.
 ///
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::{VerificationKey, ZkVmVerificationKeyError};
+
+/// The set of verification keys trusted for the proofs of each program, looked
+/// up by the program's name.
+#[derive(Debug, Clone, Default)]
+pub struct VkRegistry {
+    keys: HashMap<String, VerificationKey>,
+}
+
+impl VkRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the verification key of the program.
+    ///
+    /// Registering the same key again is fine, but a program can't have two
+    /// different keys.
+    pub fn register(
+        &mut self,
+        program: &str,
+        vk: VerificationKey,
+    ) -> Result<(), ZkVmVerificationKeyError> {
+        match self.keys.get(program) {
+            Some(existing) if *existing != vk => Err(
+                ZkVmVerificationKeyError::ConflictingProgramKey(program.to_owned()),
+            ),
+            Some(_) => Ok(()),
+            None => {
+                self.keys.insert(program.to_owned(), vk);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the verification key of the program.
+    pub fn get(&self, program: &str) -> Result<&VerificationKey, ZkVmVerificationKeyError> {
+        self.keys
+            .get(program)
+            .ok_or_else(|| ZkVmVerificationKeyError::UnknownProgram(program.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_get() {
+        let stf_vk = VerificationKey::new(vec![1; 32]);
+        let agg_vk = VerificationKey::new(vec![2; 32]);
+
+        let mut registry = VkRegistry::new();
+        registry.register("cl_stf", stf_vk.clone()).unwrap();
+        registry.register("cl_agg", agg_vk.clone()).unwrap();
+
+        assert_eq!(registry.get("cl_stf").unwrap(), &stf_vk);
+        assert_eq!(registry.get("cl_agg").unwrap(), &agg_vk);
+
+        // same key again is fine, another one isn't
+        registry.register("cl_stf", stf_vk.clone()).unwrap();
+        let res = registry.register("cl_stf", agg_vk);
+        assert!(
+            matches!(res, Err(ZkVmVerificationKeyError::ConflictingProgramKey(p)) if p == "cl_stf")
+        );
+        assert_eq!(registry.get("cl_stf").unwrap(), &stf_vk);
+    }
+
+    #[test]
+    fn test_unknown_program() {
+        let mut registry = VkRegistry::new();
+        registry
+            .register("cl_stf", VerificationKey::new(vec![1; 32]))
+            .unwrap();
+
+        let res = registry.get("evm_ee_stf");
+        assert!(
+            matches!(res, Err(ZkVmVerificationKeyError::UnknownProgram(p)) if p == "evm_ee_stf")
+        );
+    }
+}
@@ -0,0 +1,38 @@
+import flexitest
+
+import testenv
+from utils import wait_until
+
+
+@flexitest.register
+class DbMaintenanceTest(testenv.StrataTester):
+    def __init__(self, ctx: flexitest.InitContext):
+        ctx.set_env("basic")
+
+    def main(self, ctx: flexitest.RunContext):
+        seq = ctx.get_service("sequencer")
+
+        seqrpc = seq.create_rpc()
+
+        # Wait for the sequencer to produce some blocks
+        wait_until(
+            lambda: seqrpc.strata_getHeadersAtIdx(5) is not None,
+            error_with="Sequencer did not produce blocks on time",
+            timeout=30,
+        )
+
+        seqrpc.strataadmin_triggerCompaction()
+
+        stats = seqrpc.strataadmin_getDbStats()
+        self.debug(f"db stats {stats}")
+        assert len(stats) > 0, "no column family stats returned"
+
+        names = [s["name"] for s in stats]
+        assert len(set(names)) == len(names), "duplicate column family stats"
+
+        # blocks were flushed to disk by the compaction
+        l2_stats = next(s for s in stats if s["name"] == "L2BlockSchema")
+        assert l2_stats["sst_files_size"] > 0, "L2 blocks missing from stats"
+        assert l2_stats["sst_file_count"] > 0, "L2 blocks missing from stats"
+
+        return True
@@ -2,5 +2,6 @@
 #[derive(Debug, Clone)]
 pub struct ProofReport {
     pub cycles: u64,
+    pub proof_bytes: usize,
     pub report_name: String,
 }
@@ -15,6 +15,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let sp1_reports = vec![PerformanceReport {
         program: "BTC_BLOCKSPACES".to_owned(),
         cycles: 0,
+        proof_bytes: 0,
         success: true,
     }];
     results_text.push(format_results(&sp1_reports, "SP1".to_owned()));
@@ -65,6 +66,7 @@ struct EvalArgs {
 pub struct PerformanceReport {
     program: String,
     cycles: u64,
+    proof_bytes: usize,
     success: bool,
 }
 
@@ -73,6 +75,7 @@ impl From<ProofReport> for PerformanceReport {
         PerformanceReport {
             program: value.report_name,
             cycles: value.cycles,
+            proof_bytes: value.proof_bytes,
             success: true,
         }
     }
@@ -95,14 +98,15 @@ fn format_header(args: &EvalArgs) -> String {
 fn format_results(results: &[PerformanceReport], host_name: String) -> String {
     let mut table_text = String::new();
     table_text.push('\n');
-    table_text.push_str("| program           | cycles      | success  |\n");
-    table_text.push_str("|-------------------|-------------|----------|");
+    table_text.push_str("| program           | cycles      | proof bytes | success  |\n");
+    table_text.push_str("|-------------------|-------------|-------------|----------|");
 
     for result in results.iter() {
         table_text.push_str(&format!(
-            "\n| {:<17} | {:>11} | {:<7} |",
+            "\n| {:<17} | {:>11} | {:>11} | {:<7} |",
             result.program,
             result.cycles.to_formatted_string(&Locale::en),
+            result.proof_bytes.to_formatted_string(&Locale::en),
             if result.success { "✅" } else { "❌" }
         ));
     }
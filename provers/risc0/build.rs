@@ -18,7 +18,7 @@ fn main() {
 
         let elf = r#"
             pub const GUEST_RISC0_EVM_EE_STF_ELF: &[u8] = &[];
-            pub const GUEST_RISC0_EVM_EE_STF_ID: &[u32; 8] = &[0u32; 8];
+            pub const GUEST_RISC0_EVM_EE_STF_ID: [u32; 8] = [0u32; 8];
 
             pub const GUEST_RISC0_CL_STF_ELF: &[u8] = &[];
             pub const GUEST_RISC0_CL_STF_ID: [u32; 8] = [0u32; 8];
@@ -1,9 +1,10 @@
 use bitcoin::Block;
 use strata_proofimpl_btc_blockspace::{logic::BlockspaceProofInput, prover::BtcBlockspaceProver};
 use strata_test_utils::l2::gen_params;
-use strata_zkvm::{ZkVmHost, ZkVmResult};
+use strata_zkvm::ZkVmHost;
+use strata_zkvm_hosts::ProofVm;
 
-use super::ProofGenerator;
+use super::{ProofGenResult, ProofGenerator};
 
 #[derive(Clone)]
 pub struct BtcBlockProofGenerator<H: ZkVmHost> {
@@ -21,7 +22,7 @@ impl<H: ZkVmHost> ProofGenerator for BtcBlockProofGenerator<H> {
     type P = BtcBlockspaceProver;
     type H = H;
 
-    fn get_input(&self, block: &Block) -> ZkVmResult<BlockspaceProofInput> {
+    fn get_input(&self, block: &Block) -> ProofGenResult<BlockspaceProofInput> {
         let params = gen_params();
         let rollup_params = params.rollup();
         let input = BlockspaceProofInput {
@@ -38,6 +39,10 @@ impl<H: ZkVmHost> ProofGenerator for BtcBlockProofGenerator<H> {
     fn get_host(&self) -> H {
         self.host.clone()
     }
+
+    fn get_proof_vm(&self) -> ProofVm {
+        ProofVm::BtcProving
+    }
 }
 
 #[cfg(test)]
@@ -59,6 +64,24 @@ mod tests {
         test_proof(crate::TEST_NATIVE_GENERATORS.btc_blockspace());
     }
 
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_native_proof_report() {
+        let generator = crate::TEST_NATIVE_GENERATORS.btc_blockspace();
+        let block = get_btc_chain().get_block(40321);
+
+        let proof = generator.get_proof(block).unwrap();
+        let report = generator
+            .gen_proof_report(block, "BTC_BLOCKSPACE".to_owned())
+            .unwrap();
+        assert_eq!(report.report_name, "BTC_BLOCKSPACE");
+        assert_eq!(
+            report.proof_bytes,
+            strata_native_zkvm_adapter::NativeHost::proof_size_bytes(proof.proof())
+        );
+        assert!(report.proof_bytes > 0);
+    }
+
     #[test]
     #[cfg(all(feature = "risc0", feature = "test"))]
     fn test_risc0() {
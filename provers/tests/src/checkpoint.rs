@@ -1,8 +1,12 @@
 use strata_proofimpl_checkpoint::prover::{CheckpointProver, CheckpointProverInput};
 use strata_test_utils::l2::gen_params;
-use strata_zkvm::{AggregationInput, ZkVmHost, ZkVmResult};
+use strata_zkvm::{AggregationInput, ZkVmHost};
+use strata_zkvm_hosts::ProofVm;
 
-use super::{l1_batch::L1BatchProofGenerator, l2_batch::L2BatchProofGenerator, ProofGenerator};
+use super::{
+    l1_batch::L1BatchProofGenerator, l2_batch::L2BatchProofGenerator, ProofGenResult,
+    ProofGenerator,
+};
 
 #[derive(Clone)]
 pub struct CheckpointProofGenerator<H: ZkVmHost> {
@@ -36,7 +40,7 @@ impl<H: ZkVmHost> ProofGenerator for CheckpointProofGenerator<H> {
     type P = CheckpointProver;
     type H = H;
 
-    fn get_input(&self, batch_info: &CheckpointBatchInfo) -> ZkVmResult<CheckpointProverInput> {
+    fn get_input(&self, batch_info: &CheckpointBatchInfo) -> ProofGenResult<CheckpointProverInput> {
         let params = gen_params();
         let rollup_params = params.rollup();
 
@@ -45,15 +49,13 @@ impl<H: ZkVmHost> ProofGenerator for CheckpointProofGenerator<H> {
 
         let l1_batch_proof = self
             .l1_batch_prover
-            .get_proof(&(l1_start_height as u32, l1_end_height as u32))
-            .unwrap();
+            .get_dependency_proof(&(l1_start_height as u32, l1_end_height as u32))?;
         let l1_batch_vk = self.l1_batch_prover.get_host().get_verification_key();
         let l1_batch = AggregationInput::new(l1_batch_proof, l1_batch_vk);
 
         let l2_batch_proof = self
             .l2_batch_prover
-            .get_proof(&(l2_start_height, l2_end_height))
-            .unwrap();
+            .get_dependency_proof(&(l2_start_height, l2_end_height))?;
         let l2_batch_vk = self.l2_batch_prover.get_host().get_verification_key();
         let l2_batch = AggregationInput::new(l2_batch_proof, l2_batch_vk);
 
@@ -78,6 +80,10 @@ impl<H: ZkVmHost> ProofGenerator for CheckpointProofGenerator<H> {
     fn get_host(&self) -> H {
         self.host.clone()
     }
+
+    fn get_proof_vm(&self) -> ProofVm {
+        ProofVm::Checkpoint
+    }
 }
 
 #[allow(dead_code)]
@@ -1,8 +1,9 @@
 use strata_proofimpl_cl_stf::prover::{ClStfInput, ClStfProver};
 use strata_test_utils::{evm_ee::L2Segment, l2::gen_params};
-use strata_zkvm::{ZkVmHost, ZkVmResult};
+use strata_zkvm::ZkVmHost;
+use strata_zkvm_hosts::ProofVm;
 
-use super::{el::ElProofGenerator, ProofGenerator};
+use super::{el::ElProofGenerator, ProofGenResult, ProofGenerator};
 
 #[derive(Clone)]
 pub struct ClProofGenerator<H: ZkVmHost> {
@@ -24,9 +25,9 @@ impl<H: ZkVmHost> ProofGenerator for ClProofGenerator<H> {
     type P = ClStfProver;
     type H = H;
 
-    fn get_input(&self, block_num: &u64) -> ZkVmResult<ClStfInput> {
+    fn get_input(&self, block_num: &u64) -> ProofGenResult<ClStfInput> {
         // Generate EL proof required for aggregation
-        let el_proof = self.el_proof_generator.get_proof(block_num)?;
+        let el_proof = self.el_proof_generator.get_dependency_proof(block_num)?;
 
         // Read CL witness data
         let params = gen_params();
@@ -52,6 +53,10 @@ impl<H: ZkVmHost> ProofGenerator for ClProofGenerator<H> {
     fn get_host(&self) -> H {
         self.host.clone()
     }
+
+    fn get_proof_vm(&self) -> ProofVm {
+        ProofVm::CLProving
+    }
 }
 
 #[cfg(test)]
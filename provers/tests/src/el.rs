@@ -1,8 +1,9 @@
 use strata_proofimpl_evm_ee_stf::{primitives::EvmEeProofInput, prover::EvmEeProver};
 use strata_test_utils::evm_ee::EvmSegment;
-use strata_zkvm::{ZkVmHost, ZkVmResult};
+use strata_zkvm::ZkVmHost;
+use strata_zkvm_hosts::ProofVm;
 
-use super::ProofGenerator;
+use super::{ProofGenResult, ProofGenerator};
 
 #[derive(Clone)]
 pub struct ElProofGenerator<H: ZkVmHost> {
@@ -20,7 +21,7 @@ impl<H: ZkVmHost> ProofGenerator for ElProofGenerator<H> {
     type P = EvmEeProver;
     type H = H;
 
-    fn get_input(&self, block_num: &u64) -> ZkVmResult<EvmEeProofInput> {
+    fn get_input(&self, block_num: &u64) -> ProofGenResult<EvmEeProofInput> {
         let input = EvmSegment::initialize_from_saved_ee_data(*block_num, *block_num)
             .get_input(block_num)
             .clone();
@@ -34,6 +35,10 @@ impl<H: ZkVmHost> ProofGenerator for ElProofGenerator<H> {
     fn get_host(&self) -> H {
         self.host.clone()
     }
+
+    fn get_proof_vm(&self) -> ProofVm {
+        ProofVm::ELProving
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,30 @@
+use strata_zkvm::ZkVmError;
+use thiserror::Error;
+
+/// A convenient alias for results of the proof generators.
+pub type ProofGenResult<T> = Result<T, ProofGenError>;
+
+/// Errors of the proof generators, telling apart failing to gather the input
+/// of a proof, which can be retried on its own, from failing to prove it.
+#[derive(Debug, Error)]
+pub enum ProofGenError {
+    /// The data the input is made from couldn't be fetched.
+    #[error("failed to fetch input: {0}")]
+    InputFetch(String),
+
+    /// A proof the input is made from couldn't be retrieved.
+    #[error("dependency proof {proof_id} missing: {source}")]
+    DependencyMissing {
+        proof_id: String,
+        #[source]
+        source: Box<ProofGenError>,
+    },
+
+    /// The input was there, but proving it failed.
+    #[error("proving failed: {0}")]
+    Proving(#[source] ZkVmError),
+
+    /// The proof was generated or read from cache, but doesn't verify.
+    #[error("verification failed: {0}")]
+    Verification(#[source] ZkVmError),
+}
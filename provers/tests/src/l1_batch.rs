@@ -1,9 +1,10 @@
 use bitcoin::params::MAINNET;
 use strata_proofimpl_l1_batch::{L1BatchProofInput, L1BatchProver};
 use strata_test_utils::bitcoin::get_btc_chain;
-use strata_zkvm::{ZkVmHost, ZkVmResult};
+use strata_zkvm::ZkVmHost;
+use strata_zkvm_hosts::ProofVm;
 
-use super::{btc::BtcBlockProofGenerator, ProofGenerator};
+use super::{btc::BtcBlockProofGenerator, ProofGenResult, ProofGenerator};
 
 #[derive(Clone)]
 pub struct L1BatchProofGenerator<H: ZkVmHost> {
@@ -25,7 +26,7 @@ impl<H: ZkVmHost> ProofGenerator for L1BatchProofGenerator<H> {
     type P = L1BatchProver;
     type H = H;
 
-    fn get_input(&self, heights: &(u32, u32)) -> ZkVmResult<L1BatchProofInput> {
+    fn get_input(&self, heights: &(u32, u32)) -> ProofGenResult<L1BatchProofInput> {
         let (start_height, end_height) = *heights;
 
         let btc_chain = get_btc_chain();
@@ -35,7 +36,7 @@ impl<H: ZkVmHost> ProofGenerator for L1BatchProofGenerator<H> {
         let mut batch = vec![];
         for height in start_height..=end_height {
             let block = btc_chain.get_block(height);
-            let btc_proof = self.btc_proof_generator.get_proof(block)?;
+            let btc_proof = self.btc_proof_generator.get_dependency_proof(block)?;
             batch.push(btc_proof);
         }
 
@@ -56,6 +57,10 @@ impl<H: ZkVmHost> ProofGenerator for L1BatchProofGenerator<H> {
     fn get_host(&self) -> H {
         self.host.clone()
     }
+
+    fn get_proof_vm(&self) -> ProofVm {
+        ProofVm::L1Batch
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,10 @@
-use strata_proofimpl_cl_agg::{ClAggInput, ClAggProver};
-use strata_zkvm::{ZkVmHost, ZkVmResult};
+use std::sync::Arc;
 
-use super::{cl::ClProofGenerator, ProofGenerator};
+use strata_proofimpl_cl_agg::{ClAggInput, ClAggProver, CL_STF_PROGRAM};
+use strata_zkvm::{VkRegistry, ZkVmHost};
+use strata_zkvm_hosts::ProofVm;
+
+use super::{cl::ClProofGenerator, ProofGenError, ProofGenResult, ProofGenerator};
 
 #[derive(Clone)]
 pub struct L2BatchProofGenerator<H: ZkVmHost> {
@@ -23,17 +26,26 @@ impl<H: ZkVmHost> ProofGenerator for L2BatchProofGenerator<H> {
     type P = ClAggProver;
     type H = H;
 
-    fn get_input(&self, heights: &(u64, u64)) -> ZkVmResult<ClAggInput> {
+    fn get_input(&self, heights: &(u64, u64)) -> ProofGenResult<ClAggInput> {
         let (start_height, end_height) = *heights;
         let mut batch = Vec::new();
 
         for block_num in start_height..=end_height {
-            let cl_proof = self.cl_proof_generator.get_proof(&block_num)?;
+            let cl_proof = self.cl_proof_generator.get_dependency_proof(&block_num)?;
             batch.push(cl_proof);
         }
 
-        let cl_stf_vk = self.cl_proof_generator.get_host().get_verification_key();
-        Ok(ClAggInput { batch, cl_stf_vk })
+        let mut vk_registry = VkRegistry::new();
+        vk_registry
+            .register(
+                CL_STF_PROGRAM,
+                self.cl_proof_generator.get_host().get_verification_key(),
+            )
+            .map_err(|e| ProofGenError::InputFetch(e.to_string()))?;
+        Ok(ClAggInput {
+            batch,
+            vk_registry: Arc::new(vk_registry),
+        })
     }
 
     fn get_proof_id(&self, heights: &(u64, u64)) -> String {
@@ -44,13 +56,20 @@ impl<H: ZkVmHost> ProofGenerator for L2BatchProofGenerator<H> {
     fn get_host(&self) -> H {
         self.host.clone()
     }
+
+    fn get_proof_vm(&self) -> ProofVm {
+        ProofVm::CLAggregation
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use strata_zkvm_hosts::GuestProgram;
+
     use super::*;
 
-    fn test_proof<H: ZkVmHost>(cl_agg_prover: &L2BatchProofGenerator<H>) {
+    fn test_proof<H: ZkVmHost + GuestProgram>(cl_agg_prover: &L2BatchProofGenerator<H>) {
+        cl_agg_prover.verify_program_id().unwrap();
         let _ = cl_agg_prover.get_proof(&(1, 3)).unwrap();
     }
 
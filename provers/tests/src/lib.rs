@@ -2,11 +2,14 @@
 extern crate cfg_if;
 use std::{fs, path::PathBuf, sync::LazyLock};
 
+use strata_provers_perf::ProofReport;
 use strata_zkvm::{ProofReceipt, ZkVmHost, ZkVmProofError, ZkVmProver, ZkVmResult};
+use strata_zkvm_hosts::{GuestProgram, ProgramIdMismatch, ProofVm};
 mod btc;
 mod checkpoint;
 mod cl;
 mod el;
+mod errors;
 mod generators;
 mod l1_batch;
 mod l2_batch;
@@ -18,6 +21,7 @@ pub mod proof_generators {
     };
 }
 pub use checkpoint::CheckpointBatchInfo;
+pub use errors::{ProofGenError, ProofGenResult};
 pub use generators::TestProverGenerators;
 
 cfg_if! {
@@ -56,7 +60,7 @@ pub trait ProofGenerator {
     type H: ZkVmHost;
 
     /// An input required to generate a proof.
-    fn get_input(&self, input: &Self::Input) -> ZkVmResult<<Self::P as ZkVmProver>::Input>;
+    fn get_input(&self, input: &Self::Input) -> ProofGenResult<<Self::P as ZkVmProver>::Input>;
 
     // A host to generate the proof against.
     fn get_host(&self) -> Self::H;
@@ -65,8 +69,20 @@ pub trait ProofGenerator {
     /// The proof ID will be the hash of the input and potentially other unique identifiers.
     fn get_proof_id(&self, input: &Self::Input) -> String;
 
+    /// The program proofs are generated with.
+    fn get_proof_vm(&self) -> ProofVm;
+
+    /// Checks the program the host loaded is the one the guest builder
+    /// committed to, so that stale guest artifacts are caught before proving.
+    fn verify_program_id(&self) -> Result<(), ProgramIdMismatch>
+    where
+        Self::H: GuestProgram,
+    {
+        strata_zkvm_hosts::verify_program_id(self.get_proof_vm(), &self.get_host())
+    }
+
     /// Retrieves a proof from cache or generates it if not found.
-    fn get_proof(&self, input: &Self::Input) -> ZkVmResult<ProofReceipt> {
+    fn get_proof(&self, input: &Self::Input) -> ProofGenResult<ProofReceipt> {
         // 1. Create the unique proof ID
         let proof_id = format!("{}_{}.proof", self.get_proof_id(input), self.get_host());
         println!("Getting proof for {}", proof_id);
@@ -75,9 +91,10 @@ pub trait ProofGenerator {
         // 2. Check if the proof file exists
         if proof_file.exists() {
             println!("Proof found in cache, returning the cached proof...",);
-            let proof = read_proof_from_file(&proof_file)?;
+            let proof = read_proof_from_file(&proof_file)
+                .map_err(|e| ProofGenError::Verification(e.into()))?;
             let host = self.get_host();
-            verify_proof(&proof, &host)?;
+            verify_proof(&proof, &host).map_err(ProofGenError::Verification)?;
             return Ok(proof);
         }
 
@@ -86,7 +103,7 @@ pub trait ProofGenerator {
         let proof = self.gen_proof(input)?;
 
         // Verify the proof
-        verify_proof(&proof, &self.get_host())?;
+        verify_proof(&proof, &self.get_host()).map_err(ProofGenError::Verification)?;
 
         // Save the proof to cache
         write_proof_to_file(&proof, &proof_file).unwrap();
@@ -94,11 +111,35 @@ pub trait ProofGenerator {
         Ok(proof)
     }
 
+    /// Retrieves a proof the input of another proof is made from.
+    fn get_dependency_proof(&self, input: &Self::Input) -> ProofGenResult<ProofReceipt> {
+        self.get_proof(input)
+            .map_err(|e| ProofGenError::DependencyMissing {
+                proof_id: self.get_proof_id(input),
+                source: Box::new(e),
+            })
+    }
+
     /// Generates a proof based on the input.
-    fn gen_proof(&self, input: &Self::Input) -> ZkVmResult<ProofReceipt> {
+    fn gen_proof(&self, input: &Self::Input) -> ProofGenResult<ProofReceipt> {
         let input = self.get_input(input)?;
         let host = self.get_host();
-        <Self::P as ZkVmProver>::prove(&input, &host)
+        <Self::P as ZkVmProver>::prove(&input, &host).map_err(ProofGenError::Proving)
+    }
+
+    /// Generates a proof based on the input, bypassing the cache, and reports its stats.
+    fn gen_proof_report(
+        &self,
+        input: &Self::Input,
+        report_name: String,
+    ) -> ProofGenResult<ProofReport> {
+        let proof = self.gen_proof(input)?;
+        Ok(ProofReport {
+            // hosts don't expose the cycle count of the execution yet
+            cycles: 0,
+            proof_bytes: Self::H::proof_size_bytes(proof.proof()),
+            report_name,
+        })
     }
 }
 
@@ -143,3 +184,93 @@ fn write_proof_to_file(proof: &ProofReceipt, proof_file: &std::path::Path) -> Re
 fn verify_proof(proof: &ProofReceipt, host: &impl ZkVmHost) -> ZkVmResult<()> {
     host.verify(proof)
 }
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use std::sync::Arc;
+
+    use strata_native_zkvm_adapter::{NativeHost, NativeMachine};
+    use strata_proofimpl_btc_blockspace::{
+        logic::BlockspaceProofInput, prover::BtcBlockspaceProver,
+    };
+    use strata_test_utils::{bitcoin::get_btc_chain, l2::gen_params};
+    use strata_zkvm::ZkVmError;
+
+    use super::*;
+
+    /// Generator that fails to either fetch its input or to prove it.
+    #[derive(Clone)]
+    struct FailingProofGenerator {
+        fail_input: bool,
+        host: NativeHost,
+    }
+
+    impl FailingProofGenerator {
+        fn new(fail_input: bool) -> Self {
+            let host = NativeHost {
+                process_proof: Arc::new(Box::new(|_: &NativeMachine| {
+                    Err(ZkVmError::ProofGenerationError("always fails".to_owned()))
+                })),
+            };
+            Self { fail_input, host }
+        }
+    }
+
+    impl ProofGenerator for FailingProofGenerator {
+        type Input = u32;
+        type P = BtcBlockspaceProver;
+        type H = NativeHost;
+
+        fn get_input(&self, height: &u32) -> ProofGenResult<BlockspaceProofInput> {
+            if self.fail_input {
+                return Err(ProofGenError::InputFetch(format!("no block {height}")));
+            }
+
+            Ok(BlockspaceProofInput {
+                block: get_btc_chain().get_block(*height).clone(),
+                rollup_params: gen_params().rollup().clone(),
+            })
+        }
+
+        fn get_host(&self) -> NativeHost {
+            self.host.clone()
+        }
+
+        fn get_proof_id(&self, height: &u32) -> String {
+            format!("failing_{}_{}", self.fail_input, height)
+        }
+
+        fn get_proof_vm(&self) -> ProofVm {
+            ProofVm::BtcProving
+        }
+    }
+
+    #[test]
+    fn test_input_fetch_error() {
+        let generator = FailingProofGenerator::new(true);
+
+        let res = generator.get_proof(&40321);
+        assert!(matches!(res, Err(ProofGenError::InputFetch(_))));
+
+        // as a dependency of another proof, it's reported as missing
+        let res = generator.get_dependency_proof(&40321);
+        match res {
+            Err(ProofGenError::DependencyMissing { proof_id, source }) => {
+                assert_eq!(proof_id, "failing_true_40321");
+                assert!(matches!(*source, ProofGenError::InputFetch(_)));
+            }
+            res => panic!("expected a missing dependency, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_proving_error() {
+        let generator = FailingProofGenerator::new(false);
+
+        let res = generator.get_proof(&40321);
+        assert!(matches!(
+            res,
+            Err(ProofGenError::Proving(ZkVmError::ProofGenerationError(_)))
+        ));
+    }
+}